@@ -14,9 +14,14 @@ fn main() {
 fn app() -> Element {
     rsx! {
         ErrorBoundary {
-            handle_error: |error: CapturedError| rsx! {
-                h1 { "An error occurred" }
-                pre { "{error:#?}" }
+            handle_error: |error: CapturedError| {
+                // Resetting the boundary mounts DemoC again, so the user can try again
+                let retry = error.clone();
+                rsx! {
+                    h1 { "An error occurred" }
+                    pre { "{error:#?}" }
+                    button { onclick: move |_| retry.reset(), "Try again" }
+                }
             },
             DemoC { x: 1 }
         }
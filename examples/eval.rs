@@ -27,11 +27,12 @@ fn app() -> Element {
             "#,
         );
 
-        // Send a message to the JS code.
-        eval.send("Hi from Rust!".into()).unwrap();
+        // Send a message to the JS code. Anything that implements `Serialize` can be sent.
+        eval.send_serialized("Hi from Rust!").unwrap();
 
         // Our line on the JS side will log the message and then return "hello world".
-        let res = eval.recv().await.unwrap();
+        // Messages can be deserialized into any type that implements `Deserialize`.
+        let res: String = eval.recv_as().await.unwrap();
 
         // This will print "Hi from JS!" and "Hi from Rust!".
         println!("{:?}", eval.await);
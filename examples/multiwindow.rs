@@ -1,8 +1,9 @@
 //! Multiwindow example
 //!
 //! This exmaple shows how to implement a simple multiwindow application using dioxus.
-//! This works by spawning a new window when the user clicks a button. We have to build a new virtualdom which has its
-//! own context, root elements, etc.
+//! This works by spawning a new window when the user clicks a button. Each window runs its own virtualdom which has its
+//! own context, root elements, etc. The root component of the new window can be given props just like any other
+//! component.
 
 use dioxus::prelude::*;
 
@@ -11,9 +12,15 @@ fn main() {
 }
 
 fn app() -> Element {
+    let mut windows = use_signal(|| 0);
+
     let onclick = move |_| {
-        let dom = VirtualDom::new(popup);
-        dioxus::desktop::window().new_window(dom, Default::default());
+        windows += 1;
+        dioxus::desktop::window().new_window_with_props(
+            Popup,
+            PopupProps { number: windows() },
+            Default::default(),
+        );
     };
 
     rsx! {
@@ -21,8 +28,9 @@ fn app() -> Element {
     }
 }
 
-fn popup() -> Element {
+#[component]
+fn Popup(number: usize) -> Element {
     rsx! {
-        div { "This is a popup window!" }
+        div { "This is popup window #{number}!" }
     }
 }
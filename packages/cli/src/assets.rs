@@ -2,7 +2,7 @@ use std::{fs::File, io::Write, path::PathBuf};
 
 use crate::Result;
 use dioxus_cli_config::CrateConfig;
-use manganis_cli_support::{AssetManifest, AssetManifestExt};
+use manganis_cli_support::{AssetManifest, AssetManifestExt, FileAsset};
 
 pub fn asset_manifest(bin: Option<&str>, crate_config: &CrateConfig) -> AssetManifest {
     AssetManifest::load_from_path(
@@ -21,6 +21,20 @@ pub fn create_assets_head(config: &CrateConfig, manifest: &AssetManifest) -> Res
 
 /// Process any assets collected from the binary
 pub(crate) fn process_assets(config: &CrateConfig, manifest: &AssetManifest) -> anyhow::Result<()> {
+    manifest.copy_static_assets_to(static_asset_output_dir(config))?;
+
+    Ok(())
+}
+
+/// Process a single file asset collected from the binary, to the same place [`process_assets`] puts it
+pub(crate) fn process_file_asset(config: &CrateConfig, file: &FileAsset) -> anyhow::Result<()> {
+    manganis_cli_support::process_file(file, &static_asset_output_dir(config))?;
+
+    Ok(())
+}
+
+/// The directory assets are processed into
+fn static_asset_output_dir(config: &CrateConfig) -> PathBuf {
     let static_asset_output_dir = PathBuf::from(
         config
             .dioxus_config
@@ -30,11 +44,7 @@ pub(crate) fn process_assets(config: &CrateConfig, manifest: &AssetManifest) ->
             .clone()
             .unwrap_or_default(),
     );
-    let static_asset_output_dir = config.out_dir().join(static_asset_output_dir);
-
-    manifest.copy_static_assets_to(static_asset_output_dir)?;
-
-    Ok(())
+    config.out_dir().join(static_asset_output_dir)
 }
 
 /// A guard that sets up the environment for the web renderer to compile in. This guard sets the location that assets will be served from
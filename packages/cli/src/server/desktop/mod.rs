@@ -6,13 +6,15 @@ use crate::{
     },
     BuildResult, Result,
 };
-use dioxus_cli_config::CrateConfig;
-use dioxus_hot_reload::HotReloadMsg;
+use dioxus_cli_config::{CrateConfig, ExecutableType};
+use dioxus_hot_reload::{ClientInfo, ClientMsg, Encoding, Handshake, HandshakeReply, HotReloadMsg};
 use dioxus_html::HtmlCtx;
 use dioxus_rsx::hot_reload::*;
-use interprocess::local_socket::LocalSocketListener;
+use interprocess::local_socket::LocalSocketStream;
 use std::{
     fs::create_dir_all,
+    io::{BufRead, BufReader},
+    path::PathBuf,
     process::{Child, Command},
     sync::{Arc, Mutex, RwLock},
 };
@@ -95,7 +97,7 @@ async fn serve<P: Platform + Send + 'static>(
     match hot_reload_state {
         Some(hot_reload_state) => {
             // The open interprocess sockets
-            start_desktop_hot_reload(hot_reload_state).await?;
+            start_desktop_hot_reload(&config, hot_reload_state).await?;
         }
         None => {
             std::future::pending::<()>().await;
@@ -105,16 +107,13 @@ async fn serve<P: Platform + Send + 'static>(
     Ok(())
 }
 
-async fn start_desktop_hot_reload(hot_reload_state: HotReloadState) -> Result<()> {
-    let metadata = cargo_metadata::MetadataCommand::new()
-        .no_deps()
-        .exec()
-        .unwrap();
-    let target_dir = metadata.target_directory.as_std_path();
-    let _ = create_dir_all(target_dir); // `_all` is for good measure and future-proofness.
-    let path = target_dir.join("dioxusin");
-    clear_paths(&path);
-    match LocalSocketListener::bind(path) {
+async fn start_desktop_hot_reload(
+    config: &CrateConfig,
+    hot_reload_state: HotReloadState,
+) -> Result<()> {
+    let _ = create_dir_all(&config.target_dir); // `_all` is for good measure and future-proofness.
+    let path = hot_reload_socket_path(config);
+    match dioxus_hot_reload::bind_local_socket(&path) {
         Ok(local_socket_stream) => {
             let aborted = Arc::new(Mutex::new(false));
             // States
@@ -123,34 +122,20 @@ async fn start_desktop_hot_reload(hot_reload_state: HotReloadState) -> Result<()
 
             // listen for connections
             std::thread::spawn({
-                let file_map = hot_reload_state.file_map.clone();
+                let hot_reload_state = hot_reload_state.clone();
                 let channels = channels.clone();
                 let aborted = aborted.clone();
                 move || {
                     loop {
                         //accept() will block the thread when local_socket_stream is in blocking mode (default)
                         match local_socket_stream.accept() {
-                            Ok(mut connection) => {
-                                // send any templates than have changed before the socket connected
-                                let templates: Vec<_> = {
-                                    file_map
-                                        .lock()
-                                        .unwrap()
-                                        .map
-                                        .values()
-                                        .flat_map(|v| v.templates.values().copied())
-                                        .collect()
-                                };
-                                for template in templates {
-                                    if !send_msg(
-                                        HotReloadMsg::UpdateTemplate(template),
-                                        &mut connection,
-                                    ) {
-                                        continue;
-                                    }
-                                }
-                                channels.lock().unwrap().push(connection);
-                                println!("Connected to hot reloading 🚀");
+                            Ok(connection) => {
+                                // The handshake waits for the app, so every app connects on a thread of its own
+                                let hot_reload_state = hot_reload_state.clone();
+                                let channels = channels.clone();
+                                std::thread::spawn(move || {
+                                    connect_app(connection, &hot_reload_state, &channels)
+                                });
                             }
                             Err(err) => {
                                 let error_string = err.to_string();
@@ -185,25 +170,79 @@ async fn start_desktop_hot_reload(hot_reload_state: HotReloadState) -> Result<()
                 }
             }
         }
-        Err(error) => println!("failed to connect to hot reloading\n{error}"),
+        Err(error) => println!("Hot reloading failed to start: {error}"),
     }
 
     Ok(())
 }
 
-fn clear_paths(file_socket_path: &std::path::Path) {
-    if cfg!(unix) {
-        // On unix, if you force quit the application, it can leave the file socket open
-        // This will cause the local socket listener to fail to open
-        // We check if the file socket is already open from an old session and then delete it
+/// Offer the handshake to an app that connected, and send it the changes it missed or print what it reports
+fn connect_app(
+    connection: LocalSocketStream,
+    hot_reload_state: &HotReloadState,
+    channels: &Mutex<Vec<LocalSocketStream>>,
+) {
+    // The app can only pick JSON, so every message is a line
+    let handshake = Handshake::new().with_encodings(&[Encoding::Json]);
+    let mut connection = BufReader::new(connection);
+    if !send_msg(handshake, connection.get_mut()) {
+        return;
+    }
+    let mut reply = String::new();
+    if connection.read_line(&mut reply).unwrap_or(0) == 0 {
+        return;
+    }
+    let Ok(reply) = serde_json::from_str::<HandshakeReply>(&reply) else {
+        return;
+    };
+    if reply.reports {
+        print_reports(connection, reply.client);
+        return;
+    }
+    let mut connection = connection.into_inner();
 
-        if file_socket_path.exists() {
-            let _ = std::fs::remove_file(file_socket_path);
+    // send any templates and literals than have changed before the socket connected
+    for template in hot_reload_state.all_templates() {
+        if !send_msg(HotReloadMsg::UpdateTemplate(template), &mut connection) {
+            return;
+        }
+    }
+    for update in hot_reload_state.all_literals() {
+        if !send_msg(HotReloadMsg::UpdateLiterals(update), &mut connection) {
+            return;
         }
     }
+    channels.lock().unwrap().push(connection);
+    println!("Connected to hot reloading 🚀");
+}
+
+/// The local socket the app connects to. Every app gets its own, since the apps of a workspace share its target directory.
+fn hot_reload_socket_path(config: &CrateConfig) -> PathBuf {
+    let name = match &config.executable {
+        ExecutableType::Binary(name)
+        | ExecutableType::Lib(name)
+        | ExecutableType::Example(name) => name,
+    };
+    dioxus_hot_reload::socket_path(&config.target_dir, name)
 }
 
-fn send_msg(msg: HotReloadMsg, channel: &mut impl std::io::Write) -> bool {
+/// Print the log lines and panics the app reports over a connection of its own, until it closes
+fn print_reports(connection: BufReader<LocalSocketStream>, client: Option<ClientInfo>) {
+    let app = client.map_or_else(|| "app".to_string(), |client| client.name);
+    for line in connection.lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        match serde_json::from_str(&line) {
+            Ok(ClientMsg::Log(line)) => println!("[{app}] {line}"),
+            Ok(ClientMsg::Panic(panic)) => eprintln!("[{app}] {panic}"),
+            // The CLI doesn't time how long reloads take
+            Ok(ClientMsg::Ready) | Err(_) => {}
+        }
+    }
+}
+
+fn send_msg(msg: impl serde::Serialize, channel: &mut impl std::io::Write) -> bool {
     if let Ok(msg) = serde_json::to_string(&msg) {
         if channel.write_all(msg.as_bytes()).is_err() {
             return false;
@@ -225,7 +264,12 @@ fn start_desktop(
     // Run the desktop application
     // Only used for the fullstack platform,
     let result = crate::builder::build_desktop(config, true, skip_assets, rust_flags)?;
+    let child = spawn_desktop(config, &result)?;
 
+    Ok((child, result))
+}
+
+fn spawn_desktop(config: &CrateConfig, result: &BuildResult) -> Result<RAIIChild> {
     let active = "DIOXUS_ACTIVE";
     let child = RAIIChild(
         Command::new(
@@ -235,10 +279,14 @@ fn start_desktop(
                 .ok_or(anyhow::anyhow!("No executable found after desktop build"))?,
         )
         .env(active, "true")
+        .env(
+            dioxus_hot_reload::SOCKET_ENV,
+            hot_reload_socket_path(config),
+        )
         .spawn()?,
     );
 
-    Ok((child, result))
+    Ok(child)
 }
 
 pub(crate) struct DesktopPlatform {
@@ -282,9 +330,18 @@ impl DesktopPlatform {
         config: &CrateConfig,
         rust_flags: Option<String>,
     ) -> Result<BuildResult> {
+        // Windows can't overwrite the executable of a running app
+        if cfg!(windows) {
+            self.currently_running_child.0.kill()?;
+            let (child, result) = start_desktop(config, self.skip_assets, rust_flags)?;
+            self.currently_running_child = child;
+            return Ok(result);
+        }
+
+        // Keep the app running while building, so it can show the errors if the build fails
+        let result = crate::builder::build_desktop(config, true, self.skip_assets, rust_flags)?;
         self.currently_running_child.0.kill()?;
-        let (child, result) = start_desktop(config, self.skip_assets, rust_flags)?;
-        self.currently_running_child = child;
+        self.currently_running_child = spawn_desktop(config, &result)?;
         Ok(result)
     }
 }
@@ -1,14 +1,21 @@
-use crate::{cfg::ConfigOptsServe, BuildResult, Result};
+use crate::{
+    assets::{asset_manifest, process_file_asset},
+    cfg::ConfigOptsServe,
+    BuildResult, Result,
+};
 use dioxus_cli_config::CrateConfig;
 
 use cargo_metadata::diagnostic::Diagnostic;
-use dioxus_core::Template;
-use dioxus_hot_reload::HotReloadMsg;
+use dioxus_core::{LiteralUpdate, Template};
+use dioxus_hot_reload::{AssetUpdate, HotReloadMsg, ResolvedAsset, ShutdownReason};
 use dioxus_html::HtmlCtx;
 use dioxus_rsx::hot_reload::*;
 use fs_extra::dir::CopyOptions;
+use manganis_cli_support::{AssetSource, AssetType};
 use notify::{RecommendedWatcher, Watcher};
 use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
     path::PathBuf,
     sync::{Arc, Mutex},
 };
@@ -40,6 +47,20 @@ impl HotReloadState {
             .flat_map(|v| v.templates.values().copied())
             .collect()
     }
+
+    pub fn all_literals(&self) -> Vec<LiteralUpdate> {
+        self.file_map
+            .lock()
+            .unwrap()
+            .map
+            .values()
+            .flat_map(|v| v.literals.iter())
+            .map(|(name, literals)| LiteralUpdate {
+                template: name.rsplit_once(':').unwrap().0.to_string(),
+                literals: literals.clone(),
+            })
+            .collect()
+    }
 }
 
 /// Sets up a file watcher.
@@ -123,23 +144,45 @@ fn watch_event<F>(
     }
 
     if needs_full_rebuild {
-        full_rebuild(build_with, last_update_time, config, event, web_info);
+        full_rebuild(
+            build_with,
+            last_update_time,
+            hot_reload,
+            config,
+            event,
+            web_info,
+        );
     }
 }
 
 fn full_rebuild<F>(
     build_with: &F,
     last_update_time: &mut i64,
+    hot_reload: &Option<HotReloadState>,
     config: &CrateConfig,
     event: notify::Event,
     web_info: &Option<WebServerInfo>,
 ) where
     F: Fn() -> Result<BuildResult> + Send + 'static,
 {
+    // The apps keep running until the rebuilt app replaces them, and show the errors if the build fails
+    if let Some(hot_reload) = hot_reload {
+        let _ = hot_reload
+            .messages
+            .send(HotReloadMsg::Shutdown(ShutdownReason::RebuildRequired));
+    }
+
     match build_with() {
         Ok(res) => {
             *last_update_time = chrono::Local::now().timestamp();
 
+            // Clear the error overlay of the apps
+            send_build_status(
+                hot_reload,
+                vec![],
+                res.warnings.iter().map(render_diagnostic).collect(),
+            );
+
             #[allow(clippy::redundant_clone)]
             print_console_info(
                 config,
@@ -154,10 +197,33 @@ fn full_rebuild<F>(
         Err(e) => {
             *last_update_time = chrono::Local::now().timestamp();
             log::error!("{:?}", e);
+
+            // Show the error in the apps, not only in the terminal
+            send_build_status(hot_reload, vec![e.to_string()], vec![]);
         }
     }
 }
 
+/// Tell the connected apps how the last build went
+fn send_build_status(
+    hot_reload: &Option<HotReloadState>,
+    errors: Vec<String>,
+    warnings: Vec<String>,
+) {
+    if let Some(hot_reload) = hot_reload {
+        let _ = hot_reload
+            .messages
+            .send(HotReloadMsg::BuildStatus { errors, warnings });
+    }
+}
+
+fn render_diagnostic(diagnostic: &Diagnostic) -> String {
+    diagnostic
+        .rendered
+        .clone()
+        .unwrap_or_else(|| diagnostic.message.clone())
+}
+
 fn hotreload_files(
     hot_reload: &HotReloadState,
     needs_full_rebuild: &mut bool,
@@ -184,13 +250,14 @@ fn hotreload_files(
         }
 
         // If the file was hotreloaded, update the file map in place
-        match rsx_file_map.update_rsx(path, &config.crate_dir) {
-            Ok(UpdateResult::UpdatedRsx(msgs)) => {
-                messages.extend(msgs.into_iter().map(HotReloadMsg::UpdateTemplate));
+        match rsx_file_map.update_rsx_and_literals(path, &config.crate_dir) {
+            Ok((UpdateResult::UpdatedRsx(templates), literals)) => {
+                messages.extend(templates.into_iter().map(HotReloadMsg::UpdateTemplate));
+                messages.extend(literals.into_iter().map(HotReloadMsg::UpdateLiterals));
             }
 
             // If the file was not updated, we need to do a full rebuild
-            Ok(UpdateResult::NeedsRebuild) => {
+            Ok((UpdateResult::NeedsRebuild, _)) => {
                 log::trace!("Needs full rebuild because file changed: {:?}", path);
                 *needs_full_rebuild = true;
             }
@@ -250,6 +317,11 @@ fn hotreload_file(
         return None;
     }
 
+    // Assets collected by the asset! macro can live anywhere, and are served from the url they were hashed to
+    if ext != "rs" && attempt_manganis_reload(path, config, messages).is_some() {
+        return None;
+    }
+
     // Attempt to hotreload css in the asset directory
     // Currently no other assets are hotreloaded, but in theory we could hotreload pngs/jpegs, etc
     //
@@ -304,11 +376,74 @@ fn attempt_css_reload(
         &CopyOptions::new().overwrite(true),
     );
 
-    messages.push(HotReloadMsg::UpdateAsset(local_path));
+    push_asset_update(AssetUpdate::new(local_path), None, messages);
 
     Some(())
 }
 
+/// Process an asset the asset! macro collected again, and tell the apps the url it is served from
+///
+/// The app still loads the asset from the url hashed when it was compiled, so the asset is processed to the same
+/// place, and the apps bust their cache with a hash of the new contents instead.
+fn attempt_manganis_reload(
+    path: &Path,
+    config: &CrateConfig,
+    messages: &mut Vec<HotReloadMsg>,
+) -> Option<()> {
+    let path = path.canonicalize().ok()?;
+    let manifest = asset_manifest(config.executable.executable(), config);
+
+    let file = manifest
+        .packages()
+        .iter()
+        .flat_map(|package| package.assets())
+        .find_map(|asset| match asset {
+            AssetType::File(file) => match file.location().source() {
+                AssetSource::Local(source) if is_same_file(source, &path) => Some(file),
+                _ => None,
+            },
+            _ => None,
+        })?;
+    let url = file.served_location().ok()?;
+
+    // Only the asset that changed is processed again
+    if let Err(err) = process_file_asset(config, file) {
+        log::error!("Failed to process {}: {err}", path.display());
+        return None;
+    }
+
+    let mut hasher = DefaultHasher::new();
+    fs::read(&path).ok()?.hash(&mut hasher);
+    let resolved = ResolvedAsset {
+        url,
+        hash: format!("{:x}", hasher.finish()),
+    };
+
+    push_asset_update(AssetUpdate::new(path), Some(resolved), messages);
+    Some(())
+}
+
+fn is_same_file(source: &Path, canonical: &Path) -> bool {
+    source
+        .canonicalize()
+        .is_ok_and(|source| source == canonical)
+}
+
+fn push_asset_update(
+    asset: AssetUpdate,
+    resolved: Option<ResolvedAsset>,
+    messages: &mut Vec<HotReloadMsg>,
+) {
+    match (asset, resolved) {
+        // Scripts can't be swapped in the running page, but reloading it picks them up without a rebuild
+        (AssetUpdate::Script(_), _) => messages.push(HotReloadMsg::FullReload),
+        (asset, Some(resolved)) => {
+            messages.push(HotReloadMsg::UpdateResolvedAsset { asset, resolved })
+        }
+        (asset, None) => messages.push(HotReloadMsg::UpdateAsset(asset)),
+    }
+}
+
 fn local_path_of_asset(path: &Path) -> Option<PathBuf> {
     path.file_name()?.to_str()?.to_string().parse().ok()
 }
@@ -7,7 +7,7 @@ use axum::{
     response::IntoResponse,
     Extension,
 };
-use dioxus_hot_reload::HotReloadMsg;
+use dioxus_hot_reload::{HotReloadMsg, ShutdownReason};
 use futures_util::{pin_mut, FutureExt};
 
 pub async fn hot_reload_handler(
@@ -34,6 +34,14 @@ async fn hotreload_loop(mut socket: WebSocket, state: HotReloadState) -> anyhow:
             .send(Message::Text(serde_json::to_string(&template).unwrap()))
             .await?;
     }
+    for literals in state.all_literals() {
+        socket
+            .send(Message::Text(format!(
+                "update-literals: {}",
+                serde_json::to_string(&literals).unwrap()
+            )))
+            .await?;
+    }
 
     let mut rx = state.messages.subscribe();
 
@@ -58,10 +66,34 @@ async fn hotreload_loop(mut socket: WebSocket, state: HotReloadState) -> anyhow:
                 HotReloadMsg::UpdateTemplate(template) => {
                     Message::Text(serde_json::to_string(&template).unwrap())
                 }
-                HotReloadMsg::UpdateAsset(asset) => {
-                    Message::Text(format!("reload-asset: {}", asset.display()))
-                }
-                HotReloadMsg::Shutdown => {
+                HotReloadMsg::UpdateLiterals(literals) => Message::Text(format!(
+                    "update-literals: {}",
+                    serde_json::to_string(&literals).unwrap()
+                )),
+                HotReloadMsg::UpdateAsset(asset) => Message::Text(format!(
+                    "reload-asset: {}",
+                    serde_json::json!({ "kind": asset.kind(), "path": asset.path() })
+                )),
+                HotReloadMsg::UpdateResolvedAsset { asset, resolved } => Message::Text(format!(
+                    "reload-asset: {}",
+                    serde_json::json!({
+                        "kind": asset.kind(),
+                        "path": asset.path(),
+                        "resolved": resolved,
+                    })
+                )),
+                HotReloadMsg::BuildStatus { errors, warnings } => Message::Text(format!(
+                    "build-status: {}",
+                    serde_json::json!({ "errors": errors, "warnings": warnings })
+                )),
+                HotReloadMsg::FullReload => Message::Text("full-reload".to_string()),
+                // The web client doesn't track the status of the server
+                HotReloadMsg::Status(_) => continue,
+                // The CLI sends whole templates
+                HotReloadMsg::PatchTemplate(_) => continue,
+                // The page reloads once the rebuild finished
+                HotReloadMsg::Shutdown(ShutdownReason::RebuildRequired) => continue,
+                HotReloadMsg::Shutdown(_) => {
                     log::info!("🔥 Hot Reload WebSocket shutting down");
                     break;
                 }
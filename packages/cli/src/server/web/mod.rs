@@ -184,7 +184,10 @@ fn build(
         }
     }
 
-    let _ = reload_tx.send(());
+    // Keep the page of the last successful build, so it can show the error overlay
+    if result.is_ok() {
+        let _ = reload_tx.send(());
+    }
 
     result
 }
@@ -90,6 +90,11 @@ impl VirtualDom {
         replace_with: Option<usize>,
         gen_muts: bool,
     ) {
+        // The parent element is removed without mutations for the nodes in it, but portals moved theirs out of it
+        if !gen_muts {
+            self.remove_placed_portal(to, scope);
+        }
+
         // Remove the component from the dom
         if let Some(node) = self.scopes[scope.0].last_rendered_node.take() {
             node.remove_node(self, to, replace_with, gen_muts)
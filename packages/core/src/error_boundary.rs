@@ -1,11 +1,11 @@
 use crate::{
     global_context::{current_scope_id, try_consume_context},
     innerlude::provide_context,
-    use_hook, Element, IntoDynNode, Properties, ScopeId, Template, TemplateAttribute, TemplateNode,
-    VNode,
+    use_hook, Element, IntoDynNode, Properties, Runtime, ScopeId, Template, TemplateAttribute,
+    TemplateNode, VNode,
 };
 use std::{
-    any::{Any, TypeId},
+    any::Any,
     backtrace::Backtrace,
     cell::RefCell,
     error::Error,
@@ -63,17 +63,52 @@ impl<T: Any + Debug> AnyDebug for T {
     }
 }
 
-#[derive(Debug)]
+/// Where an error that was captured by an error boundary was thrown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorSource {
+    /// A component threw the error or panicked while it rendered, so the children of the boundary couldn't render
+    Render,
+
+    /// An event handler threw the error. The children of the boundary rendered fine, and might only need the error
+    /// pointed out to the user.
+    EventHandler,
+
+    /// A task a component spawned threw the error
+    Task,
+
+    /// The error was thrown somewhere else in the runtime, like in a callback the app ran in the scope itself
+    Other,
+}
+
+impl ErrorSource {
+    /// The source of an error thrown right now
+    fn current() -> Self {
+        Runtime::with(|rt| {
+            if rt.current_task.get().is_some() {
+                Self::Task
+            } else if rt.render_depth.get() > 0 {
+                Self::Render
+            } else if !rt.rendering.get() {
+                Self::EventHandler
+            } else {
+                Self::Other
+            }
+        })
+        .unwrap_or(Self::Other)
+    }
+}
+
+#[derive(Debug, Clone)]
 /// An instance of an error captured by a descendant component.
 pub struct CapturedError {
-    /// The error captured by the error boundary
-    pub error: Box<dyn AnyDebug + 'static>,
+    error: Rc<dyn AnyDebug + 'static>,
+    backtrace: Rc<Backtrace>,
+    scope: ScopeId,
+    source: ErrorSource,
 
-    /// The backtrace of the error
-    pub backtrace: Backtrace,
-
-    /// The scope that threw the error
-    pub scope: ScopeId,
+    /// The scope of the boundary that captured the error
+    boundary: ScopeId,
 }
 
 impl Display for CapturedError {
@@ -88,12 +123,47 @@ impl Display for CapturedError {
 impl Error for CapturedError {}
 
 impl CapturedError {
+    /// The error captured by the error boundary
+    pub fn error(&self) -> &dyn AnyDebug {
+        &*self.error
+    }
+
+    /// The backtrace of the error
+    pub fn backtrace(&self) -> &Backtrace {
+        &self.backtrace
+    }
+
+    /// The scope that threw the error
+    pub fn scope(&self) -> ScopeId {
+        self.scope
+    }
+
+    /// Whether the error was thrown while rendering, or from an event handler or task
+    pub fn source(&self) -> ErrorSource {
+        self.source
+    }
+
     /// Downcast the error type into a concrete error type
     pub fn downcast<T: 'static>(&self) -> Option<&T> {
-        if TypeId::of::<T>() == self.error.type_id() {
-            self.error.as_any().downcast_ref::<T>()
-        } else {
-            None
+        self.error.as_any().downcast_ref::<T>()
+    }
+
+    /// Clear the error from the boundary that captured it, and mount its children again. See [`ErrorBoundary::reset`].
+    ///
+    /// ```rust, ignore
+    /// rsx! {
+    ///     ErrorBoundary {
+    ///         handle_error: |error: CapturedError| rsx! {
+    ///             pre { "{error}" }
+    ///             button { onclick: move |_| error.reset(), "Try again" }
+    ///         },
+    ///         Profile {}
+    ///     }
+    /// }
+    /// ```
+    pub fn reset(&self) {
+        if let Some(boundary) = self.boundary.has_context::<ErrorBoundary>() {
+            boundary.reset();
         }
     }
 }
@@ -127,19 +197,35 @@ impl ErrorBoundary {
     /// Push an error into this Error Boundary
     pub fn insert_error(&self, scope: ScopeId, error: impl Debug + 'static, backtrace: Backtrace) {
         self.inner.error.replace(Some(CapturedError {
-            error: Box::new(error),
+            error: Rc::new(error),
             scope,
-            backtrace,
+            backtrace: Rc::new(backtrace),
+            source: ErrorSource::current(),
+            boundary: self.inner._id,
         }));
         if self.inner._id != ScopeId::ROOT {
             self.inner._id.needs_update();
         }
     }
 
+    /// The error that has been captured by this error boundary, if any. It stays until the boundary is reset.
+    pub fn error(&self) -> Option<CapturedError> {
+        self.inner.error.borrow().clone()
+    }
+
     /// Take any error that has been captured by this error boundary
     pub fn take_error(&self) -> Option<CapturedError> {
         self.inner.error.take()
     }
+
+    /// Clear the captured error, and render the boundary again. The [`ErrorBoundary`](fn@ErrorBoundary) component
+    /// mounts its children again from scratch, so components that failed to render get a fresh start. The boundary
+    /// every app has at its root renders the root component again.
+    pub fn reset(&self) {
+        if self.inner.error.take().is_some() {
+            self.inner._id.needs_update();
+        }
+    }
 }
 
 /// A trait to allow results to be thrown upwards to the nearest Error Boundary
@@ -218,7 +304,7 @@ pub trait Throw<S = ()>: Sized {
 pub(crate) fn throw_error<T>(e: impl Debug + 'static) -> Option<T> {
     if let Some(cx) = try_consume_context::<ErrorBoundary>() {
         match current_scope_id() {
-            Some(id) => cx.insert_error(id, Box::new(e), Backtrace::capture()),
+            Some(id) => cx.insert_error(id, e, Backtrace::capture()),
             None => {
                 tracing::error!("Cannot throw error outside of a component's scope.")
             }
@@ -443,6 +529,10 @@ impl<
 ///
 /// Error boundaries handle errors within a specific part of your application. Any errors passed in a child with [`Throw`] will be caught by the nearest error boundary.
 ///
+/// The boundary shows the result of `handle_error` in place of its children until the error is reset with
+/// [`CapturedError::reset`], which mounts the children again. Check [`CapturedError::source`] to tell errors thrown
+/// while rendering apart from errors thrown by event handlers.
+///
 /// ## Example
 ///
 /// ```rust, ignore
@@ -462,7 +552,7 @@ impl<
 #[allow(non_upper_case_globals, non_snake_case)]
 pub fn ErrorBoundary(props: ErrorBoundaryProps) -> Element {
     let error_boundary = use_error_boundary();
-    match error_boundary.take_error() {
+    match error_boundary.error() {
         Some(error) => (props.handle_error.0)(error),
         None => Some({
             static TEMPLATE: Template = Template {
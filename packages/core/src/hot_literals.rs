@@ -0,0 +1,191 @@
+//! Literal values in rsx! that hot reloading changed since the app was compiled
+//!
+//! Templates only describe the static parts of rsx!. Formatted strings and the literals passed to components are
+//! dynamic, so the rsx! macro reads them through [`hot_literal`] and [`hot_fmt`] in debug builds. Once hot reloading
+//! sent a [`LiteralUpdate`] for the template, those return the new values instead of the compiled ones.
+
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::sync::RwLock;
+
+/// A literal in rsx! that hot reloading can change without recompiling the app
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum HotLiteral {
+    /// An integer literal, like `5`
+    Int(i64),
+
+    /// A float literal, like `1.5`
+    Float(f64),
+
+    /// A boolean literal
+    Bool(bool),
+
+    /// A string literal without any formatted values
+    Str(String),
+
+    /// A formatted string, like `"{count} items"`
+    Fmt(Vec<FmtSegment>),
+}
+
+/// A part of a formatted [`HotLiteral`]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FmtSegment {
+    /// Text that is inserted as is
+    Literal(String),
+
+    /// The formatted value at the index, in the order the values appeared in the compiled string
+    Formatted(usize),
+}
+
+/// The new values of every literal of a template
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiteralUpdate {
+    /// The location of the template, without the byte index the name of the [`Template`](crate::Template) ends with
+    pub template: String,
+
+    /// The literals of the template, in the order of the dynamic nodes and then the dynamic attributes they are in
+    pub literals: Vec<HotLiteral>,
+}
+
+/// The literals hot reloading changed, by template location
+static LITERALS: RwLock<Option<FxHashMap<String, Vec<HotLiteral>>>> = RwLock::new(None);
+
+/// The string literals hot reloading sent, leaked once so `&'static str` props can borrow them
+static LEAKED_STRS: RwLock<Option<FxHashSet<&'static str>>> = RwLock::new(None);
+
+/// Strip the byte index off the name of a template. The index of a template changes when it is hot reloaded.
+pub(crate) fn template_location(name: &str) -> &str {
+    name.rsplit_once(':').map_or(name, |(location, _)| location)
+}
+
+pub(crate) fn set_literals(update: LiteralUpdate) {
+    // Literals are static values, so new strings are leaked like hot reloaded templates are leaked
+    let mut leaked = LEAKED_STRS.write().unwrap();
+    let leaked = leaked.get_or_insert_with(Default::default);
+    for literal in &update.literals {
+        if let HotLiteral::Str(str) = literal {
+            if !leaked.contains(str.as_str()) {
+                leaked.insert(Box::leak(str.clone().into_boxed_str()));
+            }
+        }
+    }
+
+    LITERALS
+        .write()
+        .unwrap()
+        .get_or_insert_with(Default::default)
+        .insert(update.template, update.literals);
+}
+
+fn with_literal<O>(template: &str, index: usize, f: impl FnOnce(&HotLiteral) -> O) -> Option<O> {
+    let literals = LITERALS.read().unwrap();
+    let literal = literals
+        .as_ref()?
+        .get(template_location(template))?
+        .get(index)?;
+    Some(f(literal))
+}
+
+/// A type a [`HotLiteral`] can be converted into
+pub trait FromHotLiteral: Sized {
+    /// Convert the literal, or return `None` if it has a different type
+    fn from_hot_literal(literal: &HotLiteral) -> Option<Self>;
+}
+
+macro_rules! from_int_literal {
+    ($($ty:ty),*) => {
+        $(
+            impl FromHotLiteral for $ty {
+                fn from_hot_literal(literal: &HotLiteral) -> Option<Self> {
+                    match literal {
+                        HotLiteral::Int(int) => (*int).try_into().ok(),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+from_int_literal!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl FromHotLiteral for f32 {
+    fn from_hot_literal(literal: &HotLiteral) -> Option<Self> {
+        f64::from_hot_literal(literal).map(|float| float as f32)
+    }
+}
+
+impl FromHotLiteral for f64 {
+    fn from_hot_literal(literal: &HotLiteral) -> Option<Self> {
+        match literal {
+            HotLiteral::Float(float) => Some(*float),
+            _ => None,
+        }
+    }
+}
+
+impl FromHotLiteral for bool {
+    fn from_hot_literal(literal: &HotLiteral) -> Option<Self> {
+        match literal {
+            HotLiteral::Bool(bool) => Some(*bool),
+            _ => None,
+        }
+    }
+}
+
+impl FromHotLiteral for String {
+    fn from_hot_literal(literal: &HotLiteral) -> Option<Self> {
+        match literal {
+            HotLiteral::Str(str) => Some(str.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl FromHotLiteral for &'static str {
+    fn from_hot_literal(literal: &HotLiteral) -> Option<Self> {
+        match literal {
+            HotLiteral::Str(str) => LEAKED_STRS
+                .read()
+                .unwrap()
+                .as_ref()?
+                .get(str.as_str())
+                .copied(),
+            _ => None,
+        }
+    }
+}
+
+/// The value of the literal at the index of the template, or the compiled value if hot reloading didn't change it
+#[doc(hidden)]
+pub fn hot_literal<T: FromHotLiteral>(template: &str, index: usize, value: T) -> T {
+    with_literal(template, index, T::from_hot_literal)
+        .flatten()
+        .unwrap_or(value)
+}
+
+/// The formatted string at the index of the template, if hot reloading changed it. The formatted values are only
+/// computed if it did.
+#[doc(hidden)]
+pub fn hot_fmt(
+    template: &str,
+    index: usize,
+    values: impl FnOnce() -> Vec<String>,
+) -> Option<String> {
+    let segments = with_literal(template, index, |literal| match literal {
+        HotLiteral::Fmt(segments) => Some(segments.clone()),
+        _ => None,
+    })??;
+
+    let values = values();
+    let mut formatted = String::new();
+    for segment in segments {
+        match segment {
+            FmtSegment::Literal(literal) => formatted.push_str(&literal),
+            FmtSegment::Formatted(index) => formatted.push_str(values.get(index)?),
+        }
+    }
+    Some(formatted)
+}
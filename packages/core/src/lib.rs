@@ -11,14 +11,17 @@ mod error_boundary;
 mod events;
 mod fragment;
 mod global_context;
+mod hot_literals;
 mod mutations;
 mod nodes;
+mod portal;
 mod properties;
 mod render_signal;
 mod runtime;
 mod scope_arena;
 mod scope_context;
 mod scopes;
+mod suspense;
 mod tasks;
 mod virtual_dom;
 
@@ -30,11 +33,14 @@ pub(crate) mod innerlude {
     pub use crate::events::*;
     pub use crate::fragment::*;
     pub use crate::global_context::*;
+    pub use crate::hot_literals::{FmtSegment, HotLiteral, LiteralUpdate};
     pub use crate::mutations::*;
     pub use crate::nodes::*;
+    pub use crate::portal::*;
     pub use crate::properties::*;
     pub use crate::runtime::{Runtime, RuntimeGuard};
     pub use crate::scopes::*;
+    pub use crate::suspense::*;
     pub use crate::tasks::*;
     pub use crate::virtual_dom::*;
 
@@ -77,12 +83,19 @@ pub(crate) mod innerlude {
 pub use crate::innerlude::{
     fc_to_builder, generation, schedule_update, schedule_update_any, use_hook, vdom_is_rendering,
     AnyValue, Attribute, AttributeValue, CapturedError, Component, ComponentFunction, DynamicNode,
-    Element, ElementId, Event, Fragment, HasAttributes, IntoDynNode, Mutation, Mutations,
-    NoOpMutations, Properties, RenderReturn, Runtime, ScopeId, ScopeState, Task, Template,
-    TemplateAttribute, TemplateNode, VComponent, VNode, VNodeInner, VPlaceholder, VText,
-    VirtualDom, WriteMutations,
+    Element, ElementId, ErrorSource, Event, FmtSegment, Fragment, HasAttributes, HotLiteral,
+    IntoDynNode, LiteralUpdate, Mutation, Mutations, NoOpMutations, Portal, PortalOutlet,
+    Properties, RenderReturn, Runtime, ScopeId, ScopeState, SuspenseBoundary, SuspenseContext,
+    Task, Template, TemplateAttribute, TemplateNode, VComponent, VNode, VNodeInner, VPlaceholder,
+    VText, VirtualDom, WriteMutations,
 };
 
+/// Functions the rsx! macro calls to read literals hot reloading changed. Not meant to be called directly.
+#[doc(hidden)]
+pub mod internal {
+    pub use crate::hot_literals::{hot_fmt, hot_literal, FromHotLiteral};
+}
+
 /// The purpose of this module is to alleviate imports of many common types
 ///
 /// This includes types like [`Element`], and [`Component`].
@@ -93,10 +106,11 @@ pub mod prelude {
         provide_root_context, remove_future, schedule_update, schedule_update_any, spawn,
         spawn_forever, spawn_isomorphic, suspend, try_consume_context, use_after_render,
         use_before_render, use_drop, use_error_boundary, use_hook, use_hook_with_cleanup,
-        wait_for_next_render, AnyValue, Attribute, Component, ComponentFunction, Element,
-        ErrorBoundary, Event, EventHandler, Fragment, HasAttributes, IntoAttributeValue,
-        IntoDynNode, OptionStringFromMarker, Properties, Runtime, RuntimeGuard, ScopeId,
-        ScopeState, SuperFrom, SuperInto, Task, Template, TemplateAttribute, TemplateNode, Throw,
-        VNode, VNodeInner, VirtualDom,
+        use_suspense_boundary, wait_for_next_render, AnyValue, Attribute, Component,
+        ComponentFunction, Element, ErrorBoundary, Event, EventHandler, Fragment, HasAttributes,
+        IntoAttributeValue, IntoDynNode, OptionStringFromMarker, Portal, PortalOutlet, Properties,
+        Runtime, RuntimeGuard, ScopeId, ScopeState, SuperFrom, SuperInto, SuspenseBoundary,
+        SuspenseContext, Task, Template, TemplateAttribute, TemplateNode, Throw, VNode, VNodeInner,
+        VirtualDom,
     };
 }
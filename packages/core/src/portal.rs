@@ -0,0 +1,259 @@
+use crate::innerlude::*;
+use rustc_hash::FxHashMap;
+
+/// The portals and outlets of a [`VirtualDom`]
+#[derive(Default)]
+pub(crate) struct Portals {
+    /// Portals that rendered since they were last placed, and the outlet they go to
+    pending: Vec<(ScopeId, Option<String>)>,
+
+    /// The scopes of the mounted [`PortalOutlet`]s by name
+    outlets: FxHashMap<String, ScopeId>,
+
+    /// Where each portal was moved last: the element that wraps its children, and the element it was appended to
+    placed: FxHashMap<ScopeId, (ElementId, ElementId)>,
+}
+
+impl Portals {
+    /// Drop everything a removed scope left behind, so a scope that reuses its id starts fresh
+    pub(crate) fn remove_scope(&mut self, id: ScopeId) {
+        self.pending.retain(|(scope, _)| *scope != id);
+        self.outlets.retain(|_, scope| *scope != id);
+        self.placed.remove(&id);
+    }
+}
+
+impl VirtualDom {
+    /// Move the children of every portal that rendered to its target, once everything was created and inserted
+    pub(crate) fn place_portals(&mut self, to: &mut impl WriteMutations) {
+        let pending = std::mem::take(&mut self.runtime.portals.borrow_mut().pending);
+
+        for (scope, target) in pending {
+            let (Some(wrapper), Some(target_id)) = (
+                self.portal_wrapper(scope),
+                self.portal_target(target.as_deref()),
+            ) else {
+                // The outlet isn't mounted yet, try again after the next render
+                self.runtime
+                    .portals
+                    .borrow_mut()
+                    .pending
+                    .push((scope, target));
+                continue;
+            };
+
+            let mut portals = self.runtime.portals.borrow_mut();
+            if portals.placed.get(&scope) == Some(&(wrapper, target_id)) {
+                continue;
+            }
+            to.push_root(wrapper);
+            to.append_children(target_id, 1);
+            portals.placed.insert(scope, (wrapper, target_id));
+        }
+    }
+
+    /// Remove the children of a portal that is removed along with an element it is in. They were moved out of that
+    /// element, so removing the element leaves them behind.
+    pub(crate) fn remove_placed_portal(&self, to: &mut impl WriteMutations, scope: ScopeId) {
+        if let Some((wrapper, _)) = self.runtime.portals.borrow().placed.get(&scope) {
+            to.remove_node(*wrapper);
+        }
+    }
+
+    /// The element the portal wraps its children in
+    fn portal_wrapper(&self, scope: ScopeId) -> Option<ElementId> {
+        let RenderReturn::Ready(node) = self.get_scope(scope)?.try_root_node()? else {
+            return None;
+        };
+        let mount = self.mounts.get(node.mount.get().0)?;
+        mount.root_ids.get(1).copied()
+    }
+
+    /// The element a portal with the target appends its children to
+    fn portal_target(&self, target: Option<&str>) -> Option<ElementId> {
+        let Some(name) = target else {
+            return Some(ElementId(0));
+        };
+        let outlet = *self.runtime.portals.borrow().outlets.get(name)?;
+        let RenderReturn::Ready(node) = self.get_scope(outlet)?.try_root_node()? else {
+            return None;
+        };
+        Some(node.find_first_element(self))
+    }
+}
+
+/// Render the children somewhere else than where the portal is, like on top of the rest of the app
+///
+/// The children of a portal belong to the component that renders it in every way but where they are placed. They
+/// can use its contexts, and their events bubble up to its elements. Only the nodes are moved, to the end of the
+/// root of the app, or into the [`PortalOutlet`] with the name in `target`.
+///
+/// ```rust, ignore
+/// rsx! {
+///     div { onclick: move |_| println!("clicked inside the modal"),
+///         Portal {
+///             div { class: "modal", "Stacked above everything else" }
+///         }
+///     }
+///     Portal { target: "tooltips",
+///         span { "A tooltip" }
+///     }
+///     PortalOutlet { name: "tooltips" }
+/// }
+/// ```
+///
+/// The children are wrapped in a `div` with the `data-dioxus-portal` attribute. Where the portal is, it leaves
+/// placeholders.
+///
+/// Portals move nodes within the document the app renders into. Each window of a desktop app runs its own
+/// [`VirtualDom`], so content for another window has to be rendered by the app of that window.
+#[allow(non_upper_case_globals, non_snake_case)]
+pub fn Portal(props: PortalProps) -> Element {
+    let scope = current_scope_id().expect("to be in a dioxus runtime");
+    Runtime::with(|rt| {
+        let mut portals = rt.portals.borrow_mut();
+        portals.pending.retain(|(pending, _)| *pending != scope);
+        portals.pending.push((scope, props.target));
+    });
+
+    // The placeholders stay where the portal is, so nodes inserted before or after it don't end up in the target
+    static TEMPLATE: Template = Template {
+        name: "packages/core/src/portal.rs:0:0:0",
+        roots: &[
+            TemplateNode::Dynamic { id: 0usize },
+            TemplateNode::Element {
+                tag: "div",
+                namespace: None,
+                attrs: &[TemplateAttribute::Static {
+                    name: "data-dioxus-portal",
+                    namespace: None,
+                    value: "true",
+                }],
+                children: &[TemplateNode::Dynamic { id: 1usize }],
+            },
+            TemplateNode::Dynamic { id: 2usize },
+        ],
+        node_paths: &[&[0u8], &[1u8, 0u8], &[2u8]],
+        attr_paths: &[],
+    };
+    Some(VNode::new(
+        None,
+        TEMPLATE,
+        Box::new([
+            None::<VNode>.into_dyn_node(),
+            props.children.into_dyn_node(),
+            None::<VNode>.into_dyn_node(),
+        ]),
+        Default::default(),
+    ))
+}
+
+/// The props of [`Portal`]
+#[derive(Clone, PartialEq)]
+pub struct PortalProps {
+    target: Option<String>,
+    children: Element,
+}
+
+pub struct PortalBuilder {
+    target: Option<String>,
+    children: Element,
+}
+
+impl PortalBuilder {
+    /// The name of the [`PortalOutlet`] to render the children into, instead of the end of the root of the app
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    pub fn children(mut self, children: Element) -> Self {
+        self.children = children;
+        self
+    }
+
+    pub fn build(self) -> PortalProps {
+        PortalProps {
+            target: self.target,
+            children: self.children,
+        }
+    }
+}
+
+impl Properties for PortalProps {
+    type Builder = PortalBuilder;
+    fn builder() -> Self::Builder {
+        PortalBuilder {
+            target: None,
+            children: None,
+        }
+    }
+    fn memoize(&mut self, _other: &Self) -> bool {
+        false
+    }
+}
+
+/// A place for the children of every [`Portal`] that targets its name
+///
+/// The outlet renders a `div` with the `data-dioxus-portal-outlet` attribute, which the children of the portals are
+/// appended to. It has to stay mounted as long as the portals that target it.
+#[allow(non_upper_case_globals, non_snake_case)]
+pub fn PortalOutlet(props: PortalOutletProps) -> Element {
+    let scope = current_scope_id().expect("to be in a dioxus runtime");
+    Runtime::with(|rt| {
+        let mut portals = rt.portals.borrow_mut();
+        portals.outlets.retain(|_, outlet| *outlet != scope);
+        portals.outlets.insert(props.name, scope);
+    });
+
+    static TEMPLATE: Template = Template {
+        name: "packages/core/src/portal.rs:1:0:0",
+        roots: &[TemplateNode::Element {
+            tag: "div",
+            namespace: None,
+            attrs: &[TemplateAttribute::Static {
+                name: "data-dioxus-portal-outlet",
+                namespace: None,
+                value: "true",
+            }],
+            children: &[],
+        }],
+        node_paths: &[],
+        attr_paths: &[],
+    };
+    Some(VNode::new(None, TEMPLATE, Box::new([]), Default::default()))
+}
+
+/// The props of [`PortalOutlet`]
+#[derive(Clone, PartialEq)]
+pub struct PortalOutletProps {
+    name: String,
+}
+
+pub struct PortalOutletBuilder {
+    name: String,
+}
+
+impl PortalOutletBuilder {
+    /// The name portals target the outlet with
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn build(self) -> PortalOutletProps {
+        PortalOutletProps { name: self.name }
+    }
+}
+
+impl Properties for PortalOutletProps {
+    type Builder = PortalOutletBuilder;
+    fn builder() -> Self::Builder {
+        PortalOutletBuilder {
+            name: String::new(),
+        }
+    }
+    fn memoize(&mut self, other: &Self) -> bool {
+        self == other
+    }
+}
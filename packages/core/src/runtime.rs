@@ -1,5 +1,5 @@
 use crate::{
-    innerlude::{LocalTask, SchedulerMsg},
+    innerlude::{LocalTask, Portals, SchedulerMsg},
     render_signal::RenderSignal,
     scope_context::Scope,
     scopes::ScopeId,
@@ -30,8 +30,17 @@ pub struct Runtime {
     // Currently suspended tasks
     pub(crate) suspended_tasks: Cell<usize>,
 
+    // Suspense boundaries that stopped showing their fallback, for servers that stream them in once they resolved
+    pub(crate) resolved_boundaries: RefCell<Vec<ScopeId>>,
+
+    // Portals that wait to be moved to their target, and the outlets they can target
+    pub(crate) portals: RefCell<Portals>,
+
     pub(crate) rendering: Cell<bool>,
 
+    // How many components are rendering right now, nested in each other
+    pub(crate) render_depth: Cell<usize>,
+
     pub(crate) sender: futures_channel::mpsc::UnboundedSender<SchedulerMsg>,
 
     // Synchronous tasks need to be run after the next render. The virtual dom stores a list of those tasks to send a signal to them when the next render is done.
@@ -44,11 +53,14 @@ impl Runtime {
             sender,
             render_signal: RenderSignal::default(),
             rendering: Cell::new(true),
+            render_depth: Default::default(),
             scope_states: Default::default(),
             scope_stack: Default::default(),
             current_task: Default::default(),
             tasks: Default::default(),
             suspended_tasks: Default::default(),
+            resolved_boundaries: Default::default(),
+            portals: Default::default(),
         })
     }
 
@@ -88,11 +100,30 @@ impl Runtime {
                     // Drop all contexts
                     scope.shared_contexts.take();
                 });
+
+                // A suspended component that is dropped no longer holds its boundary on the fallback
+                if let Some(boundary) = scope.suspended_in.take() {
+                    if boundary.resolve(id) {
+                        self.resolved_boundary(boundary.id());
+                        let _ = self
+                            .sender
+                            .unbounded_send(SchedulerMsg::Immediate(boundary.id()));
+                    }
+                }
             }
         }
+        self.portals.borrow_mut().remove_scope(id);
         self.scope_states.borrow_mut()[id.0].take();
     }
 
+    /// Remember that the suspense boundary resolved
+    pub(crate) fn resolved_boundary(&self, id: ScopeId) {
+        let mut resolved = self.resolved_boundaries.borrow_mut();
+        if !resolved.contains(&id) {
+            resolved.push(id);
+        }
+    }
+
     /// Get the current scope id
     pub(crate) fn current_scope_id(&self) -> Option<ScopeId> {
         self.scope_stack.borrow().last().copied()
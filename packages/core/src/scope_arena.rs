@@ -1,7 +1,7 @@
 use crate::innerlude::ScopeOrder;
 use crate::{
     any_props::{AnyProps, BoxedAnyProps},
-    innerlude::ScopeState,
+    innerlude::{ScopeState, SuspenseContext},
     nodes::RenderReturn,
     scope_context::Scope,
     scopes::ScopeId,
@@ -37,6 +37,9 @@ impl VirtualDom {
         );
 
         self.runtime.scope_stack.borrow_mut().push(scope_id);
+        self.runtime
+            .render_depth
+            .set(self.runtime.render_depth.get() + 1);
         let scope = &self.scopes[scope_id.0];
         let new_nodes = {
             let context = scope.state();
@@ -69,18 +72,42 @@ impl VirtualDom {
         self.dirty_scopes
             .remove(&ScopeOrder::new(context.height, scope_id));
 
-        if let Some(task) = context.last_suspendable_task.take() {
-            if matches!(new_nodes, RenderReturn::Aborted(_)) {
-                tracing::trace!("Suspending {:?} on {:?}", scope_id, task);
-                self.runtime.tasks.borrow().get(task.0).unwrap().suspend();
-                self.runtime
-                    .suspended_tasks
-                    .set(self.runtime.suspended_tasks.get() + 1);
+        // The suspense boundary that has to switch between its fallback and its children
+        let mut boundary_changed = None;
+        let suspended_on = context
+            .last_suspendable_task
+            .take()
+            .filter(|_| matches!(new_nodes, RenderReturn::Aborted(_)));
+        if let Some(task) = suspended_on {
+            tracing::trace!("Suspending {:?} on {:?}", scope_id, task);
+            self.runtime.tasks.borrow().get(task.0).unwrap().suspend();
+            self.runtime
+                .suspended_tasks
+                .set(self.runtime.suspended_tasks.get() + 1);
+
+            if let Some(boundary) = context.consume_context::<SuspenseContext>() {
+                if boundary.suspend(scope_id) {
+                    boundary_changed = Some(boundary.id());
+                }
+                *context.suspended_in.borrow_mut() = Some(boundary);
+            }
+        } else if let Some(boundary) = context.suspended_in.take() {
+            if boundary.resolve(scope_id) {
+                self.runtime.resolved_boundary(boundary.id());
+                boundary_changed = Some(boundary.id());
             }
         }
 
+        self.runtime
+            .render_depth
+            .set(self.runtime.render_depth.get() - 1);
         self.runtime.scope_stack.borrow_mut().pop();
 
+        drop(context);
+        if let Some(boundary) = boundary_changed {
+            self.mark_dirty(boundary);
+        }
+
         new_nodes
     }
 }
@@ -1,4 +1,7 @@
-use crate::{innerlude::SchedulerMsg, Element, Runtime, ScopeId, Task};
+use crate::{
+    innerlude::{SchedulerMsg, SuspenseContext},
+    Element, Runtime, ScopeId, Task,
+};
 use rustc_hash::FxHashSet;
 use std::{
     any::Any,
@@ -24,6 +27,8 @@ pub(crate) struct Scope {
     pub(crate) spawned_tasks: RefCell<FxHashSet<Task>>,
     /// The task that was last spawned that may suspend. We use this task to check what task to suspend in the event of an early None return from a component
     pub(crate) last_suspendable_task: Cell<Option<Task>>,
+    /// The suspense boundary this scope is suspended in, until it renders again
+    pub(crate) suspended_in: RefCell<Option<SuspenseContext>>,
     pub(crate) before_render: RefCell<Vec<Box<dyn FnMut()>>>,
     pub(crate) after_render: RefCell<Vec<Box<dyn FnMut()>>>,
 }
@@ -44,6 +49,7 @@ impl Scope {
             shared_contexts: RefCell::new(vec![]),
             spawned_tasks: RefCell::new(FxHashSet::default()),
             last_suspendable_task: Cell::new(None),
+            suspended_in: RefCell::new(None),
             hooks: RefCell::new(vec![]),
             hook_index: Cell::new(0),
             before_render: RefCell::new(vec![]),
@@ -0,0 +1,174 @@
+use crate::innerlude::*;
+use std::{cell::RefCell, rc::Rc};
+
+/// The suspense boundary a component is rendered in, provided as context by [`SuspenseBoundary`]
+///
+/// Components suspend by returning [`suspend`] with the task they wait for. The nearest boundary above them keeps
+/// them mounted and shows its fallback until every component that suspended in it rendered again.
+#[derive(Debug, Clone)]
+pub struct SuspenseContext {
+    inner: Rc<SuspenseContextInner>,
+}
+
+#[derive(Debug)]
+struct SuspenseContextInner {
+    id: ScopeId,
+    suspended: RefCell<Vec<ScopeId>>,
+}
+
+impl SuspenseContext {
+    pub(crate) fn new(id: ScopeId) -> Self {
+        Self {
+            inner: Rc::new(SuspenseContextInner {
+                id,
+                suspended: Default::default(),
+            }),
+        }
+    }
+
+    /// The scope of the boundary
+    pub fn id(&self) -> ScopeId {
+        self.inner.id
+    }
+
+    /// Check if any component in the boundary is suspended, and the fallback is shown
+    pub fn suspended(&self) -> bool {
+        !self.inner.suspended.borrow().is_empty()
+    }
+
+    /// The components in the boundary that are suspended
+    pub fn suspended_scopes(&self) -> Vec<ScopeId> {
+        self.inner.suspended.borrow().clone()
+    }
+
+    /// Add a suspended component, and return whether the boundary has to show its fallback now
+    pub(crate) fn suspend(&self, scope: ScopeId) -> bool {
+        let mut suspended = self.inner.suspended.borrow_mut();
+        if suspended.contains(&scope) {
+            return false;
+        }
+        suspended.push(scope);
+        suspended.len() == 1
+    }
+
+    /// Remove a component that rendered again or was dropped, and return whether the boundary can show its children
+    /// now
+    pub(crate) fn resolve(&self, scope: ScopeId) -> bool {
+        let mut suspended = self.inner.suspended.borrow_mut();
+        let Some(index) = suspended.iter().position(|id| *id == scope) else {
+            return false;
+        };
+        suspended.remove(index);
+        suspended.is_empty()
+    }
+}
+
+/// Get the suspense boundary the current component is rendered in, if there is one
+pub fn use_suspense_boundary() -> Option<SuspenseContext> {
+    use_hook(try_consume_context::<SuspenseContext>)
+}
+
+/// Show a fallback while components in the children are suspended
+///
+/// The children stay mounted behind the fallback so the tasks they suspended on keep running. Once every suspended
+/// component rendered again the fallback is removed, and the children are shown without remounting any of them.
+///
+/// ```rust, ignore
+/// rsx! {
+///     SuspenseBoundary {
+///         fallback: rsx! { "Loading..." },
+///         Profile {}
+///         Sidebar {}
+///     }
+/// }
+/// ```
+///
+/// The children are wrapped in a `div` with the `data-dioxus-suspense` attribute, which is `hidden` while they are
+/// suspended. [`VirtualDom::take_resolved_suspense_boundaries`] returns the boundaries that resolved since it was last
+/// called, for renderers that want to send them separately.
+#[allow(non_upper_case_globals, non_snake_case)]
+pub fn SuspenseBoundary(props: SuspenseBoundaryProps) -> Element {
+    let boundary = use_hook(|| {
+        let id = current_scope_id().expect("to be in a dioxus runtime");
+        provide_context(SuspenseContext::new(id))
+    });
+
+    // The fallback and the children are separate roots, so swapping the fallback out leaves the children in place
+    static TEMPLATE: Template = Template {
+        name: "packages/core/src/suspense.rs:0:0:0",
+        roots: &[
+            TemplateNode::Dynamic { id: 0usize },
+            TemplateNode::Element {
+                tag: "div",
+                namespace: None,
+                attrs: &[
+                    TemplateAttribute::Static {
+                        name: "data-dioxus-suspense",
+                        namespace: None,
+                        value: "true",
+                    },
+                    TemplateAttribute::Dynamic { id: 0usize },
+                ],
+                children: &[TemplateNode::Dynamic { id: 1usize }],
+            },
+        ],
+        node_paths: &[&[0u8], &[1u8, 0u8]],
+        attr_paths: &[&[1u8]],
+    };
+    let suspended = boundary.suspended();
+    let fallback = match suspended {
+        true => props.fallback,
+        false => None,
+    };
+    Some(VNode::new(
+        None,
+        TEMPLATE,
+        Box::new([fallback.into_dyn_node(), props.children.into_dyn_node()]),
+        Box::new([Box::new([Attribute::new("hidden", suspended, None, false)])]),
+    ))
+}
+
+/// The props of [`SuspenseBoundary`]
+#[derive(Clone, PartialEq)]
+pub struct SuspenseBoundaryProps {
+    fallback: Element,
+    children: Element,
+}
+
+pub struct SuspenseBoundaryBuilder {
+    fallback: Element,
+    children: Element,
+}
+
+impl SuspenseBoundaryBuilder {
+    /// What to show while components in the children are suspended
+    pub fn fallback(mut self, fallback: Element) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    pub fn children(mut self, children: Element) -> Self {
+        self.children = children;
+        self
+    }
+
+    pub fn build(self) -> SuspenseBoundaryProps {
+        SuspenseBoundaryProps {
+            fallback: self.fallback,
+            children: self.children,
+        }
+    }
+}
+
+impl Properties for SuspenseBoundaryProps {
+    type Builder = SuspenseBoundaryBuilder;
+    fn builder() -> Self::Builder {
+        SuspenseBoundaryBuilder {
+            fallback: None,
+            children: None,
+        }
+    }
+    fn memoize(&mut self, _other: &Self) -> bool {
+        false
+    }
+}
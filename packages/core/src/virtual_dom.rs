@@ -4,8 +4,9 @@
 
 use crate::Task;
 use crate::{
-    any_props::AnyProps,
+    any_props::{AnyProps, BoxedAnyProps},
     arena::ElementId,
+    hot_literals::{set_literals, template_location},
     innerlude::{
         DirtyTasks, ElementRef, ErrorBoundary, NoOpMutations, SchedulerMsg, ScopeOrder, ScopeState,
         VNodeMount, VProps, WriteMutations,
@@ -14,7 +15,7 @@ use crate::{
     nodes::{Template, TemplateId},
     runtime::{Runtime, RuntimeGuard},
     scopes::ScopeId,
-    AttributeValue, ComponentFunction, Element, Event, Mutations, VNode,
+    AttributeValue, ComponentFunction, Element, Event, LiteralUpdate, Mutations, VNode,
 };
 use futures_util::StreamExt;
 use rustc_hash::FxHashMap;
@@ -306,6 +307,10 @@ impl VirtualDom {
     /// ```
     #[instrument(skip(root), level = "trace", name = "VirtualDom::new")]
     pub(crate) fn new_with_component(root: impl AnyProps + 'static) -> Self {
+        Self::new_with_boxed_component(Box::new(root))
+    }
+
+    fn new_with_boxed_component(root: BoxedAnyProps) -> Self {
         let (tx, rx) = futures_channel::mpsc::unbounded();
 
         let mut dom = Self {
@@ -320,11 +325,11 @@ impl VirtualDom {
             mounts: Default::default(),
         };
 
-        let root = dom.new_scope(Box::new(root), "app");
+        let root = dom.new_scope(root, "app");
 
         // Unlike react, we provide a default error boundary that just renders the error as a string
         root.state()
-            .provide_context(Rc::new(ErrorBoundary::new_in_scope(ScopeId::ROOT)));
+            .provide_context(ErrorBoundary::new_in_scope(ScopeId::ROOT));
 
         // the root element is always given element ID 0 since it's the container for the entire tree
         dom.elements.insert(None);
@@ -533,22 +538,35 @@ impl VirtualDom {
     #[instrument(skip(self), level = "trace", name = "VirtualDom::replace_template")]
     pub fn replace_template(&mut self, template: Template) {
         self.register_template_first_byte_index(template);
+        self.mark_template_dirty(template_location(template.name));
+    }
 
+    /// Replace the literals of a template that hot reloading changed, like the text of a formatted string or a number
+    /// passed to a component
+    ///
+    /// Every component that renders the template re-renders with the new values.
+    #[instrument(skip(self), level = "trace", name = "VirtualDom::replace_literals")]
+    pub fn replace_literals(&mut self, update: LiteralUpdate) {
+        let location = update.template.clone();
+        set_literals(update);
+        self.mark_template_dirty(&location);
+    }
+
+    /// Mark every scope that renders a template at the location dirty
+    fn mark_template_dirty(&mut self, location: &str) {
         // iterating a slab is very inefficient, but this is a rare operation that will only happen during development so it's fine
         let mut dirty = Vec::new();
         for (id, scope) in self.scopes.iter() {
             // Recurse into the dynamic nodes of the existing mounted node to see if the template is alive in the tree
-            fn check_node_for_templates(node: &VNode, template: Template) -> bool {
-                let this_template_name = node.template.get().name.rsplit_once(':').unwrap().0;
-
-                if this_template_name == template.name.rsplit_once(':').unwrap().0 {
+            fn check_node_for_templates(node: &VNode, location: &str) -> bool {
+                if template_location(node.template.get().name) == location {
                     return true;
                 }
 
                 for dynamic in node.dynamic_nodes.iter() {
                     if let crate::DynamicNode::Fragment(nodes) = dynamic {
                         for node in nodes {
-                            if check_node_for_templates(node, template) {
+                            if check_node_for_templates(node, location) {
                                 return true;
                             }
                         }
@@ -559,7 +577,7 @@ impl VirtualDom {
             }
 
             if let Some(RenderReturn::Ready(sync)) = scope.try_root_node() {
-                if check_node_for_templates(sync, template) {
+                if check_node_for_templates(sync, location) {
                     dirty.push(ScopeId(id));
                 }
             }
@@ -615,6 +633,52 @@ impl VirtualDom {
         let m = self.create_scope(to, ScopeId::ROOT, new_nodes, None);
 
         to.append_children(ElementId(0), m);
+
+        self.place_portals(to);
+    }
+
+    /// Start the app over with the same root component and props, throwing away the state of every component
+    ///
+    /// This is for changes hot reloading can't patch into the running templates, but that don't need a recompile. The
+    /// contexts of the root scope and the templates replaced by hot reloading carry over, everything else is dropped.
+    ///
+    /// The renderer must throw away everything it rendered, and call [`VirtualDom::rebuild`] again.
+    ///
+    /// ```rust, ignore
+    /// dom.restart();
+    /// renderer.clear();
+    /// dom.rebuild(&mut renderer);
+    /// ```
+    #[instrument(skip(self), level = "trace", name = "VirtualDom::restart")]
+    pub fn restart(&mut self) {
+        let mut fresh = Self::new_with_boxed_component(self.base_scope().props.duplicate());
+
+        // Carry over the root contexts, except the ones the new root provides itself like its error boundary
+        {
+            let old_root = self.runtime.get_state(ScopeId::ROOT).unwrap();
+            let new_root = fresh.runtime.get_state(ScopeId::ROOT).unwrap();
+            let contexts = std::mem::take(&mut *old_root.shared_contexts.borrow_mut());
+            let mut new_contexts = new_root.shared_contexts.borrow_mut();
+            for context in contexts {
+                let provided = new_contexts
+                    .iter()
+                    .any(|new| (**new).type_id() == (*context).type_id());
+                if !provided {
+                    new_contexts.push(context);
+                }
+            }
+        }
+
+        // The renderer starts over too, so it needs every template again
+        fresh.templates = self.templates.clone();
+        fresh.queued_templates = fresh
+            .templates
+            .values()
+            .flat_map(|templates| templates.values().copied())
+            .filter(|template| !template.is_completely_dynamic())
+            .collect();
+
+        *self = fresh;
     }
 
     /// Render whatever the VirtualDom has ready as fast as possible without requiring an executor to progress
@@ -646,6 +710,8 @@ impl VirtualDom {
             }
         }
 
+        self.place_portals(to);
+
         self.runtime.render_signal.send();
     }
 
@@ -656,6 +722,33 @@ impl VirtualDom {
         mutations
     }
 
+    /// Take the [`SuspenseBoundary`](crate::SuspenseBoundary)s that stopped showing their fallback since the last call
+    ///
+    /// A server that streams its html can render the page with the fallbacks first. Once the boundaries resolve, it can
+    /// render each of these scopes again and stream it in to replace the fallback.
+    ///
+    /// ```rust, ignore
+    /// dom.rebuild_in_place();
+    /// // Components that suspended while building marked their boundaries dirty
+    /// dom.render_immediate(&mut NoOpMutations);
+    /// send_shell(renderer.render(&dom));
+    /// loop {
+    ///     dom.wait_for_work().await;
+    ///     dom.render_immediate(&mut NoOpMutations);
+    ///     for scope in dom.take_resolved_suspense_boundaries() {
+    ///         let mut html = String::new();
+    ///         renderer.render_scope(&mut html, &dom, scope)?;
+    ///         send_boundary(scope, html);
+    ///     }
+    /// }
+    /// ```
+    pub fn take_resolved_suspense_boundaries(&mut self) -> Vec<ScopeId> {
+        let mut resolved = std::mem::take(&mut *self.runtime.resolved_boundaries.borrow_mut());
+        // Boundaries that were removed since they resolved have nothing left to stream in
+        resolved.retain(|id| self.runtime.get_state(*id).is_some());
+        resolved
+    }
+
     /// Render the virtual dom, waiting for all suspense to be finished
     ///
     /// The mutations will be thrown out, so it's best to use this method for things like SSR that have async content
@@ -1,6 +1,12 @@
 #![allow(non_snake_case)]
 
 use dioxus::prelude::*;
+use dioxus_core::{CapturedError, ElementId, ErrorSource};
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
 
 #[test]
 fn catches_panic() {
@@ -30,3 +36,94 @@ fn ThrowChild() -> Element {
 
     rsx! { div {} }
 }
+
+static FAIL: AtomicBool = AtomicBool::new(true);
+static MOUNTS: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static CAPTURED: RefCell<Option<CapturedError>> = const { RefCell::new(None) };
+}
+
+#[test]
+fn reset_remounts_children() {
+    let mut dom = VirtualDom::new(reset_app);
+    dom.rebuild(&mut dioxus_core::NoOpMutations);
+    dom.render_immediate(&mut dioxus_core::NoOpMutations);
+    assert_eq!(dioxus_ssr::render(&dom), "<p>Render</p>");
+
+    // The fallback stays when the boundary renders again
+    dom.mark_dirty(ScopeId::ROOT);
+    dom.render_immediate(&mut dioxus_core::NoOpMutations);
+    assert_eq!(dioxus_ssr::render(&dom), "<p>Render</p>");
+
+    FAIL.store(false, Ordering::Relaxed);
+    let error = CAPTURED
+        .with(|captured| captured.borrow_mut().take())
+        .unwrap();
+    assert_eq!(error.source(), ErrorSource::Render);
+    assert_eq!(error.downcast::<&str>(), Some(&"failed to render"));
+    error.reset();
+    dom.render_immediate(&mut dioxus_core::NoOpMutations);
+    assert_eq!(dioxus_ssr::render(&dom), "rendered");
+    assert_eq!(MOUNTS.load(Ordering::Relaxed), 2);
+}
+
+fn reset_app() -> Element {
+    rsx! {
+        ErrorBoundary {
+            handle_error: |error: CapturedError| {
+                CAPTURED.with(|captured| *captured.borrow_mut() = Some(error.clone()));
+                rsx! { p { {format!("{:?}", error.source())} } }
+            },
+            FallibleChild {}
+        }
+    }
+}
+
+fn FallibleChild() -> Element {
+    use_hook(|| MOUNTS.fetch_add(1, Ordering::Relaxed));
+
+    if FAIL.load(Ordering::Relaxed) {
+        Err("failed to render").throw()?;
+    }
+
+    rsx! { "rendered" }
+}
+
+#[test]
+fn event_handler_errors_are_told_apart() {
+    set_event_converter(Box::new(dioxus::html::SerializedHtmlEventConverter));
+
+    let mut dom = VirtualDom::new(event_app);
+    dom.rebuild(&mut dioxus_core::NoOpMutations);
+    assert_eq!(dioxus_ssr::render(&dom), "<button>click</button>");
+
+    dom.handle_event(
+        "click",
+        Rc::new(PlatformEventData::new(Box::<SerializedMouseData>::default())),
+        ElementId(1),
+        true,
+    );
+    dom.render_immediate(&mut dioxus_core::NoOpMutations);
+    assert_eq!(dioxus_ssr::render(&dom), "<p>EventHandler</p>");
+}
+
+fn event_app() -> Element {
+    rsx! {
+        ErrorBoundary {
+            handle_error: |error: CapturedError| rsx! { p { {format!("{:?}", error.source())} } },
+            Clicker {}
+        }
+    }
+}
+
+fn Clicker() -> Element {
+    rsx! {
+        button {
+            onclick: move |_| {
+                _ = Err::<(), _>("failed to click").throw();
+            },
+            "click"
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! Hot reloading can change the literals of a template without replacing the template
+
+use dioxus::dioxus_core::{FmtSegment, HotLiteral, LiteralUpdate, NoOpMutations, TemplateNode};
+use dioxus::prelude::*;
+
+#[test]
+fn replace_literals() {
+    fn app() -> Element {
+        let count = 3;
+        let size = 2;
+        rsx! {
+            div { class: "size-{size}",
+                p { "Count: {count}" }
+                Child { step: 1, label: "one" }
+            }
+        }
+    }
+
+    #[component]
+    fn Child(step: i32, label: &'static str) -> Element {
+        rsx! { span { "{label} {step}" } }
+    }
+
+    let mut dom = VirtualDom::new(app);
+    let mutations = dom.rebuild_to_vec();
+    assert_eq!(
+        dioxus_ssr::render(&dom),
+        "<div class=\"size-2\"><p>Count: 3</p><span>one 1</span></div>"
+    );
+
+    let template = mutations
+        .templates
+        .iter()
+        .find(|template| matches!(template.roots, [TemplateNode::Element { tag: "div", .. }]))
+        .unwrap();
+    let location = template.name.rsplit_once(':').unwrap().0;
+
+    // The text node comes first, then the props of the component, then the attribute
+    dom.replace_literals(LiteralUpdate {
+        template: location.to_string(),
+        literals: vec![
+            HotLiteral::Fmt(vec![
+                FmtSegment::Literal("Total: ".to_string()),
+                FmtSegment::Formatted(0),
+            ]),
+            HotLiteral::Int(5),
+            HotLiteral::Str("five".to_string()),
+            HotLiteral::Fmt(vec![
+                FmtSegment::Literal("width-".to_string()),
+                FmtSegment::Formatted(0),
+            ]),
+        ],
+    });
+    dom.render_immediate(&mut NoOpMutations);
+
+    assert_eq!(
+        dioxus_ssr::render(&dom),
+        "<div class=\"width-2\"><p>Total: 3</p><span>five 5</span></div>"
+    );
+}
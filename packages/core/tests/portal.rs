@@ -0,0 +1,161 @@
+//! Portals render their children into another element, but keep them in the tree of the component that renders them
+
+#![allow(non_snake_case)]
+
+use dioxus::dioxus_core::Mutation::*;
+use dioxus::prelude::*;
+use dioxus_core::{ElementId, Mutation};
+use std::{
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+/// The element a portal wraps its children in, and where it was moved to
+fn placed(edits: &[Mutation]) -> Vec<(ElementId, ElementId)> {
+    edits
+        .windows(2)
+        .filter_map(|edits| match edits {
+            [PushRoot { id: wrapper }, AppendChildren { id: target, m: 1 }] => {
+                Some((*wrapper, *target))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn portal_moves_children_to_root() {
+    fn app() -> Element {
+        rsx! {
+            div {
+                Portal { p { "modal" } }
+            }
+        }
+    }
+
+    let mut dom = VirtualDom::new(app);
+    let edits = dom.rebuild_to_vec().santize();
+
+    // The portal is moved once everything was appended to the root
+    let [.., AppendChildren { id: ElementId(0), m: 1 }, PushRoot { .. }, AppendChildren { id: ElementId(0), m: 1 }] =
+        edits.edits.as_slice()
+    else {
+        panic!("the portal wasn't moved to the root: {:#?}", edits.edits);
+    };
+    assert_eq!(placed(&edits.edits).len(), 1);
+
+    // Rendering the portal again leaves its children where they are
+    dom.mark_dirty(ScopeId::ROOT);
+    let edits = dom.render_immediate_to_vec();
+    assert!(placed(&edits.edits).is_empty());
+}
+
+#[test]
+fn portal_moves_children_to_outlet() {
+    fn app() -> Element {
+        rsx! {
+            Portal { target: "tooltips", span { "tooltip" } }
+            PortalOutlet { name: "tooltips" }
+        }
+    }
+
+    let mut dom = VirtualDom::new(app);
+    let edits = dom.rebuild_to_vec().santize();
+
+    let placed = placed(&edits.edits);
+    assert_eq!(placed.len(), 1);
+    let (wrapper, outlet) = placed[0];
+    assert_ne!(outlet, ElementId(0));
+    assert_ne!(wrapper, outlet);
+
+    // Where the portal is rendered the html shows its children, since ssr doesn't move nodes
+    assert_eq!(
+        dioxus_ssr::render(&dom),
+        "<div data-dioxus-portal=\"true\"><span>tooltip</span></div><div data-dioxus-portal-outlet=\"true\"></div>"
+    );
+}
+
+static SHOW_PORTAL: AtomicBool = AtomicBool::new(true);
+
+#[test]
+fn portal_children_removed_with_parent() {
+    fn app() -> Element {
+        rsx! {
+            if SHOW_PORTAL.load(Ordering::Relaxed) {
+                div {
+                    Portal { p { "modal" } }
+                }
+            }
+        }
+    }
+
+    let mut dom = VirtualDom::new(app);
+    let edits = dom.rebuild_to_vec().santize();
+    let placed = placed(&edits.edits);
+    assert_eq!(placed.len(), 1);
+    let (wrapper, _) = placed[0];
+
+    // The div is removed without mutations for the nodes in it, but the portal moved its children out of the div
+    SHOW_PORTAL.store(false, Ordering::Relaxed);
+    dom.mark_dirty(ScopeId::ROOT);
+    let edits = dom.render_immediate_to_vec();
+    assert!(
+        edits.edits.contains(&Remove { id: wrapper }),
+        "the children of the portal were left behind: {:#?}",
+        edits.edits
+    );
+}
+
+static PARENT_CLICKS: Mutex<usize> = Mutex::new(0);
+static PORTAL_CLICKS: Mutex<usize> = Mutex::new(0);
+
+#[test]
+fn portal_children_keep_context_and_bubbling() {
+    set_event_converter(Box::new(dioxus::html::SerializedHtmlEventConverter));
+
+    fn app() -> Element {
+        use_context_provider(|| "provided by the parent");
+
+        rsx! {
+            div { onclick: move |_| *PARENT_CLICKS.lock().unwrap() += 1,
+                Portal { PortalChild {} }
+            }
+        }
+    }
+
+    fn PortalChild() -> Element {
+        assert_eq!(use_context::<&'static str>(), "provided by the parent");
+
+        rsx! {
+            button { onclick: move |_| *PORTAL_CLICKS.lock().unwrap() += 1, "click" }
+        }
+    }
+
+    let mut dom = VirtualDom::new(app);
+    let edits = dom.rebuild_to_vec().santize();
+
+    let listeners: Vec<ElementId> = edits
+        .edits
+        .iter()
+        .filter_map(|edit| match edit {
+            NewEventListener { name, id } if name == "click" => Some(*id),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(listeners.len(), 2);
+
+    // Clicking the button bubbles up to the div the portal is in, even though the button was moved out of it
+    for id in listeners {
+        dom.handle_event(
+            "click",
+            Rc::new(PlatformEventData::new(Box::<SerializedMouseData>::default())),
+            id,
+            true,
+        );
+    }
+    assert_eq!(*PORTAL_CLICKS.lock().unwrap(), 1);
+    assert_eq!(*PARENT_CLICKS.lock().unwrap(), 2);
+}
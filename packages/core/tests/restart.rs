@@ -0,0 +1,61 @@
+//! Restarting the VirtualDom starts every component over, but keeps the root props and contexts
+
+use dioxus::dioxus_core::{ElementId, Mutation::*, NoOpMutations};
+use dioxus::prelude::*;
+use std::{cell::Cell, rc::Rc};
+
+#[test]
+fn restart_drops_component_state() {
+    fn app() -> Element {
+        let renders = use_hook(|| Rc::new(Cell::new(0)));
+        renders.set(renders.get() + 1);
+        rsx!("Rendered {renders.get()} times")
+    }
+
+    let mut dom = VirtualDom::new(app);
+    dom.rebuild(&mut NoOpMutations);
+
+    dom.mark_dirty(ScopeId::ROOT);
+    assert_eq!(
+        dom.render_immediate_to_vec().santize().edits,
+        [SetText { value: "Rendered 2 times".to_string(), id: ElementId(1) }]
+    );
+
+    dom.restart();
+    assert_eq!(
+        dom.rebuild_to_vec().santize().edits,
+        [
+            CreateTextNode { value: "Rendered 1 times".to_string(), id: ElementId(1) },
+            AppendChildren { m: 1, id: ElementId(0) },
+        ]
+    );
+}
+
+#[test]
+fn restart_keeps_root_props_and_contexts() {
+    #[derive(Clone, PartialEq)]
+    struct Theme(&'static str);
+
+    #[derive(Props, Clone, PartialEq)]
+    struct AppProps {
+        name: &'static str,
+    }
+
+    fn app(props: AppProps) -> Element {
+        let theme = consume_context::<Theme>();
+        rsx!("{props.name} in {theme.0}")
+    }
+
+    let mut dom = VirtualDom::new_with_props(app, AppProps { name: "dioxus" })
+        .with_root_context(Theme("dark"));
+    dom.rebuild(&mut NoOpMutations);
+
+    dom.restart();
+    assert_eq!(
+        dom.rebuild_to_vec().santize().edits,
+        [
+            CreateTextNode { value: "dioxus in dark".to_string(), id: ElementId(1) },
+            AppendChildren { m: 1, id: ElementId(0) },
+        ]
+    );
+}
@@ -1,5 +1,6 @@
 use dioxus::prelude::*;
 use std::future::poll_fn;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::task::Poll;
 
 #[test]
@@ -65,3 +66,76 @@ fn suspended_child() -> Element {
 
     rsx!("child")
 }
+
+static SIBLING_MOUNTS: AtomicUsize = AtomicUsize::new(0);
+
+#[test]
+fn suspense_boundary_shows_fallback_until_resolved() {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(async {
+            let mut dom = VirtualDom::new(boundary_app);
+            dom.rebuild(&mut dioxus_core::NoOpMutations);
+            // The suspended child marked the boundary dirty while it was built
+            dom.render_immediate(&mut dioxus_core::NoOpMutations);
+            assert_eq!(
+                dioxus_ssr::render(&dom),
+                "<div>Loading...<div data-dioxus-suspense=\"true\" hidden=true><p>sibling</p></div></div>"
+            );
+            assert!(dom.take_resolved_suspense_boundaries().is_empty());
+
+            dom.wait_for_suspense().await;
+            assert_eq!(
+                dioxus_ssr::render(&dom),
+                "<div><div data-dioxus-suspense=\"true\"><p>sibling</p>resolved</div></div>"
+            );
+            assert_eq!(dom.take_resolved_suspense_boundaries().len(), 1);
+
+            // Swapping the fallback out doesn't remount the children
+            assert_eq!(SIBLING_MOUNTS.load(Ordering::Relaxed), 1);
+        });
+}
+
+fn boundary_app() -> Element {
+    rsx!(
+        div {
+            SuspenseBoundary {
+                fallback: rsx! { "Loading..." },
+                mounted_sibling {}
+                suspended_leaf {}
+            }
+        }
+    )
+}
+
+fn mounted_sibling() -> Element {
+    use_hook(|| SIBLING_MOUNTS.fetch_add(1, Ordering::Relaxed));
+
+    rsx!(p { "sibling" })
+}
+
+fn suspended_leaf() -> Element {
+    let mut resolved = use_signal(|| false);
+
+    if !resolved() {
+        let task = spawn(async move {
+            let mut count = 0;
+            poll_fn(|cx| {
+                if count < 3 {
+                    count += 1;
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                } else {
+                    Poll::Ready(())
+                }
+            })
+            .await;
+
+            resolved.set(true);
+        });
+        suspend(task)?;
+    }
+
+    rsx!("resolved")
+}
@@ -0,0 +1,73 @@
+use dioxus::html::geometry::WheelDelta;
+use dioxus::prelude::*;
+use dioxus_core::prelude::consume_context;
+use dioxus_desktop::{Config, DesktopContext};
+
+#[path = "./utils.rs"]
+mod utils;
+
+pub fn main() {
+    // One interval is far longer than dispatching the burst takes, so all of it lands inside the same window
+    utils::check_app_exits_with_cfg(app, Config::new().with_event_rate(Some(1)));
+}
+
+static MOVES: GlobalSignal<usize> = Signal::global(|| 0);
+static WHEELS: GlobalSignal<Vec<f64>> = Signal::global(Vec::new);
+
+fn app() -> Element {
+    let desktop_context: DesktopContext = consume_context();
+
+    use_hook(|| {
+        spawn(async move {
+            // Wait for the edits to be applied before the events are sent
+            tokio::time::sleep(std::time::Duration::from_millis(10000)).await;
+
+            eval(
+                r#"
+                const moved = document.getElementById('move');
+                for (let i = 0; i < 20; i++) {
+                    moved.dispatchEvent(new MouseEvent("mousemove", { view: window, bubbles: true, clientX: i }));
+                }
+                const wheel = document.getElementById('wheel');
+                for (let i = 0; i < 3; i++) {
+                    wheel.dispatchEvent(new WheelEvent("wheel", { bubbles: true, deltaY: 10.0, deltaMode: 0x00 }));
+                }
+                document.getElementById('done').dispatchEvent(new MouseEvent("click", { view: window, bubbles: true }));
+                "#,
+            )
+            .await
+            .unwrap();
+        });
+    });
+
+    rsx! {
+        div {
+            id: "move",
+            onmousemove: move |event| {
+                // Only the latest move of the burst is sent
+                assert_eq!(event.data.client_coordinates().x, 19.0);
+                *MOVES.write() += 1;
+            }
+        }
+        div {
+            id: "wheel",
+            onwheel: move |event| {
+                let WheelDelta::Pixels(delta) = event.data.delta() else {
+                    panic!("Expected delta to be in pixels")
+                };
+                WHEELS.write().push(delta.y);
+            }
+        }
+        button {
+            id: "done",
+            // Events that aren't coalesced send the pending ones first, so they keep their order
+            onclick: move |_| {
+                assert_eq!(MOVES(), 1);
+                // The deltas of the coalesced wheels are summed, so no scrolling is lost
+                assert_eq!(WHEELS(), vec![30.0]);
+                println!("events were coalesced");
+                desktop_context.close();
+            }
+        }
+    }
+}
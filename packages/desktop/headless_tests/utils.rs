@@ -4,8 +4,11 @@ use dioxus::prelude::*;
 use dioxus_core::Element;
 
 pub fn check_app_exits(app: fn() -> Element) {
+    check_app_exits_with_cfg(app, dioxus_desktop::Config::new());
+}
+
+pub fn check_app_exits_with_cfg(app: fn() -> Element, cfg: dioxus_desktop::Config) {
     use dioxus_desktop::tao::window::WindowBuilder;
-    use dioxus_desktop::Config;
     // This is a deadman's switch to ensure that the app exits
     let should_panic = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
     let should_panic_clone = should_panic.clone();
@@ -18,7 +21,7 @@ pub fn check_app_exits(app: fn() -> Element) {
     });
 
     LaunchBuilder::desktop()
-        .with_cfg(Config::new().with_window(WindowBuilder::new().with_visible(true)))
+        .with_cfg(cfg.with_window(WindowBuilder::new().with_visible(true)))
         .launch(app);
 
     // Stop deadman's switch
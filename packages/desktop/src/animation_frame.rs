@@ -0,0 +1,122 @@
+//! Driving continuous updates from the event loop, for canvas and game-style apps that redraw every frame
+
+use crate::{event_handlers::EventListeners, hooks::use_event_listener};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    time::{Duration, Instant},
+};
+use tao::event_loop::ControlFlow;
+
+/// The time between two animation frames, for 60 frames per second
+const FRAME_INTERVAL: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+/// The callbacks waiting for the next animation frame and the leases keeping the event loop polling
+pub(crate) struct AnimationFrames {
+    callbacks: RefCell<Vec<Box<dyn FnOnce(Duration)>>>,
+    pub(crate) listeners: EventListeners<Duration>,
+    leases: Rc<Cell<usize>>,
+    last_frame: Cell<Option<Instant>>,
+    start: Instant,
+}
+
+impl Default for AnimationFrames {
+    fn default() -> Self {
+        Self {
+            callbacks: Default::default(),
+            listeners: Default::default(),
+            leases: Default::default(),
+            last_frame: Cell::new(None),
+            start: Instant::now(),
+        }
+    }
+}
+
+impl AnimationFrames {
+    pub(crate) fn request(&self, callback: Box<dyn FnOnce(Duration)>) {
+        self.callbacks.borrow_mut().push(callback);
+    }
+
+    pub(crate) fn lease(&self) -> PollLease {
+        self.leases.set(self.leases.get() + 1);
+        PollLease {
+            leases: self.leases.clone(),
+        }
+    }
+
+    fn wants_frame(&self) -> bool {
+        !self.callbacks.borrow().is_empty() || !self.listeners.is_empty()
+    }
+
+    fn next_frame(&self) -> Instant {
+        match self.last_frame.get() {
+            Some(last) => last + FRAME_INTERVAL,
+            None => Instant::now(),
+        }
+    }
+
+    /// Run the callbacks and listeners if the next frame is due
+    pub(crate) fn run_if_due(&self) {
+        if !self.wants_frame() {
+            // Start over once someone asks for a frame again, instead of catching up
+            self.last_frame.set(None);
+            return;
+        }
+
+        let now = Instant::now();
+        if now < self.next_frame() {
+            return;
+        }
+        self.last_frame.set(Some(now));
+
+        // Callbacks that request the next frame are queued for that frame
+        let timestamp = now - self.start;
+        let callbacks = std::mem::take(&mut *self.callbacks.borrow_mut());
+        for callback in callbacks {
+            callback(timestamp);
+        }
+        self.listeners.call(&timestamp);
+    }
+
+    /// How the event loop has to wait to deliver the next frame in time
+    pub(crate) fn control_flow(&self) -> ControlFlow {
+        if self.leases.get() > 0 {
+            ControlFlow::Poll
+        } else if self.wants_frame() {
+            ControlFlow::WaitUntil(self.next_frame())
+        } else {
+            ControlFlow::Wait
+        }
+    }
+}
+
+/// Keeps the event loop polling continuously instead of waiting for events while it is alive
+///
+/// Created with [`DesktopService::keep_polling`](crate::DesktopService::keep_polling). The event loop goes back to
+/// waiting for events once every lease is dropped.
+#[must_use = "the event loop stops polling when the lease is dropped"]
+pub struct PollLease {
+    leases: Rc<Cell<usize>>,
+}
+
+impl Drop for PollLease {
+    fn drop(&mut self) {
+        self.leases.set(self.leases.get() - 1);
+    }
+}
+
+/// Run a callback on every animation frame while the component is mounted
+///
+/// Frames are delivered at up to 60 frames per second. The handler receives the time since the app started, so
+/// animations can advance by the time that actually passed.
+///
+/// ```rust, ignore
+/// let mut now = use_signal(Duration::default);
+/// use_animation_frame(move |time| now.set(time));
+///
+/// let angle = now().as_secs_f64() * 90.0;
+/// rsx! { div { style: "transform: rotate({angle}deg);", "Loading" } }
+/// ```
+pub fn use_animation_frame(handler: impl FnMut(Duration) + 'static) {
+    use_event_listener(|window| &window.shared.animation_frames.listeners, handler)
+}
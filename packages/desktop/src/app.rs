@@ -1,24 +1,48 @@
 use crate::{
-    config::{Config, WindowCloseBehaviour},
+    animation_frame::AnimationFrames,
+    app_handle::AppHandle,
+    back_button::BackButtonEvent,
+    broadcast::BroadcastRegistry,
+    compression::InitializeMessage,
+    config::{Config, WindowCloseBehaviour, WindowKind},
+    context_menu::ContextMenuRegistry,
+    desktop_context::set_window_enabled,
+    dock_menu::DockMenuRegistry,
+    download::DownloadEvent,
+    drag_region::ResizeRequest,
     element::DesktopElement,
-    event_handlers::WindowEventHandlers,
+    event_handlers::{EventListeners, WindowEventHandlers},
     file_upload::{DesktopFileDragEvent, DesktopFileUploadForm, FileDialogRequest},
-    ipc::{IpcMessage, UserWindowEvent},
+    find::FindResult,
+    ime::composition_script,
+    ipc::{IpcMessage, IpcMethod, UserWindowEvent},
+    ipc_handlers::{reply, InvokeMessage, IpcResult},
+    jump_list,
+    menubar::MenuItemRegistry,
+    navigation::{open_externally, UrlWindow},
+    notification::NotificationEvent,
+    open_file::{self, InstanceRole},
+    page_load::PageLoadEvent,
     query::QueryResult,
     shortcut::ShortcutRegistry,
+    splash::SplashScreen,
+    virtual_keyboard::VirtualKeyboardMessage,
     webview::WebviewInstance,
+    window_event::{DesktopWindowEvent, OcclusionMessage},
 };
+use dioxus_core::prelude::spawn_forever;
 use dioxus_core::ElementId;
 use dioxus_core::VirtualDom;
 use dioxus_html::{native_bind::NativeFileEngine, HasFileData, HtmlEvent, PlatformEventData};
 use std::{
     cell::{Cell, RefCell},
     collections::HashMap,
+    path::PathBuf,
     rc::Rc,
     sync::Arc,
 };
 use tao::{
-    event::Event,
+    event::{Event, StartCause, WindowEvent},
     event_loop::{ControlFlow, EventLoop, EventLoopBuilder, EventLoopProxy, EventLoopWindowTarget},
     window::WindowId,
 };
@@ -36,6 +60,37 @@ pub(crate) struct App {
     pub(crate) window_behavior: WindowCloseBehaviour,
     pub(crate) webviews: HashMap<WindowId, WebviewInstance>,
 
+    /// The window that renders the root component, once it has been created
+    pub(crate) main_window: Option<WindowId>,
+
+    /// Windows showing pages the navigation handler opened in a new window
+    pub(crate) url_windows: HashMap<WindowId, UrlWindow>,
+
+    /// The splash screen shown until the first webview is initialized
+    pub(crate) splash_screen: Option<SplashScreen>,
+
+    /// Files the app was asked to open before any component could listen for them
+    pub(crate) pending_open_files: Option<Vec<PathBuf>>,
+
+    /// Jump list tasks picked before any component could listen for them
+    pub(crate) pending_jump_list_tasks: Option<Vec<String>>,
+
+    /// Whether an instance of the app that was already running took over, so this one exits without opening windows
+    pub(crate) forwarded_to_running_instance: bool,
+
+    /// Whether the downloaded update should be installed once the event loop shuts down
+    #[cfg(feature = "updater")]
+    pub(crate) install_update_on_exit: bool,
+
+    /// Whether a hot reload finished, so the server is told the app is ready once its edits reached the webviews
+    #[cfg(all(
+        feature = "hot-reload",
+        debug_assertions,
+        not(target_os = "android"),
+        not(target_os = "ios")
+    ))]
+    pub(crate) hot_reload_ready_pending: bool,
+
     /// This single blob of state is shared between all the windows so they have access to the runtime state
     ///
     /// This includes stuff like the event handlers, shortcuts, etc as well as ways to modify *other* windows
@@ -49,18 +104,84 @@ pub(crate) struct SharedContext {
     pub(crate) event_handlers: WindowEventHandlers,
     pub(crate) pending_webviews: RefCell<Vec<WebviewInstance>>,
     pub(crate) shortcut_manager: ShortcutRegistry,
+    pub(crate) context_menus: ContextMenuRegistry,
+    pub(crate) dock_menu: DockMenuRegistry,
+    pub(crate) open_file_listeners: EventListeners<PathBuf>,
+    pub(crate) jump_list_listeners: EventListeners<String>,
+    pub(crate) exit_listeners: EventListeners<i32>,
+    pub(crate) animation_frames: AnimationFrames,
+    pub(crate) back_button_listeners: EventListeners<BackButtonEvent>,
+    pub(crate) notification_listeners: EventListeners<NotificationEvent>,
+    pub(crate) broadcasts: BroadcastRegistry,
+    pub(crate) menu_items: MenuItemRegistry,
     pub(crate) proxy: EventLoopProxy<UserWindowEvent>,
     pub(crate) target: EventLoopWindowTarget<UserWindowEvent>,
+    #[cfg(feature = "updater")]
+    pub(crate) updater: Option<crate::updater::Updater>,
+    #[cfg(feature = "updater")]
+    pub(crate) updater_listeners: EventListeners<crate::updater::UpdaterEvent>,
 }
 
 impl App {
     pub fn new(cfg: Config, virtual_dom: VirtualDom) -> (EventLoop<UserWindowEvent>, Self) {
-        let event_loop = EventLoopBuilder::<UserWindowEvent>::with_user_event().build();
+        let mut event_loop = EventLoopBuilder::<UserWindowEvent>::with_user_event();
+        cfg.wayland.apply_to_event_loop(&mut event_loop);
+        let event_loop = event_loop.build();
+
+        let proxy = event_loop.create_proxy();
+        let app = Self::with_event_loop(cfg, virtual_dom, &event_loop, proxy);
+
+        (event_loop, app)
+    }
+
+    /// Create the app on an event loop that may be owned by someone else
+    pub fn with_event_loop(
+        mut cfg: Config,
+        virtual_dom: VirtualDom,
+        target: &EventLoopWindowTarget<UserWindowEvent>,
+        proxy: EventLoopProxy<UserWindowEvent>,
+    ) -> Self {
+        if let Some(crash) = cfg.crash.take() {
+            crash.install();
+        }
+
+        // Let the instance that is already running open our files and run our jump list task instead, and exit once the
+        // event loop starts
+        let instance = cfg.single_instance.as_deref().map(|id| {
+            let files = open_file::files_from_args(std::env::args().skip(1));
+            let task = jump_list::task_from_args(std::env::args().skip(1));
+            open_file::claim_instance(id, &files, task.as_deref())
+        });
+
+        #[cfg(feature = "updater")]
+        let updater = cfg
+            .updater
+            .clone()
+            .map(|updater| crate::updater::Updater::new(updater, proxy.clone()));
 
         let app = Self {
             window_behavior: cfg.last_window_close_behaviour,
             is_visible_before_start: true,
             webviews: HashMap::new(),
+            main_window: None,
+            url_windows: HashMap::new(),
+            splash_screen: None,
+            pending_open_files: Some(open_file::files_from_args(std::env::args().skip(1))),
+            pending_jump_list_tasks: Some(
+                jump_list::task_from_args(std::env::args().skip(1))
+                    .into_iter()
+                    .collect(),
+            ),
+            forwarded_to_running_instance: matches!(instance, Some(InstanceRole::Forwarded)),
+            #[cfg(feature = "updater")]
+            install_update_on_exit: false,
+            #[cfg(all(
+                feature = "hot-reload",
+                debug_assertions,
+                not(target_os = "android"),
+                not(target_os = "ios")
+            ))]
+            hot_reload_ready_pending: false,
             control_flow: ControlFlow::Wait,
             unmounted_dom: Cell::new(Some(virtual_dom)),
             cfg: Cell::new(Some(cfg)),
@@ -68,11 +189,29 @@ impl App {
                 event_handlers: WindowEventHandlers::default(),
                 pending_webviews: Default::default(),
                 shortcut_manager: ShortcutRegistry::new(),
-                proxy: event_loop.create_proxy(),
-                target: event_loop.clone(),
+                context_menus: Default::default(),
+                dock_menu: Default::default(),
+                open_file_listeners: Default::default(),
+                jump_list_listeners: Default::default(),
+                exit_listeners: Default::default(),
+                animation_frames: Default::default(),
+                back_button_listeners: Default::default(),
+                notification_listeners: Default::default(),
+                broadcasts: Default::default(),
+                menu_items: Default::default(),
+                proxy,
+                target: target.clone(),
+                #[cfg(feature = "updater")]
+                updater,
+                #[cfg(feature = "updater")]
+                updater_listeners: Default::default(),
             }),
         };
 
+        if let Some(InstanceRole::Primary(Some(listener))) = instance {
+            open_file::listen_for_other_instances(listener, app.shared.proxy.clone());
+        }
+
         // Set the event converter
         dioxus_html::set_event_converter(Box::new(crate::events::SerializedHtmlEventConverter));
 
@@ -89,7 +228,7 @@ impl App {
         ))]
         app.connect_hotreload();
 
-        (event_loop, app)
+        app
     }
 
     pub fn tick(&mut self, window_event: &Event<'_, UserWindowEvent>) {
@@ -99,11 +238,109 @@ impl App {
             .apply_event(window_event, &self.shared.target);
     }
 
+    /// Handle an event of the event loop and return how the event loop should continue
+    pub fn handle_event(&mut self, window_event: Event<'_, UserWindowEvent>) -> ControlFlow {
+        if self.forwarded_to_running_instance {
+            return ControlFlow::ExitWithCode(0);
+        }
+
+        // Set the control flow and check if any events need to be handled in the app itself
+        self.tick(&window_event);
+
+        match window_event {
+            Event::NewEvents(StartCause::Init) => self.handle_start_cause_init(),
+            Event::LoopDestroyed => self.handle_loop_destroyed(),
+            Event::MainEventsCleared => self.handle_main_events_cleared(),
+            Event::Reopen { .. } => self.handle_show_main_window(),
+            Event::Opened { urls } => self.handle_opened_files(
+                urls.iter()
+                    .filter_map(|url| url.to_file_path().ok())
+                    .collect(),
+            ),
+            Event::WindowEvent {
+                event, window_id, ..
+            } => match event {
+                WindowEvent::CloseRequested => self.handle_close_requested(window_id),
+                WindowEvent::Destroyed { .. } => self.window_destroyed(window_id),
+                WindowEvent::Focused(focused) => self.handle_focused(window_id, focused),
+                WindowEvent::ReceivedImeText(text) => self.handle_ime_text(window_id, &text),
+                event => self.handle_window_event(window_id, &event),
+            },
+
+            Event::UserEvent(event) => match event {
+                UserWindowEvent::Poll(id) => self.poll_vdom(id),
+                UserWindowEvent::NewWindow => self.handle_new_window(),
+                UserWindowEvent::ShowMainWindow => self.handle_show_main_window(),
+                UserWindowEvent::OpenUrlWindow(url) => self.handle_open_url_window(url),
+                UserWindowEvent::ExitApp(code) => self.handle_exit_app(code),
+                UserWindowEvent::Broadcast => self.handle_broadcast(),
+                UserWindowEvent::CloseWindow(id) => self.handle_close_msg(id),
+                UserWindowEvent::Download { id, event } => self.handle_download_event(id, event),
+                UserWindowEvent::PageLoad { id, event } => self.handle_page_load_event(id, event),
+                UserWindowEvent::FileDrop { id, event } => self.handle_file_drop_event(id, event),
+                UserWindowEvent::OpenFile(path) => self.handle_open_file(path),
+                UserWindowEvent::JumpListTask(task) => self.handle_jump_list_task(task),
+                UserWindowEvent::Notification(event) => self.handle_notification_event(event),
+
+                #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+                UserWindowEvent::GlobalHotKeyEvent(evnt) => self.handle_global_hotkey(evnt),
+
+                #[cfg(all(
+                    feature = "hot-reload",
+                    debug_assertions,
+                    not(target_os = "android"),
+                    not(target_os = "ios")
+                ))]
+                UserWindowEvent::HotReloadEvent(msg) => self.handle_hot_reload_msg(msg),
+
+                #[cfg(feature = "updater")]
+                UserWindowEvent::UpdaterEvent(event) => self.handle_updater_event(event),
+                #[cfg(feature = "updater")]
+                UserWindowEvent::RestartToUpdate => self.handle_restart_to_update(),
+
+                UserWindowEvent::Ipc { id, msg } => match msg.method() {
+                    IpcMethod::Initialize => self.handle_initialize_msg(msg, id),
+                    IpcMethod::FileDialog => self.handle_file_dialog_msg(msg, id),
+                    IpcMethod::UserEvent => self.handle_user_event_msg(msg, id),
+                    IpcMethod::Query => self.handle_query_msg(msg, id),
+                    IpcMethod::BrowserOpen => self.handle_browser_open(msg, id),
+                    IpcMethod::DragWindow => self.handle_drag_window_msg(id),
+                    IpcMethod::ToggleMaximize => self.handle_toggle_maximize_msg(id),
+                    IpcMethod::MinimizeWindow => self.handle_minimize_window_msg(id),
+                    IpcMethod::CloseWindow => self.handle_close_requested(id),
+                    IpcMethod::ResizeWindow => self.handle_resize_window_msg(msg, id),
+                    IpcMethod::WindowShortcut => self.handle_window_shortcut_msg(msg, id),
+                    IpcMethod::BackButton => self.handle_back_button_msg(),
+                    IpcMethod::VirtualKeyboard => self.handle_virtual_keyboard_msg(msg, id),
+                    IpcMethod::WindowOccluded => self.handle_window_occluded_msg(msg, id),
+                    IpcMethod::Invoke => self.handle_invoke_msg(msg, id),
+                    IpcMethod::FindResult => self.handle_find_result_msg(msg, id),
+                    IpcMethod::Other(_) => self.handle_custom_ipc_msg(msg, id),
+                },
+            },
+            _ => {}
+        }
+
+        // Wait for the next animation frame unless the app is exiting
+        if self.control_flow == ControlFlow::Wait {
+            self.control_flow = self.shared.animation_frames.control_flow();
+        }
+
+        self.control_flow
+    }
+
     #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
     pub fn handle_global_hotkey(&self, event: global_hotkey::GlobalHotKeyEvent) {
         self.shared.shortcut_manager.call_handlers(event);
     }
 
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    pub fn handle_menu_event(&self, event: muda::MenuEvent) {
+        self.shared.context_menus.handle_menu_event(&event);
+        self.shared.dock_menu.handle_menu_event(&event);
+        self.shared.menu_items.handle_menu_event(&event);
+    }
+
     #[cfg(all(
         feature = "hot-reload",
         debug_assertions,
@@ -111,6 +348,7 @@ impl App {
         not(target_os = "ios")
     ))]
     pub fn connect_hotreload(&self) {
+        dioxus_hot_reload::forward_panics();
         dioxus_hot_reload::connect({
             let proxy = self.shared.proxy.clone();
             move |template| {
@@ -130,11 +368,30 @@ impl App {
     pub fn handle_close_requested(&mut self, id: WindowId) {
         use WindowCloseBehaviour::*;
 
+        // Windows opened for URLs don't count as app windows
+        if self.url_windows.remove(&id).is_some() {
+            return;
+        }
+
+        // A window can't be closed while it has a modal open, bring the modal to the front instead
+        if let Some(modal) = self.open_modal(id) {
+            modal.desktop_context.window.set_focus();
+            return;
+        }
+
+        // Kiosk windows can only be closed by the app itself
+        if let Some(view) = self.webviews.get(&id) {
+            if view.desktop_context.is_kiosk_mode() {
+                return;
+            }
+        }
+
         match self.window_behavior {
             LastWindowExitsApp => {
-                self.webviews.remove(&id);
-                if self.webviews.is_empty() {
-                    self.control_flow = ControlFlow::Exit
+                if self.closes_last_window(id) {
+                    self.exit(0);
+                } else {
+                    self.remove_webview(id);
                 }
             }
 
@@ -146,13 +403,82 @@ impl App {
             }
 
             CloseWindow => {
-                self.webviews.remove(&id);
+                self.remove_webview(id);
             }
         }
     }
 
+    /// Keep a modal window in front of its parent whenever the parent gains focus
+    pub fn handle_focused(&mut self, id: WindowId, focused: bool) {
+        self.handle_window_event(id, &WindowEvent::Focused(focused));
+
+        if !focused {
+            return;
+        }
+
+        if let Some(modal) = self.open_modal(id) {
+            modal.desktop_context.window.set_focus();
+        }
+    }
+
+    /// Find the modal window currently blocking the given window, if any
+    fn open_modal(&self, id: WindowId) -> Option<&WebviewInstance> {
+        self.webviews
+            .values()
+            .find(|view| view.kind == WindowKind::Modal && view.parent == Some(id))
+    }
+
+    /// Remove a webview along with any child windows it owns
+    ///
+    /// If the webview was a modal, its parent is re-enabled and brought back to the front.
+    fn remove_webview(&mut self, id: WindowId) {
+        let Some(view) = self.webviews.remove(&id) else {
+            return;
+        };
+
+        if view.kind == WindowKind::Modal {
+            if let Some(parent) = view.parent.and_then(|parent| self.webviews.get(&parent)) {
+                set_window_enabled(&parent.desktop_context.window, true);
+                parent.desktop_context.window.set_focus();
+            }
+        }
+
+        let children: Vec<_> = self
+            .webviews
+            .iter()
+            .filter(|(_, child)| child.parent == Some(id))
+            .map(|(child_id, _)| *child_id)
+            .collect();
+
+        for child in children {
+            self.remove_webview(child);
+        }
+    }
+
+    /// Whether closing the window would close every window of the app, including the modals and child windows it
+    /// takes along
+    fn closes_last_window(&self, id: WindowId) -> bool {
+        self.webviews.keys().all(|other| {
+            let mut current = Some(*other);
+            while let Some(window) = current {
+                if window == id {
+                    return true;
+                }
+                current = self.webviews.get(&window).and_then(|view| view.parent);
+            }
+            false
+        })
+    }
+
+    /// Let the components clean up, then close every window and stop the event loop with the exit code
+    fn exit(&mut self, code: i32) {
+        self.shared.exit_listeners.call(&code);
+        self.webviews.clear();
+        self.control_flow = ControlFlow::ExitWithCode(code);
+    }
+
     pub fn window_destroyed(&mut self, id: WindowId) {
-        self.webviews.remove(&id);
+        self.remove_webview(id);
 
         if matches!(
             self.window_behavior,
@@ -163,44 +489,419 @@ impl App {
         }
     }
 
+    /// The input method committed text to the native window, pass it on to the page as composition events
+    pub fn handle_ime_text(&mut self, id: WindowId, text: &str) {
+        let Some(view) = self.webviews.get(&id) else {
+            return;
+        };
+
+        if let Err(err) = view
+            .desktop_context
+            .webview
+            .evaluate_script(&composition_script(text))
+        {
+            tracing::warn!("Failed to forward IME text to the webview: {err}");
+        }
+    }
+
+    /// The app was asked to open a file. Hold on to it until the first window is ready to listen for it
+    pub fn handle_open_file(&mut self, path: PathBuf) {
+        match &mut self.pending_open_files {
+            Some(pending) => pending.push(path),
+            None => self.shared.open_file_listeners.call(&path),
+        }
+    }
+
+    /// A task was picked from the jump list. Hold on to it until the first window is ready to listen for it
+    pub fn handle_jump_list_task(&mut self, task: String) {
+        match &mut self.pending_jump_list_tasks {
+            Some(pending) => pending.push(task),
+            None => self.shared.jump_list_listeners.call(&task),
+        }
+    }
+
+    /// The user responded to a notification
+    pub fn handle_notification_event(&mut self, event: NotificationEvent) {
+        self.shared.notification_listeners.call(&event);
+    }
+
+    /// A window broadcast a value, hand it to the subscribers in every window
+    pub fn handle_broadcast(&mut self) {
+        self.shared.broadcasts.deliver();
+    }
+
+    /// macOS reports files opened with the app from Finder as file urls
+    pub fn handle_opened_files(&mut self, paths: Vec<PathBuf>) {
+        for path in paths {
+            self.handle_open_file(path);
+        }
+    }
+
+    /// Forward the window events components can listen to with `use_window_event`
+    pub fn handle_window_event(&mut self, id: WindowId, event: &WindowEvent) {
+        if let (WindowEvent::Resized(_), Some(view)) = (event, self.webviews.get(&id)) {
+            view.desktop_context.update_safe_area();
+        }
+
+        let Some(event) = DesktopWindowEvent::from_tao(event) else {
+            return;
+        };
+
+        if let Some(view) = self.webviews.get(&id) {
+            view.desktop_context.window_event_listeners.call(&event);
+        }
+    }
+
+    /// The event loop is done with this turn's events, run animation frames and send what was rendered
+    pub fn handle_main_events_cleared(&mut self) {
+        self.shared.animation_frames.run_if_due();
+
+        // Selecting a native menu item wakes up the event loop, so the selections can be read from muda's channel
+        // here instead of installing a global handler that would replace the app's own
+        #[cfg(not(any(target_os = "ios", target_os = "android")))]
+        while let Ok(event) = muda::MenuEvent::receiver().try_recv() {
+            self.handle_menu_event(event);
+        }
+
+        for view in self.webviews.values() {
+            view.desktop_context.flush_edits();
+        }
+
+        // Edits the webview isn't ready for yet are held back until a later turn
+        #[cfg(all(
+            feature = "hot-reload",
+            debug_assertions,
+            not(target_os = "android"),
+            not(target_os = "ios")
+        ))]
+        if self.hot_reload_ready_pending
+            && self
+                .webviews
+                .values()
+                .all(|view| !view.desktop_context.edits_pending.get())
+        {
+            self.hot_reload_ready_pending = false;
+            dioxus_hot_reload::send_to_server(dioxus_hot_reload::ClientMsg::Ready);
+        }
+    }
+
     pub fn handle_start_cause_init(&mut self) {
+        let mut cfg = self.cfg.take().unwrap();
+
+        if let Some(callback) = cfg.app_handle_callback.take() {
+            callback(AppHandle::new(self.shared.proxy.clone()));
+        }
+
+        // Background apps create the main window on demand
+        if cfg.no_initial_window {
+            self.cfg.set(Some(cfg));
+            return;
+        }
+
+        self.open_main_window(cfg);
+    }
+
+    fn open_main_window(&mut self, mut cfg: Config) {
         let virtual_dom = self.unmounted_dom.take().unwrap();
-        let cfg = self.cfg.take().unwrap();
 
         self.is_visible_before_start = cfg.window.window.visible;
 
-        let webview = WebviewInstance::new(cfg, virtual_dom, self.shared.clone());
+        // Show the splash screen right away and keep the main window hidden until it has loaded
+        if let Some(html) = cfg.splash_screen.take() {
+            let window = cfg
+                .splash_window
+                .take()
+                .unwrap_or_else(SplashScreen::default_window);
+
+            self.splash_screen = SplashScreen::new(window, &html, &self.shared.target);
+
+            if self.splash_screen.is_some() {
+                cfg.window = cfg.window.with_visible(false);
+            }
+        }
+
+        let webview = WebviewInstance::new(cfg, virtual_dom, self.shared.clone(), None);
 
         let id = webview.desktop_context.window.id();
+        self.main_window = Some(id);
         self.webviews.insert(id, webview);
     }
 
-    pub fn handle_browser_open(&mut self, msg: IpcMessage) {
-        if let Some(temp) = msg.params().as_object() {
-            if temp.contains_key("href") {
-                let open = webbrowser::open(temp["href"].as_str().unwrap());
-                if let Err(e) = open {
-                    tracing::error!("Open Browser error: {:?}", e);
+    /// Bring the main window to the front, creating it if the app was launched without it
+    pub fn handle_show_main_window(&mut self) {
+        if let Some(mut cfg) = self.cfg.take() {
+            // A window that is requested explicitly should always be shown
+            cfg.window = cfg.window.with_visible(true);
+            self.open_main_window(cfg);
+            return;
+        }
+
+        if let Some(view) = self.main_window.and_then(|id| self.webviews.get(&id)) {
+            view.desktop_context.window.set_visible(true);
+            view.desktop_context.window.set_minimized(false);
+            view.desktop_context.window.set_focus();
+        }
+    }
+
+    pub fn handle_exit_app(&mut self, code: i32) {
+        self.exit(code);
+    }
+
+    pub fn handle_browser_open(&mut self, msg: IpcMessage, id: WindowId) {
+        let Some(href) = msg.params()["href"].as_str().map(str::to_string) else {
+            return;
+        };
+
+        // Links the interpreter intercepts are handled like new windows
+        match self.webviews.get(&id) {
+            Some(view) => _ = view.navigation.navigate(href, true),
+            None => open_externally(&href),
+        }
+    }
+
+    /// Open a URL in a plain window, because the navigation handler asked for it
+    pub fn handle_open_url_window(&mut self, url: String) {
+        if let Some(window) = UrlWindow::new(&url, &self.shared.target) {
+            self.url_windows.insert(window.id(), window);
+        }
+    }
+
+    /// Whether the window belongs to the app, as a webview or a window opened for a URL
+    pub(crate) fn owns_window(&self, id: &WindowId) -> bool {
+        self.webviews.contains_key(id) || self.url_windows.contains_key(id)
+    }
+
+    pub fn handle_download_event(&mut self, id: WindowId, event: DownloadEvent) {
+        if let Some(view) = self.webviews.get(&id) {
+            view.desktop_context.download_listeners.call(&event);
+        }
+    }
+
+    /// A page started, finished or failed loading in a webview, tell the window's components about it
+    pub fn handle_page_load_event(&mut self, id: WindowId, event: PageLoadEvent) {
+        if let Some(view) = self.webviews.get(&id) {
+            view.desktop_context.page_load_listeners.call(&event);
+        }
+    }
+
+    /// The directories of a file drag were listed, handle the drag events that waited for them
+    pub fn handle_file_drop_event(&mut self, id: WindowId, event: wry::FileDropEvent) {
+        let Some(view) = self.webviews.get(&id) else {
+            return;
+        };
+
+        for msg in view.desktop_context.file_hover.finish_listing(event) {
+            self.handle_user_event_msg(msg, id);
+        }
+    }
+
+    /// A shortcut registered with the window's shortcut registry was pressed
+    pub fn handle_window_shortcut_msg(&mut self, msg: IpcMessage, id: WindowId) {
+        let Ok(message) = serde_json::from_value(msg.params()) else {
+            return;
+        };
+
+        if let Some(view) = self.webviews.get(&id) {
+            view.desktop_context.window_shortcuts.call(message);
+        }
+    }
+
+    /// The hardware back button was pressed, let the app handle it before falling back to the OS behavior
+    pub fn handle_back_button_msg(&mut self) {
+        let event = BackButtonEvent::default();
+        self.shared.back_button_listeners.call(&event);
+
+        #[cfg(target_os = "android")]
+        if !event.default_prevented() {
+            crate::back_button::move_task_to_back();
+        }
+    }
+
+    /// The page of a window was hidden or shown again
+    pub fn handle_window_occluded_msg(&mut self, msg: IpcMessage, id: WindowId) {
+        let Ok(message) = serde_json::from_value::<OcclusionMessage>(msg.params()) else {
+            return;
+        };
+
+        if let Some(view) = self.webviews.get(&id) {
+            view.desktop_context
+                .window_event_listeners
+                .call(&DesktopWindowEvent::Occluded(message.occluded));
+        }
+    }
+
+    /// The page searched its text, tell the window's components how many matches it found
+    pub fn handle_find_result_msg(&mut self, msg: IpcMessage, id: WindowId) {
+        let Ok(result) = serde_json::from_value::<FindResult>(msg.params()) else {
+            return;
+        };
+
+        if let Some(view) = self.webviews.get(&id) {
+            view.desktop_context.find_listeners.call(&result);
+        }
+    }
+
+    /// The on-screen keyboard was shown, hidden or resized
+    pub fn handle_virtual_keyboard_msg(&mut self, msg: IpcMessage, id: WindowId) {
+        let Ok(message) = serde_json::from_value::<VirtualKeyboardMessage>(msg.params()) else {
+            return;
+        };
+
+        if let Some(view) = self.webviews.get(&id) {
+            view.desktop_context.window_event_listeners.call(
+                &DesktopWindowEvent::VirtualKeyboardChanged {
+                    visible: message.visible,
+                    height: message.height,
+                },
+            );
+        }
+    }
+
+    /// A message to a method the app registered with `DesktopService::on_ipc`
+    pub fn handle_custom_ipc_msg(&mut self, msg: IpcMessage, id: WindowId) {
+        let method = msg.method_name().to_string();
+        let reply_id = msg.id().cloned();
+        self.call_ipc_handler(id, method, msg.params(), reply_id);
+    }
+
+    /// The page called one of the app's IPC methods with `dioxus.invoke`
+    pub fn handle_invoke_msg(&mut self, msg: IpcMessage, id: WindowId) {
+        let reply_id = msg.id().cloned();
+        match serde_json::from_value::<InvokeMessage>(msg.params()) {
+            Ok(invoke) => self.call_ipc_handler(id, invoke.command, invoke.args, reply_id),
+            Err(err) => {
+                if let Some(view) = self.webviews.get(&id) {
+                    let result = Err(format!("invalid invoke message: {err}"));
+                    reply(
+                        &view.desktop_context.webview,
+                        "invoke",
+                        reply_id.as_ref(),
+                        result,
+                    );
                 }
             }
         }
     }
 
+    fn call_ipc_handler(
+        &mut self,
+        id: WindowId,
+        method: String,
+        params: serde_json::Value,
+        reply_id: Option<serde_json::Value>,
+    ) {
+        let Some(view) = self.webviews.get(&id) else {
+            return;
+        };
+
+        match view.desktop_context.ipc_handlers.call(&method, params) {
+            Some(IpcResult::Ready(result)) => reply(
+                &view.desktop_context.webview,
+                &method,
+                reply_id.as_ref(),
+                result,
+            ),
+            // Async handlers run as tasks of the window's virtualdom and reply once they are done
+            Some(IpcResult::Pending(future)) => {
+                let desktop = view.desktop_context.clone();
+                view.dom.in_runtime(|| {
+                    spawn_forever(async move {
+                        let result = future.await;
+                        reply(&desktop.webview, &method, reply_id.as_ref(), result);
+                    })
+                });
+            }
+            None => {
+                tracing::warn!("Received IPC message for unknown method {method:?}");
+                let result = Err(format!("unknown IPC method {method:?}"));
+                reply(
+                    &view.desktop_context.webview,
+                    &method,
+                    reply_id.as_ref(),
+                    result,
+                );
+            }
+        }
+    }
+
+    /// A drag region was pressed, start moving the window
+    pub fn handle_drag_window_msg(&mut self, id: WindowId) {
+        if let Some(view) = self.webviews.get(&id) {
+            view.desktop_context.drag();
+        }
+    }
+
+    /// A drag region was double clicked
+    pub fn handle_toggle_maximize_msg(&mut self, id: WindowId) {
+        if let Some(view) = self.webviews.get(&id) {
+            view.desktop_context.toggle_maximized();
+        }
+    }
+
+    /// A minimize window control was clicked
+    pub fn handle_minimize_window_msg(&mut self, id: WindowId) {
+        if let Some(view) = self.webviews.get(&id) {
+            view.desktop_context.window.set_minimized(true);
+        }
+    }
+
+    /// The resize border of an undecorated window was pressed, start resizing the window
+    pub fn handle_resize_window_msg(&mut self, msg: IpcMessage, id: WindowId) {
+        let Ok(request) = serde_json::from_value::<ResizeRequest>(msg.params()) else {
+            return;
+        };
+
+        let (Some(direction), Some(view)) = (request.direction(), self.webviews.get(&id)) else {
+            return;
+        };
+
+        if let Err(err) = view.desktop_context.window.drag_resize_window(direction) {
+            tracing::warn!("Failed to resize window: {err}");
+        }
+    }
+
     /// The webview is finally loaded
     ///
     /// Let's rebuild it and then start polling it
-    pub fn handle_initialize_msg(&mut self, id: WindowId) {
+    pub fn handle_initialize_msg(&mut self, msg: IpcMessage, id: WindowId) {
         let view = self.webviews.get_mut(&id).unwrap();
 
+        // Settle on a compression before the first edits go out, they are usually the largest
+        let initialize =
+            serde_json::from_value::<InitializeMessage>(msg.params()).unwrap_or_default();
+        view.desktop_context
+            .edit_queue
+            .negotiate_compression(&initialize.compression);
+
         view.dom
             .rebuild(&mut *view.desktop_context.mutation_state.borrow_mut());
 
         view.desktop_context.send_edits();
 
+        // The first window is mounted, so its components can now handle the files the app was launched with
+        if let Some(pending) = self.pending_open_files.take() {
+            for path in pending {
+                self.shared.open_file_listeners.call(&path);
+            }
+        }
+        if let Some(pending) = self.pending_jump_list_tasks.take() {
+            for task in pending {
+                self.shared.jump_list_listeners.call(&task);
+            }
+        }
+
+        // Shortcuts registered before the page finished loading weren't seen by it yet
+        view.desktop_context
+            .window_shortcuts
+            .sync(&view.desktop_context.webview);
+
         view.desktop_context
             .window
             .set_visible(self.is_visible_before_start);
 
+        // The main window is ready, so the splash screen can go away
+        self.splash_screen.take();
+
         _ = self.shared.proxy.send_event(UserWindowEvent::Poll(id));
     }
 
@@ -208,9 +909,10 @@ impl App {
     ///
     /// Technically you can handle this with the use_window_event hook
     pub fn handle_close_msg(&mut self, id: WindowId) {
-        self.webviews.remove(&id);
-        if self.webviews.is_empty() {
-            self.control_flow = ControlFlow::Exit
+        if self.closes_last_window(id) {
+            self.exit(0);
+        } else {
+            self.remove_webview(id);
         }
     }
 
@@ -227,6 +929,14 @@ impl App {
     }
 
     pub fn handle_user_event_msg(&mut self, msg: IpcMessage, id: WindowId) {
+        let view = self.webviews.get_mut(&id).unwrap();
+
+        // Drag events carry the files of the drag, so while its directories are listed every event waits to keep
+        // them in order
+        let Some(msg) = view.desktop_context.file_hover.wait_for_listing(msg) else {
+            return;
+        };
+
         let parsed_params = serde_json::from_value(msg.params())
             .map_err(|err| tracing::error!("Error parsing user_event: {:?}", err));
 
@@ -239,7 +949,6 @@ impl App {
             data,
         } = evt;
 
-        let view = self.webviews.get_mut(&id).unwrap();
         let query = view.desktop_context.query.clone();
         let recent_file = view.desktop_context.file_hover.clone();
 
@@ -289,13 +998,87 @@ impl App {
                     webview.poll_vdom();
                 }
             }
-            dioxus_hot_reload::HotReloadMsg::Shutdown => {
-                self.control_flow = ControlFlow::Exit;
+            dioxus_hot_reload::HotReloadMsg::UpdateLiterals(literals) => {
+                for webview in self.webviews.values_mut() {
+                    webview.dom.replace_literals(literals.clone());
+                    webview.poll_vdom();
+                }
+            }
+            dioxus_hot_reload::HotReloadMsg::Shutdown(reason) => match reason {
+                // The rebuilt app replaces this one, unless the build fails and its errors are shown instead
+                dioxus_hot_reload::ShutdownReason::RebuildRequired => {
+                    for webview in self.webviews.values_mut() {
+                        webview.show_rebuilding();
+                    }
+                }
+                dioxus_hot_reload::ShutdownReason::Restart => {
+                    self.control_flow = ControlFlow::Exit;
+                }
+                dioxus_hot_reload::ShutdownReason::WatcherExiting => {
+                    tracing::info!("Hot reloading stopped, rebuild the app to see further changes");
+                }
+                dioxus_hot_reload::ShutdownReason::FatalError(err) => {
+                    tracing::error!("Hot reloading stopped: {err}");
+                }
+                // Reasons added by newer servers keep the app running
+                _ => {}
+            },
+
+            // Applied by `dioxus_hot_reload::connect`, which passes on the patched template instead
+            dioxus_hot_reload::HotReloadMsg::PatchTemplate(_) => {}
+
+            dioxus_hot_reload::HotReloadMsg::FullReload => {
+                for webview in self.webviews.values_mut() {
+                    webview.full_reload();
+                }
+            }
+
+            dioxus_hot_reload::HotReloadMsg::UpdateAsset(asset) => {
+                for webview in self.webviews.values_mut() {
+                    webview.update_asset(&asset, None);
+                }
+            }
+
+            dioxus_hot_reload::HotReloadMsg::UpdateResolvedAsset { asset, resolved } => {
+                for webview in self.webviews.values_mut() {
+                    webview.update_asset(&asset, Some(&resolved));
+                }
+            }
+
+            // Read by the app through `dioxus_hot_reload::status()`. Every reload ends with a status. The changes
+            // of the reload were rendered into the mutation state as they arrived, but only reach the webviews with
+            // the next flush, so the server hears that the app is ready after that.
+            dioxus_hot_reload::HotReloadMsg::Status(_) => {
+                self.hot_reload_ready_pending = true;
             }
 
-            dioxus_hot_reload::HotReloadMsg::UpdateAsset(_) => {
+            dioxus_hot_reload::HotReloadMsg::BuildStatus { errors, warnings } => {
                 for webview in self.webviews.values_mut() {
-                    webview.kick_stylsheets();
+                    webview.show_build_status(&errors, &warnings);
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "updater")]
+    pub fn handle_updater_event(&mut self, event: crate::updater::UpdaterEvent) {
+        self.shared.updater_listeners.call(&event);
+    }
+
+    /// Shut down the event loop - the update is installed once every window is gone
+    #[cfg(feature = "updater")]
+    pub fn handle_restart_to_update(&mut self) {
+        self.install_update_on_exit = true;
+        self.exit(0);
+    }
+
+    /// The event loop is shutting down, this is the last chance to run any cleanup
+    pub fn handle_loop_destroyed(&mut self) {
+        #[cfg(feature = "updater")]
+        if self.install_update_on_exit {
+            if let Some(updater) = &self.shared.updater {
+                if let Err(err) = updater.install_staged() {
+                    tracing::error!("Failed to install update: {err}");
                 }
             }
         }
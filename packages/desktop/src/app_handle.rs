@@ -0,0 +1,37 @@
+use crate::ipc::UserWindowEvent;
+use tao::event_loop::EventLoopProxy;
+
+/// A handle to the running app that can be used from any thread, even while the app has no windows
+///
+/// Get one with [`Config::with_app_handle`](crate::Config::with_app_handle). This is how background apps started
+/// with [`Config::with_no_initial_window`](crate::Config::with_no_initial_window) open their window from a tray icon,
+/// a socket or any other source of activity.
+#[derive(Clone)]
+pub struct AppHandle {
+    proxy: EventLoopProxy<UserWindowEvent>,
+}
+
+impl AppHandle {
+    pub(crate) fn new(proxy: EventLoopProxy<UserWindowEvent>) -> Self {
+        Self { proxy }
+    }
+
+    /// Show and focus the main window, creating it if it wasn't created yet
+    ///
+    /// The main window renders the root component the app was launched with. It can only be created once, so if it
+    /// was closed rather than hidden, this does nothing.
+    pub fn show_main_window(&self) {
+        _ = self.proxy.send_event(UserWindowEvent::ShowMainWindow);
+    }
+
+    /// Close all windows and stop the event loop
+    pub fn exit(&self) {
+        self.exit_with_code(0);
+    }
+
+    /// Close all windows and stop the event loop, exiting the process with the given code. The listeners added with
+    /// [`use_app_exit`](crate::use_app_exit) run first.
+    pub fn exit_with_code(&self, code: i32) {
+        _ = self.proxy.send_event(UserWindowEvent::ExitApp(code));
+    }
+}
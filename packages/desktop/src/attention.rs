@@ -0,0 +1,176 @@
+//! Getting the user's attention: flashing the taskbar button or bouncing the dock icon, and unread-count badges
+
+use tao::window::{UserAttentionType, Window};
+
+/// How urgently the app asks for the user's attention with
+/// [`DesktopService::request_user_attention`](crate::DesktopService::request_user_attention)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserAttention {
+    /// Keep asking until the app is focused. macOS bounces the dock icon and Windows flashes the taskbar button until
+    /// the window is focused.
+    Critical,
+    /// Ask once. macOS bounces the dock icon once and Windows flashes the taskbar button until the window is focused.
+    Informational,
+}
+
+impl From<UserAttention> for UserAttentionType {
+    fn from(attention: UserAttention) -> Self {
+        match attention {
+            UserAttention::Critical => UserAttentionType::Critical,
+            UserAttention::Informational => UserAttentionType::Informational,
+        }
+    }
+}
+
+/// Show the count on the dock icon on macOS, the taskbar button on Windows and the launcher icon on Linux
+#[allow(unused)]
+pub(crate) fn set_badge_count(window: &Window, count: Option<u32>) {
+    #[cfg(target_os = "macos")]
+    {
+        use objc::runtime::Object;
+        use objc::*;
+
+        objc::rc::autoreleasepool(|| unsafe {
+            let label: *mut Object = match count {
+                Some(count) => {
+                    let label = count.to_string();
+                    let string: *mut Object = msg_send![class!(NSString), alloc];
+                    let string: *mut Object = msg_send![
+                        string,
+                        initWithBytes: label.as_ptr()
+                        length: label.len()
+                        encoding: 4usize // NSUTF8StringEncoding
+                    ];
+                    msg_send![string, autorelease]
+                }
+                None => std::ptr::null_mut(),
+            };
+
+            let app: *mut Object = msg_send![class!(NSApplication), sharedApplication];
+            let dock_tile: *mut Object = msg_send![app, dockTile];
+            let _: () = msg_send![dock_tile, setBadgeLabel: label];
+        });
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use tao::platform::windows::WindowExtWindows;
+        let icon = count.and_then(|count| {
+            tao::window::Icon::from_rgba(badge_icon(count), BADGE_SIZE, BADGE_SIZE).ok()
+        });
+        window.set_overlay_icon(icon.as_ref());
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        use std::collections::HashMap;
+        use webkit2gtk::{gio, glib::ToVariant};
+
+        let Some(name) = std::env::current_exe()
+            .ok()
+            .and_then(|exe| Some(exe.file_stem()?.to_string_lossy().to_string()))
+        else {
+            return;
+        };
+
+        let connection = match gio::bus_get_sync(gio::BusType::Session, None::<&gio::Cancellable>) {
+            Ok(connection) => connection,
+            Err(err) => {
+                tracing::debug!("Failed to connect to the session bus: {err}");
+                return;
+            }
+        };
+
+        let mut properties = HashMap::new();
+        properties.insert(
+            "count".to_string(),
+            i64::from(count.unwrap_or(0)).to_variant(),
+        );
+        properties.insert("count-visible".to_string(), count.is_some().to_variant());
+        let parameters = (format!("application://{name}.desktop"), properties).to_variant();
+
+        if let Err(err) = connection.emit_signal(
+            None,
+            "/com/canonical/unity/launcherentry/dioxus",
+            "com.canonical.Unity.LauncherEntry",
+            "Update",
+            Some(&parameters),
+        ) {
+            tracing::debug!("Failed to update the launcher badge: {err}");
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+const BADGE_SIZE: u32 = 16;
+
+/// Draw a red circle with the count in white, or "9+" for larger counts
+#[cfg(target_os = "windows")]
+fn badge_icon(count: u32) -> Vec<u8> {
+    // The digits 0-9 and "+" in a 3x5 pixel font, one row per byte, the lowest three bits are the pixels
+    const GLYPHS: [[u8; 5]; 11] = [
+        [0b111, 0b101, 0b101, 0b101, 0b111],
+        [0b010, 0b110, 0b010, 0b010, 0b111],
+        [0b111, 0b001, 0b111, 0b100, 0b111],
+        [0b111, 0b001, 0b111, 0b001, 0b111],
+        [0b101, 0b101, 0b111, 0b001, 0b001],
+        [0b111, 0b100, 0b111, 0b001, 0b111],
+        [0b111, 0b100, 0b111, 0b101, 0b111],
+        [0b111, 0b001, 0b010, 0b010, 0b010],
+        [0b111, 0b101, 0b111, 0b101, 0b111],
+        [0b111, 0b101, 0b111, 0b001, 0b111],
+        [0b000, 0b010, 0b111, 0b010, 0b000],
+    ];
+    const SCALE: u32 = 2;
+
+    let size = BADGE_SIZE;
+    let mut rgba = vec![0u8; (size * size * 4) as usize];
+    let mut set = |x: u32, y: u32, color: [u8; 4]| {
+        let index = ((y * size + x) * 4) as usize;
+        rgba[index..index + 4].copy_from_slice(&color);
+    };
+
+    let center = (size as f32 - 1.0) / 2.0;
+    for y in 0..size {
+        for x in 0..size {
+            let (dx, dy) = (x as f32 - center, y as f32 - center);
+            if dx * dx + dy * dy <= (size as f32 / 2.0).powi(2) {
+                set(x, y, [0xd9, 0x30, 0x25, 0xff]);
+            }
+        }
+    }
+
+    let glyphs: Vec<usize> = match count {
+        0..=9 => vec![count as usize],
+        _ => vec![9, 10],
+    };
+
+    // Each glyph is 3 pixels wide with a 1 pixel gap, scaled up
+    let width = (glyphs.len() as u32 * 4 - 1) * SCALE;
+    let left = (size - width) / 2;
+    let top = (size - 5 * SCALE) / 2;
+    for (i, glyph) in glyphs.iter().enumerate() {
+        for (row, bits) in GLYPHS[*glyph].iter().enumerate() {
+            for column in 0..3 {
+                if bits & (0b100 >> column) == 0 {
+                    continue;
+                }
+                for sy in 0..SCALE {
+                    for sx in 0..SCALE {
+                        let x = left + (i as u32 * 4 + column) * SCALE + sx;
+                        let y = top + row as u32 * SCALE + sy;
+                        set(x, y, [0xff, 0xff, 0xff, 0xff]);
+                    }
+                }
+            }
+        }
+    }
+
+    rgba
+}
@@ -0,0 +1,95 @@
+//! The hardware back button on Android, surfaced as an event that components can handle instead of the OS
+//! backgrounding the app
+
+use crate::hooks::use_event_listener;
+use std::{cell::Cell, rc::Rc};
+
+/// The script that reports presses of the back button over IPC
+///
+/// Android's back button goes back in the webview's history while there is any, so presses that go back within the
+/// app's own history are left to the page and its router. A sentinel entry is kept below the first page of the app.
+/// Going back to it means the app ran out of history, which is reported as a press and undone by going forward again.
+#[allow(unused)]
+pub(crate) const BACK_BUTTON_SCRIPT: &str = r#"
+(function () {
+    // Reloads keep the history of the session, including the sentinel
+    if (!sessionStorage.getItem("dioxusBackButton")) {
+        sessionStorage.setItem("dioxusBackButton", "true");
+        const state = history.state;
+        history.replaceState({ dioxusBackButton: true }, "");
+        history.pushState(state, "");
+    }
+
+    // Registered before the page's scripts run, so the router never sees the sentinel or the way back from it
+    let restoring = false;
+    window.addEventListener("popstate", function (event) {
+        if (restoring) {
+            restoring = false;
+            event.stopImmediatePropagation();
+        } else if (event.state && event.state.dioxusBackButton) {
+            restoring = true;
+            event.stopImmediatePropagation();
+            history.forward();
+            window.ipc.postMessage(JSON.stringify({ method: "back_button", params: {} }));
+        }
+    });
+})();
+"#;
+
+/// A press of the hardware back button
+///
+/// Call [`BackButtonEvent::prevent_default`] to handle the press in the app. If no handler does, the app is moved to
+/// the background like the OS would.
+#[derive(Debug, Clone, Default)]
+pub struct BackButtonEvent {
+    prevented: Rc<Cell<bool>>,
+}
+
+impl BackButtonEvent {
+    /// Keep the app in the foreground, the press was handled by the app
+    pub fn prevent_default(&self) {
+        self.prevented.set(true);
+    }
+
+    /// Whether a handler already handled the press
+    pub fn default_prevented(&self) -> bool {
+        self.prevented.get()
+    }
+}
+
+/// Listen for presses of the hardware back button that would leave the app
+///
+/// Presses go back in the webview's history first, so a router goes back to the previous page on its own. The handler
+/// is called once there is nothing left to go back to, like to confirm before the app is moved to the background:
+///
+/// ```rust, ignore
+/// use_back_button(move |event| {
+///     if has_unsaved_changes() {
+///         show_discard_dialog.set(true);
+///         event.prevent_default();
+///     }
+/// });
+/// ```
+///
+/// > Note: Only fired on Android, and only while the activity lets the webview handle back navigation, which wry's
+/// > `WryActivity` does by default.
+pub fn use_back_button(handler: impl FnMut(BackButtonEvent) + 'static) {
+    use_event_listener(|window| &window.shared.back_button_listeners, handler)
+}
+
+/// Do what the OS does when the back button isn't handled, move the app's task to the background
+#[cfg(target_os = "android")]
+pub(crate) fn move_task_to_back() {
+    let result = (|| -> jni::errors::Result<()> {
+        let context = ndk_context::android_context();
+        let vm = unsafe { jni::JavaVM::from_raw(context.vm().cast()) }?;
+        let mut env = vm.attach_current_thread()?;
+        let activity = unsafe { jni::objects::JObject::from_raw(context.context().cast()) };
+        env.call_method(&activity, "moveTaskToBack", "(Z)Z", &[true.into()])?;
+        Ok(())
+    })();
+
+    if let Err(err) = result {
+        tracing::warn!("Failed to move the app to the background: {err}");
+    }
+}
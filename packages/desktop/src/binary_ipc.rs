@@ -0,0 +1,367 @@
+//! An IPC channel that sends the interpreter's messages as bytes over the custom protocol instead of strings over
+//! `window.ipc.postMessage`
+//!
+//! Edits already travel this way, see [`EditQueue`](crate::edits::EditQueue). Events posted in the same task are sent
+//! as one request of length-prefixed CBOR frames, which saves a round trip through the string-only postMessage bridge
+//! for every event of a burst and keeps large events small.
+//!
+//! The same endpoint carries the [fallback transport](fallback_ipc_script) for webviews where `window.ipc.postMessage`
+//! is missing.
+
+use crate::{
+    compression::{decompress, COMPRESSION_THRESHOLD},
+    ipc::IpcMessage,
+    ipc_tracing::{self, IpcTimer},
+    protocol::IpcOptions,
+};
+use std::borrow::Cow;
+use wry::http::{Method, Request};
+
+#[cfg(any(target_os = "android", target_os = "windows"))]
+pub(crate) const IPC_PATH: &str = "http://dioxus.index.html/__ipc";
+
+#[cfg(not(any(target_os = "android", target_os = "windows")))]
+pub(crate) const IPC_PATH: &str = "dioxus://index.html/__ipc";
+
+/// Whether a request to the custom protocol carries IPC messages
+pub(crate) fn is_ipc_request(request: &Request<Vec<u8>>) -> bool {
+    request.uri().path().trim_matches('/') == "__ipc"
+}
+
+/// The messages an IPC request carries, either as frames or as one JSON message in its body
+///
+/// Frames are only accepted from the [binary IPC channel](crate::Config::with_binary_ipc) and JSON messages from the
+/// [fallback transport](crate::Config::with_ipc_fallback), so pages of windows that use neither can't send messages
+/// through the custom protocol.
+pub(crate) fn decode_request(request: &Request<Vec<u8>>, options: IpcOptions) -> Vec<IpcMessage> {
+    let format = query_param(request, "format");
+    let allowed = match format {
+        Some("json") => options.fallback,
+        _ => options.binary,
+    };
+    if !allowed || request.method() != Method::POST {
+        tracing::warn!("Rejected an IPC request the page of this window isn't allowed to send");
+        return Vec::new();
+    }
+
+    match (format, request_body(request)) {
+        (Some("json"), Some(body)) => decode_json(&body).into_iter().collect(),
+        (_, Some(body)) => decode_frames(&body),
+        (_, None) => Vec::new(),
+    }
+}
+
+/// The value of a parameter in the query of a request
+fn query_param<'a>(request: &'a Request<Vec<u8>>, name: &str) -> Option<&'a str> {
+    request.uri().query()?.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        (key == name).then_some(value)
+    })
+}
+
+/// The message the fallback transport sent in the body of a request
+fn decode_json(body: &[u8]) -> Option<IpcMessage> {
+    let timer = IpcTimer::start();
+    match serde_json::from_slice(body) {
+        Ok(message) => {
+            ipc_tracing::message_received(&message, body.len(), timer);
+            Some(message)
+        }
+        Err(err) => {
+            ipc_tracing::message_rejected(body.len(), &err);
+            tracing::error!("Failed to decode IPC message: {err}");
+            None
+        }
+    }
+}
+
+/// The body of an IPC request, inflated if the page compressed it
+fn request_body(request: &Request<Vec<u8>>) -> Option<Cow<'_, [u8]>> {
+    match query_param(request, "encoding") {
+        Some("deflate") => decompress(request.body()).map(Cow::Owned),
+        Some(encoding) => {
+            tracing::error!("Received an IPC request with the unknown encoding {encoding}");
+            None
+        }
+        None => Some(Cow::Borrowed(request.body())),
+    }
+}
+
+/// Split the body of an IPC request into its messages
+///
+/// Every frame is the length of the message as a little endian `u32`, followed by the message as CBOR.
+fn decode_frames(mut body: &[u8]) -> Vec<IpcMessage> {
+    let mut messages = Vec::new();
+
+    while !body.is_empty() {
+        let Some((length, rest)) = (body.len() >= 4).then(|| body.split_at(4)) else {
+            tracing::error!("Received a truncated IPC frame");
+            break;
+        };
+        let length = u32::from_le_bytes(length.try_into().unwrap()) as usize;
+        let Some(frame) = rest.get(..length) else {
+            tracing::error!("Received a truncated IPC frame");
+            break;
+        };
+
+        let timer = IpcTimer::start();
+        match ciborium::de::from_reader::<IpcMessage, _>(frame) {
+            Ok(message) => {
+                ipc_tracing::message_received(&message, length, timer);
+                messages.push(message);
+            }
+            Err(err) => {
+                ipc_tracing::message_rejected(length, &err);
+                tracing::error!("Failed to decode IPC message: {err}");
+            }
+        }
+        body = &rest[length..];
+    }
+
+    messages
+}
+
+/// The expression that creates the channel the interpreter posts its messages to
+///
+/// The interpreter hands the channel JSON strings, which are encoded as CBOR. With `compress`, requests larger than
+/// [`COMPRESSION_THRESHOLD`] are deflated if the webview can compress.
+pub(crate) fn binary_ipc_channel(compress: bool) -> String {
+    format!(
+        r#"(function () {{
+            const encoder = new TextEncoder();
+            const compress = {compress} && typeof CompressionStream === "function";
+
+            const cbor = function (value) {{
+                const bytes = [];
+                const head = function (major, length) {{
+                    const type = major << 5;
+                    if (length < 24) {{
+                        bytes.push(type | length);
+                    }} else if (length < 0x100) {{
+                        bytes.push(type | 24, length);
+                    }} else if (length < 0x10000) {{
+                        bytes.push(type | 25, length >> 8, length & 0xff);
+                    }} else {{
+                        bytes.push(type | 26, length >>> 24, (length >> 16) & 0xff, (length >> 8) & 0xff, length & 0xff);
+                    }}
+                }};
+                const write = function (value) {{
+                    if (value === null || value === undefined) {{
+                        bytes.push(0xf6);
+                    }} else if (typeof value === "boolean") {{
+                        bytes.push(value ? 0xf5 : 0xf4);
+                    }} else if (typeof value === "number") {{
+                        if (Number.isInteger(value) && Math.abs(value) < 0x100000000) {{
+                            value < 0 ? head(1, -1 - value) : head(0, value);
+                        }} else {{
+                            const float = new DataView(new ArrayBuffer(8));
+                            float.setFloat64(0, value);
+                            bytes.push(0xfb, ...new Uint8Array(float.buffer));
+                        }}
+                    }} else if (typeof value === "string") {{
+                        const encoded = encoder.encode(value);
+                        head(3, encoded.length);
+                        for (const byte of encoded) {{
+                            bytes.push(byte);
+                        }}
+                    }} else if (Array.isArray(value)) {{
+                        head(4, value.length);
+                        value.forEach(write);
+                    }} else {{
+                        const entries = Object.entries(value);
+                        head(5, entries.length);
+                        for (const [key, entry] of entries) {{
+                            write(key);
+                            write(entry);
+                        }}
+                    }}
+                }};
+                write(value);
+                return new Uint8Array(bytes);
+            }};
+            let pending = [];
+            let sending = Promise.resolve();
+
+            const encode = function (body) {{
+                if (!compress || body.length < {COMPRESSION_THRESHOLD}) {{
+                    return Promise.resolve(["{IPC_PATH}", body]);
+                }}
+                const stream = new Blob([body]).stream().pipeThrough(new CompressionStream("deflate"));
+                return new Response(stream)
+                    .arrayBuffer()
+                    .then((compressed) => ["{IPC_PATH}?encoding=deflate", compressed]);
+            }};
+
+            const flush = function () {{
+                const frames = pending;
+                pending = [];
+
+                let size = 0;
+                for (const frame of frames) {{
+                    size += 4 + frame.length;
+                }}
+                const body = new Uint8Array(size);
+                const view = new DataView(body.buffer);
+                let offset = 0;
+                for (const frame of frames) {{
+                    view.setUint32(offset, frame.length, true);
+                    body.set(frame, offset + 4);
+                    offset += 4 + frame.length;
+                }}
+
+                // Requests are sent one after the other so the messages arrive in order
+                sending = sending
+                    .then(() => encode(body))
+                    .then(([url, body]) => fetch(url, {{ method: "POST", body: body }}))
+                    .catch((err) => console.error("Failed to send IPC message", err));
+            }};
+
+            return {{
+                postMessage: function (message) {{
+                    pending.push(cbor(JSON.parse(message)));
+                    if (pending.length === 1) {{
+                        queueMicrotask(flush);
+                    }}
+                }},
+            }};
+        }})()"#
+    )
+}
+
+/// The script that keeps messages flowing when `window.ipc.postMessage` is missing, which happens on some Android
+/// WebView and older WebKitGTK builds
+///
+/// Pages with a working `window.ipc` are left alone. Otherwise `window.ipc` is replaced by a transport that posts every
+/// message as the body of an XHR to the custom protocol.
+pub(crate) fn fallback_ipc_script() -> String {
+    format!(
+        r#"
+(function () {{
+    if (window.ipc && typeof window.ipc.postMessage === "function") {{
+        return;
+    }}
+
+    const queue = [];
+    let sending = false;
+
+    const next = function () {{
+        const message = queue.shift();
+        if (message === undefined) {{
+            sending = false;
+            return;
+        }}
+        sending = true;
+
+        // One request at a time, so the messages arrive in order
+        const request = new XMLHttpRequest();
+        request.open("POST", "{IPC_PATH}?format=json");
+        request.onloadend = next;
+        request.send(message);
+    }};
+
+    window.ipc = {{
+        postMessage: function (message) {{
+            queue.push(message);
+            if (!sending) {{
+                next();
+            }}
+        }},
+    }};
+}})();
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Write;
+
+    fn frame(message: serde_json::Value) -> Vec<u8> {
+        let mut cbor = Vec::new();
+        ciborium::ser::into_writer(&message, &mut cbor).unwrap();
+        [&(cbor.len() as u32).to_le_bytes()[..], &cbor].concat()
+    }
+
+    fn event(name: &str) -> Vec<u8> {
+        frame(json!({ "method": "user_event", "params": { "name": name } }))
+    }
+
+    fn deflate(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn frames_in_one_body() {
+        let body = [event("click"), event("input")].concat();
+        let names: Vec<_> = decode_frames(&body)
+            .into_iter()
+            .map(|message| message.params()["name"].clone())
+            .collect();
+        assert_eq!(names, [json!("click"), json!("input")]);
+    }
+
+    #[test]
+    fn malformed_frames() {
+        let click = &event("click")[..];
+        let invalid = frame(json!({ "params": {} }));
+        let cases: &[(&str, &[&[u8]], usize)] = &[
+            ("empty body", &[], 0),
+            ("truncated length", &[&[4, 0]], 0),
+            ("truncated message", &[&click[..click.len() - 1]], 0),
+            ("length past the body", &[&u32::MAX.to_le_bytes(), click], 0),
+            ("trailing length", &[click, &[1, 0, 0]], 1),
+            ("truncated second frame", &[click, &click[..6]], 1),
+            ("empty frame", &[&[0, 0, 0, 0], click], 1),
+            ("invalid cbor", &[&[2, 0, 0, 0, 0xff, 0xff], click], 1),
+            ("not a message", &[&invalid, click], 1),
+        ];
+
+        for (case, body, decoded) in cases {
+            assert_eq!(decode_frames(&body.concat()).len(), *decoded, "{case}");
+        }
+    }
+
+    #[test]
+    fn requests() {
+        let decode = |query: &str, method: Method, body: Vec<u8>, binary: bool| {
+            let request = Request::builder()
+                .method(method)
+                .uri(format!("{IPC_PATH}{query}"))
+                .body(body)
+                .unwrap();
+            assert!(is_ipc_request(&request));
+            let options = IpcOptions {
+                binary,
+                fallback: !binary,
+                compression: false,
+                event_rate: None,
+            };
+            decode_request(&request, options).len()
+        };
+        let click = event("click");
+        let json = br#"{ "method": "user_event", "params": {} }"#.to_vec();
+
+        assert_eq!(decode("", Method::POST, click.clone(), true), 1);
+        assert_eq!(decode("", Method::POST, click.clone(), false), 0);
+        assert_eq!(decode("", Method::GET, click.clone(), true), 0);
+        assert_eq!(decode("?format=json", Method::POST, json.clone(), false), 1);
+        assert_eq!(decode("?format=json", Method::POST, json, true), 0);
+        assert_eq!(
+            decode("?format=json", Method::POST, b"{".to_vec(), false),
+            0
+        );
+        assert_eq!(
+            decode("?encoding=deflate", Method::POST, deflate(&click), true),
+            1
+        );
+        assert_eq!(
+            decode("?encoding=deflate", Method::POST, click.clone(), true),
+            0
+        );
+        assert_eq!(decode("?encoding=gzip", Method::POST, click, true), 0);
+    }
+}
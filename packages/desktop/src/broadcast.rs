@@ -0,0 +1,90 @@
+//! Publish values to every window of the app without routing them through one window's VirtualDom
+
+use crate::{event_handlers::EventListeners, hooks::use_event_listener, window};
+use dioxus_core::prelude::use_hook;
+use dioxus_hooks::use_signal;
+use dioxus_signals::{Signal, Writable};
+use std::{any::Any, cell::RefCell, collections::HashMap, rc::Rc};
+
+/// A value sent to a topic
+#[derive(Clone)]
+pub(crate) struct BroadcastMessage {
+    topic: Rc<str>,
+    value: Rc<dyn Any>,
+}
+
+/// The topics shared by all windows of the app
+#[derive(Default)]
+pub(crate) struct BroadcastRegistry {
+    pub(crate) listeners: EventListeners<BroadcastMessage>,
+
+    /// The last value sent to each topic, so windows opened later start out with the current state
+    latest: RefCell<HashMap<Rc<str>, Rc<dyn Any>>>,
+
+    /// Messages that were sent but not delivered yet. They are delivered from the event loop, so a window never runs
+    /// its listeners in the middle of another window's render.
+    pending: RefCell<Vec<BroadcastMessage>>,
+}
+
+impl BroadcastRegistry {
+    /// Queue a value for delivery. Returns `true` if the event loop needs to be woken up to deliver it.
+    pub(crate) fn send(&self, topic: &str, value: Rc<dyn Any>) -> bool {
+        let topic: Rc<str> = topic.into();
+        self.latest
+            .borrow_mut()
+            .insert(topic.clone(), value.clone());
+
+        let mut pending = self.pending.borrow_mut();
+        pending.push(BroadcastMessage { topic, value });
+        pending.len() == 1
+    }
+
+    /// Deliver every queued value to the listeners
+    pub(crate) fn deliver(&self) {
+        let pending = std::mem::take(&mut *self.pending.borrow_mut());
+        for message in pending {
+            self.listeners.call(&message);
+        }
+    }
+
+    fn latest<T: Clone + 'static>(&self, topic: &str) -> Option<T> {
+        self.latest
+            .borrow()
+            .get(topic)
+            .and_then(|value| value.downcast_ref::<T>())
+            .cloned()
+    }
+}
+
+/// Subscribe to a topic that any window can send values to with
+/// [`DesktopService::broadcast`](crate::DesktopService::broadcast)
+///
+/// The signal holds the last value sent to the topic, or `None` if nothing was sent yet. Values of a different type
+/// than `T` are ignored.
+///
+/// ```rust, ignore
+/// // In the settings window
+/// window().broadcast("theme", Theme::Dark);
+///
+/// // In every other window
+/// let theme = use_broadcast::<Theme>("theme");
+/// ```
+pub fn use_broadcast<T: Clone + 'static>(topic: &str) -> Signal<Option<T>> {
+    let mut value = use_signal(|| window().shared.broadcasts.latest::<T>(topic));
+    let topic: Rc<str> = use_hook(|| topic.into());
+
+    use_event_listener(
+        |window| &window.shared.broadcasts.listeners,
+        move |message: BroadcastMessage| {
+            if message.topic != topic {
+                return;
+            }
+
+            if let Some(new) = message.value.downcast_ref::<T>() {
+                value.set(Some(new.clone()));
+            }
+        },
+    );
+
+    value
+}
@@ -0,0 +1,201 @@
+//! Compressing large IPC payloads, for apps that ship big batches of edits or messages
+//!
+//! The page tells the app whether it can decompress with the `initialize` message. From then on, every batch of edits
+//! starts with a flag byte that says whether the rest of it is deflated. Messages from the page are compressed the
+//! same way when they are sent over the [binary IPC channel](crate::Config::with_binary_ipc).
+
+use flate2::{read::ZlibDecoder, write::ZlibEncoder};
+use serde::Deserialize;
+use std::io::{Read, Write};
+
+/// Payloads smaller than this are sent as they are, compressing them costs more than it saves
+pub(crate) const COMPRESSION_THRESHOLD: usize = 16 * 1024;
+
+const RAW: u8 = 0;
+const DEFLATE: u8 = 1;
+
+/// How the edits of a window are framed for the page
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Compression {
+    /// Compression is turned off, the page reads the edits as they are
+    Off,
+    /// The page reads the flag byte, but hasn't said that it can decompress yet
+    Uncompressed,
+    /// Large batches are deflated
+    Deflate,
+}
+
+/// The params of the `initialize` message
+#[derive(Default, Deserialize)]
+pub(crate) struct InitializeMessage {
+    /// The compression formats the page can decompress
+    #[serde(default)]
+    pub(crate) compression: Vec<String>,
+}
+
+impl Compression {
+    /// Settle on a compression with the formats the page supports
+    pub(crate) fn negotiate(self, supported: &[String]) -> Self {
+        match self {
+            Compression::Off => Compression::Off,
+            _ if supported.iter().any(|format| format == "deflate") => Compression::Deflate,
+            _ => Compression::Uncompressed,
+        }
+    }
+
+    /// Frame a batch of edits for the page, compressing it if it's large enough
+    pub(crate) fn encode(self, bytes: Vec<u8>) -> Vec<u8> {
+        match self {
+            Compression::Off => bytes,
+            Compression::Deflate if bytes.len() >= COMPRESSION_THRESHOLD => {
+                let mut encoder = ZlibEncoder::new(vec![DEFLATE], flate2::Compression::fast());
+                match encoder.write_all(&bytes).and_then(|_| encoder.finish()) {
+                    Ok(compressed) => compressed,
+                    Err(err) => {
+                        tracing::error!("Failed to compress edits: {err}");
+                        raw(bytes)
+                    }
+                }
+            }
+            _ => raw(bytes),
+        }
+    }
+}
+
+fn raw(bytes: Vec<u8>) -> Vec<u8> {
+    let mut framed = Vec::with_capacity(bytes.len() + 1);
+    framed.push(RAW);
+    framed.extend_from_slice(&bytes);
+    framed
+}
+
+/// Requests that inflate to more than this are dropped, so a page can't make the app allocate without bounds
+const MAX_DECOMPRESSED_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Inflate the body of a request the page compressed
+pub(crate) fn decompress(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    let mut decoder = ZlibDecoder::new(bytes).take(MAX_DECOMPRESSED_SIZE + 1);
+    match decoder.read_to_end(&mut decompressed) {
+        Ok(_) if decompressed.len() as u64 > MAX_DECOMPRESSED_SIZE => {
+            tracing::error!(
+                "Dropped an IPC request that inflates to more than {MAX_DECOMPRESSED_SIZE} bytes"
+            );
+            None
+        }
+        Ok(_) => Some(decompressed),
+        Err(err) => {
+            tracing::error!("Failed to decompress IPC request: {err}");
+            None
+        }
+    }
+}
+
+/// The interpreter of pages that read compressed edits. It reads the flag byte of every batch and inflates the
+/// compressed ones before they run.
+///
+/// Batches still run in the order they arrived, so a small batch waits for a large one before it that is inflating.
+pub(crate) const DECOMPRESS_EDITS_SCRIPT: &str = r#"
+    class CompressedEditsInterpreter extends NativeInterpreter {
+        run_from_bytes(buffer) {
+            const bytes = new Uint8Array(buffer);
+            this.edits = (this.edits || Promise.resolve())
+                .then(() => {
+                    if (bytes[0] !== 1) {
+                        return buffer.slice(1);
+                    }
+                    const stream = new Blob([bytes.subarray(1)])
+                        .stream()
+                        .pipeThrough(new DecompressionStream("deflate"));
+                    return new Response(stream).arrayBuffer();
+                })
+                .then((edits) => super.run_from_bytes(edits))
+                .catch((err) => console.error("Failed to apply edits", err));
+        }
+    }
+"#;
+
+/// The params of the `initialize` message of a page that reads compressed edits
+pub(crate) const INITIALIZE_PARAMS: &str =
+    r#"{ compression: typeof DecompressionStream === "function" ? ["deflate"] : [] }"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The edits the page reads from a framed batch, like `DECOMPRESS_EDITS_SCRIPT` does
+    fn unframe(framed: &[u8]) -> Vec<u8> {
+        match framed[0] {
+            RAW => framed[1..].to_vec(),
+            DEFLATE => decompress(&framed[1..]).unwrap(),
+            flag => panic!("unknown flag {flag}"),
+        }
+    }
+
+    #[test]
+    fn negotiate() {
+        let deflate = ["gzip".to_string(), "deflate".to_string()];
+        let cases = [
+            (Compression::Off, &deflate[..], Compression::Off),
+            (Compression::Off, &[][..], Compression::Off),
+            (
+                Compression::Uncompressed,
+                &deflate[..],
+                Compression::Deflate,
+            ),
+            (
+                Compression::Uncompressed,
+                &deflate[..1],
+                Compression::Uncompressed,
+            ),
+            (Compression::Deflate, &[][..], Compression::Uncompressed),
+        ];
+
+        for (compression, supported, negotiated) in cases {
+            assert_eq!(
+                compression.negotiate(supported),
+                negotiated,
+                "{compression:?} {supported:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn round_trip_around_the_threshold() {
+        let cases = [
+            (Compression::Deflate, 0, RAW),
+            (Compression::Deflate, COMPRESSION_THRESHOLD - 1, RAW),
+            (Compression::Deflate, COMPRESSION_THRESHOLD, DEFLATE),
+            (Compression::Deflate, 10 * COMPRESSION_THRESHOLD, DEFLATE),
+            (Compression::Uncompressed, 10 * COMPRESSION_THRESHOLD, RAW),
+        ];
+
+        for (compression, size, flag) in cases {
+            let edits: Vec<u8> = (0..size).map(|i| (i % 7) as u8).collect();
+            let framed = compression.encode(edits.clone());
+            assert_eq!(framed[0], flag, "{compression:?} {size}");
+            assert_eq!(unframe(&framed), edits, "{compression:?} {size}");
+        }
+
+        // Repetitive edits shrink
+        let edits = vec![0; 10 * COMPRESSION_THRESHOLD];
+        assert!(Compression::Deflate.encode(edits.clone()).len() < edits.len() / 10);
+
+        // Pages that turned compression off read the edits as they are
+        let edits = vec![1; 10 * COMPRESSION_THRESHOLD];
+        assert_eq!(Compression::Off.encode(edits.clone()), edits);
+    }
+
+    #[test]
+    fn decompress_rejects_invalid_and_oversized_bodies() {
+        assert_eq!(decompress(b"not deflated"), None);
+
+        // A small body that inflates past the limit
+        let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::best());
+        let chunk = vec![0; 1024 * 1024];
+        for _ in 0..=MAX_DECOMPRESSED_SIZE / chunk.len() as u64 {
+            encoder.write_all(&chunk).unwrap();
+        }
+        assert_eq!(decompress(&encoder.finish().unwrap()), None);
+    }
+}
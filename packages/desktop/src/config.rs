@@ -1,7 +1,19 @@
+use crate::{
+    app_handle::AppHandle,
+    crash::CrashConfig,
+    download::{DownloadAction, DownloadHandler, DownloadRequest},
+    file_upload::FileDropConfig,
+    navigation::{NavigationAction, NavigationHandler, NavigationRequest, NewWindowPolicy},
+    permissions::{PermissionHandler, PermissionRequest, PermissionResponse},
+    wayland::WaylandConfig,
+    webview_options::{WKWebViewConfig, WebKitGtkConfig, WebView2Config},
+};
+use include_dir::Dir;
 use std::borrow::Cow;
 use std::path::PathBuf;
 use tao::window::{Icon, WindowBuilder};
 use wry::http::{Request as HttpRequest, Response as HttpResponse};
+use wry::ProxyConfig;
 
 /// The behaviour of the application when the last window is closed.
 #[derive(Copy, Clone, Eq, PartialEq)]
@@ -14,20 +26,67 @@ pub enum WindowCloseBehaviour {
     CloseWindow,
 }
 
+/// How a window created with [`DesktopService::new_window`](crate::DesktopService::new_window) relates to the
+/// window that created it.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum WindowKind {
+    /// Default behaviour, the new window is independent of the window that created it
+    #[default]
+    Standalone,
+    /// The new window stays on top of the window that created it and is closed along with it
+    Child,
+    /// Like [`WindowKind::Child`], but the window that created it can't be interacted with until the modal is closed
+    Modal,
+}
+
 /// The configuration for the desktop application.
 pub struct Config {
     pub(crate) window: WindowBuilder,
     pub(crate) protocols: Vec<WryProtocol>,
     pub(crate) pre_rendered: Option<String>,
     pub(crate) disable_context_menu: bool,
+    pub(crate) open_devtools: bool,
     pub(crate) resource_dir: Option<PathBuf>,
+    pub(crate) embedded_assets: Option<&'static Dir<'static>>,
     pub(crate) data_dir: Option<PathBuf>,
+    pub(crate) ephemeral: bool,
     pub(crate) custom_head: Option<String>,
     pub(crate) custom_index: Option<String>,
+    pub(crate) initialization_scripts: Vec<String>,
     pub(crate) root_name: String,
     pub(crate) background_color: Option<(u8, u8, u8, u8)>,
     pub(crate) last_window_close_behaviour: WindowCloseBehaviour,
     pub(crate) enable_default_menu_bar: bool,
+    pub(crate) hotkeys_zoom: bool,
+    pub(crate) binary_ipc: bool,
+    pub(crate) ipc_compression: bool,
+    pub(crate) ipc_fallback: bool,
+    pub(crate) event_rate: Option<u32>,
+    pub(crate) content_security_policy: Option<String>,
+    pub(crate) ipc_origins: Vec<String>,
+    pub(crate) window_kind: WindowKind,
+    pub(crate) splash_screen: Option<String>,
+    pub(crate) splash_window: Option<WindowBuilder>,
+    #[cfg(feature = "updater")]
+    pub(crate) updater: Option<crate::updater::UpdaterConfig>,
+    pub(crate) resize_border: u32,
+    pub(crate) download_handler: Option<DownloadHandler>,
+    pub(crate) navigation_handler: Option<NavigationHandler>,
+    pub(crate) new_window_policy: NewWindowPolicy,
+    pub(crate) permission_handler: Option<PermissionHandler>,
+    pub(crate) user_agent: Option<String>,
+    pub(crate) proxy: Option<ProxyConfig>,
+    pub(crate) file_drop: FileDropConfig,
+    pub(crate) webview2: WebView2Config,
+    pub(crate) wkwebview: WKWebViewConfig,
+    pub(crate) webkitgtk: WebKitGtkConfig,
+    pub(crate) wayland: WaylandConfig,
+    pub(crate) kiosk: bool,
+    pub(crate) offscreen: bool,
+    pub(crate) single_instance: Option<String>,
+    pub(crate) no_initial_window: bool,
+    pub(crate) app_handle_callback: Option<Box<dyn FnOnce(AppHandle)>>,
+    pub(crate) crash: Option<CrashConfig>,
 }
 
 pub(crate) type WryProtocol = (
@@ -51,14 +110,48 @@ impl Config {
             protocols: Vec::new(),
             pre_rendered: None,
             disable_context_menu: !cfg!(debug_assertions),
+            open_devtools: false,
             resource_dir: None,
+            embedded_assets: None,
             data_dir: None,
+            ephemeral: false,
             custom_head: None,
             custom_index: None,
+            initialization_scripts: Vec::new(),
             root_name: "main".to_string(),
             background_color: None,
             last_window_close_behaviour: WindowCloseBehaviour::LastWindowExitsApp,
             enable_default_menu_bar: true,
+            hotkeys_zoom: false,
+            binary_ipc: false,
+            ipc_compression: false,
+            ipc_fallback: cfg!(any(target_os = "android", target_os = "linux")),
+            event_rate: None,
+            content_security_policy: None,
+            ipc_origins: Vec::new(),
+            window_kind: WindowKind::Standalone,
+            splash_screen: None,
+            splash_window: None,
+            #[cfg(feature = "updater")]
+            updater: None,
+            resize_border: 5,
+            download_handler: None,
+            navigation_handler: None,
+            new_window_policy: NewWindowPolicy::default(),
+            permission_handler: None,
+            user_agent: None,
+            proxy: None,
+            file_drop: FileDropConfig::default(),
+            webview2: WebView2Config::default(),
+            wkwebview: WKWebViewConfig::default(),
+            webkitgtk: WebKitGtkConfig::default(),
+            wayland: WaylandConfig::default(),
+            kiosk: false,
+            offscreen: false,
+            single_instance: None,
+            no_initial_window: false,
+            app_handle_callback: None,
+            crash: None,
         }
     }
 
@@ -70,26 +163,222 @@ impl Config {
         self
     }
 
+    /// Set how many pixels from the edge of an undecorated window can be dragged to resize it.
+    ///
+    /// > Note: `thickness` is `5` by default. Pass `0` to disable resizing from the edges. This has no effect on
+    /// > decorated or non-resizable windows.
+    ///
+    /// Elements with the `data-dioxus-drag-region` attribute move the window when dragged and toggle maximization
+    /// when double clicked, which makes it easy to build a custom titlebar.
+    pub fn with_resize_border(mut self, thickness: u32) -> Self {
+        self.resize_border = thickness;
+        self
+    }
+
+    /// Set whether the webview can be zoomed with the platform's default bindings (Ctrl + scroll and Ctrl + +/-)
+    ///
+    /// > Note: `enable` is `false` by default. On macOS and Linux only the keyboard bindings are supported.
+    pub fn with_hotkeys_zoom(mut self, enable: bool) -> Self {
+        self.hotkeys_zoom = enable;
+        self
+    }
+
+    /// Set whether events and other messages of the page are sent as bytes over the app's custom protocol instead of
+    /// strings over the webview's message channel.
+    ///
+    /// Events posted in the same task are batched into one request of CBOR encoded messages, which helps apps that
+    /// receive bursts of events like pointer moves. Edits are always sent as bytes.
+    ///
+    /// > Note: `enable` is `false` by default. WebKitGTK only passes request bodies to the app since version 2.40.
+    pub fn with_binary_ipc(mut self, enable: bool) -> Self {
+        self.binary_ipc = enable;
+        self
+    }
+
+    /// Set whether large payloads between the app and the page are compressed.
+    ///
+    /// Batches of edits and requests of the [binary IPC channel](Config::with_binary_ipc) above 16 KiB are deflated
+    /// if the webview supports compression streams, which speeds up apps that send big initial renders. Smaller
+    /// payloads are sent as they are.
+    ///
+    /// > Note: `enable` is `false` by default. Webviews without `DecompressionStream` get uncompressed edits.
+    pub fn with_ipc_compression(mut self, enable: bool) -> Self {
+        self.ipc_compression = enable;
+        self
+    }
+
+    /// Set whether pages without a working `window.ipc.postMessage` send their messages over the app's custom
+    /// protocol instead.
+    ///
+    /// Some Android WebView and older WebKitGTK builds don't provide `window.ipc`, so events never reach the app.
+    /// With the fallback, those pages post every message as the body of a request to the custom protocol. Pages where
+    /// `window.ipc` works keep using it.
+    ///
+    /// > Note: `enable` is `true` by default on Android and Linux, and `false` on other platforms.
+    pub fn with_ipc_fallback(mut self, enable: bool) -> Self {
+        self.ipc_fallback = enable;
+        self
+    }
+
+    /// Set how many times per second high-frequency events are sent to the app at most, or `None` to send every
+    /// event.
+    ///
+    /// Pointer moves, touch moves, scrolls, wheels and drags are coalesced in the page: only the latest event of each
+    /// kind and element is sent per interval, so bursts don't flood the event loop. The deltas of coalesced wheel
+    /// events are summed. Other events are never dropped and keep their order.
+    ///
+    /// > Note: The default is `None`, every event is sent.
+    pub fn with_event_rate(mut self, rate: Option<u32>) -> Self {
+        self.event_rate = rate;
+        self
+    }
+
+    /// Set a Content-Security-Policy for the app's index page, to limit what remote or user-generated content in it
+    /// can load and run.
+    ///
+    /// The inline script that starts dioxus is allowed with a nonce, so the policy doesn't need `'unsafe-inline'` for
+    /// it. Inline scripts of a custom index or head have to be allowed by the policy itself. Scripts injected by the
+    /// app, like initialization scripts and `eval`, aren't affected by the policy.
+    ///
+    /// ```rust, ignore
+    /// Config::new().with_content_security_policy("default-src 'self' dioxus:; img-src *; connect-src 'self'")
+    /// ```
+    pub fn with_content_security_policy(mut self, policy: impl Into<String>) -> Self {
+        self.content_security_policy = Some(policy.into());
+        self
+    }
+
+    /// Allow pages from another origin, like `https://example.com`, to send IPC messages to the app.
+    ///
+    /// Messages are checked as they arrive, and only handled if they come from a page of the app itself or of an
+    /// allowed origin, so remote pages the window navigates to can't use the app's native bridge. Requests to the
+    /// app's custom protocol are checked by the origin of the frame that sent them. Messages posted to `window.ipc`
+    /// don't carry their frame, so they are checked against the page the window shows.
+    pub fn with_ipc_origin(mut self, origin: impl Into<String>) -> Self {
+        self.ipc_origins.push(origin.into());
+        self
+    }
+
     /// set the directory from which assets will be searched in release mode
     pub fn with_resource_directory(mut self, path: impl Into<PathBuf>) -> Self {
         self.resource_dir = Some(path.into());
         self
     }
 
+    /// Serve the app's assets from a directory embedded in the binary instead of reading them from disk, so release
+    /// builds don't depend on a `dist` folder next to the executable.
+    ///
+    /// ```rust, ignore
+    /// use dioxus_desktop::include_dir::{include_dir, Dir};
+    ///
+    /// static ASSETS: Dir = include_dir!("$CARGO_MANIFEST_DIR/dist");
+    ///
+    /// Config::new().with_embedded_assets(&ASSETS)
+    /// ```
+    ///
+    /// > Note: Asset handlers registered with [`use_asset_handler`](crate::use_asset_handler) still take precedence.
+    /// > Embedded assets are revalidated with an `ETag`, so an updated binary never serves stale assets from the
+    /// > webview's cache.
+    pub fn with_embedded_assets(mut self, assets: &'static Dir<'static>) -> Self {
+        self.embedded_assets = Some(assets);
+        self
+    }
+
     /// set the directory where data will be stored in release mode.
     ///
-    /// > Note: This **must** be set when bundling on Windows.
+    /// The webview keeps its local storage, IndexedDB, cookies and cache in this directory. Windows with different
+    /// data directories don't share any of them, so giving every account its own directory keeps their sessions
+    /// isolated:
+    ///
+    /// ```rust, ignore
+    /// let cfg = Config::new().with_data_directory(data_root.join("profiles").join(&account.id));
+    /// window.new_window(VirtualDom::new(Account), cfg);
+    /// ```
+    ///
+    /// > Note: This **must** be set when bundling on Windows. WKWebView doesn't support custom data directories, use
+    /// > [`Config::with_ephemeral_session`] to isolate windows on macOS.
     pub fn with_data_directory(mut self, path: impl Into<PathBuf>) -> Self {
         self.data_dir = Some(path.into());
         self
     }
 
+    /// Set whether the window uses an in-memory session. Nothing the page stores is written to disk and everything is
+    /// discarded when the window closes, and the window doesn't share any data with other windows.
+    ///
+    /// > Note: `ephemeral` is `false` by default. On Windows, this requires version 101.0.1210.39 of the WebView2
+    /// > runtime.
+    pub fn with_ephemeral_session(mut self, ephemeral: bool) -> Self {
+        self.ephemeral = ephemeral;
+        self
+    }
+
+    /// Set the user agent the webview sends with its requests instead of the platform's default one.
+    ///
+    /// > Note: Like the rest of the config, this applies to a single window, so every window can have its own user
+    /// > agent.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Send the webview's network requests through an HTTP or SOCKS5 proxy.
+    ///
+    /// ```rust, ignore
+    /// use dioxus_desktop::wry::{ProxyConfig, ProxyEndpoint};
+    ///
+    /// Config::new().with_proxy(ProxyConfig::Http(ProxyEndpoint {
+    ///     host: "proxy.corp.example".into(),
+    ///     port: "8080".into(),
+    /// }))
+    /// ```
+    ///
+    /// > Note: On macOS, wry only applies the proxy with its `mac-proxy` feature, which requires macOS 14. WebView2
+    /// > shares one browser process between all windows that use the same data directory, so give windows with
+    /// > different proxies different data directories on Windows.
+    pub fn with_proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Set the settings of the WebView2 engine used on Windows. This has no effect on other platforms.
+    pub fn with_webview2_config(mut self, config: WebView2Config) -> Self {
+        self.webview2 = config;
+        self
+    }
+
+    /// Set the settings of the WKWebView engine used on macOS and iOS. This has no effect on other platforms.
+    pub fn with_wkwebview_config(mut self, config: WKWebViewConfig) -> Self {
+        self.wkwebview = config;
+        self
+    }
+
+    /// Set the settings of the WebKitGTK engine used on Linux. This has no effect on other platforms.
+    pub fn with_webkitgtk_config(mut self, config: WebKitGtkConfig) -> Self {
+        self.webkitgtk = config;
+        self
+    }
+
+    /// Set the settings used when running under a Wayland compositor. This has no effect on other platforms.
+    pub fn with_wayland_config(mut self, wayland: WaylandConfig) -> Self {
+        self.wayland = wayland;
+        self
+    }
+
     /// Set whether or not the right-click context menu should be disabled.
     pub fn with_disable_context_menu(mut self, disable: bool) -> Self {
         self.disable_context_menu = disable;
         self
     }
 
+    /// Set whether the devtools open as soon as the window is created.
+    ///
+    /// > Note: `open` is `false` by default. This only has an effect in debug builds, or in release builds with the
+    /// > `devtools` feature enabled.
+    pub fn with_devtools_open(mut self, open: bool) -> Self {
+        self.open_devtools = open;
+        self
+    }
+
     /// Set the pre-rendered HTML content
     pub fn with_prerendered(mut self, content: String) -> Self {
         self.pre_rendered = Some(content);
@@ -104,6 +393,167 @@ impl Config {
         self
     }
 
+    /// Sets how this window relates to the window that creates it with
+    /// [`DesktopService::new_window`](crate::DesktopService::new_window).
+    ///
+    /// This has no effect on the first window of the application.
+    pub fn with_window_kind(mut self, kind: WindowKind) -> Self {
+        self.window_kind = kind;
+        self
+    }
+
+    /// Show a splash screen with the given static HTML while the main window is loading.
+    ///
+    /// The splash screen is a small undecorated window that is replaced by the main window once its webview has
+    /// loaded. This has no effect on windows created with [`DesktopService::new_window`](crate::DesktopService::new_window).
+    pub fn with_splash_screen(mut self, html: impl Into<String>) -> Self {
+        self.splash_screen = Some(html.into());
+        self
+    }
+
+    /// Set the window the splash screen is shown in. This only takes effect along with [`Config::with_splash_screen`].
+    pub fn with_splash_window(mut self, window: WindowBuilder) -> Self {
+        self.splash_window = Some(window);
+        self
+    }
+
+    /// Enable the updater, which can be accessed with [`use_updater`](crate::use_updater).
+    ///
+    /// This only takes effect on the config the app is launched with.
+    #[cfg(feature = "updater")]
+    pub fn with_updater(mut self, updater: crate::updater::UpdaterConfig) -> Self {
+        self.updater = Some(updater);
+        self
+    }
+
+    /// Decide what happens to files the webview downloads.
+    ///
+    /// The handler can save the file to a different path or cancel the download. Components can follow the
+    /// download with [`use_download_event`](crate::use_download_event). Without a handler, downloads are saved to
+    /// the path suggested by the webview.
+    pub fn with_download_handler(
+        mut self,
+        handler: impl FnMut(DownloadRequest) -> DownloadAction + 'static,
+    ) -> Self {
+        self.download_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Decide what happens when the page navigates away from the app or asks for a new window.
+    ///
+    /// The handler can let the webview navigate, open the URL in the default browser or in a new window of the app,
+    /// or cancel the navigation. The app's own pages are always allowed. Without a handler, the page can't navigate
+    /// away from the app and new windows follow the [`NewWindowPolicy`].
+    ///
+    /// ```rust, ignore
+    /// Config::new().with_navigation_handler(|request| {
+    ///     if request.url.starts_with("https://docs.example.com") {
+    ///         NavigationAction::OpenInNewWindow
+    ///     } else {
+    ///         NavigationAction::OpenExternally
+    ///     }
+    /// })
+    /// ```
+    pub fn with_navigation_handler(
+        mut self,
+        handler: impl FnMut(&NavigationRequest) -> NavigationAction + 'static,
+    ) -> Self {
+        self.navigation_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Set what happens when the page asks for a new window, like a link with `target="_blank"` or `window.open`.
+    ///
+    /// The policy also applies to new windows the [navigation handler](Config::with_navigation_handler) allows.
+    ///
+    /// > Note: The default is [`NewWindowPolicy::OpenExternally`], which opens the URL in the default browser on every
+    /// > platform.
+    pub fn with_new_window_policy(mut self, policy: NewWindowPolicy) -> Self {
+        self.new_window_policy = policy;
+        self
+    }
+
+    /// Answer permission prompts from the webview, like camera, microphone, geolocation and notification access.
+    ///
+    /// The handler can grant or deny the permission right away, or block on a dialog of its own to ask the user.
+    /// Without a handler, the webview decides, which usually means `getUserMedia` fails silently.
+    ///
+    /// > Note: This is supported on Windows and Linux. WKWebView always shows its own prompt.
+    pub fn with_permission_handler(
+        mut self,
+        handler: impl Fn(&PermissionRequest) -> PermissionResponse + 'static,
+    ) -> Self {
+        self.permission_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Set which files dropped on the window are passed to drag and drop events, and whether dropped directories
+    /// are expanded into the files they contain.
+    pub fn with_file_drop_config(mut self, config: FileDropConfig) -> Self {
+        self.file_drop = config;
+        self
+    }
+
+    /// Set whether the window starts in kiosk mode. See
+    /// [`DesktopService::set_kiosk_mode`](crate::DesktopService::set_kiosk_mode) for what kiosk mode does.
+    ///
+    /// > Note: `enable` is `false` by default.
+    pub fn with_kiosk_mode(mut self, enable: bool) -> Self {
+        self.kiosk = enable;
+        self
+    }
+
+    /// Only allow one instance of the app to run at a time. `id` identifies the app, so it should be unique, like
+    /// `"com.example.editor"`.
+    ///
+    /// When the app is launched while it is already running, the files passed on the command line are handed to the
+    /// running instance, which receives them through [`use_open_file_event`](crate::use_open_file_event), and the event
+    /// loop of the new launch exits before it opens any window. This is how files opened with the app reach the running instance on Windows and Linux.
+    /// Tasks picked from the jump list are handed over the same way and arrive at
+    /// [`use_jump_list_task`](crate::use_jump_list_task).
+    pub fn with_single_instance(mut self, id: impl Into<String>) -> Self {
+        self.single_instance = Some(id.into());
+        self
+    }
+
+    /// Launch the app without opening the main window. The event loop keeps running, so global shortcuts, single
+    /// instance launches and anything else holding an [`AppHandle`] can open it later with
+    /// [`AppHandle::show_main_window`]. On macOS, clicking the dock icon opens it as well.
+    ///
+    /// Combine this with [`WindowCloseBehaviour::LastWindowHides`] to send the app back to the background when the
+    /// window is closed.
+    pub fn with_no_initial_window(mut self) -> Self {
+        self.no_initial_window = true;
+        self
+    }
+
+    /// Render the window offscreen. The webview keeps rendering, but the window is fully transparent, lets clicks pass
+    /// through and is left out of the taskbar, so the app can capture it with
+    /// [`DesktopService::snapshot`](crate::DesktopService::snapshot) to build thumbnails or export pipelines.
+    ///
+    /// > Note: `offscreen` is `false` by default.
+    pub fn with_offscreen(mut self, offscreen: bool) -> Self {
+        self.offscreen = offscreen;
+        self
+    }
+
+    /// Get a handle to the app as soon as the event loop starts, before any window is created.
+    ///
+    /// The handle can be sent to other threads, like the one running a tray icon.
+    pub fn with_app_handle(mut self, callback: impl FnOnce(AppHandle) + 'static) -> Self {
+        self.app_handle_callback = Some(Box::new(callback));
+        self
+    }
+
+    /// Handle panics on the main thread by showing an error dialog, writing a crash report and optionally restarting
+    /// the app, instead of the window just disappearing.
+    ///
+    /// This only takes effect on the config the app is launched with.
+    pub fn with_crash_config(mut self, crash: CrashConfig) -> Self {
+        self.crash = Some(crash);
+        self
+    }
+
     /// Sets the behaviour of the application when the last window is closed.
     pub fn with_close_behaviour(mut self, behaviour: WindowCloseBehaviour) -> Self {
         self.last_window_close_behaviour = behaviour;
@@ -144,6 +594,42 @@ impl Config {
         self
     }
 
+    /// Add a script that runs in every page of the window before any script of the page, including the Dioxus
+    /// interpreter.
+    ///
+    /// This is useful for polyfilling APIs or exposing bridges to the page. Scripts run in the order they were added.
+    ///
+    /// ```rust, ignore
+    /// Config::new().with_initialization_script("window.structuredClone ??= (value) => JSON.parse(JSON.stringify(value));")
+    /// ```
+    pub fn with_initialization_script(mut self, script: impl Into<String>) -> Self {
+        self.initialization_scripts.push(script.into());
+        self
+    }
+
+    /// Load a script from a URL and run it in every page of the window before any script of the page, like
+    /// [`Config::with_initialization_script`].
+    ///
+    /// > Note: The script is loaded synchronously while the page starts, so prefer assets of the app, like
+    /// > `/assets/polyfill.js`, over remote URLs. Remote URLs have to allow the app's origin with CORS.
+    pub fn with_initialization_script_url(self, url: impl AsRef<str>) -> Self {
+        let url = serde_json::to_string(url.as_ref()).unwrap_or_default();
+        self.with_initialization_script(format!(
+            r#"
+            (function () {{
+                const request = new XMLHttpRequest();
+                request.open("GET", {url}, false);
+                request.send();
+                if (request.status >= 200 && request.status < 300) {{
+                    (0, eval)(request.responseText);
+                }} else {{
+                    console.error("Failed to load initialization script " + {url} + ": " + request.status);
+                }}
+            }})();
+            "#
+        ))
+    }
+
     /// Set the name of the element that Dioxus will use as the root.
     ///
     /// This is akint to calling React.render() on the element with the specified name.
@@ -0,0 +1,278 @@
+use crate::{menubar::MenuRole, window};
+use dioxus_core::use_hook;
+use dioxus_hooks::use_callback;
+use std::{cell::RefCell, rc::Rc};
+use tao::window::Window;
+
+/// Keep the webview from opening its own context menu for right-clicks on elements with the
+/// `data-dioxus-context-menu` attribute
+///
+/// The `contextmenu` event still reaches the element's handlers, which open the native menu.
+pub(crate) const CONTEXT_MENU_SCRIPT: &str = r#"
+(function () {
+    document.addEventListener("contextmenu", function (e) {
+        if (e.target instanceof Element && e.target.closest("[data-dioxus-context-menu]")) {
+            e.preventDefault();
+        }
+    });
+})();
+"#;
+
+/// A native context menu that can be shown with [`DesktopService::show_context_menu`](crate::DesktopService::show_context_menu)
+/// or [`use_context_menu`].
+///
+/// ```rust, ignore
+/// let menu = ContextMenu::new()
+///     .item("copy", "Copy")
+///     .item("paste", "Paste")
+///     .separator()
+///     .submenu("Share", ContextMenu::new().item("email", "Email"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContextMenu {
+    items: Vec<ContextMenuItem>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum ContextMenuItem {
+    Item {
+        id: String,
+        label: String,
+        enabled: bool,
+    },
+    Separator,
+    Role(MenuRole),
+    Submenu {
+        label: String,
+        menu: ContextMenu,
+    },
+}
+
+impl ContextMenu {
+    /// Create an empty context menu
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an item. `id` is reported back in the [`ContextMenuEvent`] when the item is selected.
+    pub fn item(mut self, id: impl Into<String>, label: impl Into<String>) -> Self {
+        self.items.push(ContextMenuItem::Item {
+            id: id.into(),
+            label: label.into(),
+            enabled: true,
+        });
+        self
+    }
+
+    /// Add an item that is shown greyed out and can't be selected
+    pub fn disabled_item(mut self, id: impl Into<String>, label: impl Into<String>) -> Self {
+        self.items.push(ContextMenuItem::Item {
+            id: id.into(),
+            label: label.into(),
+            enabled: false,
+        });
+        self
+    }
+
+    /// Add a separator line
+    pub fn separator(mut self) -> Self {
+        self.items.push(ContextMenuItem::Separator);
+        self
+    }
+
+    /// Add an item with behavior built into the platform, like [`MenuRole::Copy`]. Selecting it doesn't produce a
+    /// [`ContextMenuEvent`].
+    pub fn role(mut self, role: MenuRole) -> Self {
+        self.items.push(ContextMenuItem::Role(role));
+        self
+    }
+
+    /// Add a nested menu
+    pub fn submenu(mut self, label: impl Into<String>, menu: ContextMenu) -> Self {
+        self.items.push(ContextMenuItem::Submenu {
+            label: label.into(),
+            menu,
+        });
+        self
+    }
+}
+
+/// The item of a [`ContextMenu`] that the user selected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextMenuEvent {
+    /// The id the item was created with
+    pub id: String,
+}
+
+/// Tracks the context menu that is currently open so selections can be routed back to the component that opened it
+///
+/// Only one context menu can be open at a time, opening a new one replaces the previous one.
+#[derive(Default)]
+pub(crate) struct ContextMenuRegistry {
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    open: RefCell<Option<OpenContextMenu>>,
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+struct OpenContextMenu {
+    // The native menu has to outlive the popup on some platforms
+    _menu: muda::Menu,
+    ids: std::collections::HashMap<muda::MenuId, String>,
+    on_select: Box<dyn FnMut(ContextMenuEvent)>,
+}
+
+impl ContextMenuRegistry {
+    #[allow(unused)]
+    pub(crate) fn show(
+        &self,
+        window: &Window,
+        menu: &ContextMenu,
+        on_select: Box<dyn FnMut(ContextMenuEvent)>,
+    ) {
+        #[cfg(not(any(target_os = "ios", target_os = "android")))]
+        {
+            use muda::ContextMenu as _;
+
+            let mut ids = Default::default();
+            let native = build_menu(menu, &mut ids);
+
+            // Store the menu before showing it - on some platforms the popup blocks until an item is selected
+            let open = OpenContextMenu {
+                _menu: native.clone(),
+                ids,
+                on_select,
+            };
+            *self.open.borrow_mut() = Some(open);
+
+            #[cfg(target_os = "windows")]
+            {
+                use tao::platform::windows::WindowExtWindows;
+                native.show_context_menu_for_hwnd(window.hwnd(), None);
+            }
+
+            #[cfg(target_os = "linux")]
+            {
+                use tao::platform::unix::WindowExtUnix;
+                native.show_context_menu_for_gtk_window(window.gtk_window().as_ref(), None);
+            }
+
+            #[cfg(target_os = "macos")]
+            {
+                use tao::platform::macos::WindowExtMacOS;
+                native.show_context_menu_for_nsview(window.ns_view() as _, None);
+            }
+        }
+
+        #[cfg(any(target_os = "ios", target_os = "android"))]
+        tracing::warn!("Context menus are not supported on this platform");
+    }
+
+    /// Call the handler of the open context menu if the event came from one of its items
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    pub(crate) fn handle_menu_event(&self, event: &muda::MenuEvent) {
+        let Some(id) = self
+            .open
+            .borrow()
+            .as_ref()
+            .and_then(|menu| menu.ids.get(event.id()).cloned())
+        else {
+            return;
+        };
+
+        // Selecting an item closes the menu. Take it out before calling the handler so it can open another one
+        if let Some(mut menu) = self.open.borrow_mut().take() {
+            (menu.on_select)(ContextMenuEvent { id });
+        }
+    }
+}
+
+/// Create the native menu, recording the id of every item that reports selections
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+pub(crate) fn build_menu(
+    menu: &ContextMenu,
+    ids: &mut std::collections::HashMap<muda::MenuId, String>,
+) -> muda::Menu {
+    let native = muda::Menu::new();
+    for item in build_items(&menu.items, ids) {
+        _ = native.append(item.as_ref());
+    }
+    native
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+fn build_items(
+    items: &[ContextMenuItem],
+    ids: &mut std::collections::HashMap<muda::MenuId, String>,
+) -> Vec<Box<dyn muda::IsMenuItem>> {
+    items
+        .iter()
+        .map(|item| -> Box<dyn muda::IsMenuItem> {
+            match item {
+                ContextMenuItem::Item { id, label, enabled } => {
+                    let native = muda::MenuItem::new(label, *enabled, None);
+                    ids.insert(native.id().clone(), id.clone());
+                    Box::new(native)
+                }
+                ContextMenuItem::Separator => Box::new(muda::PredefinedMenuItem::separator()),
+                ContextMenuItem::Role(role) => Box::new(crate::menubar::predefined(*role)),
+                ContextMenuItem::Submenu { label, menu } => {
+                    let native = muda::Submenu::new(label, true);
+                    for item in build_items(&menu.items, ids) {
+                        _ = native.append(item.as_ref());
+                    }
+                    Box::new(native)
+                }
+            }
+        })
+        .collect()
+}
+
+/// A handle to show context menus from a component, created with [`use_context_menu`]
+#[derive(Clone)]
+pub struct UseContextMenu {
+    selected: Rc<RefCell<Option<ContextMenuEvent>>>,
+    callback: dioxus_hooks::UseCallback<()>,
+}
+
+impl UseContextMenu {
+    /// Show the menu at the mouse cursor
+    pub fn show(&self, menu: &ContextMenu) {
+        let selected = self.selected.clone();
+        let callback = self.callback;
+        window().show_context_menu(menu, move |event| {
+            *selected.borrow_mut() = Some(event);
+            callback.call();
+        });
+    }
+}
+
+/// Show native context menus, with selections delivered to `on_select` in the scope of this component.
+///
+/// Right-clicks on elements with the `data-dioxus-context-menu` attribute, and on their children, don't open the
+/// webview's own context menu, so the element can show its native one instead:
+///
+/// ```rust, ignore
+/// let menu = use_context_menu(|event| println!("selected {}", event.id));
+///
+/// rsx! {
+///     div {
+///         "data-dioxus-context-menu": true,
+///         oncontextmenu: move |_| menu.show(&ContextMenu::new().item("copy", "Copy")),
+///         "Right click me"
+///     }
+/// }
+/// ```
+pub fn use_context_menu(mut on_select: impl FnMut(ContextMenuEvent) + 'static) -> UseContextMenu {
+    let selected: Rc<RefCell<Option<ContextMenuEvent>>> = use_hook(Default::default);
+
+    let callback = use_callback({
+        let selected = selected.clone();
+        move || {
+            if let Some(event) = selected.borrow_mut().take() {
+                on_select(event);
+            }
+        }
+    });
+
+    UseContextMenu { selected, callback }
+}
@@ -0,0 +1,421 @@
+//! Access to the cookie store of the native webview, including HttpOnly cookies that `document.cookie` can't see
+
+use futures_channel::oneshot;
+use std::future::Future;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+use wry::WebView;
+
+/// A cookie stored by the webview
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cookie {
+    /// The name of the cookie
+    pub name: String,
+    /// The value of the cookie
+    pub value: String,
+    /// The domain the cookie is sent to, like `example.com`
+    pub domain: String,
+    /// The path the cookie is sent to
+    pub path: String,
+    /// When the cookie expires. Session cookies without an expiry date are removed when the app exits.
+    pub expires: Option<SystemTime>,
+    /// Whether the cookie is hidden from scripts running in the page
+    pub http_only: bool,
+    /// Whether the cookie is only sent over https
+    pub secure: bool,
+}
+
+impl Cookie {
+    /// Create a session cookie for the given domain with the path set to `/`
+    pub fn new(
+        name: impl Into<String>,
+        value: impl Into<String>,
+        domain: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            domain: domain.into(),
+            path: "/".to_string(),
+            expires: None,
+            http_only: false,
+            secure: false,
+        }
+    }
+}
+
+/// An error that can occur when accessing the cookie store
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum CookieError {
+    /// The platform webview doesn't expose its cookie store
+    #[error("Cookies are not supported on this platform")]
+    Unsupported,
+    /// The webview went away before the operation completed
+    #[error("The webview was closed before the cookie operation completed")]
+    Cancelled,
+    /// The platform webview reported an error
+    #[error("Failed to access the cookie store: {0}")]
+    Platform(String),
+}
+
+type CookieResult<T> = Result<T, CookieError>;
+
+/// The cookie store of a window, returned by [`DesktopService::cookies`](crate::DesktopService::cookies)
+///
+/// Windows that share a data directory share their cookies. The operations start right away and the returned futures
+/// resolve once the webview is done, so they don't hold on to the window.
+///
+/// > Note: This is supported on Windows and Linux. On other platforms every operation fails with
+/// > [`CookieError::Unsupported`].
+pub struct CookieStore<'a> {
+    webview: &'a WebView,
+}
+
+impl<'a> CookieStore<'a> {
+    pub(crate) fn new(webview: &'a WebView) -> Self {
+        Self { webview }
+    }
+
+    /// Get the cookies that would be sent with a request to the given url
+    pub fn get(&self, url: &str) -> impl Future<Output = CookieResult<Vec<Cookie>>> + 'static {
+        let (tx, rx) = oneshot::channel();
+        if let Err(err) = platform::get(self.webview, url, tx) {
+            return resolved(Err(err));
+        }
+        received(rx)
+    }
+
+    /// Add a cookie, or replace the cookie with the same name, domain and path
+    pub fn set(&self, cookie: Cookie) -> impl Future<Output = CookieResult<()>> + 'static {
+        let (tx, rx) = oneshot::channel();
+        if let Err(err) = platform::set(self.webview, cookie, tx) {
+            return resolved(Err(err));
+        }
+        received(rx)
+    }
+
+    /// Delete the cookie with the given name from a domain
+    pub fn delete(
+        &self,
+        name: &str,
+        domain: &str,
+    ) -> impl Future<Output = CookieResult<()>> + 'static {
+        let (tx, rx) = oneshot::channel();
+        if let Err(err) = platform::delete(self.webview, name, domain, tx) {
+            return resolved(Err(err));
+        }
+        received(rx)
+    }
+
+    /// Delete every cookie in the store, for example when the user logs out
+    pub fn clear(&self) -> impl Future<Output = CookieResult<()>> + 'static {
+        let (tx, rx) = oneshot::channel();
+        if let Err(err) = platform::clear(self.webview, tx) {
+            return resolved(Err(err));
+        }
+        received(rx)
+    }
+}
+
+type Sender<T> = oneshot::Sender<CookieResult<T>>;
+
+fn resolved<T: 'static>(
+    result: CookieResult<T>,
+) -> std::pin::Pin<Box<dyn Future<Output = CookieResult<T>>>> {
+    Box::pin(std::future::ready(result))
+}
+
+fn received<T: 'static>(
+    rx: oneshot::Receiver<CookieResult<T>>,
+) -> std::pin::Pin<Box<dyn Future<Output = CookieResult<T>>>> {
+    Box::pin(async move { rx.await.unwrap_or(Err(CookieError::Cancelled)) })
+}
+
+/// Convert a number of seconds since the unix epoch into a [`SystemTime`]. Negative numbers mean there is no expiry
+#[allow(unused)]
+fn expiry_from_unix(seconds: f64) -> Option<SystemTime> {
+    (seconds >= 0.0).then(|| UNIX_EPOCH + Duration::from_secs_f64(seconds))
+}
+
+#[allow(unused)]
+fn expiry_to_unix(expires: Option<SystemTime>) -> Option<f64> {
+    expires.map(|time| {
+        time.duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64()
+    })
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+    use webview2_com::{
+        take_pwstr, GetCookiesCompletedHandler, Microsoft::Web::WebView2::Win32::*,
+    };
+    use windows::{
+        core::{Interface, HSTRING, PWSTR},
+        Win32::Foundation::BOOL,
+    };
+    use wry::WebViewExtWindows;
+
+    fn manager(webview: &WebView) -> CookieResult<ICoreWebView2CookieManager> {
+        unsafe {
+            webview
+                .controller()
+                .CoreWebView2()
+                .and_then(|core| core.cast::<ICoreWebView2_2>())
+                .and_then(|core| core.CookieManager())
+                .map_err(|err| CookieError::Platform(err.to_string()))
+        }
+    }
+
+    fn string(get: impl FnOnce(*mut PWSTR) -> windows::core::Result<()>) -> String {
+        let mut value = PWSTR::null();
+        match get(&mut value) {
+            Ok(()) => take_pwstr(value),
+            Err(_) => String::new(),
+        }
+    }
+
+    fn flag(get: impl FnOnce(*mut BOOL) -> windows::core::Result<()>) -> bool {
+        let mut value = BOOL::default();
+        get(&mut value).is_ok() && value.as_bool()
+    }
+
+    fn read_cookie(cookie: &ICoreWebView2Cookie) -> Cookie {
+        unsafe {
+            let mut expires = -1.0;
+            _ = cookie.Expires(&mut expires);
+            let mut session = BOOL::default();
+            _ = cookie.IsSession(&mut session);
+
+            Cookie {
+                name: string(|value| cookie.Name(value)),
+                value: string(|value| cookie.Value(value)),
+                domain: string(|value| cookie.Domain(value)),
+                path: string(|value| cookie.Path(value)),
+                expires: if session.as_bool() {
+                    None
+                } else {
+                    expiry_from_unix(expires)
+                },
+                http_only: flag(|value| cookie.IsHttpOnly(value)),
+                secure: flag(|value| cookie.IsSecure(value)),
+            }
+        }
+    }
+
+    pub(super) fn get(webview: &WebView, url: &str, tx: Sender<Vec<Cookie>>) -> CookieResult<()> {
+        let manager = manager(webview)?;
+        let handler = GetCookiesCompletedHandler::create(Box::new(move |result, list| {
+            let cookies = result.and_then(|_| {
+                let mut cookies = Vec::new();
+                if let Some(list) = list {
+                    let mut count = 0;
+                    unsafe {
+                        list.Count(&mut count)?;
+                        for index in 0..count {
+                            cookies.push(read_cookie(&list.GetValueAtIndex(index)?));
+                        }
+                    }
+                }
+                Ok(cookies)
+            });
+            _ = tx.send(cookies.map_err(|err| CookieError::Platform(err.to_string())));
+            Ok(())
+        }));
+
+        unsafe { manager.GetCookies(&HSTRING::from(url), &handler) }
+            .map_err(|err| CookieError::Platform(err.to_string()))
+    }
+
+    pub(super) fn set(webview: &WebView, cookie: Cookie, tx: Sender<()>) -> CookieResult<()> {
+        let manager = manager(webview)?;
+        let result = unsafe {
+            manager
+                .CreateCookie(
+                    &HSTRING::from(&cookie.name),
+                    &HSTRING::from(&cookie.value),
+                    &HSTRING::from(&cookie.domain),
+                    &HSTRING::from(&cookie.path),
+                )
+                .and_then(|native| {
+                    native.SetIsHttpOnly(cookie.http_only)?;
+                    native.SetIsSecure(cookie.secure)?;
+                    if let Some(expires) = expiry_to_unix(cookie.expires) {
+                        native.SetExpires(expires)?;
+                    }
+                    manager.AddOrUpdateCookie(&native)
+                })
+        };
+
+        // Updating the store is synchronous on WebView2
+        _ = tx.send(result.map_err(|err| CookieError::Platform(err.to_string())));
+        Ok(())
+    }
+
+    pub(super) fn delete(
+        webview: &WebView,
+        name: &str,
+        domain: &str,
+        tx: Sender<()>,
+    ) -> CookieResult<()> {
+        let manager = manager(webview)?;
+        let result = unsafe {
+            manager.DeleteCookiesWithDomainAndPath(
+                &HSTRING::from(name),
+                &HSTRING::from(domain),
+                &HSTRING::new(),
+            )
+        };
+        _ = tx.send(result.map_err(|err| CookieError::Platform(err.to_string())));
+        Ok(())
+    }
+
+    pub(super) fn clear(webview: &WebView, tx: Sender<()>) -> CookieResult<()> {
+        let manager = manager(webview)?;
+        let result = unsafe { manager.DeleteAllCookies() };
+        _ = tx.send(result.map_err(|err| CookieError::Platform(err.to_string())));
+        Ok(())
+    }
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+mod platform {
+    use super::*;
+    use webkit2gtk::{
+        gio::Cancellable, glib::TimeSpan, soup, CookieManager, CookieManagerExt, WebViewExt,
+        WebsiteDataManagerExt, WebsiteDataTypes,
+    };
+    use wry::WebViewExtUnix;
+
+    fn manager(webview: &WebView) -> CookieResult<CookieManager> {
+        webview
+            .webview()
+            .website_data_manager()
+            .and_then(|manager| manager.cookie_manager())
+            .ok_or(CookieError::Unsupported)
+    }
+
+    fn read_cookie(cookie: &mut soup::Cookie) -> Cookie {
+        Cookie {
+            name: cookie.name().map(Into::into).unwrap_or_default(),
+            value: cookie.value().map(Into::into).unwrap_or_default(),
+            domain: cookie.domain().map(Into::into).unwrap_or_default(),
+            path: cookie.path().map(Into::into).unwrap_or_default(),
+            expires: cookie
+                .expires()
+                .and_then(|expires| expiry_from_unix(expires.to_unix() as f64)),
+            http_only: cookie.is_http_only(),
+            secure: cookie.is_secure(),
+        }
+    }
+
+    fn native_cookie(cookie: &Cookie) -> soup::Cookie {
+        // Session cookies have a max age of -1
+        let max_age = match cookie.expires {
+            Some(expires) => expires
+                .duration_since(SystemTime::now())
+                .unwrap_or_default()
+                .as_secs()
+                .min(i32::MAX as u64) as i32,
+            None => -1,
+        };
+
+        let mut native = soup::Cookie::new(
+            &cookie.name,
+            &cookie.value,
+            &cookie.domain,
+            &cookie.path,
+            max_age,
+        );
+        native.set_http_only(cookie.http_only);
+        native.set_secure(cookie.secure);
+        native
+    }
+
+    pub(super) fn get(webview: &WebView, url: &str, tx: Sender<Vec<Cookie>>) -> CookieResult<()> {
+        manager(webview)?.cookies(url, None::<&Cancellable>, move |result| {
+            _ = tx.send(
+                result
+                    .map(|mut cookies| cookies.iter_mut().map(read_cookie).collect())
+                    .map_err(|err| CookieError::Platform(err.to_string())),
+            );
+        });
+        Ok(())
+    }
+
+    pub(super) fn set(webview: &WebView, cookie: Cookie, tx: Sender<()>) -> CookieResult<()> {
+        manager(webview)?.add_cookie(
+            &mut native_cookie(&cookie),
+            None::<&Cancellable>,
+            move |result| {
+                _ = tx.send(result.map_err(|err| CookieError::Platform(err.to_string())));
+            },
+        );
+        Ok(())
+    }
+
+    pub(super) fn delete(
+        webview: &WebView,
+        name: &str,
+        domain: &str,
+        tx: Sender<()>,
+    ) -> CookieResult<()> {
+        let mut cookie = native_cookie(&Cookie::new(name, "", domain));
+        manager(webview)?.delete_cookie(&mut cookie, None::<&Cancellable>, move |result| {
+            _ = tx.send(result.map_err(|err| CookieError::Platform(err.to_string())));
+        });
+        Ok(())
+    }
+
+    pub(super) fn clear(webview: &WebView, tx: Sender<()>) -> CookieResult<()> {
+        let Some(manager) = webview.webview().website_data_manager() else {
+            return Err(CookieError::Unsupported);
+        };
+        manager.clear(
+            WebsiteDataTypes::COOKIES,
+            TimeSpan(0),
+            None::<&Cancellable>,
+            move |result| {
+                _ = tx.send(result.map_err(|err| CookieError::Platform(err.to_string())));
+            },
+        );
+        Ok(())
+    }
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+)))]
+mod platform {
+    use super::*;
+
+    pub(super) fn get(_: &WebView, _: &str, _: Sender<Vec<Cookie>>) -> CookieResult<()> {
+        Err(CookieError::Unsupported)
+    }
+
+    pub(super) fn set(_: &WebView, _: Cookie, _: Sender<()>) -> CookieResult<()> {
+        Err(CookieError::Unsupported)
+    }
+
+    pub(super) fn delete(_: &WebView, _: &str, _: &str, _: Sender<()>) -> CookieResult<()> {
+        Err(CookieError::Unsupported)
+    }
+
+    pub(super) fn clear(_: &WebView, _: Sender<()>) -> CookieResult<()> {
+        Err(CookieError::Unsupported)
+    }
+}
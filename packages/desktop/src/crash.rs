@@ -0,0 +1,202 @@
+//! Report panics to the user instead of letting the window disappear, and optionally restart the app
+
+use std::any::Any;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The environment variable that counts how often the app was restarted after a crash, so a crash on startup
+/// doesn't turn into a restart loop
+const RESTART_COUNT_VAR: &str = "DIOXUS_CRASH_RESTARTS";
+
+/// What happens when the app panics
+///
+/// ```rust, ignore
+/// let crash = CrashConfig::new()
+///     .with_report_dir(std::env::temp_dir().join("my-app-crashes"))
+///     .with_restart(3);
+///
+/// LaunchBuilder::desktop().with_cfg(Config::new().with_crash_config(crash)).launch(app)
+/// ```
+///
+/// The previous panic hook still runs first, so panics keep showing up in the terminal.
+#[derive(Debug, Clone)]
+pub struct CrashConfig {
+    pub(crate) dialog: bool,
+    pub(crate) report_dir: Option<PathBuf>,
+    pub(crate) max_restarts: u32,
+}
+
+impl Default for CrashConfig {
+    fn default() -> Self {
+        Self {
+            dialog: true,
+            report_dir: None,
+            max_restarts: 0,
+        }
+    }
+}
+
+impl CrashConfig {
+    /// Create the default crash handling, which shows a native error dialog and exits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether a native dialog with the panic message is shown before the app exits.
+    ///
+    /// > Note: `enable` is `true` by default. The dialog is never shown on iOS and Android.
+    pub fn with_dialog(mut self, enable: bool) -> Self {
+        self.dialog = enable;
+        self
+    }
+
+    /// Write a crash report with the panic message, location and a backtrace to a new file in the given directory.
+    pub fn with_report_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.report_dir = Some(dir.into());
+        self
+    }
+
+    /// Start the app again after it crashed, at most `max_restarts` times. The count carries over to the restarted
+    /// app, so a crash on startup doesn't restart the app forever.
+    ///
+    /// > Note: `max_restarts` is `0` by default, which disables restarting. The restarted app gets the same command
+    /// > line arguments.
+    pub fn with_restart(mut self, max_restarts: u32) -> Self {
+        self.max_restarts = max_restarts;
+        self
+    }
+
+    /// Install the panic hook. This is called once when the app is launched, on the thread that runs the event loop.
+    pub(crate) fn install(self) {
+        let previous = std::panic::take_hook();
+        let event_loop_thread = std::thread::current().id();
+
+        std::panic::set_hook(Box::new(move |info| {
+            previous(info);
+
+            // Panics on other threads, like in spawned tasks, don't take down the app
+            if std::thread::current().id() != event_loop_thread {
+                return;
+            }
+
+            // Only handle the first panic, a panic while handling a panic just exits
+            static HANDLING: std::sync::atomic::AtomicBool =
+                std::sync::atomic::AtomicBool::new(false);
+            if HANDLING.swap(true, std::sync::atomic::Ordering::SeqCst) {
+                return;
+            }
+
+            let message = panic_message(info.payload());
+            let location = info.location().map(|location| location.to_string());
+            let report = crash_report(&message, location.as_deref());
+
+            if let Some(dir) = &self.report_dir {
+                match write_report(dir, &report) {
+                    Ok(path) => tracing::info!("Wrote crash report to {}", path.display()),
+                    Err(err) => tracing::error!("Failed to write crash report: {err}"),
+                }
+            }
+
+            let restarts = std::env::var(RESTART_COUNT_VAR)
+                .ok()
+                .and_then(|count| count.parse::<u32>().ok())
+                .unwrap_or(0);
+            let restart = restarts < self.max_restarts;
+
+            if self.dialog {
+                show_dialog(&message, restart);
+            }
+
+            if restart {
+                restart_app(restarts + 1);
+            }
+
+            // Unwinding through the event loop isn't supported on every platform, exit before it aborts
+            std::process::exit(1);
+        }));
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Unknown panic".to_string())
+}
+
+fn crash_report(message: &str, location: Option<&str>) -> String {
+    let mut report = String::new();
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    _ = writeln!(report, "time: {timestamp}");
+    _ = writeln!(report, "dioxus-desktop: {}", env!("CARGO_PKG_VERSION"));
+    _ = writeln!(
+        report,
+        "os: {} {}",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    _ = writeln!(report, "message: {message}");
+    if let Some(location) = location {
+        _ = writeln!(report, "location: {location}");
+    }
+    _ = writeln!(report, "\n{}", std::backtrace::Backtrace::force_capture());
+
+    report
+}
+
+fn write_report(dir: &std::path::Path, report: &str) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let path = dir.join(format!("crash-{timestamp}.txt"));
+    std::fs::write(&path, report)?;
+
+    Ok(path)
+}
+
+#[allow(unused_variables)]
+fn show_dialog(message: &str, restarting: bool) {
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    {
+        let action = if restarting {
+            "The app will restart."
+        } else {
+            "The app will close."
+        };
+
+        rfd::MessageDialog::new()
+            .set_level(rfd::MessageLevel::Error)
+            .set_title("The app crashed")
+            .set_description(format!("{message}\n\n{action}"))
+            .set_buttons(rfd::MessageButtons::Ok)
+            .show();
+    }
+}
+
+fn restart_app(restarts: u32) {
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(err) => {
+            tracing::error!("Failed to find the executable to restart: {err}");
+            return;
+        }
+    };
+
+    let result = std::process::Command::new(exe)
+        .args(std::env::args_os().skip(1))
+        .env(RESTART_COUNT_VAR, restarts.to_string())
+        .spawn();
+
+    if let Err(err) = result {
+        tracing::error!("Failed to restart the app: {err}");
+    }
+}
@@ -1,27 +1,54 @@
 use crate::{
+    animation_frame::PollLease,
     app::SharedContext,
     assets::AssetHandlerRegistry,
+    attention::{self, UserAttention},
+    context_menu::{ContextMenu, ContextMenuEvent},
+    cookies::CookieStore,
+    download::DownloadEvent,
+    drag_image::DragImage,
     edits::EditQueue,
+    event_handlers::EventListeners,
+    file_dialog::{self, FileDialog},
     file_upload::NativeFileHover,
+    find::{self, FindOptions, FindResult},
     ipc::UserWindowEvent,
+    ipc_handlers::IpcHandlerRegistry,
+    ipc_tracing::{self, IpcTimer},
+    kiosk::{self, KioskState},
+    menubar::{MenuItemHandle, MenuRole},
+    monitor::{self, MonitorInfo},
+    notification::{self, Notification},
+    page_load::PageLoadEvent,
+    power::KeepAwakeGuard,
     query::QueryEngine,
+    safe_area::{self, SafeAreaInsets},
     shortcut::{HotKey, ShortcutHandle, ShortcutRegistryError},
+    snapshot::{self, SnapshotError},
     webview::WebviewInstance,
-    AssetRequest, Config, WryEventHandler,
+    window_chrome,
+    window_effect::{apply_window_effect, WindowEffect},
+    window_event::DesktopWindowEvent,
+    window_shortcut::{WindowShortcutHandle, WindowShortcutRegistry},
+    AssetRequest, Config, WindowKind, WryEventHandler,
 };
 use dioxus_core::{
-    prelude::{current_scope_id, ScopeId},
+    prelude::{current_scope_id, ComponentFunction, ScopeId},
     VirtualDom,
 };
 use dioxus_interpreter_js::MutationState;
 use std::{
-    cell::RefCell,
+    any::Any,
+    cell::{Cell, RefCell},
+    future::Future,
+    path::PathBuf,
     rc::{Rc, Weak},
 };
 use tao::{
+    dpi::Position,
     event::Event,
     event_loop::EventLoopWindowTarget,
-    window::{Fullscreen as WryFullscreen, Window, WindowId},
+    window::{Fullscreen as WryFullscreen, Icon, Window, WindowBuilder, WindowId},
 };
 use wry::{RequestAsyncResponder, WebView};
 
@@ -65,8 +92,29 @@ pub struct DesktopService {
     pub(super) query: QueryEngine,
     pub(crate) edit_queue: EditQueue,
     pub(crate) mutation_state: RefCell<MutationState>,
+    pub(crate) edits_pending: Cell<bool>,
     pub(crate) asset_handlers: AssetHandlerRegistry,
     pub(crate) file_hover: NativeFileHover,
+    pub(crate) download_listeners: EventListeners<DownloadEvent>,
+    pub(crate) find_listeners: EventListeners<FindResult>,
+    pub(crate) page_load_listeners: EventListeners<PageLoadEvent>,
+    pub(crate) window_event_listeners: EventListeners<DesktopWindowEvent>,
+    pub(crate) window_shortcuts: WindowShortcutRegistry,
+    pub(crate) ipc_handlers: IpcHandlerRegistry,
+    pub(crate) zoom: Cell<f64>,
+    pub(crate) window_effect: Cell<Option<WindowEffect>>,
+    pub(crate) kiosk: RefCell<Option<KioskState>>,
+    pub(crate) always_on_top: Cell<bool>,
+    pub(crate) ignore_cursor_events: Cell<bool>,
+    pub(crate) background_color: Cell<Option<(u8, u8, u8, u8)>>,
+    pub(crate) safe_area: Cell<SafeAreaInsets>,
+
+    // The menu bar of the window, which has to be kept alive as long as the window.
+    // Currently it's a box<dyn any> because we support a number of platforms like ios where muda does not give us a
+    // menu type. It sucks but alas.
+    //
+    // This would be a good thing for someone looking to contribute to fix.
+    pub(crate) menu: Option<Box<dyn Any>>,
 
     #[cfg(target_os = "ios")]
     pub(crate) views: Rc<RefCell<Vec<*mut objc::runtime::Object>>>,
@@ -89,7 +137,10 @@ impl DesktopService {
         edit_queue: EditQueue,
         asset_handlers: AssetHandlerRegistry,
         file_hover: NativeFileHover,
+        menu: Option<Box<dyn Any>>,
     ) -> Self {
+        let safe_area = safe_area::safe_area_insets(&window);
+
         Self {
             window,
             webview,
@@ -97,7 +148,22 @@ impl DesktopService {
             edit_queue,
             asset_handlers,
             file_hover,
+            download_listeners: Default::default(),
+            find_listeners: Default::default(),
+            page_load_listeners: Default::default(),
+            window_event_listeners: Default::default(),
+            window_shortcuts: Default::default(),
+            ipc_handlers: Default::default(),
+            zoom: Cell::new(1.0),
+            window_effect: Cell::new(None),
+            kiosk: RefCell::new(None),
+            always_on_top: Cell::new(false),
+            ignore_cursor_events: Cell::new(false),
+            background_color: Cell::new(None),
+            safe_area: Cell::new(safe_area),
+            menu,
             mutation_state: Default::default(),
+            edits_pending: Cell::new(false),
             query: Default::default(),
             #[cfg(target_os = "ios")]
             views: Default::default(),
@@ -105,10 +171,31 @@ impl DesktopService {
     }
 
     /// Send a list of mutations to the webview
+    ///
+    /// The edits are sent at the end of the event loop turn, so everything rendered in one turn arrives as one batch.
     pub(crate) fn send_edits(&self) {
+        self.edits_pending.set(true);
+    }
+
+    /// Send the edits collected since the last flush, unless the webview is still busy with the previous batch
+    pub(crate) fn flush_edits(&self) {
+        if !self.edits_pending.get() {
+            return;
+        }
+
+        // Keep collecting edits in the mutation state until the webview asks for more
+        if !self.edit_queue.is_ready() {
+            self.edit_queue.hold_back();
+            return;
+        }
+
+        self.edits_pending.set(false);
+        let timer = IpcTimer::start();
         let mut mutations = self.mutation_state.borrow_mut();
         let serialized_edits = mutations.export_memory();
+        let size = serialized_edits.len();
         self.edit_queue.add_edits(serialized_edits);
+        ipc_tracing::edits_sent(size, timer);
     }
 
     /// Create a new window using the props and window builder
@@ -118,8 +205,23 @@ impl DesktopService {
     /// You can use this to control other windows from the current window.
     ///
     /// Be careful to not create a cycle of windows, or you might leak memory.
-    pub fn new_window(&self, dom: VirtualDom, cfg: Config) -> Weak<DesktopService> {
-        let window = WebviewInstance::new(cfg, dom, self.shared.clone());
+    ///
+    /// If the config's [`WindowKind`] is [`WindowKind::Child`] or [`WindowKind::Modal`], the new window is parented
+    /// to this window. A modal window disables this window until it is closed.
+    pub fn new_window(&self, dom: VirtualDom, mut cfg: Config) -> Weak<DesktopService> {
+        let parent = match cfg.window_kind {
+            WindowKind::Standalone => None,
+            WindowKind::Child | WindowKind::Modal => {
+                cfg.window = with_parent_window(cfg.window, &self.window);
+                Some(self.id())
+            }
+        };
+
+        let window = WebviewInstance::new(cfg, dom, self.shared.clone(), parent);
+
+        if window.kind == WindowKind::Modal {
+            set_window_enabled(&self.window, false);
+        }
 
         let cx = window.dom.in_runtime(|| {
             ScopeId::ROOT
@@ -137,6 +239,28 @@ impl DesktopService {
         Rc::downgrade(&cx)
     }
 
+    /// Create a new window that renders the given component with the given props
+    ///
+    /// Each window runs its own [`VirtualDom`], so the new window gets an independent component tree with its own
+    /// state. Anything the new window needs from this one can be passed in through the props.
+    ///
+    /// ```rust, ignore
+    /// #[component]
+    /// fn Popup(title: String) -> Element {
+    ///     rsx! { h1 { "{title}" } }
+    /// }
+    ///
+    /// window().new_window_with_props(Popup, PopupProps { title: "Hello".into() }, Config::new());
+    /// ```
+    pub fn new_window_with_props<P: Clone + 'static, M: 'static>(
+        &self,
+        component: impl ComponentFunction<P, M>,
+        props: P,
+        cfg: Config,
+    ) -> Weak<DesktopService> {
+        self.new_window(VirtualDom::new_with_props(component, props), cfg)
+    }
+
     /// trigger the drag-window event
     ///
     /// Moves the window with the left mouse button until the button is released.
@@ -164,6 +288,15 @@ impl DesktopService {
             .send_event(UserWindowEvent::CloseWindow(self.id()));
     }
 
+    /// Close every window and stop the event loop, exiting the process with the given code. The listeners added with
+    /// [`use_app_exit`](crate::use_app_exit) run first, while every window is still open.
+    ///
+    /// If the app was launched with [`launch_and_return`](crate::launch::launch_and_return), the code is returned
+    /// from it instead.
+    pub fn exit_app(&self, code: i32) {
+        _ = self.shared.proxy.send_event(UserWindowEvent::ExitApp(code));
+    }
+
     /// Close a particular window, given its ID
     pub fn close_window(&self, id: WindowId) {
         let _ = self
@@ -181,6 +314,151 @@ impl DesktopService {
         }
     }
 
+    /// Enter or leave kiosk mode
+    ///
+    /// In kiosk mode the window covers its monitor, stays on top of other windows, has no controls and ignores
+    /// requests to close it from the user. On macOS, the dock and menu bar are hidden and app switching, force quit
+    /// and logging out are disabled. Other platforms don't let apps block their system shortcuts.
+    ///
+    /// The window can still be closed with [`DesktopService::close`], or you can leave kiosk mode from an admin screen
+    /// by passing `false`.
+    pub fn set_kiosk_mode(&self, enable: bool) {
+        let mut kiosk = self.kiosk.borrow_mut();
+        match (enable, kiosk.take()) {
+            (true, None) => *kiosk = Some(kiosk::enter(&self.window)),
+            (false, Some(state)) => kiosk::exit(&self.window, state, self.always_on_top.get()),
+            (_, state) => *kiosk = state,
+        }
+    }
+
+    /// Check if the window is in kiosk mode
+    pub fn is_kiosk_mode(&self) -> bool {
+        self.kiosk.borrow().is_some()
+    }
+
+    /// Keep the window above all other windows, for overlays and picture-in-picture players
+    ///
+    /// Kiosk mode always keeps the window on top, this takes effect again once kiosk mode is left.
+    pub fn set_always_on_top(&self, always_on_top: bool) {
+        self.always_on_top.set(always_on_top);
+        if !self.is_kiosk_mode() {
+            self.window.set_always_on_top(always_on_top);
+        }
+    }
+
+    /// Check if the window was set to stay above all other windows
+    pub fn is_always_on_top(&self) -> bool {
+        self.always_on_top.get()
+    }
+
+    /// Get how far content has to be inset from the edges of the window to stay clear of the notch and the home
+    /// indicator. See [`use_safe_area_insets`](crate::use_safe_area_insets) for a value that updates on rotation.
+    ///
+    /// > Note: The insets are all zero on platforms other than iOS.
+    pub fn safe_area_insets(&self) -> SafeAreaInsets {
+        self.safe_area.get()
+    }
+
+    /// Read the safe area insets again and tell the window's listeners if they changed
+    pub(crate) fn update_safe_area(&self) {
+        let insets = safe_area::safe_area_insets(&self.window);
+        if self.safe_area.replace(insets) != insets {
+            self.window_event_listeners
+                .call(&DesktopWindowEvent::SafeAreaChanged(insets));
+        }
+    }
+
+    /// Let clicks and scrolls pass through the window to whatever is behind it, for HUDs that only display
+    /// information. Keyboard input still goes to the window while it is focused.
+    ///
+    /// > Note: The window can't be interacted with at all while this is enabled, so keep a global shortcut or a tray
+    /// > item around to turn it off again.
+    pub fn set_ignore_cursor_events(&self, ignore: bool) -> Result<(), tao::error::ExternalError> {
+        self.window.set_ignore_cursor_events(ignore)?;
+        self.ignore_cursor_events.set(ignore);
+        Ok(())
+    }
+
+    /// Check if the window lets cursor events pass through to the windows behind it
+    pub fn ignores_cursor_events(&self) -> bool {
+        self.ignore_cursor_events.get()
+    }
+
+    /// Make the webview's background transparent, so the desktop shows through wherever the page doesn't paint
+    /// anything. Pass `false` to go back to the color set with
+    /// [`Config::with_background_color`](crate::Config::with_background_color), or white.
+    ///
+    /// > Note: This only has an effect if the window was created with `with_transparent(true)` and the page itself
+    /// > has a transparent background. On macOS and Linux the `transparent` feature has to be enabled.
+    pub fn set_transparent_background(&self, transparent: bool) {
+        let color = match transparent {
+            true => (0, 0, 0, 0),
+            false => self.background_color.get().unwrap_or((255, 255, 255, 255)),
+        };
+
+        if let Err(err) = self.webview.set_background_color(color) {
+            tracing::warn!("Failed to change the background of the webview: {err}");
+        }
+    }
+
+    /// Show a native dialog to pick files to open. The returned future resolves to the picked files, which is empty
+    /// if the user cancelled the dialog.
+    ///
+    /// The dialog is attached to this window. Unlike an `<input type="file">` element, this can be used from anywhere
+    /// in the app, including async tasks.
+    pub fn open_file_dialog(&self, dialog: FileDialog) -> impl Future<Output = Vec<PathBuf>> {
+        file_dialog::open(&dialog, &self.window)
+    }
+
+    /// Show a native dialog to pick where to save a file. The returned future resolves to `None` if the user
+    /// cancelled the dialog.
+    ///
+    /// ```rust, ignore
+    /// button {
+    ///     onclick: move |_| async move {
+    ///         let dialog = FileDialog::new()
+    ///             .with_file_name("report.csv")
+    ///             .add_filter("CSV", &["csv"]);
+    ///         if let Some(path) = window().save_file_dialog(dialog).await {
+    ///             std::fs::write(path, report()).unwrap();
+    ///         }
+    ///     },
+    ///     "Export"
+    /// }
+    /// ```
+    pub fn save_file_dialog(&self, dialog: FileDialog) -> impl Future<Output = Option<PathBuf>> {
+        file_dialog::save(&dialog, &self.window)
+    }
+
+    /// Show a native dialog to pick directories. The returned future resolves to the picked directories, which is
+    /// empty if the user cancelled the dialog.
+    pub fn directory_dialog(&self, dialog: FileDialog) -> impl Future<Output = Vec<PathBuf>> {
+        file_dialog::directory(&dialog, &self.window)
+    }
+
+    /// Search the text of the page and highlight the matches, scrolling the first one into view
+    ///
+    /// The number of matches is delivered to [`use_find_result`](crate::use_find_result) handlers. Only text within a
+    /// single element matches, so a match can't span across elements.
+    pub fn find(&self, text: &str, options: FindOptions) {
+        find::search(&self.webview, text, options)
+    }
+
+    /// Move the highlight of the current search to the next match, wrapping around at the end of the page
+    pub fn find_next(&self) {
+        find::run(&self.webview, "next()")
+    }
+
+    /// Move the highlight of the current search to the previous match, wrapping around at the start of the page
+    pub fn find_previous(&self) {
+        find::run(&self.webview, "previous()")
+    }
+
+    /// End the current search and remove its highlights
+    pub fn clear_find(&self) {
+        find::run(&self.webview, "clear()")
+    }
+
     /// launch print modal
     pub fn print(&self) {
         if let Err(e) = self.webview.print() {
@@ -190,16 +468,238 @@ impl DesktopService {
 
     /// Set the zoom level of the webview
     pub fn set_zoom_level(&self, level: f64) {
-        self.webview.zoom(level);
+        self.set_zoom(level);
+    }
+
+    /// Set the zoom factor of the webview, where `1.0` is the default size
+    ///
+    /// Non-positive factors are ignored.
+    pub fn set_zoom(&self, factor: f64) {
+        if !factor.is_finite() || factor <= 0.0 {
+            tracing::warn!("Ignoring invalid zoom factor {factor}");
+            return;
+        }
+
+        self.webview.zoom(factor);
+        self.zoom.set(factor);
+    }
+
+    /// Get the zoom factor last set with [`DesktopService::set_zoom`]
+    ///
+    /// > Note: wry doesn't report zoom changes made by the user through the platform's built-in zoom hotkeys, so
+    /// > those won't be reflected here.
+    pub fn zoom(&self) -> f64 {
+        self.zoom.get()
+    }
+
+    /// Apply a background effect to the window, replacing the current one. Pass `None` to clear the effect.
+    ///
+    /// Effects that the current platform doesn't support are skipped.
+    pub fn set_window_effect(&self, effect: Option<WindowEffect>) {
+        let previous = self.window_effect.replace(effect);
+        apply_window_effect(&self.window, previous, effect);
+    }
+
+    /// Get the background effect last applied with [`DesktopService::set_window_effect`]
+    pub fn window_effect(&self) -> Option<WindowEffect> {
+        self.window_effect.get()
+    }
+
+    /// Color the title bar of the window, for example to match a dark theme. Pass `None` to go back to the system
+    /// color.
+    ///
+    /// Supported on Windows 11 and macOS. On macOS this makes the title bar transparent and colors the window
+    /// background behind it.
+    pub fn set_title_bar_color(&self, color: Option<(u8, u8, u8)>) {
+        window_chrome::set_title_bar_color(&self.window, color);
+    }
+
+    /// Move the close, minimize and zoom buttons relative to the top left corner of the window
+    ///
+    /// Only supported on macOS.
+    pub fn set_traffic_light_position(&self, position: impl Into<Position>) {
+        window_chrome::set_traffic_light_position(&self.window, position.into());
+    }
+
+    /// Show a small icon over the window's taskbar button, for example to indicate unread messages. Pass `None` to
+    /// remove it.
+    ///
+    /// Only supported on Windows.
+    pub fn set_taskbar_overlay_icon(&self, icon: Option<Icon>) {
+        window_chrome::set_overlay_icon(&self.window, icon);
+    }
+
+    /// Ask for the user's attention when the window isn't focused, by bouncing the dock icon on macOS, flashing the
+    /// taskbar button on Windows and marking the window as urgent on Linux. This stops once the window is focused.
+    pub fn request_user_attention(&self, attention: UserAttention) {
+        self.window.request_user_attention(Some(attention.into()));
+    }
+
+    /// Stop asking for the user's attention
+    pub fn cancel_user_attention(&self) {
+        self.window.request_user_attention(None);
+    }
+
+    /// Show the number of unread items on the app's icon, or remove the badge with `None`
+    ///
+    /// macOS shows the count on the dock icon and Linux on the launcher icon, for the whole app. Windows shows it over
+    /// this window's taskbar button, replacing the icon set with [`DesktopService::set_taskbar_overlay_icon`].
+    ///
+    /// > Note: On Linux the launcher has to support the Unity launcher API, like the Ubuntu dock and KDE do, and the
+    /// > app needs a desktop entry named after its executable.
+    pub fn set_badge_count(&self, count: Option<u32>) {
+        attention::set_badge_count(&self.window, count);
+    }
+
+    /// Replace the icon of the window. On Windows this also replaces the icon shown in the taskbar.
+    pub fn set_icon(&self, icon: Option<Icon>) {
+        window_chrome::set_icon(&self.window, icon);
+    }
+
+    /// Register an image that draggable elements in this window can show under the cursor instead of the default
+    /// translucent copy of themselves. Elements pick it with the `data-dioxus-drag-image` attribute, set to `name`.
+    ///
+    /// Registering an image with a name that is already taken replaces it.
+    pub fn register_drag_image(&self, name: &str, image: DragImage) {
+        if let Err(err) = self.webview.evaluate_script(&image.register_script(name)) {
+            tracing::warn!("Failed to register drag image {name}: {err}");
+        }
+    }
+
+    /// Get the monitors connected to the machine, with their geometry and scale factor
+    ///
+    /// Unlike [`Window::available_monitors`], the returned info can be stored and is indexed, so it can be used to
+    /// build a monitor picker and passed back to [`DesktopService::move_to_monitor`].
+    pub fn monitors(&self) -> Vec<MonitorInfo> {
+        monitor::monitors(&self.window)
+    }
+
+    /// Move the window to the monitor with the given index, keeping its position relative to the top left corner of
+    /// the monitor.
+    pub fn move_to_monitor(&self, index: usize) {
+        monitor::move_to_monitor(&self.window, index)
+    }
+
+    /// Center the window on the monitor with the given index
+    pub fn center_on_monitor(&self, index: usize) {
+        monitor::center_on_monitor(&self.window, index)
+    }
+
+    /// Send a value to every window that subscribed to the topic with [`use_broadcast`](crate::use_broadcast),
+    /// including this one
+    ///
+    /// The value is delivered from the event loop once the current event is handled. Windows that subscribe later
+    /// start out with the last value sent to the topic.
+    pub fn broadcast<T: Clone + 'static>(&self, topic: &str, value: T) {
+        if self.shared.broadcasts.send(topic, Rc::new(value)) {
+            _ = self.shared.proxy.send_event(UserWindowEvent::Broadcast);
+        }
+    }
+
+    /// Capture the visible part of the webview as a PNG image
+    ///
+    /// Combined with [`Config::with_offscreen`](crate::Config::with_offscreen), this renders dioxus content without
+    /// ever showing it to the user.
+    ///
+    /// > Note: This is supported on Windows, and on Linux with the `snapshot` feature. Otherwise the future resolves to
+    /// > [`SnapshotError::Unsupported`].
+    pub fn snapshot(&self) -> impl Future<Output = Result<Vec<u8>, SnapshotError>> + 'static {
+        snapshot::capture(&self.webview)
+    }
+
+    /// Access the cookies of this window's webview, including HttpOnly cookies that scripts can't read
+    ///
+    /// ```rust, ignore
+    /// let window = use_window();
+    /// spawn(async move {
+    ///     // Log out by dropping every session cookie
+    ///     window.cookies().clear().await.unwrap();
+    /// });
+    /// ```
+    pub fn cookies(&self) -> CookieStore<'_> {
+        CookieStore::new(&self.webview)
+    }
+
+    /// Keep the machine and the display from going to sleep until the returned guard is dropped, for example while
+    /// a video is playing or a long export is running.
+    ///
+    /// On Linux this requires `systemd-inhibit`. If the machine can't be kept awake, a warning is logged and the
+    /// guard does nothing.
+    pub fn keep_awake(&self) -> KeepAwakeGuard {
+        KeepAwakeGuard::new(&self.window.title())
+    }
+
+    /// Get a handle to the app's updater, if one was configured with [`Config::with_updater`]
+    #[cfg(feature = "updater")]
+    pub fn updater(&self) -> Option<crate::Updater> {
+        self.shared.updater.clone()
+    }
+
+    /// Show a native context menu at the mouse cursor. `on_select` is called with the item the user selects.
+    ///
+    /// See [`use_context_menu`](crate::use_context_menu) for a hook that runs the handler in the scope of a component.
+    pub fn show_context_menu(
+        &self,
+        menu: &ContextMenu,
+        on_select: impl FnMut(ContextMenuEvent) + 'static,
+    ) {
+        self.shared
+            .context_menus
+            .show(&self.window, menu, Box::new(on_select));
+    }
+
+    /// Show a native notification. What the user does with it is delivered to
+    /// [`use_notification_event`](crate::use_notification_event).
+    ///
+    /// > Note: On Windows, notifications are shown for the app user model id of the process. If the app doesn't set
+    /// > one, the id `dioxus.<name of the executable>` is registered for the current user and used instead.
+    pub fn show_notification(&self, notification: Notification) {
+        notification::show(notification, self.shared.proxy.clone());
+    }
+
+    /// Replace the menu shown when the app's icon in the dock is right-clicked. Selections are delivered to
+    /// [`use_dock_menu_event`](crate::use_dock_menu_event).
+    ///
+    /// The menu belongs to the app, not this window. macOS adds the open windows and, for apps with document types,
+    /// the recent documents to it.
+    ///
+    /// > Note: Only supported on macOS.
+    pub fn set_dock_menu(&self, menu: &ContextMenu) {
+        self.shared.dock_menu.set(menu);
     }
 
     /// opens DevTool window
     pub fn devtool(&self) {
-        #[cfg(debug_assertions)]
+        self.open_devtools();
+    }
+
+    /// Open the devtools of this window's webview
+    ///
+    /// > Note: Devtools are only available in debug builds, or in release builds with the `devtools` feature enabled.
+    pub fn open_devtools(&self) {
+        #[cfg(any(debug_assertions, feature = "devtools"))]
         self.webview.open_devtools();
 
-        #[cfg(not(debug_assertions))]
-        tracing::warn!("Devtools are disabled in release builds");
+        #[cfg(not(any(debug_assertions, feature = "devtools")))]
+        tracing::warn!(
+            "Devtools are disabled in release builds, enable the `devtools` feature to use them"
+        );
+    }
+
+    /// Close the devtools of this window's webview. This does nothing on iOS and Android.
+    pub fn close_devtools(&self) {
+        #[cfg(any(debug_assertions, feature = "devtools"))]
+        self.webview.close_devtools();
+    }
+
+    /// Check whether the devtools of this window's webview are open. This is always `false` on iOS and Android, and
+    /// when devtools are disabled.
+    pub fn devtools_open(&self) -> bool {
+        #[cfg(any(debug_assertions, feature = "devtools"))]
+        return self.webview.is_devtools_open();
+
+        #[cfg(not(any(debug_assertions, feature = "devtools")))]
+        false
     }
 
     /// Create a wry event handler that listens for wry events.
@@ -236,11 +736,178 @@ impl DesktopService {
         self.shared.shortcut_manager.remove_shortcut(id)
     }
 
+    /// Run a callback once on the next animation frame, with the time since the app started
+    ///
+    /// Frames are delivered at up to 60 frames per second. Request another frame from the callback to keep animating,
+    /// or use [`use_animation_frame`](crate::use_animation_frame) to run on every frame.
+    pub fn request_animation_frame(&self, callback: impl FnOnce(std::time::Duration) + 'static) {
+        self.shared.animation_frames.request(Box::new(callback));
+    }
+
+    /// Keep the event loop polling continuously instead of waiting for events, until the returned lease is dropped
+    ///
+    /// This is for apps that drive their own updates, like games polling a gamepad. Animation frames are still
+    /// limited to 60 frames per second.
+    ///
+    /// > Note: Polling keeps a CPU core busy, so only hold the lease while the app actually needs it.
+    pub fn keep_polling(&self) -> PollLease {
+        self.shared.animation_frames.lease()
+    }
+
+    /// Create a shortcut that only fires while this window is focused, like `"CmdOrCtrl+K"` for a command palette
+    ///
+    /// Keys are named after [`KeyboardEvent.code`](https://developer.mozilla.org/en-US/docs/Web/API/KeyboardEvent/code)
+    /// values like `Escape`, `F5` or `Slash`, with single letters and digits as shorthands for `KeyA` and `Digit1`.
+    /// The page doesn't see key presses that trigger a shortcut.
+    pub fn create_window_shortcut(
+        &self,
+        accelerator: &str,
+        callback: impl FnMut() + 'static,
+    ) -> Result<WindowShortcutHandle, ShortcutRegistryError> {
+        self.window_shortcuts
+            .add(&self.webview, accelerator, Box::new(callback))
+    }
+
+    /// Remove a shortcut created with [`DesktopService::create_window_shortcut`]
+    pub fn remove_window_shortcut(&self, handle: WindowShortcutHandle) {
+        self.window_shortcuts.remove(&self.webview, handle)
+    }
+
+    /// Handle IPC messages the page sends to a method of the app, replacing any earlier handler of the method
+    ///
+    /// The params of the message are deserialized into `T`, and the value the handler returns is sent back to the
+    /// page. The easiest way to call a method from the page is `dioxus.invoke`, which returns a promise of the reply:
+    ///
+    /// ```rust, ignore
+    /// window().on_ipc("greet", |name: String| format!("Hello, {name}!"));
+    /// ```
+    ///
+    /// ```js
+    /// const greeting = await dioxus.invoke("greet", "Ferris");
+    /// ```
+    ///
+    /// Messages posted to `window.ipc` directly get a reply if they have an `id`. The page receives it as a
+    /// `dioxus-ipc-reply` event with `{ id, ok, value }` or `{ id, ok, error }` as its detail:
+    ///
+    /// ```js
+    /// window.ipc.postMessage(JSON.stringify({ method: "greet", params: "Ferris", id: 1 }));
+    /// ```
+    ///
+    /// > Note: Methods used by dioxus itself, like `user_event` or `query`, can't be handled by the app.
+    pub fn on_ipc<T, R>(&self, method: &str, handler: impl FnMut(T) -> R + 'static)
+    where
+        T: serde::de::DeserializeOwned,
+        R: serde::Serialize,
+    {
+        self.ipc_handlers.add(method, handler)
+    }
+
+    /// Handle IPC messages to a method of the app with an async handler, like [`DesktopService::on_ipc`]
+    ///
+    /// The handler runs as a task of the window's virtualdom, and the page gets the reply once it finishes:
+    ///
+    /// ```rust, ignore
+    /// window().on_ipc_async("load_settings", |name: String| async move { load_settings(&name).await });
+    /// ```
+    ///
+    /// ```js
+    /// const settings = await dioxus.invoke("load_settings", "editor");
+    /// ```
+    pub fn on_ipc_async<T, R, F>(&self, method: &str, handler: impl FnMut(T) -> F + 'static)
+    where
+        T: serde::de::DeserializeOwned,
+        R: serde::Serialize,
+        F: Future<Output = R> + 'static,
+    {
+        self.ipc_handlers.add_async(method, handler)
+    }
+
+    /// Remove the handler of an IPC method added with [`DesktopService::on_ipc`]
+    pub fn remove_ipc_handler(&self, method: &str) {
+        self.ipc_handlers.remove(method)
+    }
+
+    /// Move a global shortcut to a different hotkey, returning the handle that replaces `id`
+    ///
+    /// If the OS rejects the new hotkey, for example because another application already registered it, the
+    /// shortcut keeps its current hotkey and [`ShortcutRegistryError::AlreadyRegistered`] is returned.
+    pub fn rebind_shortcut(
+        &self,
+        id: ShortcutHandle,
+        hotkey: HotKey,
+    ) -> Result<ShortcutHandle, ShortcutRegistryError> {
+        self.shared.shortcut_manager.rebind(id, hotkey)
+    }
+
+    /// Get the hotkeys that currently have global shortcuts bound to them
+    pub fn shortcuts(&self) -> Vec<HotKey> {
+        self.shared.shortcut_manager.shortcuts()
+    }
+
     /// Remove all global shortcuts
     pub fn remove_all_shortcuts(&self) {
         self.shared.shortcut_manager.remove_all()
     }
 
+    /// Add an item to the submenu of the window's menu bar with the given title. The submenu is created if the menu
+    /// bar doesn't have one with that title yet.
+    ///
+    /// `accelerator` is a shortcut like `"CmdOrCtrl+S"` that selects the item. It is added to the window's shortcuts
+    /// like [`DesktopService::create_window_shortcut`], so unlike [`DesktopService::create_shortcut`] it only fires
+    /// while this window is focused.
+    ///
+    /// > Note: On macOS, all windows share the menu bar of the window that was created last.
+    pub fn add_menu_item(
+        &self,
+        submenu: &str,
+        label: &str,
+        accelerator: Option<&str>,
+        callback: impl FnMut() + 'static,
+    ) -> Result<MenuItemHandle, ShortcutRegistryError> {
+        let callback: Rc<RefCell<Box<dyn FnMut()>>> = Rc::new(RefCell::new(Box::new(callback)));
+
+        let shortcut = accelerator
+            .map(|accelerator| {
+                let callback = callback.clone();
+                self.create_window_shortcut(accelerator, move || (callback.borrow_mut())())
+            })
+            .transpose()?;
+
+        let item =
+            self.shared
+                .menu_items
+                .add(self.menu.as_deref(), submenu, label, shortcut, callback);
+        if let (Err(_), Some(shortcut)) = (&item, shortcut) {
+            self.remove_window_shortcut(shortcut);
+        }
+        item
+    }
+
+    /// Add an item with behavior built into the platform, like copy and paste, to the submenu of the window's menu bar
+    /// with the given title.
+    ///
+    /// ```rust, ignore
+    /// for role in [MenuRole::Undo, MenuRole::Redo, MenuRole::Separator, MenuRole::Cut, MenuRole::Copy, MenuRole::Paste] {
+    ///     window().add_menu_role("Edit", role)?;
+    /// }
+    /// ```
+    pub fn add_menu_role(
+        &self,
+        submenu: &str,
+        role: MenuRole,
+    ) -> Result<MenuItemHandle, ShortcutRegistryError> {
+        self.shared
+            .menu_items
+            .add_role(self.menu.as_deref(), submenu, role)
+    }
+
+    /// Remove a menu item added with [`DesktopService::add_menu_item`] or [`DesktopService::add_menu_role`]
+    pub fn remove_menu_item(&self, handle: &MenuItemHandle) {
+        if let Some(shortcut) = self.shared.menu_items.remove(handle) {
+            self.remove_window_shortcut(shortcut);
+        }
+    }
+
     /// Provide a callback to handle asset loading yourself.
     /// If the ScopeId isn't provided, defaults to a global handler.
     /// Note that the handler is namespaced by name, not ScopeId.
@@ -305,6 +972,42 @@ impl DesktopService {
     }
 }
 
+/// Attach the window that is about to be built to a parent window, so it stays on top of it
+#[allow(unused)]
+fn with_parent_window(mut builder: WindowBuilder, parent: &Window) -> WindowBuilder {
+    #[cfg(target_os = "windows")]
+    {
+        use tao::platform::windows::{WindowBuilderExtWindows, WindowExtWindows};
+        builder = builder.with_owner_window(parent.hwnd());
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use tao::platform::unix::{WindowBuilderExtUnix, WindowExtUnix};
+        builder = builder.with_transient_for(parent.gtk_window());
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use tao::platform::macos::{WindowBuilderExtMacOS, WindowExtMacOS};
+        builder = builder.with_parent_window(parent.ns_window());
+    }
+
+    builder
+}
+
+/// Enable or disable user input on a window
+///
+/// Only Windows can disable a window natively. On other platforms, the app keeps modal windows focused instead.
+#[allow(unused)]
+pub(crate) fn set_window_enabled(window: &Window, enabled: bool) {
+    #[cfg(target_os = "windows")]
+    {
+        use tao::platform::windows::WindowExtWindows;
+        window.set_enable(enabled);
+    }
+}
+
 #[cfg(target_os = "ios")]
 fn is_main_thread() -> bool {
     use objc::runtime::{Class, BOOL, NO};
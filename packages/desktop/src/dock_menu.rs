@@ -0,0 +1,113 @@
+//! The menu shown when the app's icon in the macOS dock is right-clicked
+
+use crate::{
+    context_menu::{ContextMenu, ContextMenuEvent},
+    event_handlers::EventListeners,
+    hooks::use_event_listener,
+};
+
+/// Keeps the native dock menu alive and routes selections of its items to the listeners
+#[derive(Default)]
+pub(crate) struct DockMenuRegistry {
+    pub(crate) listeners: EventListeners<ContextMenuEvent>,
+
+    #[cfg(target_os = "macos")]
+    menu: std::cell::RefCell<Option<DockMenu>>,
+}
+
+#[cfg(target_os = "macos")]
+struct DockMenu {
+    // The dock only borrows the native menu, so it has to be kept alive here
+    _menu: muda::Menu,
+    ids: std::collections::HashMap<muda::MenuId, String>,
+}
+
+impl DockMenuRegistry {
+    /// Replace the items of the dock menu
+    #[allow(unused)]
+    pub(crate) fn set(&self, menu: &ContextMenu) {
+        #[cfg(target_os = "macos")]
+        {
+            use muda::ContextMenu as _;
+
+            let mut ids = Default::default();
+            let native = crate::context_menu::build_menu(menu, &mut ids);
+            platform::set_dock_menu(native.ns_menu().cast());
+
+            *self.menu.borrow_mut() = Some(DockMenu { _menu: native, ids });
+        }
+    }
+
+    /// Call the listeners if the event came from an item of the dock menu
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    #[allow(unused)]
+    pub(crate) fn handle_menu_event(&self, event: &muda::MenuEvent) {
+        #[cfg(target_os = "macos")]
+        {
+            // Look up the id first so the listeners can replace the menu
+            let id = self
+                .menu
+                .borrow()
+                .as_ref()
+                .and_then(|menu| menu.ids.get(event.id()).cloned());
+
+            if let Some(id) = id {
+                self.listeners.call(&ContextMenuEvent { id });
+            }
+        }
+    }
+}
+
+/// Listen for items selected in the dock menu set with
+/// [`DesktopService::set_dock_menu`](crate::DesktopService::set_dock_menu)
+///
+/// ```rust, ignore
+/// window().set_dock_menu(&ContextMenu::new().item("new-window", "New Window"));
+///
+/// use_dock_menu_event(move |event| {
+///     if event.id == "new-window" {
+///         window().new_window(VirtualDom::new(app), Config::new());
+///     }
+/// });
+/// ```
+pub fn use_dock_menu_event(handler: impl FnMut(ContextMenuEvent) + 'static) {
+    use_event_listener(|window| &window.shared.dock_menu.listeners, handler)
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use objc::runtime::{class_addMethod, Class, Object, Sel};
+    use objc::*;
+    use std::cell::Cell;
+
+    thread_local! {
+        static DOCK_MENU: Cell<*mut Object> = const { Cell::new(std::ptr::null_mut()) };
+    }
+
+    extern "C" fn application_dock_menu(_: &Object, _: Sel, _: *mut Object) -> *mut Object {
+        DOCK_MENU.with(|menu| menu.get())
+    }
+
+    /// macOS asks the app delegate for the dock menu every time it is opened. tao's delegate doesn't answer, so the
+    /// method is added to its class the first time a menu is set.
+    pub(super) fn set_dock_menu(menu: *mut Object) {
+        DOCK_MENU.with(|current| current.set(menu));
+
+        unsafe {
+            let app: *mut Object = msg_send![class!(NSApplication), sharedApplication];
+            let delegate: *mut Object = msg_send![app, delegate];
+            let class: *mut Class = msg_send![delegate, class];
+
+            // This does nothing if an earlier call already added the method
+            class_addMethod(
+                class,
+                sel!(applicationDockMenu:),
+                std::mem::transmute(
+                    application_dock_menu
+                        as extern "C" fn(&Object, Sel, *mut Object) -> *mut Object,
+                ),
+                b"@@:@\0".as_ptr().cast(),
+            );
+        }
+    }
+}
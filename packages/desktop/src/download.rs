@@ -0,0 +1,58 @@
+use crate::hooks::use_event_listener;
+use std::path::PathBuf;
+
+/// A download the webview is about to start
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadRequest {
+    /// The URL being downloaded
+    pub url: String,
+    /// Where the webview would save the file by default
+    pub suggested_path: PathBuf,
+}
+
+/// What to do with a download, returned by the handler passed to
+/// [`Config::with_download_handler`](crate::Config::with_download_handler)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DownloadAction {
+    /// Save the file to the suggested path
+    Allow,
+    /// Save the file to a different path
+    SaveTo(PathBuf),
+    /// Don't download the file
+    Cancel,
+}
+
+/// The lifecycle of a download, delivered to [`use_download_event`] handlers
+///
+/// > Note: the webviews don't report download progress, so only the start and the end of a download are observable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DownloadEvent {
+    /// A download was allowed and started
+    Started {
+        /// The URL being downloaded
+        url: String,
+        /// Where the file is being saved
+        path: PathBuf,
+    },
+    /// A download was cancelled by the download handler
+    Cancelled {
+        /// The URL that would have been downloaded
+        url: String,
+    },
+    /// A download finished, successfully or not
+    Completed {
+        /// The URL that was downloaded
+        url: String,
+        /// Where the file was saved, if the platform reports it
+        path: Option<PathBuf>,
+        /// Whether the download succeeded
+        success: bool,
+    },
+}
+
+pub(crate) type DownloadHandler = Box<dyn FnMut(DownloadRequest) -> DownloadAction>;
+
+/// Listen for downloads started and completed in the current window
+pub fn use_download_event(handler: impl FnMut(DownloadEvent) + 'static) {
+    use_event_listener(|window| &window.download_listeners, handler)
+}
@@ -0,0 +1,127 @@
+//! Custom images shown under the cursor while an element is dragged, instead of the webview's translucent copy of it
+
+/// The attribute that picks the drag image of a draggable element. The value is the name the image was registered
+/// with.
+pub(crate) const DRAG_IMAGE_ATTRIBUTE: &str = "data-dioxus-drag-image";
+
+/// An image shown under the cursor while an element is dragged
+///
+/// Register it with [`DesktopService::register_drag_image`](crate::DesktopService::register_drag_image) and pick it
+/// with the `data-dioxus-drag-image` attribute on a draggable element or one of its ancestors:
+///
+/// ```rust, ignore
+/// window().register_drag_image("card", DragImage::element("#card-preview").with_offset(16.0, 16.0));
+///
+/// rsx! {
+///     div { draggable: "true", "data-dioxus-drag-image": "card", ondragstart: move |_| start_drag(id), "{title}" }
+///     // The preview is rendered but kept out of sight
+///     div { id: "card-preview", style: "position: fixed; left: -1000px;", "Moving {title}" }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DragImage {
+    source: DragImageSource,
+    offset: (f64, f64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum DragImageSource {
+    Element(String),
+    Png(Vec<u8>),
+    None,
+}
+
+impl DragImage {
+    /// Show a snapshot of the first element matching the CSS selector, taken when the drag starts
+    ///
+    /// > Note: The element has to be rendered for the snapshot to show anything, so move it out of sight instead of
+    /// > hiding it.
+    pub fn element(selector: impl Into<String>) -> Self {
+        Self {
+            source: DragImageSource::Element(selector.into()),
+            offset: (0.0, 0.0),
+        }
+    }
+
+    /// Show a PNG image. The image is loaded when it is registered, so it is ready when the drag starts.
+    pub fn png(png: impl Into<Vec<u8>>) -> Self {
+        Self {
+            source: DragImageSource::Png(png.into()),
+            offset: (0.0, 0.0),
+        }
+    }
+
+    /// Don't show anything under the cursor, for drags that are visualized by the app itself
+    pub fn none() -> Self {
+        Self {
+            source: DragImageSource::None,
+            offset: (0.0, 0.0),
+        }
+    }
+
+    /// Set where the cursor is on the image, in CSS pixels from its top left corner.
+    ///
+    /// > Note: The offset is `(0, 0)` by default.
+    pub fn with_offset(mut self, x: f64, y: f64) -> Self {
+        self.offset = (x, y);
+        self
+    }
+
+    /// Build the script that stores the image under the given name for the drag start listener
+    pub(crate) fn register_script(&self, name: &str) -> String {
+        let image = match &self.source {
+            DragImageSource::Element(selector) => {
+                format!(
+                    "{{ selector: {} }}",
+                    serde_json::to_string(selector).unwrap_or_default()
+                )
+            }
+            DragImageSource::Png(png) => format!(
+                r#"{{ image: (function () {{
+                    const image = new Image();
+                    image.src = URL.createObjectURL(new Blob([new Uint8Array({})], {{ type: "image/png" }}));
+                    return image;
+                }})() }}"#,
+                serde_json::to_string(png).unwrap_or_default()
+            ),
+            // A transparent pixel, an empty image falls back to the default drag image
+            DragImageSource::None => r#"{ image: (function () {
+                const image = new Image();
+                image.src = "data:image/gif;base64,R0lGODlhAQABAIAAAAAAAP///yH5BAEAAAAALAAAAAABAAEAAAIBRAA7";
+                return image;
+            })() }"#
+                .to_string(),
+        };
+
+        format!(
+            "(window.__dioxusDragImages = window.__dioxusDragImages || {{}})[{}] = Object.assign({image}, {{ x: {}, y: {} }});",
+            serde_json::to_string(name).unwrap_or_default(),
+            self.offset.0,
+            self.offset.1,
+        )
+    }
+}
+
+/// Build the script that replaces the drag image of elements that picked one with the attribute
+pub(crate) fn drag_image_script() -> String {
+    format!(
+        r#"
+        (function () {{
+            // Capture the event so the image is set before any handler of the page runs
+            document.addEventListener("dragstart", function (e) {{
+                const target = e.target instanceof Element ? e.target.closest("[{DRAG_IMAGE_ATTRIBUTE}]") : null;
+                const images = window.__dioxusDragImages || {{}};
+                const image = target ? images[target.getAttribute("{DRAG_IMAGE_ATTRIBUTE}")] : null;
+                if (!image || !e.dataTransfer) {{
+                    return;
+                }}
+
+                const source = image.selector ? document.querySelector(image.selector) : image.image;
+                if (source) {{
+                    e.dataTransfer.setDragImage(source, image.x, image.y);
+                }}
+            }}, true);
+        }})();
+        "#
+    )
+}
@@ -0,0 +1,115 @@
+use serde::Deserialize;
+use tao::window::ResizeDirection;
+
+/// The attribute that marks an element as a drag region for the window
+pub(crate) const DRAG_REGION_ATTRIBUTE: &str = "data-dioxus-drag-region";
+
+/// The attribute that turns an element into a window control button. The value is one of `minimize`, `maximize`
+/// or `close`
+pub(crate) const WINDOW_CONTROL_ATTRIBUTE: &str = "data-dioxus-window-control";
+
+/// Build the script that forwards drag and resize gestures to the host over IPC
+///
+/// Only the element that carries the attribute starts a drag - its children don't, so buttons inside a custom
+/// titlebar keep working. Clicking an element marked as a window control minimizes, maximizes or closes the window.
+/// A resize border of `0` disables resizing from the edges of the window.
+pub(crate) fn drag_region_script(resize_border: u32) -> String {
+    format!(
+        r#"
+        (function () {{
+            const RESIZE_BORDER = {resize_border};
+            const CURSORS = {{
+                North: "ns-resize", South: "ns-resize", East: "ew-resize", West: "ew-resize",
+                NorthWest: "nwse-resize", SouthEast: "nwse-resize", NorthEast: "nesw-resize", SouthWest: "nesw-resize",
+            }};
+
+            function postMessage(method, params) {{
+                window.ipc.postMessage(JSON.stringify({{ method: method, params: params || {{}} }}));
+            }}
+
+            function resizeDirection(e) {{
+                if (RESIZE_BORDER <= 0) {{
+                    return null;
+                }}
+                const vertical = e.clientY < RESIZE_BORDER ? "North" : e.clientY >= window.innerHeight - RESIZE_BORDER ? "South" : "";
+                const horizontal = e.clientX < RESIZE_BORDER ? "West" : e.clientX >= window.innerWidth - RESIZE_BORDER ? "East" : "";
+                return (vertical + horizontal) || null;
+            }}
+
+            // Only touch the cursor when the pointer enters or leaves a border, and give back the one the app set
+            let currentDirection = null;
+            let appCursor = "";
+            document.addEventListener("mousemove", function (e) {{
+                const direction = resizeDirection(e);
+                if (direction === currentDirection) {{
+                    return;
+                }}
+
+                const style = document.documentElement.style;
+                if (currentDirection === null) {{
+                    appCursor = style.cursor;
+                }}
+                style.cursor = direction ? CURSORS[direction] : appCursor;
+                currentDirection = direction;
+            }});
+
+            document.addEventListener("mousedown", function (e) {{
+                if (e.button !== 0) {{
+                    return;
+                }}
+
+                const direction = resizeDirection(e);
+                if (direction) {{
+                    e.preventDefault();
+                    postMessage("resize_window", {{ direction: direction }});
+                    return;
+                }}
+
+                if (e.target instanceof Element && e.target.hasAttribute("{DRAG_REGION_ATTRIBUTE}")) {{
+                    e.preventDefault();
+                    postMessage(e.detail === 2 ? "toggle_maximize" : "drag_window");
+                }}
+            }});
+
+            document.addEventListener("click", function (e) {{
+                const control = e.target instanceof Element && e.target.closest("[{WINDOW_CONTROL_ATTRIBUTE}]");
+                if (!control) {{
+                    return;
+                }}
+
+                const method = {{
+                    minimize: "minimize_window",
+                    maximize: "toggle_maximize",
+                    close: "close_window",
+                }}[control.getAttribute("{WINDOW_CONTROL_ATTRIBUTE}")];
+                if (method) {{
+                    e.preventDefault();
+                    postMessage(method);
+                }}
+            }});
+        }})();
+        "#
+    )
+}
+
+/// The params of a `resize_window` IPC message
+#[derive(Deserialize)]
+pub(crate) struct ResizeRequest {
+    direction: String,
+}
+
+impl ResizeRequest {
+    pub(crate) fn direction(&self) -> Option<ResizeDirection> {
+        Some(match self.direction.as_str() {
+            "North" => ResizeDirection::North,
+            "South" => ResizeDirection::South,
+            "East" => ResizeDirection::East,
+            "West" => ResizeDirection::West,
+            "NorthEast" => ResizeDirection::NorthEast,
+            "NorthWest" => ResizeDirection::NorthWest,
+            "SouthEast" => ResizeDirection::SouthEast,
+            "SouthWest" => ResizeDirection::SouthWest,
+            _ => return None,
+        })
+    }
+}
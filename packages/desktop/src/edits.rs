@@ -1,29 +1,73 @@
-use std::{cell::RefCell, rc::Rc};
+use crate::compression::Compression;
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    rc::Rc,
+    task::Waker,
+};
 
 /// This handles communication between the requests that the webview makes and the interpreter. The interpreter constantly makes long running requests to the webview to get any edits that should be made to the DOM almost like server side events.
 /// It will hold onto the requests until the interpreter is ready to handle them and hold onto any pending edits until a new request is made.
-#[derive(Default, Clone)]
+///
+/// At most one batch of edits waits here. While the webview is still busy with it, new edits are held back in the
+/// window's mutation state, where they are merged into a single batch instead of piling up.
+#[derive(Clone)]
 pub(crate) struct EditQueue {
-    queue: Rc<RefCell<Vec<Vec<u8>>>>,
+    queue: Rc<RefCell<VecDeque<Vec<u8>>>>,
     responder: Rc<RefCell<Option<wry::RequestAsyncResponder>>>,
+    held_back: Rc<Cell<bool>>,
+    compression: Rc<Cell<Compression>>,
+    // Wakes the window's virtualdom so the held back edits are sent once the webview asks for more
+    waker: Waker,
 }
 
 impl EditQueue {
+    pub fn new(waker: Waker, compression: Compression) -> Self {
+        Self {
+            queue: Default::default(),
+            responder: Default::default(),
+            held_back: Default::default(),
+            compression: Rc::new(Cell::new(compression)),
+            waker,
+        }
+    }
+
+    /// Settle on a compression for the edits with the formats the page says it supports
+    pub fn negotiate_compression(&self, supported: &[String]) {
+        self.compression
+            .set(self.compression.get().negotiate(supported));
+    }
+
     pub fn handle_request(&self, responder: wry::RequestAsyncResponder) {
         let mut queue = self.queue.borrow_mut();
-        if let Some(bytes) = queue.pop() {
+        if let Some(bytes) = queue.pop_front() {
             responder.respond(wry::http::Response::new(bytes));
         } else {
             *self.responder.borrow_mut() = Some(responder);
         }
+
+        if self.held_back.take() {
+            self.waker.wake_by_ref();
+        }
+    }
+
+    /// Whether the webview can take another batch of edits without them piling up
+    pub fn is_ready(&self) -> bool {
+        self.queue.borrow().is_empty()
+    }
+
+    /// Remember that edits are waiting, so they are sent as soon as the webview asks for more
+    pub fn hold_back(&self) {
+        self.held_back.set(true);
     }
 
     pub fn add_edits(&self, edits: Vec<u8>) {
+        let edits = self.compression.get().encode(edits);
         let mut responder = self.responder.borrow_mut();
         if let Some(responder) = responder.take() {
             responder.respond(wry::http::Response::new(edits));
         } else {
-            self.queue.borrow_mut().push(edits);
+            self.queue.borrow_mut().push_back(edits);
         }
     }
 }
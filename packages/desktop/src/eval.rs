@@ -1,7 +1,10 @@
 use dioxus_html::prelude::{EvalError, EvalProvider, Evaluator};
 use generational_box::{AnyStorage, GenerationalBox, UnsyncStorage};
 
-use crate::{query::Query, DesktopContext};
+use crate::{
+    query::{Query, QueryError},
+    DesktopContext,
+};
 
 /// Reprents the desktop-target's provider of evaluators.
 pub struct DesktopEvalProvider {
@@ -46,9 +49,7 @@ impl Evaluator for DesktopEvaluator {
         &mut self,
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Result<serde_json::Value, EvalError>> {
-        self.query
-            .poll_result(cx)
-            .map_err(|e| EvalError::Communication(e.to_string()))
+        self.query.poll_result(cx).map_err(eval_error)
     }
 
     /// Sends a message to the evaluated JavaScript.
@@ -64,8 +65,15 @@ impl Evaluator for DesktopEvaluator {
         &mut self,
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Result<serde_json::Value, EvalError>> {
-        self.query
-            .poll_recv(cx)
-            .map_err(|e| EvalError::Communication(e.to_string()))
+        self.query.poll_recv(cx).map_err(eval_error)
+    }
+}
+
+/// Surface errors thrown by the script and the end of its messages as such, instead of as communication errors
+fn eval_error(err: QueryError) -> EvalError {
+    match err {
+        QueryError::Js(err) => EvalError::InvalidJs(err),
+        QueryError::Finished => EvalError::Finished,
+        err => EvalError::Communication(err.to_string()),
     }
 }
@@ -63,3 +63,36 @@ impl WindowEventHandlers {
         }
     }
 }
+
+/// A set of listeners for one kind of desktop event, like page loads or opened files
+pub(crate) struct EventListeners<T> {
+    listeners: RefCell<Slab<Box<dyn FnMut(&T)>>>,
+}
+
+impl<T> Default for EventListeners<T> {
+    fn default() -> Self {
+        Self {
+            listeners: Default::default(),
+        }
+    }
+}
+
+impl<T> EventListeners<T> {
+    pub(crate) fn add(&self, listener: Box<dyn FnMut(&T)>) -> usize {
+        self.listeners.borrow_mut().insert(listener)
+    }
+
+    pub(crate) fn remove(&self, id: usize) {
+        self.listeners.borrow_mut().try_remove(id);
+    }
+
+    pub(crate) fn call(&self, event: &T) {
+        for (_, listener) in self.listeners.borrow_mut().iter_mut() {
+            listener(event);
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.listeners.borrow().is_empty()
+    }
+}
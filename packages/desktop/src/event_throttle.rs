@@ -0,0 +1,85 @@
+//! Coalescing high-frequency events in the page before they cross the IPC bridge
+//!
+//! Pointer moves, scrolls and drags fire far more often than the app can render. Without coalescing, each of them is
+//! a message the event loop has to handle, which starves polling the virtualdom in busy apps.
+
+/// The events that are coalesced. Only the latest of each kind and element is sent per interval, except for wheels,
+/// whose deltas are summed so no scrolling is lost.
+const COALESCED_EVENTS: &[&str] = &[
+    "mousemove",
+    "pointermove",
+    "touchmove",
+    "scroll",
+    "wheel",
+    "drag",
+    "dragover",
+];
+
+/// The expression that wraps the interpreter's IPC channel to send coalesced events at most `rate` times per second
+pub(crate) fn coalescing_channel(inner: &str, rate: u32) -> String {
+    let interval = 1000.0 / rate.max(1) as f64;
+    let events = serde_json::to_string(COALESCED_EVENTS).unwrap();
+
+    format!(
+        r#"(function (inner) {{
+            const coalesced = new Set({events});
+            const prefix = '{{"method":"user_event","params":{{"name":"';
+            let pending = new Map();
+            let timer = null;
+            let lastFlush = 0;
+
+            const flush = function () {{
+                clearTimeout(timer);
+                timer = null;
+                lastFlush = performance.now();
+                const messages = pending;
+                pending = new Map();
+                for (const message of messages.values()) {{
+                    inner.postMessage(message);
+                }}
+            }};
+
+            const eventName = function (message) {{
+                if (!message.startsWith(prefix)) {{
+                    return null;
+                }}
+                return message.slice(prefix.length, message.indexOf('"', prefix.length));
+            }};
+
+            return {{
+                postMessage: function (message) {{
+                    if (!coalesced.has(eventName(message))) {{
+                        // Everything else keeps its order relative to the coalesced events
+                        if (timer !== null) {{
+                            flush();
+                        }}
+                        inner.postMessage(message);
+                        return;
+                    }}
+
+                    const parsed = JSON.parse(message);
+                    const params = parsed.params;
+                    const key = params.name + ":" + params.element;
+                    const previous = pending.get(key);
+                    if (params.name === "wheel" && previous !== undefined) {{
+                        const before = JSON.parse(previous).params.data;
+                        if (before.delta_mode === params.data.delta_mode) {{
+                            params.data.delta_x += before.delta_x;
+                            params.data.delta_y += before.delta_y;
+                            params.data.delta_z += before.delta_z;
+                            message = JSON.stringify(parsed);
+                        }} else {{
+                            // Deltas in different units can't be added up
+                            flush();
+                        }}
+                    }}
+                    pending.set(key, message);
+                    if (timer === null) {{
+                        const wait = Math.max(0, lastFlush + {interval} - performance.now());
+                        timer = setTimeout(flush, wait);
+                    }}
+                }},
+            }};
+        }})({inner})"#
+    )
+}
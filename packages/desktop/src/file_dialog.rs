@@ -0,0 +1,176 @@
+//! Native file dialogs that can be opened from Rust, without an `<input type="file">` element
+
+use std::{future::Future, path::PathBuf};
+use tao::window::Window;
+
+/// The options of a native file dialog opened with [`DesktopService::open_file_dialog`](crate::DesktopService::open_file_dialog),
+/// [`DesktopService::save_file_dialog`](crate::DesktopService::save_file_dialog) or
+/// [`DesktopService::directory_dialog`](crate::DesktopService::directory_dialog)
+///
+/// ```rust, ignore
+/// let dialog = FileDialog::new()
+///     .with_title("Open images")
+///     .add_filter("Images", &["png", "jpg"])
+///     .with_multiple(true);
+///
+/// let files = window().open_file_dialog(dialog).await;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileDialog {
+    title: Option<String>,
+    filters: Vec<(String, Vec<String>)>,
+    directory: Option<PathBuf>,
+    file_name: Option<String>,
+    multiple: bool,
+}
+
+impl FileDialog {
+    /// Create a dialog with the platform's default options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the title of the dialog window
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Add a filter that only shows files with the given extensions. `name` is shown in the dialog's filter picker.
+    pub fn add_filter(mut self, name: impl Into<String>, extensions: &[&str]) -> Self {
+        let extensions = extensions.iter().map(|ext| ext.to_string()).collect();
+        self.filters.push((name.into(), extensions));
+        self
+    }
+
+    /// Set the directory the dialog starts in
+    pub fn with_directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.directory = Some(directory.into());
+        self
+    }
+
+    /// Set the file name the dialog suggests. This is most useful for save dialogs.
+    pub fn with_file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.file_name = Some(file_name.into());
+        self
+    }
+
+    /// Set whether multiple files or directories can be selected. This has no effect on save dialogs.
+    ///
+    /// > Note: `multiple` is `false` by default.
+    pub fn with_multiple(mut self, multiple: bool) -> Self {
+        self.multiple = multiple;
+        self
+    }
+
+    #[cfg(any(
+        target_os = "windows",
+        target_os = "macos",
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    fn build(&self, parent: &Window) -> rfd::AsyncFileDialog {
+        let mut dialog = rfd::AsyncFileDialog::new().set_parent(parent);
+
+        if let Some(title) = &self.title {
+            dialog = dialog.set_title(title);
+        }
+        for (name, extensions) in &self.filters {
+            dialog = dialog.add_filter(name, extensions);
+        }
+        if let Some(directory) = &self.directory {
+            dialog = dialog.set_directory(directory);
+        }
+        if let Some(file_name) = &self.file_name {
+            dialog = dialog.set_file_name(file_name);
+        }
+
+        dialog
+    }
+}
+
+#[cfg(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+mod impl_ {
+    use super::*;
+
+    fn paths(handles: Option<Vec<rfd::FileHandle>>) -> Vec<PathBuf> {
+        handles
+            .into_iter()
+            .flatten()
+            .map(|handle| handle.path().to_path_buf())
+            .collect()
+    }
+
+    pub fn open(dialog: &FileDialog, parent: &Window) -> impl Future<Output = Vec<PathBuf>> {
+        let multiple = dialog.multiple;
+        let dialog = dialog.build(parent);
+        async move {
+            match multiple {
+                true => paths(dialog.pick_files().await),
+                false => paths(dialog.pick_file().await.map(|file| vec![file])),
+            }
+        }
+    }
+
+    pub fn save(dialog: &FileDialog, parent: &Window) -> impl Future<Output = Option<PathBuf>> {
+        let dialog = dialog.build(parent);
+        async move {
+            dialog
+                .save_file()
+                .await
+                .map(|handle| handle.path().to_path_buf())
+        }
+    }
+
+    pub fn directory(dialog: &FileDialog, parent: &Window) -> impl Future<Output = Vec<PathBuf>> {
+        let multiple = dialog.multiple;
+        let dialog = dialog.build(parent);
+        async move {
+            match multiple {
+                true => paths(dialog.pick_folders().await),
+                false => paths(dialog.pick_folder().await.map(|folder| vec![folder])),
+            }
+        }
+    }
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+)))]
+mod impl_ {
+    use super::*;
+
+    pub fn open(_: &FileDialog, _: &Window) -> impl Future<Output = Vec<PathBuf>> {
+        tracing::warn!("File dialogs are not supported on this platform");
+        std::future::ready(Vec::new())
+    }
+
+    pub fn save(_: &FileDialog, _: &Window) -> impl Future<Output = Option<PathBuf>> {
+        tracing::warn!("File dialogs are not supported on this platform");
+        std::future::ready(None)
+    }
+
+    pub fn directory(_: &FileDialog, _: &Window) -> impl Future<Output = Vec<PathBuf>> {
+        tracing::warn!("File dialogs are not supported on this platform");
+        std::future::ready(Vec::new())
+    }
+}
+
+pub(crate) use impl_::{directory, open, save};
@@ -11,15 +11,16 @@ use dioxus_html::{
     FileEngine, HasDragData, HasFileData, HasFormData, HasMouseData,
 };
 
+use crate::ipc::IpcMessage;
 use serde::Deserialize;
 use std::{
     cell::{Cell, RefCell},
-    path::PathBuf,
+    path::{Path, PathBuf},
     rc::Rc,
     str::FromStr,
     sync::Arc,
 };
-use wry::FileDropEvent;
+use wry::FileDropEvent as WryFileDropEvent;
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct FileDialogRequest {
@@ -68,10 +69,10 @@ impl FileDialogRequest {
             }
         }
 
-        fn get_file_event_for_file(
+        fn with_accepted_extensions(
             request: &FileDialogRequest,
-            mut dialog: rfd::FileDialog,
-        ) -> Vec<PathBuf> {
+            dialog: rfd::FileDialog,
+        ) -> rfd::FileDialog {
             let filters: Vec<_> = request
                 .accept
                 .as_deref()
@@ -85,7 +86,17 @@ impl FileDialogRequest {
                 .flat_map(|f| f.as_extensions().into_iter())
                 .collect();
 
-            dialog = dialog.add_filter("name", file_extensions.as_slice());
+            if file_extensions.is_empty() {
+                return dialog;
+            }
+            dialog.add_filter("name", file_extensions.as_slice())
+        }
+
+        fn get_file_event_for_file(
+            request: &FileDialogRequest,
+            mut dialog: rfd::FileDialog,
+        ) -> Vec<PathBuf> {
+            dialog = with_accepted_extensions(request, dialog);
 
             let files: Vec<_> = if request.multiple {
                 dialog.pick_files().into_iter().flatten().collect()
@@ -160,18 +171,175 @@ impl HasFormData for DesktopFileUploadForm {
     }
 }
 
+/// Controls which dropped files are handed to drag and drop events
+///
+/// The files reach components through the regular drag events, like `ondragover` and `ondrop`. `ondragover` keeps
+/// firing with the position of the cursor while files are dragged over the window.
+///
+/// ```rust, ignore
+/// Config::new().with_file_drop_config(
+///     FileDropConfig::new()
+///         .with_extensions(["png", "jpg"])
+///         .with_recursive_directories(true),
+/// )
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileDropConfig {
+    extensions: Option<Vec<String>>,
+    recursive_directories: bool,
+}
+
+impl FileDropConfig {
+    /// Accept every dropped file and directory as is
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only accept files with one of these extensions (without the leading dot, case insensitive)
+    pub fn with_extensions(
+        mut self,
+        extensions: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.extensions = Some(
+            extensions
+                .into_iter()
+                .map(|ext| ext.into().trim_start_matches('.').to_lowercase())
+                .collect(),
+        );
+        self
+    }
+
+    /// Replace dropped directories with every file inside them, recursively
+    ///
+    /// Directories are listed on a separate thread. Drag events that carry the files wait until the listing is done.
+    pub fn with_recursive_directories(mut self, recursive: bool) -> Self {
+        self.recursive_directories = recursive;
+        self
+    }
+
+    /// Check if the paths of a drag have to be listed on a separate thread
+    pub(crate) fn lists_directories(&self) -> bool {
+        self.recursive_directories
+    }
+
+    /// Expand and filter the paths of a native drag event
+    pub(crate) fn process_event(&self, event: WryFileDropEvent) -> WryFileDropEvent {
+        match event {
+            WryFileDropEvent::Hovered { paths, position } => WryFileDropEvent::Hovered {
+                paths: self.process(paths),
+                position,
+            },
+            WryFileDropEvent::Dropped { paths, position } => WryFileDropEvent::Dropped {
+                paths: self.process(paths),
+                position,
+            },
+            event => event,
+        }
+    }
+
+    /// Expand and filter the paths of a drop event
+    pub(crate) fn process(&self, paths: Vec<PathBuf>) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        for path in paths {
+            if self.recursive_directories && path.is_dir() {
+                collect_files(&path, &mut files);
+            } else {
+                files.push(path);
+            }
+        }
+
+        if let Some(extensions) = &self.extensions {
+            files.retain(|path| {
+                path.is_dir()
+                    || path
+                        .extension()
+                        .and_then(|ext| ext.to_str())
+                        .is_some_and(|ext| extensions.contains(&ext.to_lowercase()))
+            });
+        }
+
+        files
+    }
+}
+
+/// Collect every file inside a directory, without following symlinks so cycles can't occur
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+
+        if file_type.is_dir() {
+            collect_files(&entry.path(), files);
+        } else {
+            files.push(entry.path());
+        }
+    }
+}
+
+/// Make `<input type="file" webkitdirectory>` open a directory picker
+///
+/// The interpreter opens the dialogs of file inputs, but only picks directories if the attribute is set to `"true"`,
+/// while pages usually leave it empty. This runs in the capture phase, before the interpreter reads the attribute.
+///
+/// Save dialogs aren't file inputs, since the picked path doesn't exist yet. Event handlers open them with
+/// [`DesktopService::save_file_dialog`](crate::DesktopService::save_file_dialog) instead.
+pub(crate) const FILE_DIALOG_SCRIPT: &str = r#"
+(function () {
+    window.addEventListener("click", function (event) {
+        const target = event.target;
+        if (!(target instanceof HTMLInputElement) || target.getAttribute("type") !== "file") {
+            return;
+        }
+
+        const directory = target.getAttribute("webkitdirectory");
+        if (directory !== null && directory !== "false" && directory !== "true") {
+            target.setAttribute("webkitdirectory", "true");
+        }
+    }, true);
+})();
+"#;
+
 #[derive(Default, Clone)]
 pub struct NativeFileHover {
-    event: Rc<RefCell<Option<FileDropEvent>>>,
+    event: Rc<RefCell<Option<WryFileDropEvent>>>,
+    /// Drag events that arrived while the directories of the latest drag were still being listed
+    waiting: Rc<RefCell<Option<Vec<IpcMessage>>>>,
 }
 impl NativeFileHover {
-    pub fn set(&self, event: FileDropEvent) {
+    pub fn set(&self, event: WryFileDropEvent) {
         self.event.borrow_mut().replace(event);
     }
 
-    pub fn current(&self) -> Option<FileDropEvent> {
+    pub fn current(&self) -> Option<WryFileDropEvent> {
         self.event.borrow_mut().clone()
     }
+
+    /// Hold back drag events until [`NativeFileHover::finish_listing`] is called with the files of the drag
+    pub(crate) fn start_listing(&self) {
+        self.waiting.borrow_mut().get_or_insert_with(Vec::new);
+    }
+
+    /// Keep a drag event until the files of the drag are listed. Returns the event if they already are.
+    pub(crate) fn wait_for_listing(&self, msg: IpcMessage) -> Option<IpcMessage> {
+        match self.waiting.borrow_mut().as_mut() {
+            Some(waiting) => {
+                waiting.push(msg);
+                None
+            }
+            None => Some(msg),
+        }
+    }
+
+    /// Store the listed files of the drag, and return the drag events that waited for them
+    pub(crate) fn finish_listing(&self, event: WryFileDropEvent) -> Vec<IpcMessage> {
+        self.set(event);
+        self.waiting.borrow_mut().take().unwrap_or_default()
+    }
 }
 
 #[derive(Clone)]
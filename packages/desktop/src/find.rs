@@ -0,0 +1,181 @@
+//! Searching the text of the page, for document-style apps that want a find bar
+
+use crate::hooks::use_event_listener;
+use serde::{Deserialize, Serialize};
+use wry::WebView;
+
+/// How [`DesktopService::find`](crate::DesktopService::find) matches the text of the page
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct FindOptions {
+    /// Only match text with the same case
+    pub case_sensitive: bool,
+    /// Only match whole words
+    pub whole_word: bool,
+}
+
+/// The matches of the current search, delivered to [`use_find_result`] handlers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub struct FindResult {
+    /// How many matches the page has
+    pub matches: usize,
+    /// The index of the highlighted match, if there are any matches
+    pub active: Option<usize>,
+}
+
+/// The script that searches the page and highlights the matches
+///
+/// Matches are highlighted with the CSS custom highlight API, so the DOM the interpreter manages is never touched.
+/// Webviews without it select the active match instead.
+pub(crate) const FIND_SCRIPT: &str = r#"
+(function () {
+    const supportsHighlights =
+        typeof CSS !== "undefined" && CSS.highlights !== undefined && typeof Highlight === "function";
+    let matches = [];
+    let active = -1;
+
+    if (supportsHighlights) {
+        document.addEventListener("DOMContentLoaded", function () {
+            const style = document.createElement("style");
+            style.textContent =
+                "::highlight(dioxus-find) { background-color: rgba(255, 235, 59, 0.6); }" +
+                "::highlight(dioxus-find-active) { background-color: rgb(255, 150, 50); }";
+            document.head.appendChild(style);
+        });
+    }
+
+    function report() {
+        window.ipc.postMessage(JSON.stringify({
+            method: "find_result",
+            params: { matches: matches.length, active: active < 0 ? null : active },
+        }));
+    }
+
+    function paint() {
+        if (supportsHighlights) {
+            CSS.highlights.set("dioxus-find", new Highlight(...matches));
+            if (active >= 0) {
+                CSS.highlights.set("dioxus-find-active", new Highlight(matches[active]));
+            } else {
+                CSS.highlights.delete("dioxus-find-active");
+            }
+        } else if (active >= 0) {
+            const selection = window.getSelection();
+            selection.removeAllRanges();
+            selection.addRange(matches[active]);
+        }
+
+        if (active >= 0) {
+            const element = matches[active].startContainer.parentElement;
+            if (element) {
+                element.scrollIntoView({ block: "center" });
+            }
+        }
+        report();
+    }
+
+    function isWholeWord(text, start, length) {
+        const isWordCharacter = (c) => c !== undefined && /\w/.test(c);
+        return !isWordCharacter(text[start - 1]) && !isWordCharacter(text[start + length]);
+    }
+
+    function search(text, options) {
+        matches = [];
+        if (text) {
+            const needle = options.case_sensitive ? text : text.toLowerCase();
+            const walker = document.createTreeWalker(document.body, NodeFilter.SHOW_TEXT, {
+                acceptNode(node) {
+                    const parent = node.parentElement;
+                    if (!parent || parent.closest("script, style, noscript")) {
+                        return NodeFilter.FILTER_REJECT;
+                    }
+                    return NodeFilter.FILTER_ACCEPT;
+                },
+            });
+
+            let node;
+            while ((node = walker.nextNode())) {
+                const haystack = options.case_sensitive ? node.data : node.data.toLowerCase();
+                let index = haystack.indexOf(needle);
+                while (index !== -1) {
+                    if (!options.whole_word || isWholeWord(haystack, index, needle.length)) {
+                        const range = document.createRange();
+                        range.setStart(node, index);
+                        range.setEnd(node, index + needle.length);
+                        matches.push(range);
+                    }
+                    index = haystack.indexOf(needle, index + needle.length);
+                }
+            }
+        }
+
+        active = matches.length > 0 ? 0 : -1;
+        paint();
+    }
+
+    function step(delta) {
+        if (matches.length === 0) {
+            return report();
+        }
+        active = (active + delta + matches.length) % matches.length;
+        paint();
+    }
+
+    function clear() {
+        matches = [];
+        active = -1;
+        if (supportsHighlights) {
+            CSS.highlights.delete("dioxus-find");
+            CSS.highlights.delete("dioxus-find-active");
+        } else {
+            window.getSelection().removeAllRanges();
+        }
+        report();
+    }
+
+    window.dioxus = window.dioxus || {};
+    window.dioxus.find = {
+        search: search,
+        next: () => step(1),
+        previous: () => step(-1),
+        clear: clear,
+    };
+})();
+"#;
+
+/// Run one of the functions of [`FIND_SCRIPT`] in the page
+pub(crate) fn run(webview: &WebView, call: &str) {
+    if let Err(err) = webview.evaluate_script(&format!("window.dioxus.find.{call};")) {
+        tracing::error!("Failed to search the page: {err}");
+    }
+}
+
+/// Start a new search of the page for the text
+pub(crate) fn search(webview: &WebView, text: &str, options: FindOptions) {
+    let (Ok(text), Ok(options)) = (serde_json::to_string(text), serde_json::to_string(&options))
+    else {
+        return;
+    };
+    run(webview, &format!("search({text}, {options})"));
+}
+
+/// Listen for the results of searches in the current window
+///
+/// A result is delivered after every call to [`find`](crate::DesktopService::find),
+/// [`find_next`](crate::DesktopService::find_next), [`find_previous`](crate::DesktopService::find_previous) and
+/// [`clear_find`](crate::DesktopService::clear_find).
+///
+/// ```rust, ignore
+/// let mut result = use_signal(FindResult::default);
+/// use_find_result(move |new| result.set(new));
+///
+/// rsx! {
+///     input { oninput: move |e| window().find(&e.value(), FindOptions::default()) }
+///     match result().active {
+///         Some(active) => rsx! { "{active + 1} of {result().matches}" },
+///         None => rsx! { "No matches" },
+///     }
+/// }
+/// ```
+pub fn use_find_result(handler: impl FnMut(FindResult) + 'static) {
+    use_event_listener(|window| &window.find_listeners, handler)
+}
@@ -1,7 +1,8 @@
-use std::rc::Rc;
+use std::{cell::RefCell, rc::Rc};
 
 use crate::{
-    assets::*, ipc::UserWindowEvent, shortcut::IntoAccelerator, window, DesktopContext,
+    assets::*, event_handlers::EventListeners, ipc::UserWindowEvent, menubar::MenuItemHandle,
+    shortcut::IntoAccelerator, window, window_shortcut::WindowShortcutHandle, DesktopContext,
     ShortcutHandle, ShortcutRegistryError, WryEventHandler,
 };
 use dioxus_core::{
@@ -69,3 +70,92 @@ pub fn use_global_shortcut(
         },
     )
 }
+
+/// Create a shortcut that only fires while the window is focused, for the lifetime of the component
+///
+/// See [`DesktopService::create_window_shortcut`](crate::DesktopService::create_window_shortcut) for the accelerator
+/// format. The handler runs in the scope of the component that called this hook.
+pub fn use_window_shortcut(
+    accelerator: &str,
+    handler: impl FnMut() + 'static,
+) -> Result<WindowShortcutHandle, ShortcutRegistryError> {
+    let cb = use_callback(handler);
+
+    use_hook_with_cleanup(
+        || window().create_window_shortcut(accelerator, move || cb.call()),
+        |handle| {
+            if let Ok(handle) = handle {
+                handle.remove();
+            }
+        },
+    )
+}
+
+/// Add an item with an optional keyboard accelerator to the window's menu bar for the lifetime of the component
+///
+/// The handler runs in the scope of the component that called this hook.
+///
+/// ```rust, ignore
+/// use_menu_item("File", "Save", Some("CmdOrCtrl+S"), move || save_document());
+/// ```
+pub fn use_menu_item(
+    submenu: &str,
+    label: &str,
+    accelerator: Option<&str>,
+    handler: impl FnMut() + 'static,
+) -> Result<MenuItemHandle, ShortcutRegistryError> {
+    let cb = use_callback(handler);
+
+    use_hook_with_cleanup(
+        || window().add_menu_item(submenu, label, accelerator, move || cb.call()),
+        |handle| {
+            if let Ok(handle) = handle {
+                handle.remove();
+            }
+        },
+    )
+}
+
+/// Run cleanup code when the app exits, with the exit code
+///
+/// The handler runs in the scope of the component that called this hook, before any window is closed. This happens
+/// when the app exits with [`DesktopService::exit_app`](crate::DesktopService::exit_app) or
+/// [`AppHandle::exit`](crate::AppHandle::exit), or when the last window is closed. Async tasks don't get to run
+/// anymore, so the cleanup has to be done synchronously.
+///
+/// ```rust, ignore
+/// use_app_exit(move |_| settings.read().save());
+/// ```
+pub fn use_app_exit(handler: impl FnMut(i32) + 'static) {
+    use_event_listener(|window| &window.shared.exit_listeners, handler)
+}
+
+/// Add a listener to one of the window's [`EventListeners`] for the lifetime of the component
+///
+/// The handler runs in the scope of the component that called this hook.
+pub(crate) fn use_event_listener<T: Clone + 'static>(
+    listeners: fn(&DesktopContext) -> &EventListeners<T>,
+    mut handler: impl FnMut(T) + 'static,
+) {
+    // use_callback can't take arguments, so the event is handed over through this slot
+    let latest: Rc<RefCell<Option<T>>> = use_hook(|| Rc::new(RefCell::new(None)));
+
+    let cb = use_callback({
+        let latest = latest.clone();
+        move || {
+            if let Some(event) = latest.borrow_mut().take() {
+                handler(event);
+            }
+        }
+    });
+
+    use_hook_with_cleanup(
+        move || {
+            listeners(&window()).add(Box::new(move |event| {
+                *latest.borrow_mut() = Some(event.clone());
+                cb.call();
+            }))
+        },
+        move |id| listeners(&window()).remove(id),
+    );
+}
@@ -0,0 +1,32 @@
+/// Build the script that forwards text committed by the platform's input method to Dioxus as composition events
+///
+/// When the native window rather than the webview has keyboard focus, tao receives the IME text instead of the page.
+/// The events are sent over the same IPC path as the page's own events, to the element that has focus, so
+/// `oncompositionstart`, `oncompositionupdate` and `oncompositionend` handlers see them without the page dispatching
+/// or inserting anything itself.
+pub(crate) fn composition_script(text: &str) -> String {
+    let data = serde_json::to_string(text).unwrap_or_else(|_| "\"\"".to_string());
+
+    format!(
+        r#"
+        (function () {{
+            const data = {data};
+            let target = document.activeElement;
+            while (target && !target.hasAttribute("data-dioxus-id")) {{
+                target = target.parentElement;
+            }}
+            if (!target) {{
+                return;
+            }}
+
+            const element = parseInt(target.getAttribute("data-dioxus-id"));
+            for (const [name, text] of [["compositionstart", ""], ["compositionupdate", data], ["compositionend", data]]) {{
+                window.ipc.postMessage(JSON.stringify({{
+                    method: "user_event",
+                    params: {{ name: name, element: element, data: {{ data: text }}, bubbles: true }},
+                }}));
+            }}
+        }})();
+        "#
+    )
+}
@@ -1,4 +1,6 @@
+use crate::{download::DownloadEvent, notification::NotificationEvent, page_load::PageLoadEvent};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use tao::window::WindowId;
 
 #[derive(Debug, Clone)]
@@ -11,7 +13,12 @@ pub enum UserWindowEvent {
     Poll(WindowId),
 
     /// Handle an ipc message eminating from the window.postMessage of a given webview
-    Ipc { id: WindowId, msg: IpcMessage },
+    Ipc {
+        /// The window the message came from
+        id: WindowId,
+        /// The message
+        msg: IpcMessage,
+    },
 
     /// Handle a hotreload event, basically telling us to update our templates
     #[cfg(all(
@@ -22,11 +29,64 @@ pub enum UserWindowEvent {
     ))]
     HotReloadEvent(dioxus_hot_reload::HotReloadMsg),
 
+    /// The directories of a file drag over a given webview were listed
+    FileDrop {
+        /// The window the files were dragged over
+        id: WindowId,
+        /// The drag, with the files in the directories
+        event: wry::FileDropEvent,
+    },
+
+    /// The app was asked to open a file, by the OS or by another instance of the app
+    OpenFile(PathBuf),
+
+    /// A task was picked from the jump list of another instance of the app
+    JumpListTask(String),
+
+    /// The user responded to a notification
+    Notification(NotificationEvent),
+
+    /// A download started or finished in a given webview
+    Download {
+        /// The window the download started in
+        id: WindowId,
+        /// The progress of the download
+        event: DownloadEvent,
+    },
+
+    /// A page started, finished or failed loading in a given webview
+    PageLoad {
+        /// The window the page is loading in
+        id: WindowId,
+        /// The progress of the page load
+        event: PageLoadEvent,
+    },
+
     /// Create a new window
     NewWindow,
 
+    /// Deliver the values that were broadcast since the last time
+    Broadcast,
+
+    /// Open a URL in a new plain window, as decided by the navigation handler
+    OpenUrlWindow(String),
+
+    /// Show the main window, creating it if the app was launched without one
+    ShowMainWindow,
+
+    /// Close all windows and stop the event loop with the given exit code
+    ExitApp(i32),
+
     /// Close a given window (could be any window!)
     CloseWindow(WindowId),
+
+    /// Progress reported by the updater
+    #[cfg(feature = "updater")]
+    UpdaterEvent(crate::updater::UpdaterEvent),
+
+    /// Shut down the app and install the downloaded update
+    #[cfg(feature = "updater")]
+    RestartToUpdate,
 }
 
 /// A message struct that manages the communication between the webview and the eventloop code
@@ -36,6 +96,9 @@ pub enum UserWindowEvent {
 pub struct IpcMessage {
     method: String,
     params: serde_json::Value,
+    /// Set by the page on messages to methods of the app that expect a reply
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<serde_json::Value>,
 }
 
 /// A set of known messages that we need to respond to
@@ -46,6 +109,17 @@ pub enum IpcMethod<'a> {
     Query,
     BrowserOpen,
     Initialize,
+    DragWindow,
+    ToggleMaximize,
+    MinimizeWindow,
+    CloseWindow,
+    ResizeWindow,
+    WindowShortcut,
+    BackButton,
+    VirtualKeyboard,
+    WindowOccluded,
+    Invoke,
+    FindResult,
     Other(&'a str),
 }
 
@@ -57,10 +131,29 @@ impl IpcMessage {
             "query" => IpcMethod::Query,
             "browser_open" => IpcMethod::BrowserOpen,
             "initialize" => IpcMethod::Initialize,
+            "drag_window" => IpcMethod::DragWindow,
+            "toggle_maximize" => IpcMethod::ToggleMaximize,
+            "minimize_window" => IpcMethod::MinimizeWindow,
+            "close_window" => IpcMethod::CloseWindow,
+            "resize_window" => IpcMethod::ResizeWindow,
+            "window_shortcut" => IpcMethod::WindowShortcut,
+            "back_button" => IpcMethod::BackButton,
+            "virtual_keyboard" => IpcMethod::VirtualKeyboard,
+            "window_occluded" => IpcMethod::WindowOccluded,
+            "invoke" => IpcMethod::Invoke,
+            "find_result" => IpcMethod::FindResult,
             _ => IpcMethod::Other(&self.method),
         }
     }
 
+    pub(crate) fn method_name(&self) -> &str {
+        &self.method
+    }
+
+    pub(crate) fn id(&self) -> Option<&serde_json::Value> {
+        self.id.as_ref()
+    }
+
     pub(crate) fn params(self) -> serde_json::Value {
         self.params
     }
@@ -0,0 +1,156 @@
+//! IPC methods defined by the app, for pages and JS libraries that talk to Rust directly
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::{cell::RefCell, collections::HashMap, future::Future, pin::Pin, rc::Rc};
+use wry::WebView;
+
+type IpcFuture = Pin<Box<dyn Future<Output = Result<Value, String>>>>;
+type IpcHandler = Rc<RefCell<dyn FnMut(Value) -> IpcResult>>;
+
+/// The result of an IPC handler, right away or once its future finishes
+pub(crate) enum IpcResult {
+    Ready(Result<Value, String>),
+    Pending(IpcFuture),
+}
+
+/// The IPC methods registered on a window with [`DesktopService::on_ipc`](crate::DesktopService::on_ipc)
+#[derive(Default)]
+pub(crate) struct IpcHandlerRegistry {
+    handlers: RefCell<HashMap<String, IpcHandler>>,
+}
+
+impl IpcHandlerRegistry {
+    pub(crate) fn add<T, R>(&self, method: &str, mut handler: impl FnMut(T) -> R + 'static)
+    where
+        T: DeserializeOwned,
+        R: Serialize,
+    {
+        self.insert(method, move |params: Value| {
+            IpcResult::Ready(
+                serde_json::from_value(params)
+                    .map_err(|err| err.to_string())
+                    .and_then(|params| {
+                        serde_json::to_value(handler(params)).map_err(|err| err.to_string())
+                    }),
+            )
+        })
+    }
+
+    pub(crate) fn add_async<T, R, F>(&self, method: &str, mut handler: impl FnMut(T) -> F + 'static)
+    where
+        T: DeserializeOwned,
+        R: Serialize,
+        F: Future<Output = R> + 'static,
+    {
+        self.insert(method, move |params: Value| {
+            match serde_json::from_value(params) {
+                Ok(params) => {
+                    let future = handler(params);
+                    IpcResult::Pending(Box::pin(async move {
+                        serde_json::to_value(future.await).map_err(|err| err.to_string())
+                    }))
+                }
+                Err(err) => IpcResult::Ready(Err(err.to_string())),
+            }
+        })
+    }
+
+    fn insert(&self, method: &str, handler: impl FnMut(Value) -> IpcResult + 'static) {
+        self.handlers
+            .borrow_mut()
+            .insert(method.to_string(), Rc::new(RefCell::new(handler)));
+    }
+
+    pub(crate) fn remove(&self, method: &str) {
+        self.handlers.borrow_mut().remove(method);
+    }
+
+    /// Call the handler of a method, or return `None` if no handler is registered for it
+    pub(crate) fn call(&self, method: &str, params: Value) -> Option<IpcResult> {
+        // The handler may register or remove handlers itself
+        let handler = self.handlers.borrow().get(method).cloned()?;
+        let result = (handler.borrow_mut())(params);
+        Some(result)
+    }
+}
+
+/// The params of an `invoke` message, sent by `dioxus.invoke(command, args)` in the page
+#[derive(Deserialize)]
+pub(crate) struct InvokeMessage {
+    pub(crate) command: String,
+    #[serde(default)]
+    pub(crate) args: Value,
+}
+
+/// The script that lets the page call the app's IPC methods and get the reply back as a promise
+///
+/// ```js
+/// const greeting = await dioxus.invoke("greet", "Ferris");
+/// ```
+pub(crate) const INVOKE_SCRIPT: &str = r#"
+(function () {
+    let nextId = 0;
+    const pending = new Map();
+
+    window.addEventListener("dioxus-ipc-reply", function (e) {
+        const reply = e.detail;
+        const request = pending.get(reply.id);
+        if (!request) {
+            return;
+        }
+        pending.delete(reply.id);
+
+        if (reply.ok) {
+            request.resolve(reply.value);
+        } else {
+            request.reject(new Error(reply.error));
+        }
+    });
+
+    window.dioxus = window.dioxus || {};
+    window.dioxus.invoke = function (command, args) {
+        const id = "__dioxus_invoke_" + nextId++;
+        return new Promise(function (resolve, reject) {
+            pending.set(id, { resolve: resolve, reject: reject });
+            window.ipc.postMessage(JSON.stringify({
+                method: "invoke",
+                params: { command: command, args: args === undefined ? null : args },
+                id: id,
+            }));
+        });
+    };
+})();
+"#;
+
+/// Hand the result of an IPC method back to the page, if the message asked for a reply
+pub(crate) fn reply(
+    webview: &WebView,
+    method: &str,
+    reply_id: Option<&Value>,
+    result: Result<Value, String>,
+) {
+    let Some(reply_id) = reply_id else {
+        if let Err(err) = result {
+            tracing::warn!("IPC method {method:?} failed: {err}");
+        }
+        return;
+    };
+
+    if let Err(err) = webview.evaluate_script(&reply_script(reply_id, &result)) {
+        tracing::warn!("Failed to reply to IPC message: {err}");
+    }
+}
+
+/// The script that hands the reply to a message with an `id` back to the page
+///
+/// The page receives it as a `dioxus-ipc-reply` event on `window`, with `{ id, ok, value }` or `{ id, ok, error }` as
+/// its detail.
+fn reply_script(id: &Value, result: &Result<Value, String>) -> String {
+    let detail = match result {
+        Ok(value) => serde_json::json!({ "id": id, "ok": true, "value": value }),
+        Err(error) => serde_json::json!({ "id": id, "ok": false, "error": error }),
+    };
+
+    format!(r#"window.dispatchEvent(new CustomEvent("dioxus-ipc-reply", {{ detail: {detail} }}));"#)
+}
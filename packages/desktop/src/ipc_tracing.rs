@@ -0,0 +1,77 @@
+//! Logging the traffic between the app and the webview, for diagnosing slow IPC
+//!
+//! With the `ipc-tracing` feature, every message, batch of edits and query is logged to `tracing` with the
+//! `dioxus_desktop::ipc` target at the debug level. Without it, everything here compiles to nothing.
+
+use crate::ipc::IpcMessage;
+
+/// Measures how long a step of the IPC took. Zero-sized without the `ipc-tracing` feature.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct IpcTimer {
+    #[cfg(feature = "ipc-tracing")]
+    started: std::time::Instant,
+}
+
+impl IpcTimer {
+    #[inline]
+    pub(crate) fn start() -> Self {
+        Self {
+            #[cfg(feature = "ipc-tracing")]
+            started: std::time::Instant::now(),
+        }
+    }
+}
+
+/// The page sent a message, which took `timer` to deserialize
+#[inline]
+#[allow(unused_variables)]
+pub(crate) fn message_received(msg: &IpcMessage, size: usize, timer: IpcTimer) {
+    #[cfg(feature = "ipc-tracing")]
+    tracing::debug!(
+        target: "dioxus_desktop::ipc",
+        method = msg.method_name(),
+        size,
+        deserialize = ?timer.started.elapsed(),
+        "received message"
+    );
+}
+
+/// The page sent a message that couldn't be deserialized
+#[inline]
+#[allow(unused_variables)]
+pub(crate) fn message_rejected(size: usize, err: &dyn std::fmt::Display) {
+    #[cfg(feature = "ipc-tracing")]
+    tracing::debug!(
+        target: "dioxus_desktop::ipc",
+        size,
+        %err,
+        "rejected message"
+    );
+}
+
+/// A batch of edits was handed to the webview, which took `timer` to serialize
+#[inline]
+#[allow(unused_variables)]
+pub(crate) fn edits_sent(size: usize, timer: IpcTimer) {
+    #[cfg(feature = "ipc-tracing")]
+    tracing::debug!(
+        target: "dioxus_desktop::ipc",
+        size,
+        serialize = ?timer.started.elapsed(),
+        "sent edits"
+    );
+}
+
+/// A query returned, `timer` after it was started
+#[inline]
+#[allow(unused_variables)]
+pub(crate) fn query_returned(id: usize, success: bool, timer: IpcTimer) {
+    #[cfg(feature = "ipc-tracing")]
+    tracing::debug!(
+        target: "dioxus_desktop::ipc",
+        id,
+        success,
+        roundtrip = ?timer.started.elapsed(),
+        "query returned"
+    );
+}
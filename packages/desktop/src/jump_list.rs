@@ -0,0 +1,293 @@
+//! Tasks and recent documents in the jump list that opens when the app is right-clicked in the Windows taskbar, and
+//! the recent documents macOS shows in the dock menu and the "Open Recent" menu
+
+use crate::hooks::use_event_listener;
+use std::path::{Path, PathBuf};
+
+/// The argument the app is launched with when a task is picked from the jump list
+const TASK_ARG: &str = "--dioxus-jump-list-task=";
+
+/// An entry in the tasks section of the jump list
+///
+/// Picking a task launches the app again. Together with
+/// [`Config::with_single_instance`](crate::Config::with_single_instance) the task is handed to the running instance
+/// instead, where components receive it through [`use_jump_list_task`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JumpListTask {
+    pub(crate) id: String,
+    pub(crate) title: String,
+    pub(crate) description: Option<String>,
+    pub(crate) icon: Option<PathBuf>,
+}
+
+impl JumpListTask {
+    /// Create a task with the id that is handed to [`use_jump_list_task`] and the title shown in the jump list
+    pub fn new(id: impl Into<String>, title: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            title: title.into(),
+            description: None,
+            icon: None,
+        }
+    }
+
+    /// Set the tooltip shown when hovering the task
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Set the icon of the task to the first icon in an `.ico`, `.exe` or `.dll` file
+    pub fn with_icon(mut self, icon: impl Into<PathBuf>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+}
+
+/// The contents of the app's jump list
+///
+/// ```rust, ignore
+/// let jump_list = JumpList::new()
+///     .with_task(JumpListTask::new("new-document", "New document"))
+///     .with_recent_documents(true);
+///
+/// set_jump_list(&jump_list)?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JumpList {
+    pub(crate) tasks: Vec<JumpListTask>,
+    pub(crate) recent_documents: bool,
+}
+
+impl JumpList {
+    /// Create an empty jump list
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a task to the end of the tasks section
+    pub fn with_task(mut self, task: JumpListTask) -> Self {
+        self.tasks.push(task);
+        self
+    }
+
+    /// Set whether the jump list shows the documents added with [`add_recent_document`].
+    ///
+    /// > Note: `show` is `false` by default. Windows only lists documents of file types the app is registered for,
+    /// > see [`register_file_association`](crate::register_file_association).
+    pub fn with_recent_documents(mut self, show: bool) -> Self {
+        self.recent_documents = show;
+        self
+    }
+}
+
+/// Replace the app's jump list. The jump list is kept by Windows, so it stays in place after the app exits.
+///
+/// This does nothing on other platforms.
+pub fn set_jump_list(jump_list: &JumpList) -> std::io::Result<()> {
+    platform::set_jump_list(jump_list)
+}
+
+/// Add a document to the recent documents of the app, shown in its jump list on Windows and its dock menu on macOS
+///
+/// Picking a recent document opens it with the app, so it arrives at
+/// [`use_open_file_event`](crate::use_open_file_event).
+///
+/// > Note: macOS only keeps recent documents of the types declared with `CFBundleDocumentTypes` in the app bundle's
+/// > `Info.plist`. This does nothing on other platforms.
+pub fn add_recent_document(path: impl AsRef<Path>) {
+    platform::add_recent_document(path.as_ref())
+}
+
+/// Remove every document from the recent documents of the app
+///
+/// This does nothing on platforms other than Windows and macOS.
+pub fn clear_recent_documents() {
+    platform::clear_recent_documents()
+}
+
+/// Listen for tasks picked from the app's jump list
+///
+/// The handler receives the id the task was created with. Tasks picked while the app is launching are delivered once
+/// the first window is ready.
+///
+/// ```rust, ignore
+/// use_jump_list_task(move |task| {
+///     if task == "new-document" {
+///         documents.push(Document::default());
+///     }
+/// });
+/// ```
+pub fn use_jump_list_task(handler: impl FnMut(String) + 'static) {
+    use_event_listener(|window| &window.shared.jump_list_listeners, handler)
+}
+
+/// The jump list task the current process was launched with
+pub(crate) fn task_from_args(args: impl IntoIterator<Item = String>) -> Option<String> {
+    args.into_iter()
+        .find_map(|arg| task_from_arg(&arg).map(str::to_string))
+}
+
+/// The id of the task if the argument was added by a jump list task. Instances of the app also forward tasks to the
+/// running instance in this form.
+pub(crate) fn task_from_arg(arg: &str) -> Option<&str> {
+    arg.strip_prefix(TASK_ARG)
+}
+
+/// The argument that launches the app with a task
+pub(crate) fn task_arg(id: &str) -> String {
+    format!("{TASK_ARG}{id}")
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+    use windows::{
+        core::{Interface, Result, HSTRING, PWSTR},
+        Win32::{
+            Storage::EnhancedStorage::PKEY_Title,
+            System::{
+                Com::{
+                    CoCreateInstance,
+                    StructuredStorage::{
+                        PROPVARIANT, PROPVARIANT_0, PROPVARIANT_0_0, PROPVARIANT_0_0_0,
+                    },
+                    CLSCTX_INPROC_SERVER,
+                },
+                Variant::VT_LPWSTR,
+            },
+            UI::Shell::{
+                Common::{IObjectArray, IObjectCollection},
+                DestinationList, EnumerableObjectCollection, ICustomDestinationList, IShellLinkW,
+                PropertiesSystem::IPropertyStore,
+                SHAddToRecentDocs, ShellLink, KDC_RECENT, SHARD_PATHW,
+            },
+        },
+    };
+
+    pub(super) fn set_jump_list(jump_list: &JumpList) -> std::io::Result<()> {
+        let exe = std::env::current_exe()?;
+        build_jump_list(jump_list, &exe).map_err(|err| std::io::Error::other(err.to_string()))
+    }
+
+    fn build_jump_list(jump_list: &JumpList, exe: &Path) -> Result<()> {
+        unsafe {
+            let destinations: ICustomDestinationList =
+                CoCreateInstance(&DestinationList, None, CLSCTX_INPROC_SERVER)?;
+
+            let mut min_slots = 0;
+            let _removed: IObjectArray = destinations.BeginList(&mut min_slots)?;
+
+            if jump_list.recent_documents {
+                destinations.AppendKnownCategory(KDC_RECENT)?;
+            }
+
+            if !jump_list.tasks.is_empty() {
+                let tasks: IObjectCollection =
+                    CoCreateInstance(&EnumerableObjectCollection, None, CLSCTX_INPROC_SERVER)?;
+                for task in &jump_list.tasks {
+                    tasks.AddObject(&shell_link(exe, task)?)?;
+                }
+                destinations.AddUserTasks(&tasks.cast::<IObjectArray>()?)?;
+            }
+
+            destinations.CommitList()
+        }
+    }
+
+    /// A shortcut that launches the app with the task's argument
+    unsafe fn shell_link(exe: &Path, task: &JumpListTask) -> Result<IShellLinkW> {
+        let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)?;
+        link.SetPath(&HSTRING::from(exe.as_os_str()))?;
+        link.SetArguments(&HSTRING::from(task_arg(&task.id)))?;
+
+        if let Some(description) = &task.description {
+            link.SetDescription(&HSTRING::from(description.as_str()))?;
+        }
+        if let Some(icon) = &task.icon {
+            link.SetIconLocation(&HSTRING::from(icon.as_os_str()), 0)?;
+        }
+
+        // The title of a task is a property of the shortcut, not its name. The string is owned by `title` and only
+        // borrowed by the value, so the value is never cleared.
+        let mut title: Vec<u16> = task.title.encode_utf16().chain(Some(0)).collect();
+        let value = PROPVARIANT {
+            Anonymous: PROPVARIANT_0 {
+                Anonymous: std::mem::ManuallyDrop::new(PROPVARIANT_0_0 {
+                    vt: VT_LPWSTR,
+                    Anonymous: PROPVARIANT_0_0_0 {
+                        pwszVal: PWSTR(title.as_mut_ptr()),
+                    },
+                    ..Default::default()
+                }),
+            },
+        };
+
+        let properties: IPropertyStore = link.cast()?;
+        properties.SetValue(&PKEY_Title, &value)?;
+        properties.Commit()?;
+
+        Ok(link)
+    }
+
+    pub(super) fn add_recent_document(path: &Path) {
+        let path = HSTRING::from(path.as_os_str());
+        unsafe { SHAddToRecentDocs(SHARD_PATHW.0 as u32, Some(path.as_ptr().cast())) }
+    }
+
+    pub(super) fn clear_recent_documents() {
+        unsafe { SHAddToRecentDocs(SHARD_PATHW.0 as u32, None) }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+    use objc::runtime::Object;
+    use objc::*;
+
+    pub(super) fn set_jump_list(_: &JumpList) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn document_controller() -> *mut Object {
+        unsafe { msg_send![class!(NSDocumentController), sharedDocumentController] }
+    }
+
+    pub(super) fn add_recent_document(path: &Path) {
+        let path = path.to_string_lossy();
+
+        objc::rc::autoreleasepool(|| unsafe {
+            let string: *mut Object = msg_send![class!(NSString), alloc];
+            let string: *mut Object = msg_send![
+                string,
+                initWithBytes: path.as_ptr()
+                length: path.len()
+                encoding: 4usize // NSUTF8StringEncoding
+            ];
+            let url: *mut Object = msg_send![class!(NSURL), fileURLWithPath: string];
+            let _: () = msg_send![document_controller(), noteNewRecentDocumentURL: url];
+            let _: () = msg_send![string, release];
+        });
+    }
+
+    pub(super) fn clear_recent_documents() {
+        let nil: *mut Object = std::ptr::null_mut();
+        unsafe {
+            let _: () = msg_send![document_controller(), clearRecentDocuments: nil];
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use super::*;
+
+    pub(super) fn set_jump_list(_: &JumpList) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    pub(super) fn add_recent_document(_: &Path) {}
+
+    pub(super) fn clear_recent_documents() {}
+}
@@ -0,0 +1,80 @@
+//! Locking a window down for point of sale terminals, signage and other unattended displays
+
+use tao::window::{Fullscreen, Window};
+
+/// The window state kiosk mode changes, so it can be restored when kiosk mode is left
+pub(crate) struct KioskState {
+    decorated: bool,
+    resizable: bool,
+    minimizable: bool,
+    maximizable: bool,
+    closable: bool,
+}
+
+/// Make the window fullscreen, remove its controls and block the system shortcuts the platform lets us block
+pub(crate) fn enter(window: &Window) -> KioskState {
+    let state = KioskState {
+        decorated: window.is_decorated(),
+        resizable: window.is_resizable(),
+        minimizable: window.is_minimizable(),
+        maximizable: window.is_maximizable(),
+        closable: window.is_closable(),
+    };
+
+    window.set_fullscreen(Some(Fullscreen::Borderless(window.current_monitor())));
+    window.set_decorations(false);
+    window.set_resizable(false);
+    window.set_minimizable(false);
+    window.set_maximizable(false);
+    window.set_closable(false);
+    window.set_always_on_top(true);
+    set_system_shortcuts_blocked(true);
+
+    state
+}
+
+/// Undo everything [`enter`] did. `always_on_top` is what the window was set to outside of kiosk mode.
+pub(crate) fn exit(window: &Window, state: KioskState, always_on_top: bool) {
+    set_system_shortcuts_blocked(false);
+    window.set_always_on_top(always_on_top);
+    window.set_closable(state.closable);
+    window.set_maximizable(state.maximizable);
+    window.set_minimizable(state.minimizable);
+    window.set_resizable(state.resizable);
+    window.set_decorations(state.decorated);
+    window.set_fullscreen(None);
+}
+
+/// Hide the dock and menu bar and disable app switching, force quit and logging out
+///
+/// Only macOS lets apps block these. On other platforms kiosk mode relies on the window covering the screen and
+/// refusing to close.
+#[allow(unused)]
+fn set_system_shortcuts_blocked(blocked: bool) {
+    #[cfg(target_os = "macos")]
+    unsafe {
+        use objc::runtime::Object;
+        use objc::*;
+
+        const HIDE_DOCK: u64 = 1 << 1;
+        const HIDE_MENU_BAR: u64 = 1 << 3;
+        const DISABLE_PROCESS_SWITCHING: u64 = 1 << 5;
+        const DISABLE_FORCE_QUIT: u64 = 1 << 6;
+        const DISABLE_SESSION_TERMINATION: u64 = 1 << 7;
+        const DISABLE_HIDE_APPLICATION: u64 = 1 << 8;
+
+        let options = if blocked {
+            HIDE_DOCK
+                | HIDE_MENU_BAR
+                | DISABLE_PROCESS_SWITCHING
+                | DISABLE_FORCE_QUIT
+                | DISABLE_SESSION_TERMINATION
+                | DISABLE_HIDE_APPLICATION
+        } else {
+            0
+        };
+
+        let app: *mut Object = msg_send![class!(NSApplication), sharedApplication];
+        let _: () = msg_send![app, setPresentationOptions: options];
+    }
+}
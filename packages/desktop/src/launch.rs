@@ -1,11 +1,11 @@
 pub use crate::Config;
-use crate::{
-    app::App,
-    ipc::{IpcMethod, UserWindowEvent},
-};
+use crate::{app::App, ipc::UserWindowEvent};
 use dioxus_core::*;
 use std::any::Any;
-use tao::event::{Event, StartCause, WindowEvent};
+use tao::{
+    event::{Event, StartCause},
+    event_loop::{ControlFlow, EventLoop, EventLoopBuilder, EventLoopProxy, EventLoopWindowTarget},
+};
 
 /// Launch the WebView and run the event loop, with configuration and root props.
 ///
@@ -15,48 +15,7 @@ pub fn launch_virtual_dom_blocking(virtual_dom: VirtualDom, desktop_config: Conf
     let (event_loop, mut app) = App::new(desktop_config, virtual_dom);
 
     event_loop.run(move |window_event, _, control_flow| {
-        // Set the control flow and check if any events need to be handled in the app itself
-        app.tick(&window_event);
-
-        match window_event {
-            Event::NewEvents(StartCause::Init) => app.handle_start_cause_init(),
-            Event::WindowEvent {
-                event, window_id, ..
-            } => match event {
-                WindowEvent::CloseRequested => app.handle_close_requested(window_id),
-                WindowEvent::Destroyed { .. } => app.window_destroyed(window_id),
-                _ => {}
-            },
-
-            Event::UserEvent(event) => match event {
-                UserWindowEvent::Poll(id) => app.poll_vdom(id),
-                UserWindowEvent::NewWindow => app.handle_new_window(),
-                UserWindowEvent::CloseWindow(id) => app.handle_close_msg(id),
-
-                #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
-                UserWindowEvent::GlobalHotKeyEvent(evnt) => app.handle_global_hotkey(evnt),
-
-                #[cfg(all(
-                    feature = "hot-reload",
-                    debug_assertions,
-                    not(target_os = "android"),
-                    not(target_os = "ios")
-                ))]
-                UserWindowEvent::HotReloadEvent(msg) => app.handle_hot_reload_msg(msg),
-
-                UserWindowEvent::Ipc { id, msg } => match msg.method() {
-                    IpcMethod::Initialize => app.handle_initialize_msg(id),
-                    IpcMethod::FileDialog => app.handle_file_dialog_msg(msg, id),
-                    IpcMethod::UserEvent => app.handle_user_event_msg(msg, id),
-                    IpcMethod::Query => app.handle_query_msg(msg, id),
-                    IpcMethod::BrowserOpen => app.handle_browser_open(msg),
-                    IpcMethod::Other(_) => {}
-                },
-            },
-            _ => {}
-        }
-
-        *control_flow = app.control_flow;
+        *control_flow = app.handle_event(window_event);
     })
 }
 
@@ -89,3 +48,159 @@ pub fn launch(
 
     launch_virtual_dom(virtual_dom, platform_config)
 }
+
+/// Launch the WebView and run the event loop like [`launch_virtual_dom_blocking`], but return the exit code passed to
+/// [`DesktopService::exit_app`](crate::DesktopService::exit_app) once the app exits instead of exiting the process.
+///
+/// > Note: Only supported on Windows, macOS and Linux. The event loop can only be created once per process, so the app
+/// > can't be launched again after this returns.
+#[cfg(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+pub fn launch_virtual_dom_blocking_and_return(
+    virtual_dom: VirtualDom,
+    desktop_config: Config,
+) -> i32 {
+    use tao::platform::run_return::EventLoopExtRunReturn;
+
+    let (mut event_loop, mut app) = App::new(desktop_config, virtual_dom);
+
+    event_loop.run_return(move |window_event, _, control_flow| {
+        *control_flow = app.handle_event(window_event);
+    })
+}
+
+/// Launches the WebView and runs the event loop, returning the exit code once the app exits. See
+/// [`launch_virtual_dom_blocking_and_return`].
+#[cfg(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+pub fn launch_virtual_dom_and_return(virtual_dom: VirtualDom, desktop_config: Config) -> i32 {
+    #[cfg(feature = "tokio")]
+    return tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(tokio::task::unconstrained(async move {
+            launch_virtual_dom_blocking_and_return(virtual_dom, desktop_config)
+        }));
+
+    #[cfg(not(feature = "tokio"))]
+    launch_virtual_dom_blocking_and_return(virtual_dom, desktop_config)
+}
+
+/// Launches the WebView and runs the event loop, returning the exit code once the app exits. See
+/// [`launch_virtual_dom_blocking_and_return`].
+///
+/// ```rust, ignore
+/// let code = dioxus_desktop::launch::launch_and_return(app, vec![], Config::new());
+/// cleanup();
+/// std::process::exit(code);
+/// ```
+#[cfg(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+pub fn launch_and_return(
+    root: fn() -> Element,
+    contexts: Vec<Box<dyn Fn() -> Box<dyn Any>>>,
+    platform_config: Config,
+) -> i32 {
+    let mut virtual_dom = VirtualDom::new(root);
+
+    for context in contexts {
+        virtual_dom.insert_any_root_context(context());
+    }
+
+    launch_virtual_dom_and_return(virtual_dom, platform_config)
+}
+
+/// A desktop app driven by an event loop that is owned by the host application, so dioxus webviews can live inside a
+/// larger native application or a game editor.
+///
+/// The event loop has to be created with [`DesktopApp::event_loop`], since dioxus sends its own events through it, and
+/// every event has to be handed to [`DesktopApp::handle_event`]. Events of windows that don't belong to dioxus are
+/// ignored, so the host can handle its own windows in the same loop.
+///
+/// ```rust, ignore
+/// let config = Config::new();
+/// let event_loop = DesktopApp::event_loop(&config);
+/// let mut app = DesktopApp::new(&event_loop, event_loop.create_proxy(), VirtualDom::new(app), config);
+///
+/// event_loop.run(move |event, _, control_flow| {
+///     if let Event::WindowEvent { window_id, .. } = &event {
+///         if *window_id == editor_window.id() {
+///             // handle the host's own window
+///         }
+///     }
+///
+///     *control_flow = app.handle_event(event);
+/// });
+/// ```
+pub struct DesktopApp {
+    app: App,
+    started: bool,
+}
+
+impl DesktopApp {
+    /// Create an event loop that can run dioxus windows next to the host's own windows, with the settings of the
+    /// config the app will be created with that apply to the whole event loop
+    pub fn event_loop(desktop_config: &Config) -> EventLoop<UserWindowEvent> {
+        let mut event_loop = EventLoopBuilder::with_user_event();
+        desktop_config.wayland.apply_to_event_loop(&mut event_loop);
+        event_loop.build()
+    }
+
+    /// Create the app on the host's event loop. The windows of the app are created when the event loop starts, or with
+    /// the first event if it is already running.
+    pub fn new(
+        target: &EventLoopWindowTarget<UserWindowEvent>,
+        proxy: EventLoopProxy<UserWindowEvent>,
+        virtual_dom: VirtualDom,
+        desktop_config: Config,
+    ) -> Self {
+        Self {
+            app: App::with_event_loop(desktop_config, virtual_dom, target, proxy),
+            started: false,
+        }
+    }
+
+    /// Handle an event of the event loop and return the control flow the app needs
+    ///
+    /// [`ControlFlow::ExitWithCode`] means the app is done, because its last window was closed or it asked to exit.
+    /// The host can keep running the event loop for its own windows instead of exiting.
+    pub fn handle_event(&mut self, event: Event<'_, UserWindowEvent>) -> ControlFlow {
+        // The app creates its windows when the event loop starts, the host may have started it before the app existed
+        if !self.started {
+            self.started = true;
+            if !matches!(event, Event::NewEvents(StartCause::Init)) {
+                self.app.handle_start_cause_init();
+            }
+        }
+
+        if let Event::WindowEvent { window_id, .. } = &event {
+            if !self.app.owns_window(window_id) {
+                return ControlFlow::Wait;
+            }
+        }
+
+        self.app.handle_event(event)
+    }
+}
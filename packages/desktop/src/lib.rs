@@ -3,24 +3,68 @@
 #![doc(html_favicon_url = "https://avatars.githubusercontent.com/u/79236386")]
 #![deny(missing_docs)]
 
+mod animation_frame;
 mod app;
+mod app_handle;
 mod assets;
+mod attention;
+mod back_button;
+mod binary_ipc;
+mod broadcast;
+mod compression;
 mod config;
+mod context_menu;
+mod cookies;
+mod crash;
 mod desktop_context;
+mod dock_menu;
+mod download;
+mod drag_image;
+mod drag_region;
 mod edits;
 mod element;
 mod eval;
 mod event_handlers;
+mod event_throttle;
 mod events;
+mod file_dialog;
 mod file_upload;
+mod find;
 mod hooks;
+mod ime;
 mod ipc;
+mod ipc_handlers;
+mod ipc_tracing;
+mod jump_list;
+mod kiosk;
 mod menubar;
+mod monitor;
+mod navigation;
+mod notification;
+mod open_file;
+mod page_load;
+mod permissions;
+mod power;
 mod protocol;
 mod query;
+mod safe_area;
+mod security;
 mod shortcut;
+mod snapshot;
+mod splash;
+mod titlebar;
+mod virtual_keyboard;
 mod waker;
+mod wayland;
 mod webview;
+mod webview_options;
+mod window_chrome;
+mod window_effect;
+mod window_event;
+mod window_shortcut;
+
+#[cfg(feature = "updater")]
+mod updater;
 
 // mobile shortcut is only supported on mobile platforms
 #[cfg(any(target_os = "ios", target_os = "android"))]
@@ -30,6 +74,7 @@ mod mobile_shortcut;
 pub mod launch;
 
 // Reexport tao and wry, might want to re-export other important things
+pub use include_dir;
 pub use tao;
 pub use tao::dpi::{LogicalPosition, LogicalSize};
 pub use tao::event::WindowEvent;
@@ -37,10 +82,62 @@ pub use tao::window::WindowBuilder;
 pub use wry;
 
 // Public exports
+pub use animation_frame::{use_animation_frame, PollLease};
+pub use app_handle::AppHandle;
 pub use assets::AssetRequest;
-pub use config::{Config, WindowCloseBehaviour};
+pub use attention::UserAttention;
+pub use back_button::{use_back_button, BackButtonEvent};
+pub use broadcast::use_broadcast;
+pub use config::{Config, WindowCloseBehaviour, WindowKind};
+pub use context_menu::{use_context_menu, ContextMenu, ContextMenuEvent, UseContextMenu};
+pub use cookies::{Cookie, CookieError, CookieStore};
+pub use crash::CrashConfig;
 pub use desktop_context::{window, DesktopContext, DesktopService};
+pub use dock_menu::use_dock_menu_event;
+pub use download::{use_download_event, DownloadAction, DownloadEvent, DownloadRequest};
+pub use drag_image::DragImage;
 pub use event_handlers::WryEventHandler;
-pub use hooks::{use_asset_handler, use_global_shortcut, use_window, use_wry_event_handler};
+pub use file_dialog::FileDialog;
+pub use file_upload::FileDropConfig;
+pub use find::{use_find_result, FindOptions, FindResult};
+pub use hooks::{
+    use_app_exit, use_asset_handler, use_global_shortcut, use_menu_item, use_window,
+    use_window_shortcut, use_wry_event_handler,
+};
+pub use jump_list::{
+    add_recent_document, clear_recent_documents, set_jump_list, use_jump_list_task, JumpList,
+    JumpListTask,
+};
+pub use launch::DesktopApp;
+pub use menubar::{MenuItemHandle, MenuRole};
+pub use monitor::MonitorInfo;
+pub use navigation::{NavigationAction, NavigationRequest, NewWindowPolicy};
+pub use notification::{use_notification_event, Notification, NotificationEvent};
+pub use open_file::{register_file_association, use_open_file_event};
+pub use page_load::{use_page_load_event, PageLoadEvent};
+pub use permissions::{PermissionKind, PermissionRequest, PermissionResponse};
+pub use power::KeepAwakeGuard;
+pub use safe_area::{use_safe_area_insets, SafeAreaInsets};
 pub use shortcut::{ShortcutHandle, ShortcutRegistryError};
+pub use snapshot::SnapshotError;
+pub use titlebar::{TitleBar, TitleBarProps, WindowControls, WindowControlsProps};
+pub use wayland::WaylandConfig;
+pub use webview_options::{HardwareAcceleration, WKWebViewConfig, WebKitGtkConfig, WebView2Config};
+pub use window_effect::WindowEffect;
+pub use window_event::{use_window_event, DesktopWindowEvent};
+pub use window_shortcut::WindowShortcutHandle;
 pub use wry::RequestAsyncResponder;
+
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+pub use window_effect::NSVisualEffectMaterial;
+
+#[cfg(feature = "wayland-decorations")]
+pub use wayland::WaylandDecorations;
+
+#[cfg(feature = "wayland-layer-shell")]
+pub use wayland::{WaylandAnchor, WaylandLayer, WaylandLayerShell};
+
+#[cfg(feature = "updater")]
+pub use updater::{
+    use_updater, use_updater_event, Update, Updater, UpdaterConfig, UpdaterError, UpdaterEvent,
+};
@@ -1,7 +1,11 @@
 use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
 
 use tao::window::Window;
 
+use crate::{window, window_shortcut::WindowShortcutHandle, ShortcutRegistryError};
+
 #[allow(unused)]
 pub fn build_menu(window: &Window, default_menu_bar: bool) -> Option<Box<dyn Any>> {
     #[cfg(not(any(target_os = "ios", target_os = "android")))]
@@ -15,7 +19,52 @@ pub fn build_menu(window: &Window, default_menu_bar: bool) -> Option<Box<dyn Any
 #[cfg(not(any(target_os = "ios", target_os = "android")))]
 mod impl_ {
     use super::*;
-    use muda::{Menu, MenuItem, PredefinedMenuItem, Submenu};
+    use muda::{Menu, MenuItem, MenuItemKind, PredefinedMenuItem, Submenu};
+
+    /// Create the native item for a role
+    pub fn predefined(role: MenuRole) -> PredefinedMenuItem {
+        match role {
+            MenuRole::About => PredefinedMenuItem::about(None, None),
+            MenuRole::Hide => PredefinedMenuItem::hide(None),
+            MenuRole::HideOthers => PredefinedMenuItem::hide_others(None),
+            MenuRole::ShowAll => PredefinedMenuItem::show_all(None),
+            MenuRole::Quit => PredefinedMenuItem::quit(None),
+            MenuRole::Undo => PredefinedMenuItem::undo(None),
+            MenuRole::Redo => PredefinedMenuItem::redo(None),
+            MenuRole::Cut => PredefinedMenuItem::cut(None),
+            MenuRole::Copy => PredefinedMenuItem::copy(None),
+            MenuRole::Paste => PredefinedMenuItem::paste(None),
+            MenuRole::SelectAll => PredefinedMenuItem::select_all(None),
+            MenuRole::Minimize => PredefinedMenuItem::minimize(None),
+            MenuRole::Maximize => PredefinedMenuItem::maximize(None),
+            MenuRole::Fullscreen => PredefinedMenuItem::fullscreen(None),
+            MenuRole::CloseWindow => PredefinedMenuItem::close_window(None),
+            MenuRole::Separator => PredefinedMenuItem::separator(),
+        }
+    }
+
+    /// Find the submenu of the menu bar with the given title, appending a new one if there isn't one
+    pub fn find_or_create_submenu(menu: &Menu, title: &str) -> muda::Result<Submenu> {
+        let existing = menu.items().into_iter().find_map(|item| match item {
+            MenuItemKind::Submenu(submenu) if submenu.text() == title => Some(submenu),
+            _ => None,
+        });
+
+        if let Some(submenu) = existing {
+            return Ok(submenu);
+        }
+
+        let submenu = Submenu::new(title, true);
+        menu.append(&submenu)?;
+
+        // Let macOS list the open windows in the window menu, like it does for the default menu bar
+        #[cfg(target_os = "macos")]
+        if title == "Window" {
+            submenu.set_as_windows_menu_for_nsapp();
+        }
+
+        Ok(submenu)
+    }
 
     /// Builds a standard menu bar depending on the users platform. It may be used as a starting point
     /// to further customize the menu bar and pass it to a [`WindowBuilder`](tao::window::WindowBuilder).
@@ -96,3 +145,179 @@ mod impl_ {
         menu
     }
 }
+
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+pub(crate) use impl_::predefined;
+
+/// A menu item with behavior built into the platform
+///
+/// On macOS these are wired to the responder chain, so for example [`MenuRole::Copy`] and its `Cmd+C` accelerator
+/// copy the text selected in the webview. Hand rolled menu items can't do that, so any custom menu bar that replaces
+/// the default one should include the edit roles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MenuRole {
+    /// Show the about dialog of the app
+    About,
+    /// Hide the app. Only supported on macOS
+    Hide,
+    /// Hide all other apps. Only supported on macOS
+    HideOthers,
+    /// Show all apps. Only supported on macOS
+    ShowAll,
+    /// Quit the app
+    Quit,
+    /// Undo the last edit
+    Undo,
+    /// Redo the last undone edit
+    Redo,
+    /// Cut the selection to the clipboard
+    Cut,
+    /// Copy the selection to the clipboard
+    Copy,
+    /// Paste from the clipboard
+    Paste,
+    /// Select everything in the focused element
+    SelectAll,
+    /// Minimize the window
+    Minimize,
+    /// Maximize the window
+    Maximize,
+    /// Toggle fullscreen. Only supported on macOS
+    Fullscreen,
+    /// Close the window
+    CloseWindow,
+    /// A separator line
+    Separator,
+}
+
+/// A handle to a menu item added with [`DesktopService::add_menu_item`](crate::DesktopService::add_menu_item)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MenuItemHandle {
+    id: String,
+    shortcut: Option<WindowShortcutHandle>,
+}
+
+impl MenuItemHandle {
+    /// Remove the menu item from the menu bar
+    pub fn remove(&self) {
+        window().remove_menu_item(self);
+    }
+}
+
+/// Routes selections of menu items added at runtime to their callbacks
+#[derive(Default)]
+pub(crate) struct MenuItemRegistry {
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    items: RefCell<std::collections::HashMap<String, MenuItemEntry>>,
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+struct MenuItemEntry {
+    submenu: muda::Submenu,
+    item: Box<dyn muda::IsMenuItem>,
+    callback: Option<Rc<RefCell<Box<dyn FnMut()>>>>,
+}
+
+impl MenuItemRegistry {
+    /// Add an item to the submenu with the given title, creating the submenu if the menu bar doesn't have one yet
+    ///
+    /// The accelerator of the item is a shortcut of the window's shortcut registry, which is removed with the item.
+    #[allow(unused)]
+    pub(crate) fn add(
+        &self,
+        menu: Option<&dyn Any>,
+        submenu: &str,
+        label: &str,
+        shortcut: Option<WindowShortcutHandle>,
+        callback: Rc<RefCell<Box<dyn FnMut()>>>,
+    ) -> Result<MenuItemHandle, ShortcutRegistryError> {
+        #[cfg(not(any(target_os = "ios", target_os = "android")))]
+        {
+            let item = muda::MenuItem::new(label, true, None);
+            self.append(menu, submenu, Box::new(item), shortcut, Some(callback))
+        }
+
+        #[cfg(any(target_os = "ios", target_os = "android"))]
+        Err(ShortcutRegistryError::InvalidShortcut(
+            "Menu items are not supported on this platform".to_string(),
+        ))
+    }
+
+    /// Add an item with built in behavior to the submenu with the given title
+    #[allow(unused)]
+    pub(crate) fn add_role(
+        &self,
+        menu: Option<&dyn Any>,
+        submenu: &str,
+        role: MenuRole,
+    ) -> Result<MenuItemHandle, ShortcutRegistryError> {
+        #[cfg(not(any(target_os = "ios", target_os = "android")))]
+        return self.append(menu, submenu, Box::new(impl_::predefined(role)), None, None);
+
+        #[cfg(any(target_os = "ios", target_os = "android"))]
+        Err(ShortcutRegistryError::InvalidShortcut(
+            "Menu items are not supported on this platform".to_string(),
+        ))
+    }
+
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    fn append(
+        &self,
+        menu: Option<&dyn Any>,
+        submenu: &str,
+        item: Box<dyn muda::IsMenuItem>,
+        shortcut: Option<WindowShortcutHandle>,
+        callback: Option<Rc<RefCell<Box<dyn FnMut()>>>>,
+    ) -> Result<MenuItemHandle, ShortcutRegistryError> {
+        let Some(menu) = menu.and_then(|menu| menu.downcast_ref::<muda::Menu>()) else {
+            return Err(ShortcutRegistryError::Other(Rc::new(
+                muda::Error::NotInitialized,
+            )));
+        };
+
+        let submenu = impl_::find_or_create_submenu(menu, submenu)
+            .map_err(|err| ShortcutRegistryError::Other(Rc::new(err)))?;
+
+        submenu
+            .append(item.as_ref())
+            .map_err(|err| ShortcutRegistryError::Other(Rc::new(err)))?;
+
+        let id = item.id().0.clone();
+        self.items.borrow_mut().insert(
+            id.clone(),
+            MenuItemEntry {
+                submenu,
+                item,
+                callback,
+            },
+        );
+
+        Ok(MenuItemHandle { id, shortcut })
+    }
+
+    /// Remove an item from its submenu, and return the shortcut that selected it
+    #[allow(unused)]
+    pub(crate) fn remove(&self, handle: &MenuItemHandle) -> Option<WindowShortcutHandle> {
+        #[cfg(not(any(target_os = "ios", target_os = "android")))]
+        if let Some(entry) = self.items.borrow_mut().remove(&handle.id) {
+            _ = entry.submenu.remove(entry.item.as_ref());
+        }
+
+        handle.shortcut
+    }
+
+    /// Call the callback of the menu item the event came from, if it was added at runtime
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    pub(crate) fn handle_menu_event(&self, event: &muda::MenuEvent) {
+        // Clone the callback out so it can add or remove menu items itself
+        let callback = self
+            .items
+            .borrow()
+            .get(&event.id().0)
+            .and_then(|entry| entry.callback.clone());
+
+        if let Some(callback) = callback {
+            (callback.borrow_mut())();
+        }
+    }
+}
@@ -9,7 +9,7 @@ use dioxus_html::input_data::keyboard_types::Modifiers;
 #[derive(Clone, Debug)]
 pub struct Accelerator;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 pub struct HotKey;
 
 impl HotKey {
@@ -56,8 +56,9 @@ use std::{error, fmt};
 #[non_exhaustive]
 #[derive(Debug)]
 pub enum HotkeyError {
-    AcceleratorAlreadyRegistered(Accelerator),
-    AcceleratorNotRegistered(Accelerator),
+    AlreadyRegistered(HotKey),
+    FailedToRegister(String),
+    FailedToUnRegister(HotKey),
     HotKeyParseError(String),
 }
 
@@ -65,12 +66,11 @@ impl error::Error for HotkeyError {}
 impl fmt::Display for HotkeyError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         match self {
-            HotkeyError::AcceleratorAlreadyRegistered(e) => {
+            HotkeyError::AlreadyRegistered(e) => {
                 f.pad(&format!("hotkey already registered: {:?}", e))
             }
-            HotkeyError::AcceleratorNotRegistered(e) => {
-                f.pad(&format!("hotkey not registered: {:?}", e))
-            }
+            HotkeyError::FailedToRegister(e) => f.pad(&format!("failed to register hotkey: {e}")),
+            HotkeyError::FailedToUnRegister(e) => f.pad(&format!("hotkey not registered: {:?}", e)),
             HotkeyError::HotKeyParseError(e) => e.fmt(f),
         }
     }
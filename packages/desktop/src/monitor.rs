@@ -0,0 +1,96 @@
+//! Information about the monitors connected to the machine and helpers to place windows on them
+
+use tao::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    monitor::MonitorHandle,
+    window::Window,
+};
+
+/// A monitor connected to the machine, as returned by [`DesktopService::monitors`](crate::DesktopService::monitors)
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorInfo {
+    /// The index of the monitor, which can be passed to [`DesktopService::move_to_monitor`](crate::DesktopService::move_to_monitor)
+    /// and [`DesktopService::center_on_monitor`](crate::DesktopService::center_on_monitor)
+    pub index: usize,
+    /// The human readable name of the monitor, if the platform provides one
+    pub name: Option<String>,
+    /// The position of the top left corner of the monitor on the virtual desktop
+    pub position: PhysicalPosition<i32>,
+    /// The resolution of the monitor
+    pub size: PhysicalSize<u32>,
+    /// The factor logical pixels are scaled by to get physical pixels on this monitor
+    pub scale_factor: f64,
+    /// Whether this is the primary monitor of the system
+    pub is_primary: bool,
+    /// Whether the window this was queried from is currently on this monitor
+    pub is_current: bool,
+}
+
+pub(crate) fn monitors(window: &Window) -> Vec<MonitorInfo> {
+    let primary = window.primary_monitor();
+    let current = window.current_monitor();
+
+    window
+        .available_monitors()
+        .enumerate()
+        .map(|(index, monitor)| MonitorInfo {
+            index,
+            name: monitor.name(),
+            position: monitor.position(),
+            size: monitor.size(),
+            scale_factor: monitor.scale_factor(),
+            is_primary: primary.as_ref() == Some(&monitor),
+            is_current: current.as_ref() == Some(&monitor),
+        })
+        .collect()
+}
+
+fn monitor(window: &Window, index: usize) -> Option<MonitorHandle> {
+    let monitor = window.available_monitors().nth(index);
+    if monitor.is_none() {
+        tracing::warn!("There is no monitor with index {index}");
+    }
+    monitor
+}
+
+/// Move the window to another monitor, keeping its position relative to the top left corner of the monitor it is on
+pub(crate) fn move_to_monitor(window: &Window, index: usize) {
+    let Some(target) = monitor(window, index) else {
+        return;
+    };
+
+    let Ok(position) = window.outer_position() else {
+        return;
+    };
+
+    let origin = window
+        .current_monitor()
+        .map(|monitor| monitor.position())
+        .unwrap_or_default();
+    let offset = (position.x - origin.x, position.y - origin.y);
+
+    // Keep the window on the target monitor if it is smaller than the current one
+    let size = window.outer_size();
+    let max_x = target.size().width.saturating_sub(size.width) as i32;
+    let max_y = target.size().height.saturating_sub(size.height) as i32;
+
+    let target_origin = target.position();
+    window.set_outer_position(PhysicalPosition::new(
+        target_origin.x + offset.0.clamp(0, max_x),
+        target_origin.y + offset.1.clamp(0, max_y),
+    ));
+}
+
+/// Center the window on a monitor
+pub(crate) fn center_on_monitor(window: &Window, index: usize) {
+    let Some(target) = monitor(window, index) else {
+        return;
+    };
+
+    let size = window.outer_size();
+    let origin = target.position();
+    window.set_outer_position(PhysicalPosition::new(
+        origin.x + (target.size().width as i32 - size.width as i32) / 2,
+        origin.y + (target.size().height as i32 - size.height as i32) / 2,
+    ));
+}
@@ -0,0 +1,194 @@
+//! Deciding what happens when the page navigates away from the app or asks for a new window
+
+use crate::ipc::UserWindowEvent;
+use std::cell::RefCell;
+use tao::{
+    event_loop::{EventLoopProxy, EventLoopWindowTarget},
+    window::{Window, WindowBuilder, WindowId},
+};
+use wry::{http::Uri, WebView, WebViewBuilder};
+
+/// A navigation the page started, passed to the handler set with
+/// [`Config::with_navigation_handler`](crate::Config::with_navigation_handler)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NavigationRequest {
+    /// The URL the page wants to go to
+    pub url: String,
+    /// Whether the page asked for a new window, like a link with `target="_blank"`, instead of navigating in place
+    pub new_window: bool,
+}
+
+/// What to do with a navigation, returned by the handler set with
+/// [`Config::with_navigation_handler`](crate::Config::with_navigation_handler)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavigationAction {
+    /// Let the webview navigate. New windows follow the window's [`NewWindowPolicy`].
+    Allow,
+    /// Open the URL in the default browser
+    OpenExternally,
+    /// Open the URL in a new window of the app
+    OpenInNewWindow,
+    /// Don't navigate anywhere
+    Cancel,
+}
+
+/// What happens when the page asks for a new window, set with
+/// [`Config::with_new_window_policy`](crate::Config::with_new_window_policy)
+///
+/// This applies to links with `target="_blank"`, `window.open` and links to other sites the interpreter intercepts,
+/// unless the navigation handler decides otherwise.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NewWindowPolicy {
+    /// Open the URL in the default browser
+    #[default]
+    OpenExternally,
+    /// Open the URL in a new window of the app
+    OpenInNewWindow,
+    /// Ignore the request
+    Suppress,
+}
+
+impl NewWindowPolicy {
+    fn action(self) -> NavigationAction {
+        match self {
+            NewWindowPolicy::OpenExternally => NavigationAction::OpenExternally,
+            NewWindowPolicy::OpenInNewWindow => NavigationAction::OpenInNewWindow,
+            NewWindowPolicy::Suppress => NavigationAction::Cancel,
+        }
+    }
+}
+
+pub(crate) type NavigationHandler = Box<dyn FnMut(&NavigationRequest) -> NavigationAction>;
+
+/// Applies the navigation handler of a window to the navigations its page starts
+pub(crate) struct NavigationPolicy {
+    handler: Option<RefCell<NavigationHandler>>,
+    new_windows: NewWindowPolicy,
+    proxy: EventLoopProxy<UserWindowEvent>,
+}
+
+impl NavigationPolicy {
+    pub(crate) fn new(
+        handler: Option<NavigationHandler>,
+        new_windows: NewWindowPolicy,
+        proxy: EventLoopProxy<UserWindowEvent>,
+    ) -> Self {
+        Self {
+            handler: handler.map(RefCell::new),
+            new_windows,
+            proxy,
+        }
+    }
+
+    /// Decide about a navigation and carry out the decision, returning whether the webview should go ahead itself
+    pub(crate) fn navigate(&self, url: String, new_window: bool) -> bool {
+        // The app's own pages are always allowed
+        if !new_window && is_app_url(&url) {
+            return true;
+        }
+
+        let request = NavigationRequest { url, new_window };
+        let action = match &self.handler {
+            Some(handler) => (handler.borrow_mut())(&request),
+            // Without a handler, the page only ever shows the app
+            None if new_window => NavigationAction::Allow,
+            None => NavigationAction::Cancel,
+        };
+
+        // The webviews disagree on what to do with new windows, so the app always decides
+        let action = match action {
+            NavigationAction::Allow if new_window => self.new_windows.action(),
+            action => action,
+        };
+
+        match action {
+            NavigationAction::Allow => return true,
+            NavigationAction::OpenExternally => open_externally(&request.url),
+            NavigationAction::OpenInNewWindow => {
+                _ = self
+                    .proxy
+                    .send_event(UserWindowEvent::OpenUrlWindow(request.url));
+            }
+            NavigationAction::Cancel => {}
+        }
+
+        false
+    }
+}
+
+/// Whether the URL points at a page served by the app itself
+///
+/// The webviews serve the `dioxus` protocol either as `dioxus://index.html/` or, on Windows and Android, as
+/// `http://dioxus.index.html/`.
+fn is_app_url(url: &str) -> bool {
+    let Ok(uri) = url.parse::<Uri>() else {
+        return false;
+    };
+
+    match (uri.scheme_str(), uri.host()) {
+        (Some("dioxus"), _) => true,
+        // Other hosts that start with `dioxus.` are real domains
+        (Some("http" | "https"), Some(host)) => host == "dioxus.index.html",
+        _ => false,
+    }
+}
+
+/// Open a URL in the default browser
+pub(crate) fn open_externally(url: &str) {
+    if let Err(err) = webbrowser::open(url) {
+        tracing::error!("Open Browser error: {:?}", err);
+    }
+}
+
+/// A window that shows a web page the app navigated to with [`NavigationAction::OpenInNewWindow`]
+pub(crate) struct UrlWindow {
+    // The webview must be dropped before the window it lives in
+    _webview: WebView,
+    window: Window,
+}
+
+impl UrlWindow {
+    pub(crate) fn new(url: &str, target: &EventLoopWindowTarget<UserWindowEvent>) -> Option<Self> {
+        let window = WindowBuilder::new()
+            .with_title(url)
+            .build(target)
+            .map_err(|err| tracing::error!("Failed to create window for {url}: {err}"))
+            .ok()?;
+
+        #[cfg(any(
+            target_os = "windows",
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "android"
+        ))]
+        let webview = WebViewBuilder::new(&window);
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "android"
+        )))]
+        let webview = {
+            use tao::platform::unix::WindowExtUnix;
+            use wry::WebViewBuilderExtUnix;
+            let vbox = window.default_vbox().unwrap();
+            WebViewBuilder::new_gtk(vbox)
+        };
+
+        let webview = webview
+            .with_url(url)
+            .build()
+            .map_err(|err| tracing::error!("Failed to create webview for {url}: {err}"))
+            .ok()?;
+
+        Some(Self {
+            _webview: webview,
+            window,
+        })
+    }
+
+    pub(crate) fn id(&self) -> WindowId {
+        self.window.id()
+    }
+}
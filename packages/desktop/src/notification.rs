@@ -0,0 +1,423 @@
+//! Native notifications with action buttons and inline replies
+
+use crate::{hooks::use_event_listener, ipc::UserWindowEvent};
+use tao::event_loop::EventLoopProxy;
+
+/// A native notification shown with [`DesktopService::show_notification`](crate::DesktopService::show_notification)
+///
+/// ```rust, ignore
+/// let notification = Notification::new("message-42", "Alice")
+///     .with_body("Are you coming tonight?")
+///     .with_action("like", "Like")
+///     .with_reply("Reply to Alice");
+///
+/// window().show_notification(notification);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub(crate) id: String,
+    pub(crate) title: String,
+    pub(crate) body: Option<String>,
+    pub(crate) actions: Vec<NotificationAction>,
+    pub(crate) reply: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct NotificationAction {
+    pub(crate) id: String,
+    pub(crate) label: String,
+}
+
+impl Notification {
+    /// Create a notification. `id` is reported back in every [`NotificationEvent`] of the notification.
+    pub fn new(id: impl Into<String>, title: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            title: title.into(),
+            body: None,
+            actions: Vec::new(),
+            reply: None,
+        }
+    }
+
+    /// Set the text shown below the title
+    pub fn with_body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Add a button. `id` is reported back in [`NotificationEvent::Action`] when the button is clicked.
+    ///
+    /// > Note: macOS shows a single button, or a dropdown for more than one action.
+    pub fn with_action(mut self, id: impl Into<String>, label: impl Into<String>) -> Self {
+        self.actions.push(NotificationAction {
+            id: id.into(),
+            label: label.into(),
+        });
+        self
+    }
+
+    /// Add a text field to answer from the notification itself. The text is reported back in
+    /// [`NotificationEvent::Reply`].
+    ///
+    /// > Note: Only supported on Windows and macOS. On macOS the reply replaces the action buttons.
+    pub fn with_reply(mut self, placeholder: impl Into<String>) -> Self {
+        self.reply = Some(placeholder.into());
+        self
+    }
+}
+
+/// What the user did with a notification
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NotificationEvent {
+    /// The notification itself was clicked
+    Clicked {
+        /// The id the notification was created with
+        notification: String,
+    },
+    /// One of the buttons added with [`Notification::with_action`] was clicked
+    Action {
+        /// The id the notification was created with
+        notification: String,
+        /// The id the action was created with
+        action: String,
+    },
+    /// The user answered in the text field added with [`Notification::with_reply`]
+    Reply {
+        /// The id the notification was created with
+        notification: String,
+        /// The text the user entered
+        text: String,
+    },
+    /// The notification was closed without choosing anything
+    Dismissed {
+        /// The id the notification was created with
+        notification: String,
+    },
+}
+
+/// Listen for what the user does with the notifications shown by the app
+///
+/// ```rust, ignore
+/// use_notification_event(move |event| match event {
+///     NotificationEvent::Reply { notification, text } => send_message(&notification, &text),
+///     NotificationEvent::Clicked { .. } => window().set_focus(),
+///     _ => {}
+/// });
+/// ```
+pub fn use_notification_event(handler: impl FnMut(NotificationEvent) + 'static) {
+    use_event_listener(|window| &window.shared.notification_listeners, handler)
+}
+
+/// Show a notification. The responses arrive on other threads, so they are sent through the event loop.
+pub(crate) fn show(notification: Notification, proxy: EventLoopProxy<UserWindowEvent>) {
+    let send = move |event| _ = proxy.send_event(UserWindowEvent::Notification(event));
+
+    if let Err(err) = platform::show(notification, send) {
+        tracing::warn!("Failed to show notification: {err}");
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+    use windows::{
+        core::{IInspectable, Interface, HSTRING},
+        Data::Xml::Dom::XmlDocument,
+        Foundation::{IPropertyValue, TypedEventHandler},
+        UI::Notifications::{
+            ToastActivatedEventArgs, ToastDismissalReason, ToastDismissedEventArgs,
+            ToastNotification, ToastNotificationManager,
+        },
+    };
+
+    /// The arguments of the button that sends the reply
+    const REPLY_ARGUMENTS: &str = "__dioxus_reply";
+
+    pub(super) fn show(
+        notification: Notification,
+        send: impl Fn(NotificationEvent) + Send + Clone + 'static,
+    ) -> windows::core::Result<()> {
+        let xml = XmlDocument::new()?;
+        xml.LoadXml(&HSTRING::from(toast_xml(&notification)))?;
+        let toast = ToastNotification::CreateToastNotification(&xml)?;
+
+        let id = notification.id.clone();
+        let send_ = send.clone();
+        toast.Activated(&TypedEventHandler::<ToastNotification, IInspectable>::new(
+            move |_, args| {
+                let Some(args) = args.as_ref() else {
+                    return Ok(());
+                };
+                let args: ToastActivatedEventArgs = args.cast()?;
+                let arguments = args.Arguments()?.to_string();
+                let notification = id.clone();
+
+                send_(if arguments == REPLY_ARGUMENTS {
+                    let text = args
+                        .UserInput()?
+                        .Lookup(&HSTRING::from("reply"))?
+                        .cast::<IPropertyValue>()?
+                        .GetString()?
+                        .to_string();
+                    NotificationEvent::Reply { notification, text }
+                } else if arguments.is_empty() {
+                    NotificationEvent::Clicked { notification }
+                } else {
+                    NotificationEvent::Action {
+                        notification,
+                        action: arguments,
+                    }
+                });
+                Ok(())
+            },
+        ))?;
+
+        let id = notification.id.clone();
+        toast.Dismissed(&TypedEventHandler::<
+            ToastNotification,
+            ToastDismissedEventArgs,
+        >::new(move |_, args| {
+            // Notifications that time out move to the action center, they can still be answered from there
+            let timed_out = args
+                .as_ref()
+                .and_then(|args| args.Reason().ok())
+                .is_some_and(|reason| reason == ToastDismissalReason::TimedOut);
+            if !timed_out {
+                send(NotificationEvent::Dismissed {
+                    notification: id.clone(),
+                });
+            }
+            Ok(())
+        }))?;
+
+        ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(app_id()?))?.Show(&toast)
+    }
+
+    /// The app id notifications are shown for, so they show the app's name
+    ///
+    /// Apps that set an id for their process, like installed apps that are started from their shortcut, keep it.
+    /// Otherwise an id is derived from the name of the executable and registered for the current user the first time
+    /// a notification is shown, since Windows drops notifications of unregistered ids.
+    fn app_id() -> windows::core::Result<String> {
+        use std::sync::OnceLock;
+        use windows::Win32::{
+            Foundation::E_FAIL,
+            System::Com::CoTaskMemFree,
+            UI::Shell::{
+                GetCurrentProcessExplicitAppUserModelID, SetCurrentProcessExplicitAppUserModelID,
+            },
+        };
+
+        static APP_ID: OnceLock<Result<String, String>> = OnceLock::new();
+
+        let app_id = APP_ID.get_or_init(|| unsafe {
+            if let Ok(id) = GetCurrentProcessExplicitAppUserModelID() {
+                let app_id = id.to_string().unwrap_or_default();
+                CoTaskMemFree(Some(id.0 as _));
+                return Ok(app_id);
+            }
+
+            let name = std::env::current_exe()
+                .ok()
+                .and_then(|exe| Some(exe.file_stem()?.to_string_lossy().to_string()))
+                .ok_or_else(|| "could not find the name of the executable".to_string())?;
+            let app_id = format!("dioxus.{name}");
+
+            register_app_id(&app_id, &name).map_err(|err| err.to_string())?;
+            SetCurrentProcessExplicitAppUserModelID(&HSTRING::from(&app_id))
+                .map_err(|err| err.to_string())?;
+            Ok(app_id)
+        });
+
+        app_id
+            .clone()
+            .map_err(|err| windows::core::Error::new(E_FAIL, HSTRING::from(err)))
+    }
+
+    /// Register the app id with the name Windows shows in the notifications of the app
+    fn register_app_id(app_id: &str, name: &str) -> std::io::Result<()> {
+        let key = format!(r"HKCU\Software\Classes\AppUserModelId\{app_id}");
+        let status = std::process::Command::new("reg")
+            .args(["add", &key, "/v", "DisplayName", "/d", name, "/f"])
+            .status()?;
+
+        match status.success() {
+            true => Ok(()),
+            false => Err(std::io::Error::other(format!("failed to write {key}"))),
+        }
+    }
+
+    fn toast_xml(notification: &Notification) -> String {
+        let mut xml = String::from(r#"<toast><visual><binding template="ToastGeneric">"#);
+        xml.push_str(&format!("<text>{}</text>", escape(&notification.title)));
+        if let Some(body) = &notification.body {
+            xml.push_str(&format!("<text>{}</text>", escape(body)));
+        }
+        xml.push_str("</binding></visual><actions>");
+
+        if let Some(placeholder) = &notification.reply {
+            xml.push_str(&format!(
+                r#"<input id="reply" type="text" placeHolderContent="{}"/>"#,
+                escape(placeholder)
+            ));
+            xml.push_str(&format!(
+                r#"<action content="Send" arguments="{}" hint-inputId="reply"/>"#,
+                escape(REPLY_ARGUMENTS)
+            ));
+        }
+        for action in &notification.actions {
+            xml.push_str(&format!(
+                r#"<action content="{}" arguments="{}"/>"#,
+                escape(&action.label),
+                escape(&action.id)
+            ));
+        }
+
+        xml.push_str("</actions></toast>");
+        xml
+    }
+
+    fn escape(text: &str) -> String {
+        text.chars()
+            .map(|c| match c {
+                '&' => "&amp;".to_string(),
+                '<' => "&lt;".to_string(),
+                '>' => "&gt;".to_string(),
+                '"' => "&quot;".to_string(),
+                c => c.to_string(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+    use mac_notification_sys::{MainButton, NotificationResponse};
+
+    pub(super) fn show(
+        notification: Notification,
+        send: impl Fn(NotificationEvent) + Send + 'static,
+    ) -> Result<(), std::convert::Infallible> {
+        // Notifications with buttons block until the user responds
+        std::thread::spawn(move || {
+            let labels: Vec<&str> = notification
+                .actions
+                .iter()
+                .map(|action| action.label.as_str())
+                .collect();
+
+            let mut native = mac_notification_sys::Notification::new();
+            native.title(&notification.title);
+            if let Some(body) = &notification.body {
+                native.message(body);
+            }
+            if let Some(placeholder) = &notification.reply {
+                native.main_button(MainButton::Response(placeholder));
+            } else if let [label] = labels.as_slice() {
+                native.main_button(MainButton::SingleAction(label));
+            } else if !labels.is_empty() {
+                native.main_button(MainButton::DropdownActions("Actions", &labels));
+            }
+
+            let response = match native.send() {
+                Ok(response) => response,
+                Err(err) => {
+                    tracing::warn!("Failed to show notification: {err}");
+                    return;
+                }
+            };
+
+            let id = notification.id.clone();
+            let event = match response {
+                NotificationResponse::Click => NotificationEvent::Clicked { notification: id },
+                NotificationResponse::Reply(text) => NotificationEvent::Reply {
+                    notification: id,
+                    text,
+                },
+                NotificationResponse::ActionButton(label) => {
+                    // macOS reports the label of the button, not the action
+                    let Some(action) = notification
+                        .actions
+                        .iter()
+                        .find(|action| action.label == label)
+                    else {
+                        return;
+                    };
+                    NotificationEvent::Action {
+                        notification: id,
+                        action: action.id.clone(),
+                    }
+                }
+                NotificationResponse::CloseButton(_) | NotificationResponse::None => {
+                    NotificationEvent::Dismissed { notification: id }
+                }
+            };
+            send(event);
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+mod platform {
+    use super::*;
+
+    pub(super) fn show(
+        notification: Notification,
+        send: impl Fn(NotificationEvent) + Send + 'static,
+    ) -> notify_rust::error::Result<()> {
+        let mut native = notify_rust::Notification::new();
+        native.summary(&notification.title);
+        if let Some(body) = &notification.body {
+            native.body(body);
+        }
+
+        // The default action is invoked when the notification itself is clicked
+        native.action("default", "");
+        for action in &notification.actions {
+            native.action(&action.id, &action.label);
+        }
+
+        let handle = native.show()?;
+        let id = notification.id;
+
+        // Waiting for the response blocks until the notification is closed
+        std::thread::spawn(move || {
+            handle.wait_for_action(|action| {
+                let notification = id.clone();
+                send(match action {
+                    "default" => NotificationEvent::Clicked { notification },
+                    "__closed" => NotificationEvent::Dismissed { notification },
+                    action => NotificationEvent::Action {
+                        notification,
+                        action: action.to_string(),
+                    },
+                });
+            });
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(any(target_os = "ios", target_os = "android"))]
+mod platform {
+    use super::*;
+
+    pub(super) fn show(
+        _: Notification,
+        _: impl Fn(NotificationEvent) + Send + 'static,
+    ) -> Result<(), &'static str> {
+        Err("notifications are not supported on this platform")
+    }
+}
@@ -0,0 +1,286 @@
+//! Opening files with the app, from the command line, the file manager's "open with" menu or a second launch
+
+use crate::{hooks::use_event_listener, ipc::UserWindowEvent, jump_list};
+use interprocess::local_socket::{LocalSocketListener, LocalSocketStream};
+use std::{
+    ffi::OsString,
+    io::{BufRead, BufReader, Write},
+    path::PathBuf,
+};
+use tao::event_loop::EventLoopProxy;
+
+/// Listen for files the app is asked to open
+///
+/// This includes files passed on the command line when the app starts, files opened with the app from the file manager
+/// and, with [`Config::with_single_instance`](crate::Config::with_single_instance), files passed to later launches of
+/// the app. The handler runs in the scope of the component that called this hook.
+///
+/// ```rust, ignore
+/// use_open_file_event(move |path| documents.push(path));
+/// ```
+pub fn use_open_file_event(handler: impl FnMut(PathBuf) + 'static) {
+    use_event_listener(|window| &window.shared.open_file_listeners, handler)
+}
+
+/// The files passed as arguments to the current process
+pub(crate) fn files_from_args(args: impl IntoIterator<Item = String>) -> Vec<PathBuf> {
+    args.into_iter()
+        .map(PathBuf::from)
+        .filter(|path| path.is_file())
+        .filter_map(|path| dunce::canonicalize(path).ok())
+        .collect()
+}
+
+/// The local socket the running instance listens on, so later launches can find it
+///
+/// The socket only accepts connections from the current user. On unix it lives in `XDG_RUNTIME_DIR`, or in a
+/// directory in the temporary directory that only the user can access. On Windows it's a named pipe that includes the
+/// user's name.
+fn instance_socket(id: &str) -> std::io::Result<OsString> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+        let dir = match std::env::var_os("XDG_RUNTIME_DIR") {
+            Some(dir) => PathBuf::from(dir),
+            None => {
+                let dir = std::env::temp_dir().join(format!("{id}.dioxus-instance"));
+                match std::fs::DirBuilder::new().mode(0o700).create(&dir) {
+                    Ok(()) => {}
+                    Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {}
+                    Err(err) => return Err(err),
+                }
+
+                // Someone else could have created the directory first. Only its owner can create files in it once
+                // nobody else has access.
+                let metadata = std::fs::symlink_metadata(&dir)?;
+                let probe = dir.join(format!(".{}", std::process::id()));
+                if !metadata.is_dir()
+                    || metadata.mode() & 0o777 != 0o700
+                    || std::fs::write(&probe, []).is_err()
+                {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::PermissionDenied,
+                        format!("{} is not private to the current user", dir.display()),
+                    ));
+                }
+                _ = std::fs::remove_file(probe);
+                dir
+            }
+        };
+        Ok(dir.join(format!("{id}.dioxus-instance")).into_os_string())
+    }
+
+    #[cfg(not(unix))]
+    {
+        let user = std::env::var("USERNAME").unwrap_or_default();
+        Ok(format!("@{id}-{user}.dioxus-instance").into())
+    }
+}
+
+/// Whether this process is the instance of the app that runs, or handed its work over to the one that already did
+pub(crate) enum InstanceRole {
+    /// No other instance is running. Later launches connect to the listener, if it could be created.
+    Primary(Option<LocalSocketListener>),
+
+    /// An instance that was already running accepted the files and jump list task, so this process should exit
+    Forwarded,
+}
+
+/// Hand the files this process was asked to open and the jump list task it was launched with over to an instance of
+/// the app that is already running, or become the instance later launches hand theirs to
+///
+/// Binding the socket decides which of two instances that start at the same time runs, so only one of them does.
+pub(crate) fn claim_instance(id: &str, files: &[PathBuf], task: Option<&str>) -> InstanceRole {
+    let socket = match instance_socket(id) {
+        Ok(socket) => socket,
+        Err(err) => {
+            tracing::warn!("Failed to find the socket of the running instance of the app: {err}");
+            return InstanceRole::Primary(None);
+        }
+    };
+
+    let mut message = String::new();
+    for file in files {
+        message.push_str(&file.to_string_lossy());
+        message.push('\n');
+    }
+    if let Some(task) = task {
+        message.push_str(&jump_list::task_arg(task));
+        message.push('\n');
+    }
+
+    for _ in 0..2 {
+        if let Ok(mut stream) = LocalSocketStream::connect(socket.as_os_str()) {
+            match stream.write_all(message.as_bytes()) {
+                Ok(()) => return InstanceRole::Forwarded,
+                Err(err) => {
+                    tracing::warn!("Failed to hand the files over to the running instance: {err}");
+                    return InstanceRole::Primary(None);
+                }
+            }
+        }
+
+        match LocalSocketListener::bind(socket.as_os_str()) {
+            Ok(listener) => return InstanceRole::Primary(Some(listener)),
+            // An instance that crashed leaves its socket file behind on unix. The socket is only removed if nothing
+            // accepts connections on it, since another instance may have bound it since we tried to connect.
+            Err(err) if cfg!(unix) && err.kind() == std::io::ErrorKind::AddrInUse => {
+                if LocalSocketStream::connect(socket.as_os_str()).is_err() {
+                    _ = std::fs::remove_file(&socket);
+                }
+            }
+            Err(err) => {
+                tracing::warn!("Failed to listen for other instances of the app: {err}");
+                return InstanceRole::Primary(None);
+            }
+        }
+    }
+
+    tracing::warn!("Failed to listen for other instances of the app");
+    InstanceRole::Primary(None)
+}
+
+/// Accept files and jump list tasks from later launches of the app and deliver them to this instance
+pub(crate) fn listen_for_other_instances(
+    listener: LocalSocketListener,
+    proxy: EventLoopProxy<UserWindowEvent>,
+) {
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                if let Some(task) = jump_list::task_from_arg(&line) {
+                    _ = proxy.send_event(UserWindowEvent::JumpListTask(task.to_string()));
+                } else if !line.is_empty() {
+                    _ = proxy.send_event(UserWindowEvent::OpenFile(PathBuf::from(line)));
+                }
+            }
+        }
+    });
+}
+
+/// Register the app as a handler for files with the given extension for the current user
+///
+/// On Windows the association is written to the registry and on Linux a desktop entry for the app is installed and
+/// made the default for the extension's MIME type. On macOS, file associations are declared with
+/// `CFBundleDocumentTypes` in the app bundle's `Info.plist` instead and this function does nothing.
+///
+/// `extension` is given without the leading dot and `description` is shown by the file manager for files of that type.
+pub fn register_file_association(extension: &str, description: &str) -> std::io::Result<()> {
+    let exe = std::env::current_exe()?;
+    let extension = extension.trim_start_matches('.');
+
+    #[cfg(target_os = "windows")]
+    {
+        let prog_id = format!(
+            "{}.{extension}",
+            exe.file_stem().unwrap_or_default().to_string_lossy()
+        );
+        let command = format!("\"{}\" \"%1\"", exe.display());
+        let classes = r"HKCU\Software\Classes";
+
+        reg_add(&format!(r"{classes}\.{extension}"), &prog_id)?;
+        reg_add(&format!(r"{classes}\{prog_id}"), description)?;
+        reg_add(
+            &format!(r"{classes}\{prog_id}\shell\open\command"),
+            &command,
+        )?;
+    }
+
+    #[cfg(not(any(
+        target_os = "windows",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "android"
+    )))]
+    {
+        let name = exe
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let mime = format!("application/x-{name}-{extension}");
+        let data_dir = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share"))
+            })
+            .ok_or_else(|| std::io::Error::other("could not find the data directory"))?;
+
+        let mime_dir = data_dir.join("mime/packages");
+        std::fs::create_dir_all(&mime_dir)?;
+        std::fs::write(
+            mime_dir.join(format!("{name}-{extension}.xml")),
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<mime-info xmlns="http://www.freedesktop.org/standards/shared-mime-info">
+  <mime-type type="{}">
+    <comment>{}</comment>
+    <glob pattern="*.{}"/>
+  </mime-type>
+</mime-info>
+"#,
+                escape_xml(&mime),
+                escape_xml(description),
+                escape_xml(extension)
+            ),
+        )?;
+
+        let applications = data_dir.join("applications");
+        std::fs::create_dir_all(&applications)?;
+        let desktop_file = format!("{name}.desktop");
+        std::fs::write(
+            applications.join(&desktop_file),
+            format!(
+                "[Desktop Entry]\nType=Application\nName={name}\nExec=\"{}\" %F\nMimeType={mime};\n",
+                exe.display()
+            ),
+        )?;
+
+        _ = std::process::Command::new("update-mime-database")
+            .arg(data_dir.join("mime"))
+            .status();
+        _ = std::process::Command::new("xdg-mime")
+            .args(["default", &desktop_file, &mime])
+            .status();
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "ios", target_os = "android"))]
+    let _ = (exe, extension, description);
+
+    Ok(())
+}
+
+/// Escape text for the content or an attribute of an XML element
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "android"
+)))]
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(target_os = "windows")]
+fn reg_add(key: &str, value: &str) -> std::io::Result<()> {
+    let status = std::process::Command::new("reg")
+        .args(["add", key, "/ve", "/d", value, "/f"])
+        .status()?;
+
+    match status.success() {
+        true => Ok(()),
+        false => Err(std::io::Error::other(format!("failed to write {key}"))),
+    }
+}
@@ -0,0 +1,136 @@
+//! Reporting the progress of page loads in the webview, for apps that show external content
+
+use crate::{hooks::use_event_listener, ipc::UserWindowEvent};
+use tao::{event_loop::EventLoopProxy, window::WindowId};
+use wry::WebView;
+
+/// The lifecycle of a page load in the webview, delivered to [`use_page_load_event`] handlers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PageLoadEvent {
+    /// The webview started loading a page
+    Started {
+        /// The URL of the page
+        url: String,
+    },
+    /// The webview finished loading a page
+    Finished {
+        /// The URL of the page
+        url: String,
+    },
+    /// The webview failed to load a page, for example because the network is down
+    ///
+    /// > Note: Failures are only reported on Windows and Linux. The other webviews show their own error page, if any.
+    Failed {
+        /// The URL of the page
+        url: String,
+        /// Why the page couldn't be loaded, as described by the webview
+        error: String,
+    },
+}
+
+/// Forward the page loads of a webview to the event loop
+pub(crate) fn page_load_handler(
+    proxy: EventLoopProxy<UserWindowEvent>,
+    id: WindowId,
+) -> impl Fn(wry::PageLoadEvent, String) + 'static {
+    move |event, url| {
+        let event = match event {
+            wry::PageLoadEvent::Started => PageLoadEvent::Started { url },
+            wry::PageLoadEvent::Finished => PageLoadEvent::Finished { url },
+        };
+        _ = proxy.send_event(UserWindowEvent::PageLoad { id, event });
+    }
+}
+
+/// Hook the report of failed page loads up to the webview once it is built
+///
+/// wry only reports when loads start and finish, so failures come from the platform webviews directly.
+#[allow(unused_variables)]
+pub(crate) fn attach_load_failed_handler(
+    webview: &WebView,
+    proxy: EventLoopProxy<UserWindowEvent>,
+    id: WindowId,
+) {
+    #[cfg(target_os = "windows")]
+    {
+        use webview2_com::{
+            take_pwstr, Microsoft::Web::WebView2::Win32::*, NavigationCompletedEventHandler,
+        };
+        use windows::{core::PWSTR, Win32::Foundation::BOOL};
+        use wry::WebViewExtWindows;
+
+        let Ok(core) = (unsafe { webview.controller().CoreWebView2() }) else {
+            tracing::error!("Failed to get the WebView2 core to attach the load failed handler");
+            return;
+        };
+
+        let event_handler =
+            NavigationCompletedEventHandler::create(Box::new(move |webview, args| {
+                let (Some(webview), Some(args)) = (webview, args) else {
+                    return Ok(());
+                };
+
+                let mut success = BOOL::default();
+                let mut status = COREWEBVIEW2_WEB_ERROR_STATUS::default();
+                let mut uri = PWSTR::null();
+                unsafe {
+                    args.IsSuccess(&mut success)?;
+                    if success.as_bool() {
+                        return Ok(());
+                    }
+                    args.WebErrorStatus(&mut status)?;
+                    webview.Source(&mut uri)?;
+                }
+
+                let event = PageLoadEvent::Failed {
+                    url: take_pwstr(uri),
+                    error: format!("WebView2 error status {}", status.0),
+                };
+                _ = proxy.send_event(UserWindowEvent::PageLoad { id, event });
+                Ok(())
+            }));
+
+        let mut token = Default::default();
+        if let Err(err) = unsafe { core.add_NavigationCompleted(&event_handler, &mut token) } {
+            tracing::error!("Failed to attach the load failed handler: {err}");
+        }
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        use webkit2gtk::WebViewExt;
+        use wry::WebViewExtUnix;
+
+        webview
+            .webview()
+            .connect_load_failed(move |_, _, url, error| {
+                let event = PageLoadEvent::Failed {
+                    url: url.to_string(),
+                    error: error.to_string(),
+                };
+                _ = proxy.send_event(UserWindowEvent::PageLoad { id, event });
+
+                // Returning false lets the webview show its error page
+                false
+            });
+    }
+}
+
+/// Listen for pages starting, finishing and failing to load in the current window
+///
+/// ```rust, ignore
+/// let mut loading = use_signal(|| false);
+/// use_page_load_event(move |event| match event {
+///     PageLoadEvent::Started { .. } => loading.set(true),
+///     _ => loading.set(false),
+/// });
+/// ```
+pub fn use_page_load_event(handler: impl FnMut(PageLoadEvent) + 'static) {
+    use_event_listener(|window| &window.page_load_listeners, handler)
+}
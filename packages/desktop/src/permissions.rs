@@ -0,0 +1,149 @@
+//! Route the webview's permission prompts to the app instead of letting the platform decide
+
+use wry::WebView;
+
+/// A permission a page in the webview asked for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PermissionKind {
+    /// Access to a camera with `getUserMedia`
+    Camera,
+    /// Access to a microphone with `getUserMedia`
+    Microphone,
+    /// Access to the position of the device with the geolocation API
+    Geolocation,
+    /// Showing notifications with the notifications API
+    Notifications,
+    /// A permission that doesn't have a dedicated variant yet
+    Other,
+}
+
+/// A permission prompt from a page in the webview
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionRequest {
+    /// The permissions the page asked for. Requesting a camera and a microphone at once shows up as one request.
+    pub kinds: Vec<PermissionKind>,
+    /// The url of the page that asked for the permission
+    pub origin: String,
+}
+
+/// How to answer a [`PermissionRequest`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PermissionResponse {
+    /// Grant the permission
+    Allow,
+    /// Deny the permission
+    Deny,
+    /// Let the webview do what it would do without a handler, which is usually denying or showing its own prompt
+    #[default]
+    Default,
+}
+
+pub(crate) type PermissionHandler = Box<dyn Fn(&PermissionRequest) -> PermissionResponse>;
+
+/// Hook the permission handler up to the webview once it is built
+///
+/// > Note: WKWebView doesn't let us replace its prompts without replacing wry's UI delegate, so the handler is
+/// > ignored on macOS and iOS.
+#[allow(unused_variables)]
+pub(crate) fn attach_permission_handler(webview: &WebView, handler: PermissionHandler) {
+    #[cfg(target_os = "windows")]
+    {
+        use webview2_com::{
+            take_pwstr, Microsoft::Web::WebView2::Win32::*, PermissionRequestedEventHandler,
+        };
+        use windows::core::PWSTR;
+        use wry::WebViewExtWindows;
+
+        let Ok(core) = (unsafe { webview.controller().CoreWebView2() }) else {
+            tracing::error!("Failed to get the WebView2 core to attach the permission handler");
+            return;
+        };
+
+        let event_handler = PermissionRequestedEventHandler::create(Box::new(move |_, args| {
+            let Some(args) = args else {
+                return Ok(());
+            };
+
+            let mut kind = COREWEBVIEW2_PERMISSION_KIND::default();
+            let mut uri = PWSTR::null();
+            unsafe {
+                args.PermissionKind(&mut kind)?;
+                args.Uri(&mut uri)?;
+            }
+
+            let kind = match kind {
+                COREWEBVIEW2_PERMISSION_KIND_CAMERA => PermissionKind::Camera,
+                COREWEBVIEW2_PERMISSION_KIND_MICROPHONE => PermissionKind::Microphone,
+                COREWEBVIEW2_PERMISSION_KIND_GEOLOCATION => PermissionKind::Geolocation,
+                COREWEBVIEW2_PERMISSION_KIND_NOTIFICATIONS => PermissionKind::Notifications,
+                _ => PermissionKind::Other,
+            };
+
+            let request = PermissionRequest {
+                kinds: vec![kind],
+                origin: take_pwstr(uri),
+            };
+
+            let state = match handler(&request) {
+                PermissionResponse::Allow => COREWEBVIEW2_PERMISSION_STATE_ALLOW,
+                PermissionResponse::Deny => COREWEBVIEW2_PERMISSION_STATE_DENY,
+                PermissionResponse::Default => COREWEBVIEW2_PERMISSION_STATE_DEFAULT,
+            };
+
+            unsafe { args.SetState(state) }
+        }));
+
+        let mut token = Default::default();
+        if let Err(err) = unsafe { core.add_PermissionRequested(&event_handler, &mut token) } {
+            tracing::error!("Failed to attach the permission handler: {err}");
+        }
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        use webkit2gtk::{glib::ObjectExt, PermissionRequestExt, WebViewExt};
+        use wry::WebViewExtUnix;
+
+        webview
+            .webview()
+            .connect_permission_request(move |webview, request| {
+                let kinds = if request.is::<webkit2gtk::UserMediaPermissionRequest>() {
+                    let mut kinds = Vec::new();
+                    if request.property::<bool>("is-for-video-device") {
+                        kinds.push(PermissionKind::Camera);
+                    }
+                    if request.property::<bool>("is-for-audio-device") {
+                        kinds.push(PermissionKind::Microphone);
+                    }
+                    kinds
+                } else if request.is::<webkit2gtk::GeolocationPermissionRequest>() {
+                    vec![PermissionKind::Geolocation]
+                } else if request.is::<webkit2gtk::NotificationPermissionRequest>() {
+                    vec![PermissionKind::Notifications]
+                } else {
+                    vec![PermissionKind::Other]
+                };
+
+                let request_info = PermissionRequest {
+                    kinds,
+                    origin: webview.uri().map(|uri| uri.to_string()).unwrap_or_default(),
+                };
+
+                match handler(&request_info) {
+                    PermissionResponse::Allow => request.allow(),
+                    PermissionResponse::Deny => request.deny(),
+                    // Returning false lets the default handler run
+                    PermissionResponse::Default => return false,
+                }
+
+                true
+            });
+    }
+}
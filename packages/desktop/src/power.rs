@@ -0,0 +1,174 @@
+//! Keeping the machine awake while the app is busy
+
+/// Keeps the system and the display from going to sleep until it is dropped
+///
+/// Created with [`DesktopService::keep_awake`](crate::DesktopService::keep_awake). Multiple guards can be alive at
+/// the same time, the machine may sleep again once all of them are dropped.
+#[must_use = "the machine may go to sleep as soon as the guard is dropped"]
+pub struct KeepAwakeGuard {
+    #[allow(unused)]
+    inner: Option<impl_::Inhibitor>,
+}
+
+impl KeepAwakeGuard {
+    pub(crate) fn new(reason: &str) -> Self {
+        let inner = impl_::Inhibitor::new(reason);
+        if inner.is_none() {
+            tracing::warn!("Failed to keep the machine awake");
+        }
+        Self { inner }
+    }
+}
+
+impl Drop for KeepAwakeGuard {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.take() {
+            inner.release();
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod impl_ {
+    use windows_sys::Win32::{
+        Foundation::{CloseHandle, HANDLE, INVALID_HANDLE_VALUE},
+        System::{
+            Power::{
+                PowerClearRequest, PowerCreateRequest, PowerRequestDisplayRequired,
+                PowerRequestSystemRequired, PowerSetRequest,
+            },
+            Threading::{POWER_REQUEST_CONTEXT_SIMPLE_STRING, REASON_CONTEXT, REASON_CONTEXT_0},
+        },
+    };
+
+    /// Power requests are owned by their handle instead of the thread that made them, so the guard can be dropped on
+    /// any thread
+    pub struct Inhibitor(HANDLE);
+
+    impl Inhibitor {
+        pub fn new(reason: &str) -> Option<Self> {
+            let mut reason: Vec<u16> = reason.encode_utf16().chain([0]).collect();
+            let context = REASON_CONTEXT {
+                // POWER_REQUEST_CONTEXT_VERSION
+                Version: 0,
+                Flags: POWER_REQUEST_CONTEXT_SIMPLE_STRING,
+                Reason: REASON_CONTEXT_0 {
+                    SimpleReasonString: reason.as_mut_ptr(),
+                },
+            };
+
+            let handle = unsafe { PowerCreateRequest(&context) };
+            if handle == INVALID_HANDLE_VALUE {
+                return None;
+            }
+
+            let inhibitor = Self(handle);
+            for request in [PowerRequestSystemRequired, PowerRequestDisplayRequired] {
+                if unsafe { PowerSetRequest(handle, request) } == 0 {
+                    inhibitor.release();
+                    return None;
+                }
+            }
+            Some(inhibitor)
+        }
+
+        pub fn release(self) {
+            unsafe {
+                PowerClearRequest(self.0, PowerRequestSystemRequired);
+                PowerClearRequest(self.0, PowerRequestDisplayRequired);
+                CloseHandle(self.0);
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod impl_ {
+    use core_foundation::base::TCFType;
+    use core_foundation::string::{CFString, CFStringRef};
+
+    type IOPMAssertionID = u32;
+
+    const IOPM_ASSERTION_LEVEL_ON: u32 = 255;
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOPMAssertionCreateWithName(
+            assertion_type: CFStringRef,
+            assertion_level: u32,
+            assertion_name: CFStringRef,
+            assertion_id: *mut IOPMAssertionID,
+        ) -> i32;
+        fn IOPMAssertionRelease(assertion_id: IOPMAssertionID) -> i32;
+    }
+
+    pub struct Inhibitor(IOPMAssertionID);
+
+    impl Inhibitor {
+        pub fn new(reason: &str) -> Option<Self> {
+            // Keeping the display on also keeps the system from idle sleeping
+            let kind = CFString::new("PreventUserIdleDisplaySleep");
+            let reason = CFString::new(reason);
+            let mut id = 0;
+            let result = unsafe {
+                IOPMAssertionCreateWithName(
+                    kind.as_concrete_TypeRef(),
+                    IOPM_ASSERTION_LEVEL_ON,
+                    reason.as_concrete_TypeRef(),
+                    &mut id,
+                )
+            };
+            (result == 0).then_some(Self(id))
+        }
+
+        pub fn release(self) {
+            unsafe { IOPMAssertionRelease(self.0) };
+        }
+    }
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "android"
+)))]
+mod impl_ {
+    use zbus::{blocking::Connection, zvariant::OwnedFd};
+
+    /// logind holds the inhibitor lock until the file descriptor it returns is closed
+    pub struct Inhibitor(OwnedFd);
+
+    impl Inhibitor {
+        pub fn new(reason: &str) -> Option<Self> {
+            let connection = Connection::system().ok()?;
+            let reply = connection
+                .call_method(
+                    Some("org.freedesktop.login1"),
+                    "/org/freedesktop/login1",
+                    Some("org.freedesktop.login1.Manager"),
+                    "Inhibit",
+                    &("idle:sleep", "dioxus", reason, "block"),
+                )
+                .ok()?;
+            reply.body::<OwnedFd>().ok().map(Self)
+        }
+
+        pub fn release(self) {
+            drop(self.0);
+        }
+    }
+}
+
+#[cfg(any(target_os = "ios", target_os = "android"))]
+mod impl_ {
+    pub struct Inhibitor;
+
+    impl Inhibitor {
+        pub fn new(_reason: &str) -> Option<Self> {
+            None
+        }
+
+        pub fn release(self) {}
+    }
+}
@@ -1,9 +1,29 @@
-use crate::{assets::*, edits::EditQueue};
+use crate::{
+    assets::*,
+    binary_ipc::binary_ipc_channel,
+    compression::{DECOMPRESS_EDITS_SCRIPT, INITIALIZE_PARAMS},
+    edits::EditQueue,
+    event_throttle::coalescing_channel,
+};
 use dioxus_interpreter_js::unified_bindings::SLEDGEHAMMER_JS;
 use dioxus_interpreter_js::NATIVE_JS;
-use std::path::{Path, PathBuf};
+use include_dir::{Dir, File};
+use rustc_hash::FxHashMap;
+use std::{
+    hash::{Hash, Hasher},
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+};
 use wry::{
-    http::{status::StatusCode, Request, Response},
+    http::{
+        header::{
+            ACCEPT_RANGES, CACHE_CONTROL, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, ETAG,
+            IF_NONE_MATCH, RANGE,
+        },
+        status::StatusCode,
+        Request, Response,
+    },
     RequestAsyncResponder, Result,
 };
 
@@ -30,6 +50,9 @@ pub(super) fn index_request(
     custom_index: Option<String>,
     root_name: &str,
     headless: bool,
+    ipc: IpcOptions,
+    script_nonce: Option<&str>,
+    embedded_assets: Option<&'static Dir<'static>>,
 ) -> Option<Response<Vec<u8>>> {
     // If the request is for the root, we'll serve the index.html file.
     if request.uri().path() != "/" {
@@ -43,12 +66,12 @@ pub(super) fn index_request(
     // We look just for the closing head tag. If a user provided a custom index with weird syntax, this might fail
     let head = match custom_head {
         Some(mut head) => {
-            if let Some(assets_head) = assets_head() {
+            if let Some(assets_head) = assets_head(embedded_assets) {
                 head.push_str(&assets_head);
             }
             Some(head)
         }
-        None => assets_head(),
+        None => assets_head(embedded_assets),
     };
 
     if let Some(head) = head {
@@ -60,7 +83,7 @@ pub(super) fn index_request(
     // Might want to document this
     index.insert_str(
         index.find("</body>").expect("Body element to exist"),
-        &module_loader(root_name, headless),
+        &module_loader(root_name, headless, ipc, script_nonce),
     );
 
     Response::builder()
@@ -70,7 +93,14 @@ pub(super) fn index_request(
         .ok()
 }
 
-fn assets_head() -> Option<String> {
+fn assets_head(embedded_assets: Option<&'static Dir<'static>>) -> Option<String> {
+    if let Some(assets) = embedded_assets {
+        return assets
+            .get_file("__assets_head.html")
+            .and_then(|file| file.contents_utf8())
+            .map(str::to_string);
+    }
+
     #[cfg(any(
         target_os = "windows",
         target_os = "macos",
@@ -109,11 +139,13 @@ fn assets_head() -> Option<String> {
 ///
 /// - Tries to stream edits if they're requested.
 /// - If that doesn't match, tries a user provided asset handler
-/// - If that doesn't match, tries to serve a file from the filesystem
+/// - If that doesn't match, tries to serve a file embedded in the binary, if the app embeds its assets
+/// - Otherwise, tries to serve a file from the filesystem
 pub(super) fn desktop_handler(
     request: Request<Vec<u8>>,
     asset_handlers: AssetHandlerRegistry,
     edit_queue: &EditQueue,
+    embedded_assets: Option<&'static Dir<'static>>,
     responder: RequestAsyncResponder,
 ) {
     // If the request is asking for edits (ie binary protocol streaming, do that)
@@ -139,14 +171,31 @@ pub(super) fn desktop_handler(
         }
     }
 
+    let range = request
+        .headers()
+        .get(RANGE)
+        .and_then(|range| range.to_str().ok());
+
+    // Apps that embed their assets don't read anything from disk
+    if let Some(assets) = embedded_assets {
+        let if_none_match = request
+            .headers()
+            .get(IF_NONE_MATCH)
+            .and_then(|etag| etag.to_str().ok());
+        return match serve_embedded(assets, &path, range, if_none_match) {
+            Ok(res) => responder.respond(res),
+            Err(e) => tracing::error!("Error serving embedded asset {}", e),
+        };
+    }
+
     // Else, try to serve a file from the filesystem.
-    match serve_from_fs(path) {
+    match serve_from_fs(path, range) {
         Ok(res) => responder.respond(res),
         Err(e) => tracing::error!("Error serving request from filesystem {}", e),
     }
 }
 
-fn serve_from_fs(path: PathBuf) -> Result<Response<Vec<u8>>> {
+fn serve_from_fs(path: PathBuf, range: Option<&str>) -> Result<Response<Vec<u8>>> {
     // If the path is relative, we'll try to serve it from the assets directory.
     let mut asset = get_asset_root_or_default().join(&path);
 
@@ -161,9 +210,157 @@ fn serve_from_fs(path: PathBuf) -> Result<Response<Vec<u8>>> {
             .body(String::from("Not Found").into_bytes())?);
     }
 
+    let content_type = get_mime_from_path(&asset)?;
+    let mut file = std::fs::File::open(&asset)?;
+    let len = file.metadata()?.len();
+
+    // Media elements request the parts of the file they need to play or seek with range requests. Large files are
+    // never read whole either: a request without a range gets the first chunk, and its `Content-Range` tells the
+    // webview how much is left to request.
+    let range = match range {
+        None if len > MAX_RANGE_CHUNK => Some("bytes=0-"),
+        range => range,
+    };
+    if let Some(range) = range {
+        let Some((start, end)) = parse_range(range, len) else {
+            return Ok(Response::builder()
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .header(CONTENT_RANGE, format!("bytes */{len}"))
+                .body(Vec::new())?);
+        };
+
+        let mut body = vec![0; (end - start + 1) as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(&mut body)?;
+
+        return Ok(Response::builder()
+            .status(StatusCode::PARTIAL_CONTENT)
+            .header(CONTENT_TYPE, content_type)
+            .header(ACCEPT_RANGES, "bytes")
+            .header(CONTENT_LENGTH, body.len())
+            .header(CONTENT_RANGE, format!("bytes {start}-{end}/{len}"))
+            .body(body)?);
+    }
+
+    let mut body = Vec::with_capacity(len as usize);
+    file.read_to_end(&mut body)?;
     Ok(Response::builder()
-        .header("Content-Type", get_mime_from_path(&asset)?)
-        .body(std::fs::read(asset)?)?)
+        .header(CONTENT_TYPE, content_type)
+        .header(ACCEPT_RANGES, "bytes")
+        .body(body)?)
+}
+
+fn serve_embedded(
+    assets: &'static Dir<'static>,
+    path: &Path,
+    range: Option<&str>,
+    if_none_match: Option<&str>,
+) -> Result<Response<Vec<u8>>> {
+    let Some(file) = assets.get_file(path) else {
+        return Ok(Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(String::from("Not Found").into_bytes())?);
+    };
+
+    let contents = file.contents();
+    let content_type = get_mime(path, infer::get(contents));
+
+    // The webview's cache outlives the binary, so make it check whether an asset changed after an update. The
+    // check is cheap since unchanged assets are answered with an empty response.
+    let etag = embedded_etag(file);
+
+    if if_none_match == Some(etag.as_str()) {
+        return Ok(Response::builder()
+            .status(StatusCode::NOT_MODIFIED)
+            .header(ETAG, etag)
+            .body(Vec::new())?);
+    }
+
+    let builder = Response::builder()
+        .header(CONTENT_TYPE, content_type)
+        .header(ACCEPT_RANGES, "bytes")
+        .header(CACHE_CONTROL, "no-cache")
+        .header(ETAG, etag);
+
+    let len = contents.len() as u64;
+    match range.map(|range| parse_range(range, len)) {
+        Some(Some((start, end))) => Ok(builder
+            .status(StatusCode::PARTIAL_CONTENT)
+            .header(CONTENT_RANGE, format!("bytes {start}-{end}/{len}"))
+            .body(contents[start as usize..=end as usize].to_vec())?),
+        Some(None) => Ok(Response::builder()
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(CONTENT_RANGE, format!("bytes */{len}"))
+            .body(Vec::new())?),
+        None => Ok(builder.body(contents.to_vec())?),
+    }
+}
+
+/// The `ETag` of an embedded file, which is only hashed the first time it is served
+fn embedded_etag(file: &'static File<'static>) -> String {
+    static ETAGS: OnceLock<Mutex<FxHashMap<&'static Path, String>>> = OnceLock::new();
+
+    let mut etags = ETAGS.get_or_init(Default::default).lock().unwrap();
+    etags
+        .entry(file.path())
+        .or_insert_with(|| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            file.contents().hash(&mut hasher);
+            format!("\"{:x}\"", hasher.finish())
+        })
+        .clone()
+}
+
+/// The most we read from disk for a single open ended range request, or a request for a larger file without a range.
+/// The webview requests the next chunk when it needs it, so large videos are never read into memory at once.
+const MAX_RANGE_CHUNK: u64 = 1024 * 1024;
+
+/// Parse the first range of a `Range: bytes=...` header into an inclusive range of bytes within a file of `len` bytes
+fn parse_range(header: &str, len: u64) -> Option<(u64, u64)> {
+    let range = header
+        .trim()
+        .strip_prefix("bytes=")?
+        .split(',')
+        .next()?
+        .trim();
+    let (start, end) = range.split_once('-')?;
+
+    let (start, end) = match (start.trim(), end.trim()) {
+        // The last `n` bytes of the file
+        ("", suffix) => {
+            let suffix = suffix.parse::<u64>().ok()?.min(len);
+            (len.checked_sub(suffix)?, len.checked_sub(1)?)
+        }
+        (start, "") => {
+            let start = start.parse::<u64>().ok()?;
+            (
+                start,
+                start
+                    .saturating_add(MAX_RANGE_CHUNK - 1)
+                    .min(len.checked_sub(1)?),
+            )
+        }
+        (start, end) => {
+            let start = start.parse::<u64>().ok()?;
+            let end = end.parse::<u64>().ok()?.min(len.checked_sub(1)?);
+            (start, end.min(start.saturating_add(MAX_RANGE_CHUNK - 1)))
+        }
+    };
+
+    (start <= end && start < len).then_some((start, end))
+}
+
+/// How the page talks to the app, from the window's [`Config`](crate::Config)
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct IpcOptions {
+    /// Send the interpreter's messages over the custom protocol instead of `window.ipc`
+    pub(crate) binary: bool,
+    /// Accept messages from the fallback transport for pages without `window.ipc`
+    pub(crate) fallback: bool,
+    /// Compress large payloads if the page can inflate them
+    pub(crate) compression: bool,
+    /// How many times per second high-frequency events are sent at most, if they are coalesced
+    pub(crate) event_rate: Option<u32>,
 }
 
 /// Construct the inline script that boots up the page and bridges the webview with rust code.
@@ -172,25 +369,65 @@ fn serve_from_fs(path: PathBuf) -> Result<Response<Vec<u8>>> {
 /// - root_name: the root element (by Id) that we stream edits into
 /// - headless: is this page being loaded but invisible? Important because not all windows are visible and the
 ///             interpreter can't connect until the window is ready.
-fn module_loader(root_id: &str, headless: bool) -> String {
+/// - ipc: how the interpreter talks to the app
+/// - script_nonce: the nonce the content security policy allows the script with, if there is one
+fn module_loader(
+    root_id: &str,
+    headless: bool,
+    ipc: IpcOptions,
+    script_nonce: Option<&str>,
+) -> String {
+    // The interpreter posts its messages to `window.ipc` unless it is handed another channel
+    let mut channel = match ipc.binary {
+        true => binary_ipc_channel(ipc.compression),
+        false => "window.interpreter.ipc".to_string(),
+    };
+    if let Some(rate) = ipc.event_rate {
+        channel = coalescing_channel(&channel, rate);
+    }
+    let ipc_channel = if ipc.binary || ipc.event_rate.is_some() {
+        format!("window.interpreter.ipc = {channel};")
+    } else {
+        String::new()
+    };
+
+    // With compression, the page reads a flag byte before every batch of edits and tells the app what it can inflate
+    let (decompress_edits, interpreter, initialize_params) = if ipc.compression {
+        (
+            DECOMPRESS_EDITS_SCRIPT,
+            "CompressedEditsInterpreter",
+            INITIALIZE_PARAMS,
+        )
+    } else {
+        ("", "NativeInterpreter", "{}")
+    };
+
+    let nonce = match script_nonce {
+        Some(nonce) => format!(r#" nonce="{nonce}""#),
+        None => String::new(),
+    };
+
     format!(
         r#"
-<script type="module">
+<script type="module"{nonce}>
     // Bring the sledgehammer code
     {SLEDGEHAMMER_JS}
 
     // And then extend it with our native bindings
     {NATIVE_JS}
 
+    {decompress_edits}
+
     // The nativeinterprerter extends the sledgehammer interpreter with a few extra methods that we use for IPC
-    window.interpreter = new NativeInterpreter("{EDITS_PATH}");
+    window.interpreter = new {interpreter}("{EDITS_PATH}");
 
     // Wait for the page to load before sending the initialize message
     window.onload = function() {{
         let root_element = window.document.getElementById("{root_id}");
         if (root_element != null) {{
             window.interpreter.initialize(root_element);
-            window.ipc.postMessage(window.interpreter.serializeIpcMessage("initialize"));
+            {ipc_channel}
+            window.ipc.postMessage(window.interpreter.serializeIpcMessage("initialize", {initialize_params}));
         }}
         window.interpreter.waitForRequest({headless});
     }}
@@ -241,13 +478,18 @@ fn get_asset_root() -> Option<PathBuf> {
 
 /// Get the mime type from a path-like string
 fn get_mime_from_path(trimmed: &Path) -> Result<&'static str> {
+    Ok(get_mime(trimmed, infer::get_from_path(trimmed)?))
+}
+
+/// Get the mime type of a file from the type detected from its first bytes, or its extension
+fn get_mime(trimmed: &Path, detected: Option<infer::Type>) -> &'static str {
     if trimmed.extension().is_some_and(|ext| ext == "svg") {
-        return Ok("image/svg+xml");
+        return "image/svg+xml";
     }
 
-    match infer::get_from_path(trimmed)?.map(|f| f.mime_type()) {
-        Some(f) if f != "text/plain" => Ok(f),
-        _ => Ok(get_mime_by_ext(trimmed)),
+    match detected.map(|f| f.mime_type()) {
+        Some(f) if f != "text/plain" => f,
+        _ => get_mime_by_ext(trimmed),
     }
 }
 
@@ -273,3 +515,75 @@ fn get_mime_by_ext(trimmed: &Path) -> &'static str {
         None => "application/octet-stream",
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn open_ended_range() {
+        assert_eq!(parse_range("bytes=10-", 100), Some((10, 99)));
+        assert_eq!(
+            parse_range("bytes=0-", 10 * MAX_RANGE_CHUNK),
+            Some((0, MAX_RANGE_CHUNK - 1))
+        );
+        assert_eq!(parse_range(&format!("bytes={}-", u64::MAX), u64::MAX), None);
+    }
+
+    #[test]
+    fn suffix_range() {
+        assert_eq!(parse_range("bytes=-10", 100), Some((90, 99)));
+        assert_eq!(parse_range("bytes=-1000", 100), Some((0, 99)));
+        assert_eq!(parse_range("bytes=-10", 0), None);
+    }
+
+    #[test]
+    fn bounded_range() {
+        assert_eq!(parse_range("bytes=0-9", 100), Some((0, 9)));
+        assert_eq!(parse_range("bytes=90-1000", 100), Some((90, 99)));
+        assert_eq!(
+            parse_range(&format!("bytes={}-{}", u64::MAX - 1, u64::MAX), u64::MAX),
+            Some((u64::MAX - 1, u64::MAX - 1))
+        );
+    }
+
+    #[test]
+    fn large_files_are_served_in_chunks() {
+        let dir =
+            std::env::temp_dir().join(format!("dioxus-desktop-protocol-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let small = dir.join("small.bin");
+        let large = dir.join("large.bin");
+        std::fs::write(&small, vec![1; 10]).unwrap();
+        std::fs::write(&large, vec![2; 3 * MAX_RANGE_CHUNK as usize + 5]).unwrap();
+        let len = 3 * MAX_RANGE_CHUNK + 5;
+
+        let response = serve_from_fs(small, None).unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.body().len(), 10);
+
+        let response = serve_from_fs(large.clone(), None).unwrap();
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.headers()[ACCEPT_RANGES], "bytes");
+        assert_eq!(
+            response.headers()[CONTENT_RANGE],
+            format!("bytes 0-{}/{len}", MAX_RANGE_CHUNK - 1)
+        );
+        assert_eq!(response.body().len() as u64, MAX_RANGE_CHUNK);
+
+        let response = serve_from_fs(large, Some("bytes=-5")).unwrap();
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.body(), &vec![2; 5]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert_eq!(parse_range("bytes=100-", 100), None);
+        assert_eq!(parse_range("bytes=100-200", 100), None);
+        assert_eq!(parse_range("bytes=20-10", 100), None);
+        assert_eq!(parse_range("bytes=a-b", 100), None);
+        assert_eq!(parse_range("items=0-10", 100), None);
+    }
+}
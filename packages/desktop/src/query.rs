@@ -1,7 +1,10 @@
-use crate::DesktopContext;
+use crate::{
+    ipc_tracing::{self, IpcTimer},
+    DesktopContext,
+};
 use futures_util::{FutureExt, StreamExt};
 use generational_box::Owner;
-use serde::{de::DeserializeOwned, Deserialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
 use slab::Slab;
 use std::{cell::RefCell, rc::Rc};
@@ -68,9 +71,10 @@ impl<T> Default for SharedSlab<T> {
 }
 
 pub(crate) struct QueryEntry {
-    channel_sender: futures_channel::mpsc::UnboundedSender<Value>,
-    return_sender: Option<futures_channel::oneshot::Sender<Value>>,
+    channel_sender: futures_channel::mpsc::UnboundedSender<Result<Value, String>>,
+    return_sender: Option<futures_channel::oneshot::Sender<Result<Value, String>>>,
     pub owner: Option<Owner>,
+    started: IpcTimer,
 }
 
 const QUEUE_NAME: &str = "__msg_queues";
@@ -94,6 +98,7 @@ impl QueryEngine {
             channel_sender: tx,
             return_sender: Some(return_tx),
             owner: None,
+            started: IpcTimer::start(),
         });
 
         // start the query
@@ -126,6 +131,19 @@ impl QueryEngine {
                     window.ipc.postMessage(
                         JSON.stringify(returned_value)
                     );
+                }}, (error)=>{{
+                    let returned_error = {{
+                        "method":"query",
+                        "params": {{
+                            "id": {request_id},
+                            "data": null,
+                            "returned_value": true,
+                            "error": String(error)
+                        }}
+                    }};
+                    window.ipc.postMessage(
+                        JSON.stringify(returned_error)
+                    );
                 }})
             }})();"#
         )) {
@@ -148,15 +166,26 @@ impl QueryEngine {
             id,
             data,
             returned_value,
+            error,
         } = data;
         let mut slab = self.active_requests.slab.borrow_mut();
         if let Some(entry) = slab.get_mut(id) {
             if returned_value {
+                let result = match error {
+                    Some(error) => Err(error),
+                    None => Ok(data),
+                };
+                ipc_tracing::query_returned(id, result.is_ok(), entry.started);
+                // The script is done, so the stream of messages ends here. An error is delivered to both sides.
+                if let Err(error) = &result {
+                    let _ = entry.channel_sender.unbounded_send(Err(error.clone()));
+                }
+                entry.channel_sender.close_channel();
                 if let Some(sender) = entry.return_sender.take() {
-                    let _ = sender.send(data);
+                    let _ = sender.send(result);
                 }
             } else {
-                let _ = entry.channel_sender.unbounded_send(data);
+                let _ = entry.channel_sender.unbounded_send(Ok(data));
             }
         }
     }
@@ -165,8 +194,8 @@ impl QueryEngine {
 pub(crate) struct Query<V: DeserializeOwned> {
     desktop: DesktopContext,
     slab: SharedSlab<QueryEntry>,
-    receiver: futures_channel::mpsc::UnboundedReceiver<Value>,
-    return_receiver: Option<futures_channel::oneshot::Receiver<Value>>,
+    receiver: futures_channel::mpsc::UnboundedReceiver<Result<Value, String>>,
+    return_receiver: Option<futures_channel::oneshot::Receiver<Result<Value, String>>>,
     pub id: usize,
     phantom: std::marker::PhantomData<V>,
 }
@@ -179,6 +208,8 @@ impl<V: DeserializeOwned> Query<V> {
     }
 
     /// Send a message to the query
+    ///
+    /// The message is inserted into the script as is, so it has to be a JavaScript expression.
     pub fn send<S: ToString>(&self, message: S) -> Result<(), QueryError> {
         let queue_id = self.id;
 
@@ -204,20 +235,34 @@ impl<V: DeserializeOwned> Query<V> {
         Ok(())
     }
 
+    /// Serialize a value to JSON and send it to the query
+    pub fn send_serialized<S: Serialize + ?Sized>(&self, message: &S) -> Result<(), QueryError> {
+        self.send(serde_json::to_string(message).map_err(QueryError::Serialize)?)
+    }
+
     /// Poll the query for a message
+    ///
+    /// Once the script completed and every message it sent was received, this returns [`QueryError::Finished`]. If
+    /// the script threw, the error is returned before that.
     pub fn poll_recv(
         &mut self,
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Result<Value, QueryError>> {
         self.receiver
             .poll_next_unpin(cx)
-            .map(|result| result.ok_or(QueryError::Recv))
+            .map(|message| match message {
+                Some(message) => message.map_err(QueryError::Js),
+                None => Err(QueryError::Finished),
+            })
     }
 
     /// Receive the result of the query
     pub async fn result(&mut self) -> Result<Value, QueryError> {
         match self.return_receiver.take() {
-            Some(receiver) => receiver.await.map_err(|_| QueryError::Recv),
+            Some(receiver) => receiver
+                .await
+                .map_err(|_| QueryError::Recv)?
+                .map_err(QueryError::Js),
             None => Err(QueryError::Finished),
         }
     }
@@ -228,7 +273,11 @@ impl<V: DeserializeOwned> Query<V> {
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Result<Value, QueryError>> {
         match self.return_receiver.as_mut() {
-            Some(receiver) => receiver.poll_unpin(cx).map_err(|_| QueryError::Recv),
+            Some(receiver) => receiver.poll_unpin(cx).map(|result| {
+                result
+                    .map_err(|_| QueryError::Recv)?
+                    .map_err(QueryError::Js)
+            }),
             None => std::task::Poll::Ready(Err(QueryError::Finished)),
         }
     }
@@ -259,16 +308,24 @@ pub enum QueryError {
     Recv,
     #[error("Error sending message to query: {0}")]
     Send(String),
+    #[error("Error serializing query message: {0}")]
+    Serialize(serde_json::Error),
     #[error("Error deserializing query result: {0}")]
     Deserialize(serde_json::Error),
     #[error("Query has already been resolved")]
     Finished,
+    #[error("Error thrown by the query script: {0}")]
+    Js(String),
 }
 
 #[derive(Clone, Debug, Deserialize)]
 pub(crate) struct QueryResult {
     id: usize,
+    #[serde(default)]
     data: Value,
     #[serde(default)]
     returned_value: bool,
+    /// The error the script threw, if it didn't complete
+    #[serde(default)]
+    error: Option<String>,
 }
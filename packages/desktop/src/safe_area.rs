@@ -0,0 +1,77 @@
+//! The parts of the screen that aren't covered by the notch, the home indicator or rounded corners on iOS
+
+use crate::{window, window_event::DesktopWindowEvent};
+use dioxus_hooks::use_signal;
+use dioxus_signals::{Signal, Writable};
+use tao::window::Window;
+
+/// How far content has to be inset from each edge of the window to stay clear of the notch, the home indicator and
+/// rounded corners, in logical pixels
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SafeAreaInsets {
+    /// The inset from the top edge
+    pub top: f64,
+    /// The inset from the right edge
+    pub right: f64,
+    /// The inset from the bottom edge
+    pub bottom: f64,
+    /// The inset from the left edge
+    pub left: f64,
+}
+
+/// Read the safe area insets of the window. They are all zero on platforms other than iOS.
+#[allow(unused)]
+pub(crate) fn safe_area_insets(window: &Window) -> SafeAreaInsets {
+    #[cfg(target_os = "ios")]
+    {
+        use objc::runtime::Object;
+        use objc::*;
+        use tao::platform::ios::WindowExtIOS;
+
+        #[repr(C)]
+        struct UIEdgeInsets {
+            top: f64,
+            left: f64,
+            bottom: f64,
+            right: f64,
+        }
+
+        let view = window.ui_view() as *mut Object;
+        if !view.is_null() {
+            let insets: UIEdgeInsets = unsafe { msg_send![view, safeAreaInsets] };
+            return SafeAreaInsets {
+                top: insets.top,
+                right: insets.right,
+                bottom: insets.bottom,
+                left: insets.left,
+            };
+        }
+    }
+
+    SafeAreaInsets::default()
+}
+
+/// Get the safe area insets of the window this component is rendered in
+///
+/// The signal updates when the insets change, for example when the device is rotated, so layouts can pad the edges
+/// that are covered:
+///
+/// ```rust, ignore
+/// let insets = use_safe_area_insets();
+/// let SafeAreaInsets { top, right, bottom, left } = insets();
+///
+/// rsx! {
+///     div { style: "padding: {top}px {right}px {bottom}px {left}px;", Content {} }
+/// }
+/// ```
+pub fn use_safe_area_insets() -> Signal<SafeAreaInsets> {
+    let mut insets = use_signal(|| window().safe_area_insets());
+
+    crate::use_window_event(move |event| {
+        if let DesktopWindowEvent::SafeAreaChanged(new) = event {
+            insets.set(new);
+        }
+    });
+
+    insets
+}
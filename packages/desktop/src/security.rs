@@ -0,0 +1,164 @@
+//! Hardening the bridge between the page and the app, for apps that show remote or user-generated content
+
+use crate::DesktopService;
+use std::{
+    cell::OnceCell,
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    rc::{Rc, Weak},
+    time::SystemTime,
+};
+use wry::http::{
+    header::{HeaderValue, ORIGIN},
+    Request, Response,
+};
+
+/// The origins the app's own pages are served from, depending on the platform
+const APP_ORIGINS: &[&str] = &[
+    "dioxus://index.html",
+    "http://dioxus.index.html",
+    "https://dioxus.index.html",
+];
+
+/// The origins whose pages may send IPC messages to the app
+#[derive(Debug, Clone, Default)]
+pub(crate) struct IpcOrigins {
+    extra: Vec<String>,
+}
+
+impl IpcOrigins {
+    pub(crate) fn new(extra: Vec<String>) -> Self {
+        Self { extra }
+    }
+
+    /// Whether a page at the URL may talk to the app
+    pub(crate) fn allows(&self, url: &str) -> bool {
+        let origin = origin(url);
+        APP_ORIGINS.contains(&origin) || self.extra.iter().any(|allowed| allowed == origin)
+    }
+
+    /// Whether a request to the custom protocol may carry IPC messages
+    ///
+    /// The webview sets the `Origin` header to the frame that sent the request, so frames of other origins are told
+    /// apart from the page. Webviews that leave it out are checked like [`IpcOrigins::allows_message`].
+    pub(crate) fn allows_request(&self, request: &Request<Vec<u8>>, page: &CurrentPage) -> bool {
+        let origin = request
+            .headers()
+            .get(ORIGIN)
+            .and_then(|value| value.to_str().ok());
+        match origin {
+            Some(origin) if origin != "null" => self.allows(origin),
+            _ => self.allows_message(page),
+        }
+    }
+
+    /// Whether a message posted to `window.ipc` may be handled, checked as it arrives
+    ///
+    /// The webview doesn't say which frame posted the message, so the page the window shows at that moment is checked.
+    pub(crate) fn allows_message(&self, page: &CurrentPage) -> bool {
+        page.url().is_some_and(|url| self.allows(&url))
+    }
+}
+
+/// The page a window shows, for handlers that are created before the webview
+#[derive(Clone, Default)]
+pub(crate) struct CurrentPage(Rc<OnceCell<Weak<DesktopService>>>);
+
+impl CurrentPage {
+    /// Start reporting the page of the window, once its webview is built
+    pub(crate) fn set(&self, service: &Rc<DesktopService>) {
+        _ = self.0.set(Rc::downgrade(service));
+    }
+
+    fn url(&self) -> Option<String> {
+        let service = self.0.get()?.upgrade()?;
+        Some(service.webview.url())
+    }
+}
+
+/// The `scheme://host[:port]` part of a URL
+fn origin(url: &str) -> &str {
+    let Some(scheme_end) = url.find("://") else {
+        return url;
+    };
+    let authority_start = scheme_end + 3;
+    let authority_end = url[authority_start..]
+        .find(['/', '?', '#'])
+        .map_or(url.len(), |end| authority_start + end);
+    &url[..authority_end]
+}
+
+/// Apply a content security policy to the index page
+///
+/// The script that boots the interpreter is inline. It is generated with the nonce, which is added to the policy, so
+/// apps don't have to allow inline scripts in general.
+pub(crate) fn apply_content_security_policy(
+    mut response: Response<Vec<u8>>,
+    policy: &str,
+    nonce: &str,
+) -> Response<Vec<u8>> {
+    let policy = with_script_nonce(policy, nonce);
+
+    let Ok(index) = std::str::from_utf8(response.body()) else {
+        return response;
+    };
+    let mut index = index.to_string();
+
+    // The meta tag has to come before every script to apply to them
+    let meta = format!(
+        r#"<meta http-equiv="Content-Security-Policy" content="{}">"#,
+        policy.replace('"', "&quot;")
+    );
+    match index.find("<head>") {
+        Some(head) => index.insert_str(head + "<head>".len(), &meta),
+        None => index.insert_str(0, &meta),
+    }
+    *response.body_mut() = index.into_bytes();
+
+    if let Ok(value) = HeaderValue::from_str(&policy) {
+        response
+            .headers_mut()
+            .insert("Content-Security-Policy", value);
+    }
+
+    response
+}
+
+/// Allow scripts with the nonce in the directive that governs scripts, if the policy restricts them
+fn with_script_nonce(policy: &str, nonce: &str) -> String {
+    let directives: Vec<&str> = policy.split(';').map(str::trim).collect();
+    let governs_scripts = |name: &str| {
+        directives
+            .iter()
+            .position(|directive| directive.split_whitespace().next() == Some(name))
+    };
+
+    let Some(index) = governs_scripts("script-src").or_else(|| governs_scripts("default-src"))
+    else {
+        return policy.to_string();
+    };
+
+    directives
+        .iter()
+        .enumerate()
+        .filter(|(_, directive)| !directive.is_empty())
+        .map(|(i, directive)| match i == index {
+            true => format!("{directive} 'nonce-{nonce}'"),
+            false => directive.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// A random nonce for one load of the index page
+pub(crate) fn nonce() -> String {
+    let random = |salt: u64| {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(salt);
+        if let Ok(now) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+            hasher.write_u128(now.as_nanos());
+        }
+        hasher.finish()
+    };
+    format!("{:016x}{:016x}", random(0), random(1))
+}
@@ -33,6 +33,14 @@ impl ShortcutHandle {
     pub fn remove(&self) {
         window().remove_shortcut(*self);
     }
+
+    /// Move the shortcut's callback to a different hotkey.
+    ///
+    /// The returned handle replaces this one. If the new hotkey can't be registered, the shortcut keeps its current
+    /// hotkey.
+    pub fn rebind(&self, hotkey: HotKey) -> Result<ShortcutHandle, ShortcutRegistryError> {
+        window().rebind_shortcut(*self, hotkey)
+    }
 }
 
 /// An error that can occur when registering a shortcut.
@@ -41,6 +49,10 @@ impl ShortcutHandle {
 pub enum ShortcutRegistryError {
     /// The shortcut is invalid.
     InvalidShortcut(String),
+    /// The operating system rejected the hotkey, usually because another application already registered it.
+    AlreadyRegistered(HotKey),
+    /// The shortcut was already removed.
+    NotRegistered,
     /// An unknown error occurred.
     Other(Rc<dyn std::error::Error>),
 }
@@ -51,7 +63,6 @@ pub(crate) struct ShortcutRegistry {
 }
 
 struct ShortcutInner {
-    #[allow(unused)]
     shortcut: HotKey,
     callbacks: Slab<Box<dyn FnMut()>>,
 }
@@ -89,12 +100,7 @@ impl ShortcutRegistry {
             });
         };
 
-        self.manager.register(hotkey).map_err(|e| match e {
-            HotkeyError::HotKeyParseError(shortcut) => {
-                ShortcutRegistryError::InvalidShortcut(shortcut)
-            }
-            err => ShortcutRegistryError::Other(Rc::new(err)),
-        })?;
+        self.register(hotkey.clone())?;
 
         let mut shortcut = ShortcutInner {
             shortcut: hotkey,
@@ -111,10 +117,89 @@ impl ShortcutRegistry {
         })
     }
 
+    /// Register the hotkey with the OS, reporting hotkeys that are already taken
+    fn register(&self, hotkey: HotKey) -> Result<(), ShortcutRegistryError> {
+        self.manager
+            .register(hotkey.clone())
+            .map(|_| ())
+            .map_err(|e| match e {
+                HotkeyError::HotKeyParseError(shortcut) => {
+                    ShortcutRegistryError::InvalidShortcut(shortcut)
+                }
+                HotkeyError::AlreadyRegistered(_) | HotkeyError::FailedToRegister(_) => {
+                    ShortcutRegistryError::AlreadyRegistered(hotkey)
+                }
+                err => ShortcutRegistryError::Other(Rc::new(err)),
+            })
+    }
+
+    /// Move the callback of a shortcut to a different hotkey
+    ///
+    /// The new hotkey is registered before the old one is released, so the shortcut is left untouched if the OS
+    /// rejects the new hotkey.
+    pub(crate) fn rebind(
+        &self,
+        handle: ShortcutHandle,
+        hotkey: HotKey,
+    ) -> Result<ShortcutHandle, ShortcutRegistryError> {
+        let accelerator_id = hotkey.clone().id();
+        if accelerator_id == handle.id {
+            return Ok(handle);
+        }
+
+        let mut shortcuts = self.shortcuts.borrow_mut();
+
+        let registered = shortcuts
+            .get(&handle.id)
+            .is_some_and(|inner| inner.callbacks.contains(handle.number));
+        if !registered {
+            return Err(ShortcutRegistryError::NotRegistered);
+        }
+
+        if !shortcuts.contains_key(&accelerator_id) {
+            self.register(hotkey.clone())?;
+            shortcuts.insert(
+                accelerator_id,
+                ShortcutInner {
+                    shortcut: hotkey,
+                    callbacks: Slab::new(),
+                },
+            );
+        }
+
+        let old = shortcuts.get_mut(&handle.id).unwrap();
+        let callback = old.callbacks.remove(handle.number);
+        if old.callbacks.is_empty() {
+            if let Some(old) = shortcuts.remove(&handle.id) {
+                let _ = self.manager.unregister(old.shortcut);
+            }
+        }
+
+        let number = shortcuts
+            .get_mut(&accelerator_id)
+            .unwrap()
+            .callbacks
+            .insert(callback);
+
+        Ok(ShortcutHandle {
+            id: accelerator_id,
+            number,
+        })
+    }
+
+    /// The hotkeys that currently have at least one shortcut bound to them
+    pub(crate) fn shortcuts(&self) -> Vec<HotKey> {
+        self.shortcuts
+            .borrow()
+            .values()
+            .map(|inner| inner.shortcut.clone())
+            .collect()
+    }
+
     pub(crate) fn remove_shortcut(&self, id: ShortcutHandle) {
         let mut shortcuts = self.shortcuts.borrow_mut();
         if let Some(callbacks) = shortcuts.get_mut(&id.id) {
-            let _ = callbacks.callbacks.remove(id.number);
+            let _ = callbacks.callbacks.try_remove(id.number);
             if callbacks.callbacks.is_empty() {
                 if let Some(_shortcut) = shortcuts.remove(&id.id) {
                     let _ = self.manager.unregister(_shortcut.shortcut);
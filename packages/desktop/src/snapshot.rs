@@ -0,0 +1,235 @@
+//! Capture what the webview renders as an image, for thumbnails, exports and offscreen windows
+
+use futures_channel::oneshot;
+use std::future::Future;
+use tao::window::{Window, WindowBuilder};
+use thiserror::Error;
+use wry::WebView;
+
+/// An error that can occur when capturing the contents of a webview
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum SnapshotError {
+    /// The platform webview can't capture its contents
+    #[error("Capturing the webview is not supported on this platform")]
+    Unsupported,
+    /// The webview went away before the capture completed
+    #[error("The webview was closed before the capture completed")]
+    Cancelled,
+    /// The platform webview reported an error
+    #[error("Failed to capture the webview: {0}")]
+    Platform(String),
+}
+
+type SnapshotResult = Result<Vec<u8>, SnapshotError>;
+
+/// Start capturing the visible part of the webview as a PNG image
+pub(crate) fn capture(webview: &WebView) -> impl Future<Output = SnapshotResult> + 'static {
+    let (tx, rx) = oneshot::channel();
+    let started = platform::capture(webview, tx);
+
+    async move {
+        started?;
+        rx.await.unwrap_or(Err(SnapshotError::Cancelled))
+    }
+}
+
+/// Build a window for offscreen rendering hidden, so it never flashes on screen before
+/// [`make_invisible`] is applied to it
+pub(crate) fn offscreen_window(window: WindowBuilder) -> WindowBuilder {
+    let window = window
+        .with_visible(false)
+        .with_decorations(false)
+        .with_focused(false);
+
+    #[cfg(target_os = "windows")]
+    let window = {
+        use tao::platform::windows::WindowBuilderExtWindows;
+        window.with_skip_taskbar(true)
+    };
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    let window = {
+        use tao::platform::unix::WindowBuilderExtUnix;
+        window.with_skip_taskbar(true)
+    };
+
+    window
+}
+
+/// Make an offscreen window fully transparent and let clicks pass through it
+///
+/// Most webviews stop painting in hidden windows, so offscreen windows are shown like any other window, just without
+/// anything to see. This works on every window system, unlike moving the window off the screen.
+pub(crate) fn make_invisible(window: &Window) {
+    _ = window.set_ignore_cursor_events(true);
+
+    #[cfg(target_os = "windows")]
+    unsafe {
+        use tao::platform::windows::WindowExtWindows;
+        use windows_sys::Win32::UI::WindowsAndMessaging::{
+            GetWindowLongPtrW, SetLayeredWindowAttributes, SetWindowLongPtrW, GWL_EXSTYLE,
+            LWA_ALPHA, WS_EX_LAYERED,
+        };
+
+        let hwnd = window.hwnd() as _;
+        let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style | WS_EX_LAYERED as isize);
+        SetLayeredWindowAttributes(hwnd, 0, 0, LWA_ALPHA);
+    }
+
+    #[cfg(target_os = "macos")]
+    unsafe {
+        use objc::runtime::Object;
+        use objc::*;
+        use tao::platform::macos::WindowExtMacOS;
+
+        let ns_window = window.ns_window() as *mut Object;
+        let _: () = msg_send![ns_window, setAlphaValue: 0.0f64];
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        use tao::platform::unix::WindowExtUnix;
+        use webkit2gtk::glib::prelude::ObjectExt;
+
+        window.gtk_window().set_property("opacity", 0.0f64);
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+    use webview2_com::{CapturePreviewCompletedHandler, Microsoft::Web::WebView2::Win32::*};
+    use windows::Win32::{
+        System::Com::{STATFLAG_NONAME, STATSTG, STREAM_SEEK_SET},
+        UI::Shell::SHCreateMemStream,
+    };
+    use wry::WebViewExtWindows;
+
+    pub(super) fn capture(
+        webview: &WebView,
+        tx: oneshot::Sender<SnapshotResult>,
+    ) -> Result<(), SnapshotError> {
+        let platform_error = |err: windows::core::Error| SnapshotError::Platform(err.to_string());
+
+        let core = unsafe { webview.controller().CoreWebView2() }.map_err(platform_error)?;
+        let stream = unsafe { SHCreateMemStream(None) }
+            .ok_or_else(|| SnapshotError::Platform("Failed to create a stream".to_string()))?;
+
+        let stream_ = stream.clone();
+        let handler = CapturePreviewCompletedHandler::create(Box::new(move |result| {
+            let image = result.and_then(|_| unsafe {
+                let mut stat = STATSTG::default();
+                stream_.Stat(&mut stat, STATFLAG_NONAME)?;
+                stream_.Seek(0, STREAM_SEEK_SET, None)?;
+
+                let mut image = vec![0u8; stat.cbSize as usize];
+                let mut read = 0;
+                stream_
+                    .Read(
+                        image.as_mut_ptr().cast(),
+                        image.len() as u32,
+                        Some(&mut read),
+                    )
+                    .ok()?;
+                image.truncate(read as usize);
+                Ok(image)
+            });
+
+            _ = tx.send(image.map_err(platform_error));
+            Ok(())
+        }));
+
+        unsafe {
+            core.CapturePreview(
+                COREWEBVIEW2_CAPTURE_PREVIEW_IMAGE_FORMAT_PNG,
+                &stream,
+                &handler,
+            )
+        }
+        .map_err(platform_error)
+    }
+}
+
+#[cfg(all(
+    feature = "snapshot",
+    any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )
+))]
+mod platform {
+    use super::*;
+    use webkit2gtk::{gio::Cancellable, SnapshotOptions, SnapshotRegion, WebViewExt};
+    use wry::WebViewExtUnix;
+
+    pub(super) fn capture(
+        webview: &WebView,
+        tx: oneshot::Sender<SnapshotResult>,
+    ) -> Result<(), SnapshotError> {
+        webview.webview().snapshot(
+            SnapshotRegion::Visible,
+            SnapshotOptions::NONE,
+            None::<&Cancellable>,
+            move |result| {
+                let image = result
+                    .map_err(|err| SnapshotError::Platform(err.to_string()))
+                    .and_then(|surface| {
+                        let surface = cairo::ImageSurface::try_from(surface).map_err(|_| {
+                            SnapshotError::Platform("The snapshot is not an image".to_string())
+                        })?;
+
+                        let mut image = Vec::new();
+                        surface
+                            .write_to_png(&mut image)
+                            .map_err(|err| SnapshotError::Platform(err.to_string()))?;
+                        Ok(image)
+                    });
+
+                _ = tx.send(image);
+            },
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    all(
+        feature = "snapshot",
+        any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        )
+    )
+)))]
+mod platform {
+    use super::*;
+
+    pub(super) fn capture(
+        _: &WebView,
+        _: oneshot::Sender<SnapshotResult>,
+    ) -> Result<(), SnapshotError> {
+        Err(SnapshotError::Unsupported)
+    }
+}
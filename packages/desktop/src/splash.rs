@@ -0,0 +1,84 @@
+use crate::ipc::UserWindowEvent;
+use tao::{
+    dpi::{LogicalSize, PhysicalPosition},
+    event_loop::EventLoopWindowTarget,
+    window::{Window, WindowBuilder},
+};
+use wry::{WebView, WebViewBuilder};
+
+/// A lightweight window shown while the main window boots up
+///
+/// The splash screen only renders static HTML - it never talks to the VirtualDom. It is dropped (and its window
+/// closed) as soon as the main window's webview sends the `initialize` message.
+pub(crate) struct SplashScreen {
+    // The webview must be dropped before the window it lives in
+    _webview: WebView,
+    _window: Window,
+}
+
+impl SplashScreen {
+    /// The window used for splash screens when the user doesn't provide one
+    pub fn default_window() -> WindowBuilder {
+        WindowBuilder::new()
+            .with_title("")
+            .with_inner_size(LogicalSize::new(400.0, 300.0))
+            .with_decorations(false)
+            .with_resizable(false)
+    }
+
+    pub fn new(
+        window: WindowBuilder,
+        html: &str,
+        target: &EventLoopWindowTarget<UserWindowEvent>,
+    ) -> Option<Self> {
+        let window = window
+            .build(target)
+            .map_err(|err| tracing::error!("Failed to create splash screen window: {err}"))
+            .ok()?;
+
+        // Center the splash screen on the monitor the window opened on
+        if let Some(monitor) = window.current_monitor() {
+            let monitor_size = monitor.size();
+            let window_size = window.outer_size();
+            let position = monitor.position();
+            window.set_outer_position(PhysicalPosition::new(
+                position.x + (monitor_size.width as i32 - window_size.width as i32) / 2,
+                position.y + (monitor_size.height as i32 - window_size.height as i32) / 2,
+            ));
+        }
+
+        #[cfg(any(
+            target_os = "windows",
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "android"
+        ))]
+        let webview = WebViewBuilder::new(&window);
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "android"
+        )))]
+        let webview = {
+            use tao::platform::unix::WindowExtUnix;
+            use wry::WebViewBuilderExtUnix;
+            let vbox = window.default_vbox().unwrap();
+            WebViewBuilder::new_gtk(vbox)
+        };
+
+        let webview = webview
+            .with_html(html)
+            // The splash screen is static, don't let it navigate anywhere
+            .with_navigation_handler(|_| false)
+            .build()
+            .map_err(|err| tracing::error!("Failed to create splash screen webview: {err}"))
+            .ok()?;
+
+        Some(Self {
+            _webview: webview,
+            _window: window,
+        })
+    }
+}
@@ -0,0 +1,97 @@
+//! Drop-in titlebar components for windows created with `decorations: false`
+//!
+//! The components don't handle any events themselves. They only render elements marked with the drag region and
+//! window control attributes, which the drag region script forwards to the event loop over IPC. That means a
+//! custom titlebar behaves the same as one built with the components, as long as it uses the same attributes.
+
+use dioxus_core::prelude::*;
+use dioxus_core_macro::{rsx, Props};
+use dioxus_html as dioxus_elements;
+
+/// The properties for a [`TitleBar`]
+#[derive(Props, Clone, PartialEq)]
+pub struct TitleBarProps {
+    /// Whether to render the minimize, maximize and close buttons at the end of the titlebar
+    #[props(default = true)]
+    pub controls: bool,
+
+    /// The contents of the titlebar, usually the title of the window
+    pub children: Element,
+}
+
+/// A titlebar for undecorated windows
+///
+/// Dragging the titlebar moves the window and double clicking it toggles whether the window is maximized. The
+/// element gets the `dioxus-titlebar` class so it can be styled from the app's stylesheet.
+///
+/// ```rust, ignore
+/// rsx! {
+///     TitleBar { "My app" }
+///     main { "Content" }
+/// }
+/// ```
+#[allow(non_snake_case)]
+pub fn TitleBar(props: TitleBarProps) -> Element {
+    let TitleBarProps { controls, children } = props;
+
+    rsx! {
+        div {
+            class: "dioxus-titlebar",
+            "data-dioxus-drag-region": true,
+            style: "display: flex; align-items: center; user-select: none; -webkit-user-select: none;",
+            {children}
+            if controls {
+                div { style: "margin-left: auto;", WindowControls {} }
+            }
+        }
+    }
+}
+
+/// The properties for [`WindowControls`]
+#[derive(Props, Clone, PartialEq)]
+pub struct WindowControlsProps {
+    /// Whether to render the minimize button
+    #[props(default = true)]
+    pub minimize: bool,
+
+    /// Whether to render the maximize button
+    #[props(default = true)]
+    pub maximize: bool,
+}
+
+/// Minimize, maximize and close buttons for undecorated windows
+///
+/// The close button goes through the same path as the native close button, so the configured
+/// [`crate::WindowCloseBehaviour`] is respected. The buttons get the `dioxus-window-control` class and a
+/// `data-dioxus-window-control` attribute naming the action for styling.
+#[allow(non_snake_case)]
+pub fn WindowControls(props: WindowControlsProps) -> Element {
+    let WindowControlsProps { minimize, maximize } = props;
+
+    rsx! {
+        div { class: "dioxus-window-controls", style: "display: flex;",
+            if minimize {
+                button {
+                    class: "dioxus-window-control",
+                    "data-dioxus-window-control": "minimize",
+                    "aria-label": "Minimize",
+                    "\u{2013}"
+                }
+            }
+            if maximize {
+                button {
+                    class: "dioxus-window-control",
+                    "data-dioxus-window-control": "maximize",
+                    "aria-label": "Maximize",
+                    "\u{25a1}"
+                }
+            }
+            button {
+                class: "dioxus-window-control",
+                "data-dioxus-window-control": "close",
+                "aria-label": "Close",
+                "\u{2715}"
+            }
+        }
+    }
+}
@@ -0,0 +1,387 @@
+//! Check for, download, and install new versions of the app
+//!
+//! The updater reads a JSON manifest from a URL you control:
+//!
+//! ```json
+//! {
+//!     "version": "1.2.0",
+//!     "notes": "Bug fixes and performance improvements",
+//!     "platforms": {
+//!         "windows-x86_64": { "url": "https://example.com/app-1.2.0.exe", "signature": "..." },
+//!         "macos-aarch64": { "url": "https://example.com/app-1.2.0", "signature": "..." }
+//!     }
+//! }
+//! ```
+//!
+//! Platforms are keyed by `{std::env::consts::OS}-{std::env::consts::ARCH}`. Each artifact is the new executable
+//! itself, signed with [minisign](https://jedisct1.github.io/minisign/). The trusted comment of the signature has to
+//! name the version of the manifest, so an older artifact can't be passed off as a newer one:
+//!
+//! ```sh
+//! minisign -S -m app-1.2.0.exe -t "version:1.2.0"
+//! ```
+//!
+//! Artifacts whose signature doesn't match the configured public key or the version are never installed.
+//!
+//! Only the executable is updated. Apps that ship other files in their bundle have to update those some other way.
+
+use crate::{hooks::use_event_listener, ipc::UserWindowEvent, window};
+use dioxus_core::use_hook;
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+use tao::event_loop::EventLoopProxy;
+use thiserror::Error;
+
+/// Where to look for updates and how to verify them
+#[derive(Debug, Clone)]
+pub struct UpdaterConfig {
+    manifest_url: String,
+    public_key: String,
+    current_version: String,
+}
+
+impl UpdaterConfig {
+    /// Create a new updater config.
+    ///
+    /// - `manifest_url`: the URL of the JSON update manifest
+    /// - `public_key`: the base64 encoded minisign public key artifacts are signed with
+    /// - `current_version`: the semver version of the running app, usually `env!("CARGO_PKG_VERSION")`
+    pub fn new(
+        manifest_url: impl Into<String>,
+        public_key: impl Into<String>,
+        current_version: impl Into<String>,
+    ) -> Self {
+        Self {
+            manifest_url: manifest_url.into(),
+            public_key: public_key.into(),
+            current_version: current_version.into(),
+        }
+    }
+}
+
+/// A newer version of the app that is available to download
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Update {
+    /// The version of the update
+    pub version: String,
+    /// The release notes from the manifest, if any
+    pub notes: Option<String>,
+    url: String,
+    signature: String,
+}
+
+/// Progress and results reported by the [`Updater`]
+#[derive(Debug, Clone)]
+pub enum UpdaterEvent {
+    /// The running version is the latest one
+    UpToDate,
+    /// A newer version is available. Call [`Updater::download`] to download it
+    UpdateAvailable(Update),
+    /// Part of the update has been downloaded
+    DownloadProgress {
+        /// The number of bytes downloaded so far
+        downloaded: u64,
+        /// The total size of the update, if the server reported it
+        total: Option<u64>,
+    },
+    /// The update has been downloaded and verified. Call [`Updater::restart`] to install it
+    ReadyToInstall(Update),
+    /// Checking, downloading or verifying the update failed
+    Error(Arc<UpdaterError>),
+}
+
+/// An error that can occur while updating the app
+#[derive(Debug, Error)]
+pub enum UpdaterError {
+    /// The manifest or the artifact couldn't be fetched
+    #[error("Failed to fetch update: {0}")]
+    Network(#[from] reqwest::Error),
+    /// The manifest isn't valid
+    #[error("Invalid update manifest: {0}")]
+    Manifest(#[from] serde_json::Error),
+    /// A version in the config or the manifest isn't valid semver
+    #[error("Invalid version: {0}")]
+    Version(#[from] semver::Error),
+    /// The manifest doesn't contain an artifact for this platform
+    #[error("No update available for platform {0}")]
+    UnsupportedPlatform(String),
+    /// The artifact's signature doesn't match the public key
+    #[error("Failed to verify update signature: {0}")]
+    Signature(#[from] minisign_verify::Error),
+    /// The trusted comment of the artifact's signature doesn't name the version of the manifest
+    #[error("The update signature is not for version {0}")]
+    SignedVersion(String),
+    /// The artifact couldn't be written or installed
+    #[error("Failed to install update: {0}")]
+    Io(#[from] std::io::Error),
+    /// [`Updater::download`] was called before an update was found
+    #[error("No update has been found yet")]
+    NoUpdate,
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    version: String,
+    #[serde(default)]
+    notes: Option<String>,
+    platforms: HashMap<String, ManifestArtifact>,
+}
+
+#[derive(Deserialize)]
+struct ManifestArtifact {
+    url: String,
+    signature: String,
+}
+
+#[derive(Default)]
+struct UpdaterState {
+    available: Option<Update>,
+    staged: Option<(PathBuf, Update)>,
+}
+
+/// A handle to the app's updater. Get it with [`use_updater`] or
+/// [`DesktopService::updater`](crate::DesktopService::updater).
+///
+/// All work happens in the background - results are delivered to [`use_updater_event`] handlers.
+#[derive(Clone)]
+pub struct Updater {
+    config: Arc<UpdaterConfig>,
+    state: Arc<Mutex<UpdaterState>>,
+    proxy: EventLoopProxy<UserWindowEvent>,
+}
+
+impl Updater {
+    pub(crate) fn new(config: UpdaterConfig, proxy: EventLoopProxy<UserWindowEvent>) -> Self {
+        Self {
+            config: Arc::new(config),
+            state: Default::default(),
+            proxy,
+        }
+    }
+
+    /// Check the manifest for a newer version
+    pub fn check(&self) {
+        let updater = self.clone();
+        spawn_background(async move {
+            let event = match updater.fetch_update().await {
+                Ok(Some(update)) => {
+                    updater.state.lock().unwrap().available = Some(update.clone());
+                    UpdaterEvent::UpdateAvailable(update)
+                }
+                Ok(None) => UpdaterEvent::UpToDate,
+                Err(err) => UpdaterEvent::Error(Arc::new(err)),
+            };
+            updater.emit(event);
+        });
+    }
+
+    /// Download and verify the update found by [`Updater::check`]
+    pub fn download(&self) {
+        let updater = self.clone();
+        spawn_background(async move {
+            let event = match updater.download_update().await {
+                Ok(update) => UpdaterEvent::ReadyToInstall(update),
+                Err(err) => UpdaterEvent::Error(Arc::new(err)),
+            };
+            updater.emit(event);
+        });
+    }
+
+    /// Close the app, install the downloaded update, and start the new version
+    ///
+    /// This does nothing if no update has been downloaded.
+    pub fn restart(&self) {
+        if self.state.lock().unwrap().staged.is_none() {
+            tracing::warn!("Restart requested before an update was downloaded");
+            return;
+        }
+
+        _ = self.proxy.send_event(UserWindowEvent::RestartToUpdate);
+    }
+
+    fn emit(&self, event: UpdaterEvent) {
+        _ = self.proxy.send_event(UserWindowEvent::UpdaterEvent(event));
+    }
+
+    async fn fetch_update(&self) -> Result<Option<Update>, UpdaterError> {
+        let body = reqwest::get(&self.config.manifest_url)
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        let manifest: Manifest = serde_json::from_slice(&body)?;
+
+        let current = semver::Version::parse(&self.config.current_version)?;
+        let latest = semver::Version::parse(&manifest.version)?;
+        if latest <= current {
+            return Ok(None);
+        }
+
+        let platform = format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH);
+        let Some(artifact) = manifest.platforms.get(&platform) else {
+            return Err(UpdaterError::UnsupportedPlatform(platform));
+        };
+
+        Ok(Some(Update {
+            version: manifest.version,
+            notes: manifest.notes,
+            url: artifact.url.clone(),
+            signature: artifact.signature.clone(),
+        }))
+    }
+
+    async fn download_update(&self) -> Result<Update, UpdaterError> {
+        let update = self
+            .state
+            .lock()
+            .unwrap()
+            .available
+            .clone()
+            .ok_or(UpdaterError::NoUpdate)?;
+
+        let mut response = reqwest::get(&update.url).await?.error_for_status()?;
+        let total = response.content_length();
+        let mut bytes = Vec::with_capacity(total.unwrap_or_default() as usize);
+
+        while let Some(chunk) = response.chunk().await? {
+            bytes.extend_from_slice(&chunk);
+            self.emit(UpdaterEvent::DownloadProgress {
+                downloaded: bytes.len() as u64,
+                total,
+            });
+        }
+
+        // Never touch the disk with an artifact we can't verify
+        self.verify(&update, &bytes)?;
+
+        let staged = stage(&update, &bytes)?;
+        self.state.lock().unwrap().staged = Some((staged, update.clone()));
+
+        Ok(update)
+    }
+
+    /// Check that the artifact was signed with the public key, for the version the manifest announced
+    fn verify(&self, update: &Update, bytes: &[u8]) -> Result<(), UpdaterError> {
+        let public_key = minisign_verify::PublicKey::from_base64(&self.config.public_key)?;
+        let signature = minisign_verify::Signature::decode(&update.signature)?;
+        public_key.verify(bytes, &signature, false)?;
+
+        // The trusted comment is covered by the signature, unlike the manifest
+        let signed_version = signature
+            .trusted_comment()
+            .split_whitespace()
+            .find_map(|field| field.strip_prefix("version:"));
+        if signed_version != Some(update.version.as_str()) {
+            return Err(UpdaterError::SignedVersion(update.version.clone()));
+        }
+
+        Ok(())
+    }
+
+    /// Swap the running executable for the staged update and launch it
+    ///
+    /// This runs after the event loop has shut down, so there are no windows left to report errors to.
+    ///
+    /// Only the executable is replaced. Resources bundled next to it, like the contents of a macOS `.app` or assets
+    /// installed alongside the binary, are left as they are, so updates that change them have to be installed with
+    /// the platform's installer instead.
+    pub(crate) fn install_staged(&self) -> Result<(), UpdaterError> {
+        let Some((staged, update)) = self.state.lock().unwrap().staged.take() else {
+            return Ok(());
+        };
+
+        // Install the bytes that were verified just now, not whatever the staged file holds by the time it is copied
+        let bytes = std::fs::read(&staged)?;
+        _ = std::fs::remove_file(&staged);
+        self.verify(&update, &bytes)?;
+
+        let current = std::env::current_exe()?;
+
+        // The running executable can't be overwritten on Windows, but it can be renamed
+        let backup = current.with_extension("old");
+        _ = std::fs::remove_file(&backup);
+        std::fs::rename(&current, &backup)?;
+
+        if let Err(err) = std::fs::write(&current, &bytes) {
+            _ = std::fs::rename(&backup, &current);
+            return Err(err.into());
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&current, std::fs::Permissions::from_mode(0o755))?;
+        }
+
+        std::process::Command::new(current)
+            .args(std::env::args_os().skip(1))
+            .spawn()?;
+
+        Ok(())
+    }
+}
+
+/// Write the verified artifact next to the executable, in a directory only the current user can access. The shared
+/// temp directory would let other users plant or swap the file before it is installed.
+fn stage(update: &Update, bytes: &[u8]) -> Result<PathBuf, UpdaterError> {
+    let current = std::env::current_exe()?;
+    let name = current
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let dir = current
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join(format!(".{name}-update"));
+
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder.create(&dir)?;
+
+    let staged = dir.join(&update.version);
+    _ = std::fs::remove_file(&staged);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(&staged)?.write_all(bytes)?;
+
+    Ok(staged)
+}
+
+/// Run the future on its own thread, since the updater can be used from code that isn't running in a tokio runtime
+fn spawn_background(future: impl std::future::Future<Output = ()> + Send + 'static) {
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build();
+        match runtime {
+            Ok(runtime) => runtime.block_on(future),
+            Err(err) => tracing::error!("Failed to start the updater runtime: {err}"),
+        }
+    });
+}
+
+/// Get a handle to the app's updater, if one was configured with
+/// [`Config::with_updater`](crate::Config::with_updater)
+pub fn use_updater() -> Option<Updater> {
+    use_hook(|| window().updater())
+}
+
+/// Listen for [`UpdaterEvent`]s, for example to render the download progress of an update
+pub fn use_updater_event(handler: impl FnMut(UpdaterEvent) + 'static) {
+    use_event_listener(|window| &window.shared.updater_listeners, handler)
+}
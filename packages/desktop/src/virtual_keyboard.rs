@@ -0,0 +1,53 @@
+//! Showing and hiding of the on-screen keyboard on mobile
+
+use serde::Deserialize;
+
+/// The script that reports the on-screen keyboard over IPC whenever it is shown, hidden or resized
+///
+/// The keyboard shrinks the visual viewport, so its height is how much the viewport shrank compared to the tallest it
+/// has been at the current width. The width changes when the device is rotated, which starts over.
+#[allow(unused)]
+pub(crate) const VIRTUAL_KEYBOARD_SCRIPT: &str = r#"
+(function () {
+    const viewport = window.visualViewport;
+    if (!viewport) {
+        return;
+    }
+
+    // Smaller changes come from toolbars that collapse while scrolling
+    const MIN_HEIGHT = 80;
+    let width = 0;
+    let fullHeight = 0;
+    let reported = 0;
+
+    const update = function () {
+        if (window.innerWidth !== width) {
+            width = window.innerWidth;
+            fullHeight = 0;
+        }
+        fullHeight = Math.max(fullHeight, window.innerHeight, viewport.height);
+
+        let height = Math.round(fullHeight - viewport.height);
+        if (height < MIN_HEIGHT) {
+            height = 0;
+        }
+        if (height !== reported) {
+            reported = height;
+            window.ipc.postMessage(JSON.stringify({
+                method: "virtual_keyboard",
+                params: { visible: height > 0, height: height },
+            }));
+        }
+    };
+
+    viewport.addEventListener("resize", update);
+    window.addEventListener("DOMContentLoaded", update);
+})();
+"#;
+
+/// The state of the on-screen keyboard the page reports
+#[derive(Deserialize)]
+pub(crate) struct VirtualKeyboardMessage {
+    pub(crate) visible: bool,
+    pub(crate) height: f64,
+}
@@ -0,0 +1,261 @@
+//! Settings for running under Wayland compositors. These are ignored on other platforms and on X11, so they can be set
+//! unconditionally.
+
+use tao::window::Window;
+
+/// Who draws the titlebar and borders of a window on Wayland
+#[cfg(feature = "wayland-decorations")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WaylandDecorations {
+    /// Let GTK decide. It uses server side decorations when the compositor supports them and draws its own otherwise.
+    #[default]
+    Auto,
+    /// Always draw the decorations in the app, so the window looks the same under every compositor
+    ClientSide,
+    /// Don't draw any decorations
+    None,
+}
+
+/// The layer a layer shell surface is placed in, from bottom to top
+#[cfg(feature = "wayland-layer-shell")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WaylandLayer {
+    /// Below all other surfaces, like a wallpaper
+    Background,
+    /// Below normal windows, like desktop widgets
+    Bottom,
+    /// Above normal windows, like bars and docks
+    #[default]
+    Top,
+    /// Above everything, including fullscreen windows, like lock screens and notifications
+    Overlay,
+}
+
+/// The edges of the output a layer shell surface is anchored to
+#[cfg(feature = "wayland-layer-shell")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WaylandAnchor {
+    /// Anchor the surface to the top edge of the output
+    pub top: bool,
+    /// Anchor the surface to the bottom edge of the output
+    pub bottom: bool,
+    /// Anchor the surface to the left edge of the output
+    pub left: bool,
+    /// Anchor the surface to the right edge of the output
+    pub right: bool,
+}
+
+/// Turn a window into a layer shell surface to build bars, docks and launchers
+///
+/// ```rust, ignore
+/// // A bar at the top of the screen that other windows don't cover
+/// let bar = WaylandLayerShell::new(WaylandLayer::Top)
+///     .with_anchor(WaylandAnchor { top: true, left: true, right: true, bottom: false })
+///     .with_exclusive_zone(true);
+/// ```
+///
+/// > Note: This requires a compositor that implements `wlr-layer-shell`, like Sway or Hyprland.
+#[cfg(feature = "wayland-layer-shell")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WaylandLayerShell {
+    pub(crate) layer: WaylandLayer,
+    pub(crate) anchor: WaylandAnchor,
+    pub(crate) exclusive_zone: bool,
+    pub(crate) keyboard_interactivity: bool,
+    pub(crate) namespace: Option<String>,
+}
+
+#[cfg(feature = "wayland-layer-shell")]
+impl WaylandLayerShell {
+    /// Place the window in the given layer, without anchoring it to any edge
+    pub fn new(layer: WaylandLayer) -> Self {
+        Self {
+            layer,
+            anchor: WaylandAnchor::default(),
+            exclusive_zone: false,
+            keyboard_interactivity: false,
+            namespace: None,
+        }
+    }
+
+    /// Set the edges of the output the window is anchored to. Anchoring to opposite edges stretches the window.
+    pub fn with_anchor(mut self, anchor: WaylandAnchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// Set whether the compositor keeps other windows from covering this one, like it does for a bar.
+    ///
+    /// > Note: `exclusive` is `false` by default.
+    pub fn with_exclusive_zone(mut self, exclusive: bool) -> Self {
+        self.exclusive_zone = exclusive;
+        self
+    }
+
+    /// Set whether the window can receive keyboard focus, which launchers need for their search field.
+    ///
+    /// > Note: `enable` is `false` by default.
+    pub fn with_keyboard_interactivity(mut self, enable: bool) -> Self {
+        self.keyboard_interactivity = enable;
+        self
+    }
+
+    /// Set the namespace compositors use to identify the surface in their rules
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+}
+
+/// Settings for running under Wayland compositors
+///
+/// ```rust, ignore
+/// let wayland = WaylandConfig::new()
+///     .with_app_id("com.example.MyApp")
+///     .with_decorations(WaylandDecorations::ClientSide);
+///
+/// Config::new().with_wayland_config(wayland)
+/// ```
+///
+/// > Note: Decorations and layer shell surfaces need the `wayland-decorations` and `wayland-layer-shell` features.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WaylandConfig {
+    pub(crate) app_id: Option<String>,
+    #[cfg(feature = "wayland-decorations")]
+    pub(crate) decorations: WaylandDecorations,
+    #[cfg(feature = "wayland-layer-shell")]
+    pub(crate) layer_shell: Option<WaylandLayerShell>,
+}
+
+impl WaylandConfig {
+    /// Create the default Wayland settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the app id compositors use to match windows with their `.desktop` file, for the icon in the taskbar and
+    /// for window rules.
+    ///
+    /// > Note: The app id is shared by all windows, so it only takes effect on the config the app is launched with.
+    pub fn with_app_id(mut self, app_id: impl Into<String>) -> Self {
+        self.app_id = Some(app_id.into());
+        self
+    }
+
+    /// Set who draws the titlebar and borders of the window. Windows on X11 keep the decorations of the window
+    /// manager.
+    #[cfg(feature = "wayland-decorations")]
+    pub fn with_decorations(mut self, decorations: WaylandDecorations) -> Self {
+        self.decorations = decorations;
+        self
+    }
+
+    /// Turn the window into a layer shell surface instead of a normal window.
+    #[cfg(feature = "wayland-layer-shell")]
+    pub fn with_layer_shell(mut self, layer_shell: WaylandLayerShell) -> Self {
+        self.layer_shell = Some(layer_shell);
+        self
+    }
+
+    /// Whether the window has to stay hidden until [`WaylandConfig::apply_to_window`] ran. Layer shell surfaces have
+    /// to be set up before the window is shown for the first time.
+    pub(crate) fn needs_hidden_window(&self) -> bool {
+        #[cfg(feature = "wayland-layer-shell")]
+        return self.layer_shell.is_some();
+
+        #[cfg(not(feature = "wayland-layer-shell"))]
+        false
+    }
+
+    /// Apply the settings that have to be known before the event loop is created
+    #[allow(unused_variables)]
+    pub(crate) fn apply_to_event_loop<T>(
+        &self,
+        builder: &mut tao::event_loop::EventLoopBuilder<T>,
+    ) {
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        if let Some(app_id) = &self.app_id {
+            use tao::platform::unix::EventLoopBuilderExtUnix;
+            builder.with_app_id(app_id);
+        }
+    }
+
+    /// Apply the settings to a window once it is built, if it runs under a Wayland compositor
+    #[allow(unused_variables)]
+    pub(crate) fn apply_to_window(&self, window: &Window) {
+        #[cfg(all(
+            any(feature = "wayland-decorations", feature = "wayland-layer-shell"),
+            any(
+                target_os = "linux",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd"
+            )
+        ))]
+        {
+            use gtk::prelude::*;
+            use tao::platform::unix::WindowExtUnix;
+
+            let gtk_window = window.gtk_window();
+
+            // GTK can run on X11 even if the session uses Wayland, so ask the display the window is on
+            if gtk_window.display().type_().name() != "GdkWaylandDisplay" {
+                return;
+            }
+
+            #[cfg(feature = "wayland-decorations")]
+            match self.decorations {
+                WaylandDecorations::Auto => {}
+                // GTK always draws the decorations itself when the window has a header bar
+                WaylandDecorations::ClientSide => {
+                    let header_bar = gtk::HeaderBar::new();
+                    header_bar.set_show_close_button(true);
+                    header_bar.set_title(Some(&window.title()));
+                    header_bar.show();
+                    gtk_window.set_titlebar(Some(&header_bar));
+                }
+                WaylandDecorations::None => gtk_window.set_decorated(false),
+            }
+
+            #[cfg(feature = "wayland-layer-shell")]
+            if let Some(layer_shell) = &self.layer_shell {
+                use gtk_layer_shell::{Edge, KeyboardMode, Layer, LayerShell};
+
+                gtk_window.init_layer_shell();
+                gtk_window.set_layer(match layer_shell.layer {
+                    WaylandLayer::Background => Layer::Background,
+                    WaylandLayer::Bottom => Layer::Bottom,
+                    WaylandLayer::Top => Layer::Top,
+                    WaylandLayer::Overlay => Layer::Overlay,
+                });
+
+                let anchor = layer_shell.anchor;
+                gtk_window.set_anchor(Edge::Top, anchor.top);
+                gtk_window.set_anchor(Edge::Bottom, anchor.bottom);
+                gtk_window.set_anchor(Edge::Left, anchor.left);
+                gtk_window.set_anchor(Edge::Right, anchor.right);
+
+                if layer_shell.exclusive_zone {
+                    gtk_window.auto_exclusive_zone_enable();
+                }
+
+                gtk_window.set_keyboard_mode(if layer_shell.keyboard_interactivity {
+                    KeyboardMode::OnDemand
+                } else {
+                    KeyboardMode::None
+                });
+
+                if let Some(namespace) = &layer_shell.namespace {
+                    gtk_window.set_namespace(namespace);
+                }
+            }
+        }
+    }
+}
@@ -1,12 +1,35 @@
 use crate::{
-    app::SharedContext, assets::AssetHandlerRegistry, edits::EditQueue, eval::DesktopEvalProvider,
-    file_upload::NativeFileHover, ipc::UserWindowEvent, protocol, waker::tao_waker, Config,
-    DesktopContext, DesktopService,
+    app::SharedContext,
+    assets::AssetHandlerRegistry,
+    binary_ipc,
+    compression::Compression,
+    context_menu::CONTEXT_MENU_SCRIPT,
+    download::{DownloadAction, DownloadEvent, DownloadRequest},
+    drag_image::drag_image_script,
+    drag_region::drag_region_script,
+    edits::EditQueue,
+    eval::DesktopEvalProvider,
+    file_upload::{NativeFileHover, FILE_DIALOG_SCRIPT},
+    find::FIND_SCRIPT,
+    ipc::UserWindowEvent,
+    ipc_handlers::INVOKE_SCRIPT,
+    ipc_tracing::{self, IpcTimer},
+    navigation::NavigationPolicy,
+    page_load::{attach_load_failed_handler, page_load_handler},
+    permissions::attach_permission_handler,
+    protocol::{self, IpcOptions},
+    security::{self, apply_content_security_policy, CurrentPage, IpcOrigins},
+    snapshot::{make_invisible, offscreen_window},
+    waker::tao_waker,
+    window_event::OCCLUSION_SCRIPT,
+    window_shortcut::WINDOW_SHORTCUT_SCRIPT,
+    Config, DesktopContext, DesktopService, WindowKind,
 };
 use dioxus_core::{ScopeId, VirtualDom};
 use dioxus_html::prelude::EvalProvider;
 use futures_util::{pin_mut, FutureExt};
-use std::{any::Any, rc::Rc, task::Waker};
+use std::{rc::Rc, task::Waker};
+use tao::window::WindowId;
 use wry::{RequestAsyncResponder, WebContext, WebViewBuilder};
 
 pub(crate) struct WebviewInstance {
@@ -14,16 +37,16 @@ pub(crate) struct WebviewInstance {
     pub desktop_context: DesktopContext,
     pub waker: Waker,
 
+    /// The window that created this one, if it was created as a child or modal window
+    pub parent: Option<WindowId>,
+    pub kind: WindowKind,
+
+    /// Decides where the page may navigate, shared with the webview's navigation handlers
+    pub navigation: Rc<NavigationPolicy>,
+
     // Wry assumes the webcontext is alive for the lifetime of the webview.
     // We need to keep the webcontext alive, otherwise the webview will crash
     _web_context: WebContext,
-
-    // Same with the menu.
-    // Currently it's a box<dyn any> because 1) we don't touch it and 2) we support a number of platforms
-    // like ios where muda does not give us a menu type. It sucks but alas.
-    //
-    // This would be a good thing for someone looking to contribute to fix.
-    _menu: Option<Box<dyn Any>>,
 }
 
 impl WebviewInstance {
@@ -31,6 +54,7 @@ impl WebviewInstance {
         mut cfg: Config,
         dom: VirtualDom,
         shared: Rc<SharedContext>,
+        parent: Option<WindowId>,
     ) -> WebviewInstance {
         let mut window = cfg.window.clone();
 
@@ -51,10 +75,37 @@ impl WebviewInstance {
             ));
         }
 
+        if cfg.offscreen {
+            window = offscreen_window(window);
+        }
+
+        // Layer shell surfaces have to be set up before the window is shown
+        let show_after_setup = cfg.wayland.needs_hidden_window() && cfg.window.window.visible;
+        if show_after_setup {
+            window = window.with_visible(false);
+        }
+
         let window = window.build(&shared.target).unwrap();
+        cfg.wayland.apply_to_window(&window);
+        if cfg.offscreen {
+            make_invisible(&window);
+            window.set_visible(cfg.window.window.visible);
+        }
+        if show_after_setup {
+            window.set_visible(true);
+        }
 
-        let mut web_context = WebContext::new(cfg.data_dir.clone());
-        let edit_queue = EditQueue::default();
+        // WebView2 keeps its profile in the web context's data directory
+        let data_dir = match &cfg.webview2.user_data_folder {
+            Some(folder) if cfg!(target_os = "windows") => Some(folder.clone()),
+            _ => cfg.data_dir.clone(),
+        };
+        let mut web_context = WebContext::new(data_dir);
+        let compression = match cfg.ipc_compression {
+            true => Compression::Uncompressed,
+            false => Compression::Off,
+        };
+        let edit_queue = EditQueue::new(tao_waker(shared.proxy.clone(), window.id()), compression);
         let file_hover = NativeFileHover::default();
         let asset_handlers = AssetHandlerRegistry::new(dom.runtime());
         let headless = !cfg.window.window.visible;
@@ -62,46 +113,133 @@ impl WebviewInstance {
         // Rust :(
         let window_id = window.id();
         let custom_head = cfg.custom_head.clone();
+        let content_security_policy = cfg.content_security_policy.clone();
         let index_file = cfg.custom_index.clone();
         let root_name = cfg.root_name.clone();
+        let embedded_assets = cfg.embedded_assets;
         let asset_handlers_ = asset_handlers.clone();
         let edit_queue_ = edit_queue.clone();
         let proxy_ = shared.proxy.clone();
         let file_hover_ = file_hover.clone();
 
+        let navigation = Rc::new(NavigationPolicy::new(
+            cfg.navigation_handler.take(),
+            cfg.new_window_policy,
+            shared.proxy.clone(),
+        ));
+
+        let ipc_options = IpcOptions {
+            binary: cfg.binary_ipc,
+            fallback: cfg.ipc_fallback,
+            compression: cfg.ipc_compression,
+            event_rate: cfg.event_rate,
+        };
+        let ipc_proxy = shared.proxy.clone();
+
+        // Messages are checked as they arrive, not once the page may have navigated somewhere else
+        let ipc_origins = IpcOrigins::new(cfg.ipc_origins.clone());
+        let current_page = CurrentPage::default();
+        let request_origins = ipc_origins.clone();
+        let request_page = current_page.clone();
+
         let request_handler = move |request, responder: RequestAsyncResponder| {
+            // Messages sent over the binary IPC channel are handled like the ones from `window.ipc`
+            if binary_ipc::is_ipc_request(&request) {
+                let messages = match request_origins.allows_request(&request, &request_page) {
+                    true => binary_ipc::decode_request(&request, ipc_options),
+                    false => {
+                        tracing::warn!(
+                            "Ignored an IPC request from a page that may not send messages"
+                        );
+                        Vec::new()
+                    }
+                };
+                for msg in messages {
+                    _ = ipc_proxy.send_event(UserWindowEvent::Ipc { id: window_id, msg });
+                }
+                return responder.respond(
+                    wry::http::Response::builder()
+                        .status(204)
+                        .body(Vec::new())
+                        .unwrap(),
+                );
+            }
+
             // Try to serve the index file first
+            let nonce = content_security_policy.as_ref().map(|_| security::nonce());
             let index_bytes = protocol::index_request(
                 &request,
                 custom_head.clone(),
                 index_file.clone(),
                 &root_name,
                 headless,
+                ipc_options,
+                nonce.as_deref(),
+                embedded_assets,
             );
 
             // Otherwise, try to serve an asset, either from the user or the filesystem
             match index_bytes {
-                Some(body) => responder.respond(body),
+                Some(body) => responder.respond(match (&content_security_policy, &nonce) {
+                    (Some(policy), Some(nonce)) => {
+                        apply_content_security_policy(body, policy, nonce)
+                    }
+                    _ => body,
+                }),
                 None => protocol::desktop_handler(
                     request,
                     asset_handlers_.clone(),
                     &edit_queue_,
+                    embedded_assets,
                     responder,
                 ),
             }
         };
 
+        let message_page = current_page.clone();
         let ipc_handler = move |payload: String| {
+            if !ipc_origins.allows_message(&message_page) {
+                tracing::warn!(
+                    "Ignored an IPC message from a page that isn't allowed to send messages"
+                );
+                return;
+            }
+
             // defer the event to the main thread
-            if let Ok(msg) = serde_json::from_str(&payload) {
-                _ = proxy_.send_event(UserWindowEvent::Ipc { id: window_id, msg });
+            let timer = IpcTimer::start();
+            match serde_json::from_str(&payload) {
+                Ok(msg) => {
+                    ipc_tracing::message_received(&msg, payload.len(), timer);
+                    _ = proxy_.send_event(UserWindowEvent::Ipc { id: window_id, msg });
+                }
+                Err(err) => ipc_tracing::message_rejected(payload.len(), &err),
             }
         };
 
+        let file_drop_config = cfg.file_drop.clone();
+        let proxy_ = shared.proxy.clone();
         let file_drop_handler = move |evt| {
-            // Update the most recent file drop event - when the event comes in from the webview we can use the
-            // most recent event to build a new event with the files in it.
-            file_hover_.set(evt);
+            match evt {
+                // Listing directories can take a while, so it happens off the event loop
+                wry::FileDropEvent::Hovered { .. } | wry::FileDropEvent::Dropped { .. }
+                    if file_drop_config.lists_directories() =>
+                {
+                    file_hover_.start_listing();
+                    let config = file_drop_config.clone();
+                    let proxy = proxy_.clone();
+                    std::thread::spawn(move || {
+                        let event = config.process_event(evt);
+                        _ = proxy.send_event(UserWindowEvent::FileDrop {
+                            id: window_id,
+                            event,
+                        });
+                    });
+                }
+
+                // Update the most recent file drop event - when the event comes in from the webview we can use the
+                // most recent event to build a new event with the files in it.
+                evt => file_hover_.set(file_drop_config.process_event(evt)),
+            }
             false
         };
 
@@ -130,10 +268,78 @@ impl WebviewInstance {
             .with_transparent(cfg.window.window.transparent)
             .with_url("dioxus://index.html/")
             .with_ipc_handler(ipc_handler)
-            .with_navigation_handler(|var| var.contains("dioxus")) // prevent all navigations
+            .with_navigation_handler({
+                let navigation = navigation.clone();
+                move |url| navigation.navigate(url, false)
+            })
             .with_asynchronous_custom_protocol(String::from("dioxus"), request_handler)
             .with_web_context(&mut web_context)
-            .with_file_drop_handler(file_drop_handler);
+            .with_hotkeys_zoom(cfg.hotkeys_zoom)
+            .with_file_drop_handler(file_drop_handler)
+            .with_on_page_load_handler(page_load_handler(shared.proxy.clone(), window_id))
+            .with_new_window_req_handler({
+                let navigation = navigation.clone();
+                move |url| navigation.navigate(url, true)
+            });
+
+        if let Some(user_agent) = &cfg.user_agent {
+            webview = webview.with_user_agent(user_agent);
+        }
+
+        if let Some(proxy) = cfg.proxy.take() {
+            webview = webview.with_proxy_config(proxy);
+        }
+
+        webview = cfg.webview2.apply(webview);
+        webview = cfg.wkwebview.apply(webview);
+
+        // The WKWebView settings can only make a window more ephemeral, not less
+        if cfg.ephemeral {
+            webview = webview.with_incognito(true);
+        }
+
+        // Let the user decide where downloads go, and tell the window's components about them
+        let mut download_handler = cfg.download_handler.take();
+        let proxy_ = shared.proxy.clone();
+        webview = webview.with_download_started_handler(move |url, path| {
+            let action = match &mut download_handler {
+                Some(handler) => handler(DownloadRequest {
+                    url: url.clone(),
+                    suggested_path: path.clone(),
+                }),
+                None => DownloadAction::Allow,
+            };
+
+            let event = match action {
+                DownloadAction::Allow => DownloadEvent::Started {
+                    url,
+                    path: path.clone(),
+                },
+                DownloadAction::SaveTo(destination) => {
+                    *path = destination;
+                    DownloadEvent::Started {
+                        url,
+                        path: path.clone(),
+                    }
+                }
+                DownloadAction::Cancel => DownloadEvent::Cancelled { url },
+            };
+
+            let allowed = !matches!(event, DownloadEvent::Cancelled { .. });
+            _ = proxy_.send_event(UserWindowEvent::Download {
+                id: window_id,
+                event,
+            });
+            allowed
+        });
+
+        let proxy_ = shared.proxy.clone();
+        webview = webview.with_download_completed_handler(move |url, path, success| {
+            _ = proxy_.send_event(UserWindowEvent::Download {
+                id: window_id,
+                event: DownloadEvent::Completed { url, path, success },
+            });
+        });
 
         if let Some(color) = cfg.background_color {
             webview = webview.with_background_color(color);
@@ -155,6 +361,43 @@ impl WebviewInstance {
         }
         "#;
 
+        // Only undecorated windows need us to draw a resize border
+        let attributes = &cfg.window.window;
+        let resize_border = if attributes.decorations || !attributes.resizable {
+            0
+        } else {
+            cfg.resize_border
+        };
+        // The fallback transport has to provide `window.ipc` before any other script uses it
+        if cfg.ipc_fallback {
+            webview = webview.with_initialization_script(&binary_ipc::fallback_ipc_script());
+        }
+        webview = webview
+            .with_initialization_script(&drag_region_script(resize_border))
+            .with_initialization_script(&drag_image_script())
+            .with_initialization_script(FILE_DIALOG_SCRIPT)
+            .with_initialization_script(WINDOW_SHORTCUT_SCRIPT)
+            .with_initialization_script(OCCLUSION_SCRIPT)
+            .with_initialization_script(INVOKE_SCRIPT)
+            .with_initialization_script(FIND_SCRIPT)
+            .with_initialization_script(CONTEXT_MENU_SCRIPT);
+
+        #[cfg(target_os = "android")]
+        {
+            webview = webview.with_initialization_script(crate::back_button::BACK_BUTTON_SCRIPT);
+        }
+
+        #[cfg(any(target_os = "ios", target_os = "android"))]
+        {
+            webview = webview
+                .with_initialization_script(crate::virtual_keyboard::VIRTUAL_KEYBOARD_SCRIPT);
+        }
+
+        // The app's own scripts run last, so they can build on everything above
+        for script in &cfg.initialization_scripts {
+            webview = webview.with_initialization_script(script);
+        }
+
         if cfg.disable_context_menu {
             // in release mode, we don't want to show the dev tool or reload menus
             webview = webview.with_initialization_script(INITIALIZATION_SCRIPT)
@@ -163,7 +406,18 @@ impl WebviewInstance {
             webview = webview.with_devtools(true);
         }
 
+        let open_devtools = cfg.open_devtools && cfg!(any(debug_assertions, feature = "devtools"));
+        if open_devtools {
+            webview = webview.with_devtools(true);
+        }
+
         let webview = webview.build().unwrap();
+        cfg.wkwebview.apply_to_webview(&webview);
+        cfg.webkitgtk.apply_to_webview(&webview);
+        if let Some(handler) = cfg.permission_handler.take() {
+            attach_permission_handler(&webview, handler);
+        }
+        attach_load_failed_handler(&webview, shared.proxy.clone(), window_id);
 
         // TODO: allow users to specify their own menubars, again :/
         let menu = if cfg!(not(any(target_os = "android", target_os = "ios"))) {
@@ -179,7 +433,22 @@ impl WebviewInstance {
             edit_queue,
             asset_handlers,
             file_hover,
+            menu,
         ));
+        current_page.set(&desktop_context);
+
+        desktop_context
+            .always_on_top
+            .set(cfg.window.window.always_on_top);
+        desktop_context.background_color.set(cfg.background_color);
+
+        if cfg.kiosk {
+            desktop_context.set_kiosk_mode(true);
+        }
+
+        if open_devtools {
+            desktop_context.open_devtools();
+        }
 
         let provider: Rc<dyn EvalProvider> =
             Rc::new(DesktopEvalProvider::new(desktop_context.clone()));
@@ -193,7 +462,9 @@ impl WebviewInstance {
             waker: tao_waker(shared.proxy.clone(), desktop_context.window.id()),
             desktop_context,
             dom,
-            _menu: menu,
+            parent,
+            kind: cfg.window_kind,
+            navigation,
             _web_context: web_context,
         }
     }
@@ -229,4 +500,122 @@ impl WebviewInstance {
             .webview
             .evaluate_script("document.querySelectorAll('link[rel=\"stylesheet\"]').forEach((el) => el.href = el.href + \"?\" + Math.random());");
     }
+
+    /// Start the app in this window over, for changes hot reloading can't patch into the running templates
+    #[cfg(all(
+        feature = "hot-reload",
+        debug_assertions,
+        not(target_os = "android"),
+        not(target_os = "ios")
+    ))]
+    pub fn full_reload(&mut self) {
+        self.dom.restart();
+
+        // The page forgets every template and element, and asks for the edits of the new VirtualDom once it loaded
+        *self.desktop_context.mutation_state.borrow_mut() = Default::default();
+        _ = self
+            .desktop_context
+            .webview
+            .evaluate_script("window.location.reload();");
+    }
+
+    /// Show the errors of a failed build over the app, or remove the overlay once a build succeeded
+    #[cfg(all(
+        feature = "hot-reload",
+        debug_assertions,
+        not(target_os = "android"),
+        not(target_os = "ios")
+    ))]
+    pub fn show_build_status(&self, errors: &[String], warnings: &[String]) {
+        let errors = serde_json::to_string(errors).unwrap_or_default();
+        let warnings = serde_json::to_string(warnings).unwrap_or_default();
+
+        _ = self.desktop_context.webview.evaluate_script(&format!(
+            "({})({errors}, {warnings});",
+            dioxus_interpreter_js::BUILD_STATUS_JS
+        ));
+    }
+
+    /// Show that the app is being rebuilt, until the build status replaces it or the rebuilt app replaces this one
+    #[cfg(all(
+        feature = "hot-reload",
+        debug_assertions,
+        not(target_os = "android"),
+        not(target_os = "ios")
+    ))]
+    pub fn show_rebuilding(&self) {
+        _ = self.desktop_context.webview.evaluate_script(
+            r##"(() => {
+                document.getElementById("dioxus-build-status")?.remove();
+
+                const overlay = document.createElement("div");
+                overlay.id = "dioxus-build-status";
+                overlay.textContent = "Rebuilding…";
+                overlay.style.cssText = "position: fixed; top: 12px; right: 12px; z-index: 2147483647; padding: 8px 16px; border-radius: 6px; background: rgba(24, 24, 27, 0.9); color: #f4f4f5; font-family: monospace;";
+
+                document.body.appendChild(overlay);
+            })();"##,
+        );
+    }
+
+    /// Reload just the elements that load the changed asset, instead of every stylesheet
+    #[cfg(all(
+        feature = "hot-reload",
+        debug_assertions,
+        not(target_os = "android"),
+        not(target_os = "ios")
+    ))]
+    pub fn update_asset(
+        &self,
+        asset: &dioxus_hot_reload::AssetUpdate,
+        resolved: Option<&dioxus_hot_reload::ResolvedAsset>,
+    ) {
+        use dioxus_hot_reload::AssetUpdate;
+
+        let (selector, attribute) = match asset {
+            AssetUpdate::Stylesheet(_) => ("link[rel=\"stylesheet\"]", "href"),
+            AssetUpdate::Image(_) => ("img", "src"),
+            AssetUpdate::Script(path) => {
+                tracing::info!(
+                    "{} changed, scripts can't be hot reloaded - restart the app to load it",
+                    path.display()
+                );
+                return;
+            }
+            // Fonts and anything else may be loaded from any stylesheet
+            AssetUpdate::Font(_) | AssetUpdate::Other(_) => return self.kick_stylsheets(),
+        };
+
+        let Some(name) = asset.served_name(resolved) else {
+            return self.kick_stylsheets();
+        };
+        let name = serde_json::to_string(name).unwrap_or_default();
+        // Assets resolved by the asset! macro are busted with the hash of their new contents
+        let version = match resolved {
+            Some(resolved) => serde_json::to_string(&resolved.hash).unwrap_or_default(),
+            None => "Date.now()".to_string(),
+        };
+
+        // Bust the cache of the matching urls. Stylesheets may be @imported by another one, so if no link loads the
+        // stylesheet directly, kick all of them.
+        let script = format!(
+            r#"(() => {{
+                let found = false;
+                document.querySelectorAll('{selector}').forEach((el) => {{
+                    const url = new URL(el.getAttribute("{attribute}"), location.href);
+                    if (decodeURIComponent(url.pathname).endsWith({name})) {{
+                        url.searchParams.set("dx-reload", {version});
+                        el.setAttribute("{attribute}", url.href);
+                        found = true;
+                    }}
+                }});
+                if (!found && {fallback}) {{
+                    document.querySelectorAll('link[rel="stylesheet"]').forEach((el) => el.href = el.href + "?" + Math.random());
+                }}
+            }})();"#,
+            fallback = matches!(asset, AssetUpdate::Stylesheet(_)),
+        );
+
+        _ = self.desktop_context.webview.evaluate_script(&script);
+    }
 }
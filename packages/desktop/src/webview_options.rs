@@ -0,0 +1,335 @@
+//! Settings for the native webview engine of each platform. These are ignored on the other platforms, so they can
+//! all be set unconditionally.
+
+use std::path::PathBuf;
+use wry::{WebView, WebViewBuilder};
+
+/// The arguments wry passes to the WebView2 browser process unless it is given others
+#[allow(unused)]
+const WRY_BROWSER_ARGS: &str = "--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection";
+
+/// Settings for the WebView2 engine used on Windows
+///
+/// ```rust, ignore
+/// let webview2 = WebView2Config::new()
+///     .with_user_data_folder(dirs::data_local_dir().unwrap().join("my-app"))
+///     .with_browser_arg("--lang=de")
+///     .with_gpu(false);
+///
+/// Config::new().with_webview2_config(webview2)
+/// ```
+///
+/// To use a fixed version of the WebView2 runtime instead of the evergreen runtime installed on the system, set the
+/// `WEBVIEW2_BROWSER_EXECUTABLE_FOLDER` environment variable to its folder before launching the app.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WebView2Config {
+    pub(crate) user_data_folder: Option<PathBuf>,
+    pub(crate) browser_args: Vec<String>,
+    pub(crate) disable_gpu: bool,
+    pub(crate) disable_browser_accelerator_keys: bool,
+}
+
+impl WebView2Config {
+    /// Create the default WebView2 settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the folder WebView2 stores its profile (cookies, cache, local storage, ...) in.
+    ///
+    /// > Note: This takes precedence over [`Config::with_data_directory`](crate::Config::with_data_directory) on
+    /// > Windows. By default the profile is stored next to the executable, which fails if the app is installed in a
+    /// > read only location.
+    pub fn with_user_data_folder(mut self, path: impl Into<PathBuf>) -> Self {
+        self.user_data_folder = Some(path.into());
+        self
+    }
+
+    /// Pass an additional command line argument to the browser process, like `--lang=de` or
+    /// `--proxy-server=http://proxy:8080`
+    pub fn with_browser_arg(mut self, arg: impl Into<String>) -> Self {
+        self.browser_args.push(arg.into());
+        self
+    }
+
+    /// Set whether the browser may use the GPU. Disabling it works around broken graphics drivers and remote desktop
+    /// sessions at the cost of performance.
+    ///
+    /// > Note: `enable` is `true` by default.
+    pub fn with_gpu(mut self, enable: bool) -> Self {
+        self.disable_gpu = !enable;
+        self
+    }
+
+    /// Set whether the browser handles its own keyboard shortcuts, like `Ctrl+F` for find and `Ctrl+P` for print.
+    ///
+    /// > Note: `enable` is `true` by default.
+    pub fn with_browser_accelerator_keys(mut self, enable: bool) -> Self {
+        self.disable_browser_accelerator_keys = !enable;
+        self
+    }
+
+    /// The command line arguments passed to the browser process, if any were added to wry's defaults
+    #[allow(unused)]
+    fn browser_args(&self) -> Option<String> {
+        let gpu = self.disable_gpu.then_some("--disable-gpu");
+        let added: Vec<&str> = gpu
+            .into_iter()
+            .chain(self.browser_args.iter().map(String::as_str))
+            .collect();
+        if added.is_empty() {
+            return None;
+        }
+
+        // Additional arguments replace the ones wry passes by default, so they have to be passed again
+        let mut args = vec![WRY_BROWSER_ARGS];
+        args.extend(added);
+        Some(args.join(" "))
+    }
+
+    #[allow(unused_mut)]
+    pub(crate) fn apply<'a>(&self, mut webview: WebViewBuilder<'a>) -> WebViewBuilder<'a> {
+        #[cfg(target_os = "windows")]
+        {
+            use wry::WebViewBuilderExtWindows;
+
+            if let Some(args) = self.browser_args() {
+                webview = webview.with_additional_browser_args(&args);
+            }
+            webview = webview.with_browser_accelerator_keys(!self.disable_browser_accelerator_keys);
+        }
+
+        webview
+    }
+}
+
+/// Settings for the WKWebView engine used on macOS and iOS
+///
+/// ```rust, ignore
+/// let wkwebview = WKWebViewConfig::new()
+///     .with_inspectable(true)
+///     .with_link_previews(false)
+///     .with_ephemeral_data_store(true);
+///
+/// Config::new().with_wkwebview_config(wkwebview)
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WKWebViewConfig {
+    pub(crate) inspectable: Option<bool>,
+    pub(crate) link_previews: bool,
+    pub(crate) autoplay: bool,
+    pub(crate) ephemeral: bool,
+    pub(crate) back_forward_gestures: bool,
+}
+
+impl Default for WKWebViewConfig {
+    fn default() -> Self {
+        Self {
+            inspectable: None,
+            link_previews: true,
+            autoplay: true,
+            ephemeral: false,
+            back_forward_gestures: false,
+        }
+    }
+}
+
+impl WKWebViewConfig {
+    /// Create the default WKWebView settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether Safari's web inspector can attach to the webview. Requires macOS 13.3 or iOS 16.4.
+    ///
+    /// > Note: By default the webview is inspectable whenever the devtools are enabled, which is the case in debug
+    /// > builds.
+    pub fn with_inspectable(mut self, inspectable: bool) -> Self {
+        self.inspectable = Some(inspectable);
+        self
+    }
+
+    /// Set whether force touching or long pressing a link shows a preview of the page it points to.
+    ///
+    /// > Note: `enable` is `true` by default.
+    pub fn with_link_previews(mut self, enable: bool) -> Self {
+        self.link_previews = enable;
+        self
+    }
+
+    /// Set whether audio and video may start playing without the user interacting with the page first.
+    ///
+    /// > Note: `enable` is `true` by default.
+    pub fn with_autoplay(mut self, enable: bool) -> Self {
+        self.autoplay = enable;
+        self
+    }
+
+    /// Set whether the webview uses a non persistent data store. Cookies, caches and local storage are discarded when
+    /// the window closes.
+    ///
+    /// > Note: `ephemeral` is `false` by default, which uses the app's persistent default data store.
+    pub fn with_ephemeral_data_store(mut self, ephemeral: bool) -> Self {
+        self.ephemeral = ephemeral;
+        self
+    }
+
+    /// Set whether horizontal swipe gestures navigate back and forward through the webview's history.
+    ///
+    /// > Note: `enable` is `false` by default.
+    pub fn with_back_forward_gestures(mut self, enable: bool) -> Self {
+        self.back_forward_gestures = enable;
+        self
+    }
+
+    /// Apply the settings that have to be known before the webview is created
+    #[allow(unused_mut)]
+    pub(crate) fn apply<'a>(&self, mut webview: WebViewBuilder<'a>) -> WebViewBuilder<'a> {
+        #[cfg(any(target_os = "macos", target_os = "ios"))]
+        {
+            webview = webview
+                .with_autoplay(self.autoplay)
+                .with_incognito(self.ephemeral)
+                .with_back_forward_navigation_gestures(self.back_forward_gestures);
+        }
+
+        webview
+    }
+
+    /// Apply the settings that wry doesn't expose directly to the built webview
+    #[allow(unused)]
+    pub(crate) fn apply_to_webview(&self, webview: &WebView) {
+        #[cfg(any(target_os = "macos", target_os = "ios"))]
+        unsafe {
+            use objc::runtime::{Object, BOOL, NO, YES};
+            use objc::*;
+            #[cfg(target_os = "ios")]
+            use wry::WebViewExtIOS;
+            #[cfg(target_os = "macos")]
+            use wry::WebViewExtMacOS;
+
+            let wkwebview = webview.webview() as *mut Object;
+            let flag = |enable: bool| if enable { YES } else { NO };
+
+            let _: () = msg_send![wkwebview, setAllowsLinkPreview: flag(self.link_previews)];
+
+            if let Some(inspectable) = self.inspectable {
+                // isInspectable only exists on macOS 13.3 / iOS 16.4 and later
+                let supported: BOOL =
+                    msg_send![wkwebview, respondsToSelector: sel!(setInspectable:)];
+                if supported == YES {
+                    let _: () = msg_send![wkwebview, setInspectable: flag(inspectable)];
+                } else {
+                    tracing::debug!(
+                        "This version of WKWebView can't change whether it is inspectable"
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// When WebKitGTK renders pages with the GPU
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HardwareAcceleration {
+    /// Let WebKitGTK decide based on the contents of the page
+    OnDemand,
+    /// Always render with the GPU
+    Always,
+    /// Never render with the GPU. This works around blank or flickering windows with some graphics drivers.
+    Never,
+}
+
+/// Settings for the WebKitGTK engine used on Linux
+///
+/// ```rust, ignore
+/// let webkitgtk = WebKitGtkConfig::new()
+///     .with_hardware_acceleration(HardwareAcceleration::Never)
+///     .with_default_charset("iso-8859-1");
+///
+/// Config::new().with_webkitgtk_config(webkitgtk)
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WebKitGtkConfig {
+    pub(crate) hardware_acceleration: Option<HardwareAcceleration>,
+    pub(crate) dns_prefetching: Option<bool>,
+    pub(crate) developer_extras: Option<bool>,
+    pub(crate) default_charset: Option<String>,
+}
+
+impl WebKitGtkConfig {
+    /// Create the default WebKitGTK settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set when pages are rendered with the GPU
+    ///
+    /// > Note: By default the policy of the installed WebKitGTK version is kept.
+    pub fn with_hardware_acceleration(mut self, policy: HardwareAcceleration) -> Self {
+        self.hardware_acceleration = Some(policy);
+        self
+    }
+
+    /// Set whether WebKitGTK resolves the domains of links on the page before they are clicked
+    pub fn with_dns_prefetching(mut self, enable: bool) -> Self {
+        self.dns_prefetching = Some(enable);
+        self
+    }
+
+    /// Set whether the web inspector is available.
+    ///
+    /// > Note: By default the inspector is available whenever the devtools are enabled, which is the case in debug
+    /// > builds.
+    pub fn with_developer_extras(mut self, enable: bool) -> Self {
+        self.developer_extras = Some(enable);
+        self
+    }
+
+    /// Set the charset used for pages that don't declare one. WebKitGTK uses `iso-8859-1` by default.
+    pub fn with_default_charset(mut self, charset: impl Into<String>) -> Self {
+        self.default_charset = Some(charset.into());
+        self
+    }
+
+    /// Apply the settings to the built webview
+    #[allow(unused)]
+    pub(crate) fn apply_to_webview(&self, webview: &WebView) {
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "android"
+        )))]
+        {
+            use webkit2gtk::{SettingsExt, WebViewExt};
+            use wry::WebViewExtUnix;
+
+            let Some(settings) = WebViewExt::settings(&webview.webview()) else {
+                return;
+            };
+
+            if let Some(policy) = self.hardware_acceleration {
+                settings.set_hardware_acceleration_policy(match policy {
+                    HardwareAcceleration::OnDemand => {
+                        webkit2gtk::HardwareAccelerationPolicy::OnDemand
+                    }
+                    HardwareAcceleration::Always => webkit2gtk::HardwareAccelerationPolicy::Always,
+                    HardwareAcceleration::Never => webkit2gtk::HardwareAccelerationPolicy::Never,
+                });
+            }
+
+            if let Some(enable) = self.dns_prefetching {
+                settings.set_enable_dns_prefetching(enable);
+            }
+
+            if let Some(enable) = self.developer_extras {
+                settings.set_enable_developer_extras(enable);
+            }
+
+            if let Some(charset) = &self.default_charset {
+                settings.set_default_charset(charset);
+            }
+        }
+    }
+}
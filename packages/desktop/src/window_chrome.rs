@@ -0,0 +1,106 @@
+//! Platform specific tweaks to the window decorations that tao doesn't expose on [`Window`] directly.
+
+use tao::{dpi::Position, window::Icon, window::Window};
+
+/// Color the title bar of the window, or restore the system color with `None`
+///
+/// Supported on Windows 11 and macOS.
+#[allow(unused)]
+pub(crate) fn set_title_bar_color(window: &Window, color: Option<(u8, u8, u8)>) {
+    #[cfg(target_os = "windows")]
+    {
+        use tao::platform::windows::WindowExtWindows;
+        use windows_sys::Win32::Graphics::Dwm::{DwmSetWindowAttribute, DWMWA_CAPTION_COLOR};
+
+        // Tells DWM to go back to the color of the system theme
+        const DWMWA_COLOR_DEFAULT: u32 = 0xFFFFFFFF;
+
+        // DWM expects a COLORREF, which is laid out as 0x00BBGGRR
+        let color = match color {
+            Some((r, g, b)) => r as u32 | (g as u32) << 8 | (b as u32) << 16,
+            None => DWMWA_COLOR_DEFAULT,
+        };
+
+        let result = unsafe {
+            DwmSetWindowAttribute(
+                window.hwnd() as _,
+                DWMWA_CAPTION_COLOR as _,
+                &color as *const u32 as *const _,
+                std::mem::size_of::<u32>() as u32,
+            )
+        };
+
+        // Older versions of windows don't know about the caption color attribute
+        if result < 0 {
+            tracing::debug!("Failed to set the title bar color, it requires Windows 11");
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    unsafe {
+        use objc::runtime::{Object, NO, YES};
+        use objc::*;
+        use tao::platform::macos::WindowExtMacOS;
+
+        let ns_window = window.ns_window() as *mut Object;
+
+        match color {
+            Some((r, g, b)) => {
+                let (r, g, b) = (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0);
+                let color: *mut Object =
+                    msg_send![class!(NSColor), colorWithSRGBRed: r green: g blue: b alpha: 1.0f64];
+                let _: () = msg_send![ns_window, setTitlebarAppearsTransparent: YES];
+                let _: () = msg_send![ns_window, setBackgroundColor: color];
+            }
+            None => {
+                let color: *mut Object = msg_send![class!(NSColor), windowBackgroundColor];
+                let _: () = msg_send![ns_window, setTitlebarAppearsTransparent: NO];
+                let _: () = msg_send![ns_window, setBackgroundColor: color];
+            }
+        }
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    tracing::debug!("Setting the title bar color is not supported on this platform");
+}
+
+/// Move the close, minimize and zoom buttons of the window
+///
+/// Only supported on macOS.
+#[allow(unused)]
+pub(crate) fn set_traffic_light_position(window: &Window, position: Position) {
+    #[cfg(target_os = "macos")]
+    {
+        use tao::platform::macos::WindowExtMacOS;
+        window.set_traffic_light_inset(position);
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    tracing::debug!("Traffic lights only exist on macOS");
+}
+
+/// Show a small icon over the window's taskbar button, or remove it with `None`
+///
+/// Only supported on Windows.
+#[allow(unused)]
+pub(crate) fn set_overlay_icon(window: &Window, icon: Option<Icon>) {
+    #[cfg(target_os = "windows")]
+    {
+        use tao::platform::windows::WindowExtWindows;
+        window.set_overlay_icon(icon.as_ref());
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    tracing::debug!("Taskbar overlay icons are only supported on Windows");
+}
+
+/// Replace the icon of the window, including its taskbar icon on Windows
+pub(crate) fn set_icon(window: &Window, icon: Option<Icon>) {
+    #[cfg(target_os = "windows")]
+    {
+        use tao::platform::windows::WindowExtWindows;
+        window.set_taskbar_icon(icon.clone());
+    }
+
+    window.set_window_icon(icon);
+}
@@ -0,0 +1,102 @@
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+pub use window_vibrancy::NSVisualEffectMaterial;
+
+use tao::window::Window;
+
+/// A background effect that can be applied to a window after it has been created.
+///
+/// Effects only show through where both the window and the webview are transparent, so you probably want to
+/// create the window with `with_transparent(true)` and give your root element a transparent background.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WindowEffect {
+    /// A blurred background, optionally tinted with an RGBA color.
+    ///
+    /// Supported on Windows 7, 10 and 11.
+    Blur(Option<(u8, u8, u8, u8)>),
+
+    /// The acrylic material, optionally tinted with an RGBA color.
+    ///
+    /// Supported on Windows 10 and 11.
+    Acrylic(Option<(u8, u8, u8, u8)>),
+
+    /// The mica material. `dark` forces a light or dark variant, otherwise the system theme is used.
+    ///
+    /// Supported on Windows 11.
+    Mica {
+        /// Force the dark (`Some(true)`) or light (`Some(false)`) variant
+        dark: Option<bool>,
+    },
+
+    /// The native vibrancy effect with the given material.
+    ///
+    /// Supported on macOS 10.10 and later.
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    Vibrancy(NSVisualEffectMaterial),
+}
+
+/// Apply an effect to the window, clearing the previously applied one first.
+///
+/// Platforms that don't support the effect are logged and skipped rather than treated as an error.
+#[allow(unused)]
+pub(crate) fn apply_window_effect(
+    window: &Window,
+    previous: Option<WindowEffect>,
+    effect: Option<WindowEffect>,
+) {
+    #[cfg(not(any(target_os = "ios", target_os = "android")))]
+    {
+        if let Some(previous) = previous {
+            report(previous, impl_::clear(window, previous));
+        }
+
+        if let Some(effect) = effect {
+            report(effect, impl_::apply(window, effect));
+        }
+    }
+
+    #[cfg(any(target_os = "ios", target_os = "android"))]
+    if effect.is_some() {
+        tracing::debug!("Window effects are not supported on this platform");
+    }
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+fn report(effect: WindowEffect, result: Result<(), window_vibrancy::Error>) {
+    match result {
+        Ok(()) => {}
+        Err(window_vibrancy::Error::UnsupportedPlatform(_))
+        | Err(window_vibrancy::Error::UnsupportedPlatformVersion(_)) => {
+            tracing::debug!("{effect:?} is not supported on this platform, skipping it")
+        }
+        Err(err) => tracing::warn!("Failed to update window effect {effect:?}: {err}"),
+    }
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+mod impl_ {
+    use super::*;
+    use window_vibrancy::Error;
+
+    pub fn apply(window: &Window, effect: WindowEffect) -> Result<(), Error> {
+        match effect {
+            WindowEffect::Blur(color) => window_vibrancy::apply_blur(window, color),
+            WindowEffect::Acrylic(color) => window_vibrancy::apply_acrylic(window, color),
+            WindowEffect::Mica { dark } => window_vibrancy::apply_mica(window, dark),
+            WindowEffect::Vibrancy(material) => {
+                window_vibrancy::apply_vibrancy(window, material, None, None)
+            }
+        }
+    }
+
+    pub fn clear(window: &Window, effect: WindowEffect) -> Result<(), Error> {
+        match effect {
+            WindowEffect::Blur(_) => window_vibrancy::clear_blur(window),
+            WindowEffect::Acrylic(_) => window_vibrancy::clear_acrylic(window),
+            WindowEffect::Mica { .. } => window_vibrancy::clear_mica(window),
+            // window-vibrancy doesn't provide a way to remove the visual effect view it inserts
+            WindowEffect::Vibrancy(_) => Err(Error::UnsupportedPlatform(
+                "vibrancy can't be cleared once it has been applied",
+            )),
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{hooks::use_event_listener, safe_area::SafeAreaInsets};
+use serde::Deserialize;
+use tao::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    event::WindowEvent,
+    window::Theme,
+};
+
+/// The script that reports over IPC when the page becomes hidden or visible again
+///
+/// The webview hides the page when the window is minimized or, on some platforms, completely covered by other
+/// windows, so this is how occlusion is detected.
+pub(crate) const OCCLUSION_SCRIPT: &str = r#"
+(function () {
+    document.addEventListener("visibilitychange", function () {
+        window.ipc.postMessage(JSON.stringify({
+            method: "window_occluded",
+            params: { occluded: document.visibilityState === "hidden" },
+        }));
+    });
+})();
+"#;
+
+/// Whether the page is hidden, as reported by [`OCCLUSION_SCRIPT`]
+#[derive(Deserialize)]
+pub(crate) struct OcclusionMessage {
+    pub(crate) occluded: bool,
+}
+
+/// A change to the window the component is rendered in, delivered to [`use_window_event`] handlers
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum DesktopWindowEvent {
+    /// The window was moved, with the new position of its top left corner on the desktop
+    Moved(PhysicalPosition<i32>),
+    /// The window was resized, with the new size of its content
+    Resized(PhysicalSize<u32>),
+    /// The window gained (`true`) or lost (`false`) the keyboard focus
+    Focused(bool),
+    /// The window was minimized or covered by other windows (`true`), or became visible again (`false`)
+    ///
+    /// > Note: Whether covering windows count depends on the webview. Minimizing is reported on every desktop
+    /// > platform.
+    Occluded(bool),
+    /// The window moved to a monitor with a different scale factor, or the scale factor of its monitor changed
+    ScaleFactorChanged {
+        /// The new number of physical pixels per logical pixel
+        scale_factor: f64,
+        /// The physical size the window will be resized to
+        inner_size: PhysicalSize<u32>,
+    },
+    /// The system switched between light and dark mode
+    ThemeChanged(Theme),
+    /// The safe area insets of the window changed, for example because the device was rotated
+    SafeAreaChanged(SafeAreaInsets),
+    /// The on-screen keyboard was shown, hidden or resized. Only sent on mobile.
+    ///
+    /// The keyboard covers the bottom of the window, so layouts can shrink by its height to keep the focused input in
+    /// view.
+    VirtualKeyboardChanged {
+        /// Whether the keyboard is shown
+        visible: bool,
+        /// How much of the window the keyboard covers, in logical pixels. Zero while it is hidden.
+        height: f64,
+    },
+}
+
+impl DesktopWindowEvent {
+    /// Convert the tao events that have a typed counterpart
+    pub(crate) fn from_tao(event: &WindowEvent) -> Option<Self> {
+        match event {
+            WindowEvent::ScaleFactorChanged {
+                scale_factor,
+                new_inner_size,
+            } => Some(Self::ScaleFactorChanged {
+                scale_factor: *scale_factor,
+                inner_size: **new_inner_size,
+            }),
+            WindowEvent::ThemeChanged(theme) => Some(Self::ThemeChanged(*theme)),
+            WindowEvent::Moved(position) => Some(Self::Moved(*position)),
+            WindowEvent::Resized(size) => Some(Self::Resized(*size)),
+            WindowEvent::Focused(focused) => Some(Self::Focused(*focused)),
+            _ => None,
+        }
+    }
+}
+
+/// Listen for changes to the window this component is rendered in, like moves, resizes, focus, scale factor and theme
+/// changes
+///
+/// The handler runs in the scope of the component that called this hook, so it can write to signals to re-render
+/// size, density or theme dependent UI.
+///
+/// ```rust, ignore
+/// let mut scale = use_signal(|| window().scale_factor());
+/// use_window_event(move |event| {
+///     if let DesktopWindowEvent::ScaleFactorChanged { scale_factor, .. } = event {
+///         scale.set(scale_factor);
+///     }
+/// });
+///
+/// let mut focused = use_signal(|| true);
+/// use_window_event(move |event| {
+///     if let DesktopWindowEvent::Focused(now) = event {
+///         focused.set(now);
+///     }
+/// });
+/// ```
+pub fn use_window_event(handler: impl FnMut(DesktopWindowEvent) + 'static) {
+    use_event_listener(|window| &window.window_event_listeners, handler)
+}
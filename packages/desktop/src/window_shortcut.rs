@@ -0,0 +1,165 @@
+//! Keyboard shortcuts that only fire while their window is focused
+//!
+//! Unlike global shortcuts, these are detected by the page itself, so they never take a hotkey away from other apps.
+
+use crate::{window, ShortcutRegistryError};
+use serde::Deserialize;
+use slab::Slab;
+use std::{cell::RefCell, rc::Rc};
+use wry::WebView;
+
+/// The script that reports key combinations registered with the window's shortcut registry over IPC
+///
+/// Keys are identified by `KeyboardEvent.code`, so shortcuts stay on the same physical keys across keyboard layouts.
+pub(crate) const WINDOW_SHORTCUT_SCRIPT: &str = r#"
+(function () {
+    let shortcuts = new Set();
+
+    window.__dioxusSetWindowShortcuts = function (combos) {
+        shortcuts = new Set(combos);
+    };
+
+    document.addEventListener("keydown", function (e) {
+        const parts = [];
+        if (e.ctrlKey) parts.push("Ctrl");
+        if (e.altKey) parts.push("Alt");
+        if (e.shiftKey) parts.push("Shift");
+        if (e.metaKey) parts.push("Meta");
+        parts.push(e.code);
+
+        const combo = parts.join("+");
+        if (shortcuts.has(combo)) {
+            e.preventDefault();
+            e.stopPropagation();
+            window.ipc.postMessage(JSON.stringify({ method: "window_shortcut", params: { combo: combo } }));
+        }
+    }, true);
+})();
+"#;
+
+/// A handle to a shortcut created with [`DesktopService::create_window_shortcut`](crate::DesktopService::create_window_shortcut)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WindowShortcutHandle(usize);
+
+impl WindowShortcutHandle {
+    /// Remove the shortcut.
+    pub fn remove(&self) {
+        window().remove_window_shortcut(*self);
+    }
+}
+
+/// The key combination the page reports when a registered shortcut is pressed
+#[derive(Deserialize)]
+pub(crate) struct WindowShortcutMessage {
+    combo: String,
+}
+
+struct WindowShortcut {
+    combo: String,
+    callback: Rc<RefCell<Box<dyn FnMut()>>>,
+}
+
+/// The shortcuts of a single window
+#[derive(Default)]
+pub(crate) struct WindowShortcutRegistry {
+    shortcuts: RefCell<Slab<WindowShortcut>>,
+}
+
+impl WindowShortcutRegistry {
+    pub(crate) fn add(
+        &self,
+        webview: &WebView,
+        accelerator: &str,
+        callback: Box<dyn FnMut()>,
+    ) -> Result<WindowShortcutHandle, ShortcutRegistryError> {
+        let combo = normalize_accelerator(accelerator)?;
+        let id = self.shortcuts.borrow_mut().insert(WindowShortcut {
+            combo,
+            callback: Rc::new(RefCell::new(callback)),
+        });
+        self.sync(webview);
+        Ok(WindowShortcutHandle(id))
+    }
+
+    pub(crate) fn remove(&self, webview: &WebView, handle: WindowShortcutHandle) {
+        if self.shortcuts.borrow_mut().try_remove(handle.0).is_some() {
+            self.sync(webview);
+        }
+    }
+
+    /// Call every callback registered for the combination the page reported
+    pub(crate) fn call(&self, message: WindowShortcutMessage) {
+        // Clone the callbacks out so they can add or remove shortcuts themselves
+        let callbacks: Vec<_> = self
+            .shortcuts
+            .borrow()
+            .iter()
+            .filter(|(_, shortcut)| shortcut.combo == message.combo)
+            .map(|(_, shortcut)| shortcut.callback.clone())
+            .collect();
+
+        for callback in callbacks {
+            (callback.borrow_mut())();
+        }
+    }
+
+    /// Tell the page which key combinations to report
+    pub(crate) fn sync(&self, webview: &WebView) {
+        let combos: Vec<_> = self
+            .shortcuts
+            .borrow()
+            .iter()
+            .map(|(_, shortcut)| shortcut.combo.clone())
+            .collect();
+
+        let combos = serde_json::to_string(&combos).unwrap_or_else(|_| "[]".to_string());
+        _ = webview.evaluate_script(&format!(
+            "window.__dioxusSetWindowShortcuts && window.__dioxusSetWindowShortcuts({combos});"
+        ));
+    }
+}
+
+/// Turn an accelerator like `"CmdOrCtrl+Shift+K"` into the combination the page reports, like `"Ctrl+Shift+KeyK"`
+fn normalize_accelerator(accelerator: &str) -> Result<String, ShortcutRegistryError> {
+    let invalid = || ShortcutRegistryError::InvalidShortcut(accelerator.to_string());
+
+    let (mut ctrl, mut alt, mut shift, mut meta) = (false, false, false, false);
+    let mut key = None;
+
+    for token in accelerator.split('+').map(str::trim) {
+        match token.to_lowercase().as_str() {
+            "ctrl" | "control" => ctrl = true,
+            "alt" | "option" => alt = true,
+            "shift" => shift = true,
+            "meta" | "super" | "cmd" | "command" => meta = true,
+            "cmdorctrl" | "commandorcontrol" if cfg!(target_os = "macos") => meta = true,
+            "cmdorctrl" | "commandorcontrol" => ctrl = true,
+            _ if key.is_some() || token.is_empty() => return Err(invalid()),
+            _ => {
+                let mut chars = token.chars();
+                key = Some(match (chars.next(), chars.next()) {
+                    (Some(c), None) if c.is_ascii_alphabetic() => {
+                        format!("Key{}", c.to_ascii_uppercase())
+                    }
+                    (Some(c), None) if c.is_ascii_digit() => format!("Digit{c}"),
+                    _ => token.to_string(),
+                });
+            }
+        }
+    }
+
+    let mut parts = Vec::new();
+    for (enabled, name) in [
+        (ctrl, "Ctrl"),
+        (alt, "Alt"),
+        (shift, "Shift"),
+        (meta, "Meta"),
+    ] {
+        if enabled {
+            parts.push(name.to_string());
+        }
+    }
+    parts.push(key.ok_or_else(invalid)?);
+
+    Ok(parts.join("+"))
+}
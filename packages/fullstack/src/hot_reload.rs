@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use dioxus_lib::prelude::Template;
+use futures_util::StreamExt;
 use tokio::sync::{
     watch::{channel, Receiver},
     RwLock,
@@ -14,28 +15,33 @@ pub struct HotReloadState {
     pub(crate) message_receiver: Receiver<Option<Template>>,
 }
 
-impl Default for HotReloadState {
-    fn default() -> Self {
+impl HotReloadState {
+    /// Start receiving hot reloading messages on the current runtime
+    fn spawn() -> Self {
         let templates = Arc::new(RwLock::new(std::collections::HashSet::new()));
         let (tx, rx) = channel(None);
 
-        dioxus_hot_reload::connect({
+        tokio::spawn({
             let templates = templates.clone();
-            move |msg| match msg {
-                dioxus_hot_reload::HotReloadMsg::UpdateTemplate(template) => {
-                    {
-                        let mut templates = templates.blocking_write();
-                        templates.insert(template);
-                    }
+            async move {
+                let mut messages = std::pin::pin!(dioxus_hot_reload::connect_async());
+                while let Some(msg) = messages.next().await {
+                    match msg {
+                        dioxus_hot_reload::HotReloadMsg::UpdateTemplate(template) => {
+                            templates.write().await.insert(template);
 
-                    if let Err(err) = tx.send(Some(template)) {
-                        tracing::error!("Failed to send hot reload message: {}", err);
+                            if let Err(err) = tx.send(Some(template)) {
+                                tracing::error!("Failed to send hot reload message: {}", err);
+                            }
+                        }
+                        dioxus_hot_reload::HotReloadMsg::Shutdown(
+                            dioxus_hot_reload::ShutdownReason::Restart,
+                        ) => {
+                            std::process::exit(0);
+                        }
+                        _ => {}
                     }
                 }
-                dioxus_hot_reload::HotReloadMsg::Shutdown => {
-                    std::process::exit(0);
-                }
-                _ => {}
             }
         });
 
@@ -46,17 +52,9 @@ impl Default for HotReloadState {
     }
 }
 
-// Hot reloading can be expensive to start so we spawn a new thread
 static HOT_RELOAD_STATE: tokio::sync::OnceCell<HotReloadState> = tokio::sync::OnceCell::const_new();
 pub(crate) async fn spawn_hot_reload() -> &'static HotReloadState {
     HOT_RELOAD_STATE
-        .get_or_init(|| async {
-            println!("spinning up hot reloading");
-            let r = tokio::task::spawn_blocking(HotReloadState::default)
-                .await
-                .unwrap();
-            println!("hot reloading ready");
-            r
-        })
+        .get_or_init(|| async { HotReloadState::spawn() })
         .await
 }
@@ -0,0 +1,90 @@
+//! Changed assets, classified so clients can reload just what depends on them
+//!
+//! Assets collected by the `asset!` macro are served from a url with a hash of their contents, which is compiled into
+//! the app. The server processes a changed asset to the same url and sends it along with a hash of the new contents,
+//! so clients can find the elements that load it and bust their cache.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// An asset that changed, by what kind of element loads it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AssetUpdate {
+    /// A stylesheet, loaded by `<link rel="stylesheet">`
+    Stylesheet(PathBuf),
+
+    /// An image, loaded by `<img>` or by stylesheets
+    Image(PathBuf),
+
+    /// A font, loaded by stylesheets
+    Font(PathBuf),
+
+    /// A script, which can't be reloaded without reloading the page
+    Script(PathBuf),
+
+    /// Any other asset
+    Other(PathBuf),
+}
+
+/// The url an `asset!` is served from, and the hash of its new contents
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResolvedAsset {
+    /// The url the app loads the asset from, like `/style-1a2b3c.css`. It keeps the hash the asset had when the app
+    /// was compiled.
+    pub url: String,
+
+    /// A hash of the new contents of the asset, to bust the cache of the url with
+    pub hash: String,
+}
+
+impl AssetUpdate {
+    /// Classify the asset at the path by its extension
+    pub fn new(path: PathBuf) -> Self {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+
+        match extension.as_deref() {
+            Some("css") => Self::Stylesheet(path),
+            Some("png" | "jpg" | "jpeg" | "gif" | "webp" | "avif" | "svg" | "ico" | "bmp") => {
+                Self::Image(path)
+            }
+            Some("woff" | "woff2" | "ttf" | "otf" | "eot") => Self::Font(path),
+            Some("js" | "mjs") => Self::Script(path),
+            _ => Self::Other(path),
+        }
+    }
+
+    /// The path of the asset
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Stylesheet(path)
+            | Self::Image(path)
+            | Self::Font(path)
+            | Self::Script(path)
+            | Self::Other(path) => path,
+        }
+    }
+
+    /// The kind of the asset, as it is named in the `reload-asset` messages of the web client
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Stylesheet(_) => "stylesheet",
+            Self::Image(_) => "image",
+            Self::Font(_) => "font",
+            Self::Script(_) => "script",
+            Self::Other(_) => "other",
+        }
+    }
+
+    /// The name of the file the app loads, which is what clients match the urls of elements against
+    pub fn served_name<'a>(&'a self, resolved: Option<&'a ResolvedAsset>) -> Option<&'a str> {
+        let served = match resolved {
+            Some(resolved) => resolved.url.split(['?', '#']).next()?,
+            None => self.path().file_name()?.to_str()?,
+        };
+        served.rsplit(['/', '\\']).next()
+    }
+}
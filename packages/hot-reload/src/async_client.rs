@@ -0,0 +1,243 @@
+//! Receiving hot reloading messages on a tokio runtime, for renderers that already run one
+//!
+//! [`connect`](crate::connect) spawns a thread that blocks on the connection. Renderers with a runtime can poll a
+//! stream of messages next to their other work instead.
+
+use std::io;
+
+use futures_util::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader},
+    stream, SinkExt, Stream, StreamExt,
+};
+use interprocess::local_socket::tokio::LocalSocketStream;
+use serde::Serialize;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tokio_util::compat::TokioAsyncReadCompatExt;
+
+use crate::{
+    handshake, patch,
+    reconnect::{Delays, Session},
+    report, status,
+    transport::{
+        explain_connect_error, local_socket_name, stream_frame, websocket_error, websocket_frame,
+        websocket_message,
+    },
+    Backoff, Encoding, HotReloadMsg, HotReloadStatus, Transport,
+};
+
+/// An async byte stream both ends can read from and write to
+trait AsyncStream: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> AsyncStream for T {}
+
+/// The app's end of a connection to the server
+struct AsyncConnection {
+    frames: AsyncFrames,
+    encoding: Encoding,
+}
+
+/// How the messages of a connection are delimited
+enum AsyncFrames {
+    /// Lines of JSON, or length-prefixed binary messages
+    Stream(BufReader<Box<dyn AsyncStream>>),
+
+    /// One message per text or binary frame
+    WebSocket(WebSocketStream<MaybeTlsStream<TcpStream>>),
+}
+
+impl AsyncConnection {
+    async fn connect(transport: &Transport) -> io::Result<Self> {
+        let frames = match transport {
+            Transport::LocalSocket(path) => {
+                let socket = LocalSocketStream::connect(local_socket_name(path))
+                    .await
+                    .map_err(|err| explain_connect_error(path, err))?;
+                AsyncFrames::Stream(BufReader::new(Box::new(socket)))
+            }
+            Transport::WebSocket(url) => {
+                let (socket, _) = tokio_tungstenite::connect_async(url.as_str())
+                    .await
+                    .map_err(websocket_error)?;
+                AsyncFrames::WebSocket(socket)
+            }
+            Transport::Tcp(addr) => {
+                let socket = TcpStream::connect(addr.as_str()).await?;
+                AsyncFrames::Stream(BufReader::new(Box::new(socket.compat())))
+            }
+        };
+
+        Ok(Self {
+            frames,
+            // Until the handshake picked another encoding
+            encoding: Encoding::Json,
+        })
+    }
+
+    /// Send the message, and return whether the server is still connected
+    async fn send(&mut self, msg: &impl Serialize) -> bool {
+        let Some(frame) = self.encoding.encode(msg) else {
+            return false;
+        };
+
+        match &mut self.frames {
+            AsyncFrames::Stream(reader) => reader
+                .get_mut()
+                .write_all(&stream_frame(self.encoding, frame))
+                .await
+                .is_ok(),
+            AsyncFrames::WebSocket(socket) => socket
+                .send(websocket_message(self.encoding, frame))
+                .await
+                .is_ok(),
+        }
+    }
+
+    /// Wait for the next message. Returns `None` once the server closed the connection.
+    async fn recv(&mut self) -> io::Result<Option<Vec<u8>>> {
+        match (&mut self.frames, self.encoding) {
+            (AsyncFrames::Stream(reader), Encoding::Json) => {
+                let mut frame = Vec::new();
+                match reader.read_until(b'\n', &mut frame).await? {
+                    0 => Ok(None),
+                    _ => Ok(Some(frame)),
+                }
+            }
+            (AsyncFrames::Stream(reader), Encoding::MessagePack) => {
+                let mut length = [0; 4];
+                match reader.read_exact(&mut length).await {
+                    Ok(()) => {}
+                    Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+                    Err(err) => return Err(err),
+                }
+                let mut frame = vec![0; u32::from_le_bytes(length) as usize];
+                reader.read_exact(&mut frame).await?;
+                Ok(Some(frame))
+            }
+            (AsyncFrames::WebSocket(socket), _) => loop {
+                match socket.next().await {
+                    Some(Ok(message)) => match websocket_frame(message) {
+                        Some(frame) => return Ok(frame),
+                        None => continue,
+                    },
+                    Some(Err(tungstenite::Error::ConnectionClosed)) | None => return Ok(None),
+                    Some(Err(err)) => return Err(websocket_error(err)),
+                }
+            },
+        }
+    }
+
+    /// Connect over the transport and answer the handshake of the server
+    async fn open(transport: &Transport) -> Result<Self, Session> {
+        // There is no server if the app isn't running under hot reloading
+        let mut connection = Self::connect(transport)
+            .await
+            .map_err(|_| Session::Unreachable)?;
+
+        let Ok(Some(first)) = connection.recv().await else {
+            return Err(Session::Unreachable);
+        };
+        let (_, reply) = handshake::check(&first).map_err(|err| {
+            eprintln!("{err}");
+            Session::Incompatible
+        })?;
+        if !connection.send(&reply).await {
+            return Err(Session::Unreachable);
+        }
+        connection.encoding = reply.encoding;
+        status::set_client_status(Some(HotReloadStatus::default()));
+
+        Ok(connection)
+    }
+}
+
+/// Connect to the hot reloading listener, and receive its messages as a stream
+///
+/// The transport is picked from the environment, see [`Transport::from_env`]. If the server isn't up yet or restarts,
+/// the stream keeps reconnecting with the default [`Backoff`].
+///
+/// ```rust, ignore
+/// let mut messages = std::pin::pin!(dioxus_hot_reload::connect_async());
+///
+/// loop {
+///     tokio::select! {
+///         Some(msg) = messages.next() => {
+///             if let HotReloadMsg::UpdateTemplate(template) = msg {
+///                 vdom.replace_template(template);
+///             }
+///         }
+///         _ = vdom.wait_for_work() => {}
+///     }
+/// }
+/// ```
+pub fn connect_async() -> impl Stream<Item = HotReloadMsg> + Send {
+    connect_async_with(Transport::from_env())
+}
+
+/// Connect to the hot reloading listener over the transport, and receive its messages as a stream
+pub fn connect_async_with(transport: Transport) -> impl Stream<Item = HotReloadMsg> + Send {
+    connect_async_with_backoff(transport, Backoff::default())
+}
+
+/// Connect to the hot reloading listener over the transport, and receive its messages as a stream. The stream
+/// reconnects with the backoff whenever the connection fails or closes, and ends once the backoff gives up.
+pub fn connect_async_with_backoff(
+    transport: Transport,
+    backoff: Backoff,
+) -> impl Stream<Item = HotReloadMsg> + Send {
+    // Reports are rare and small, so they are sent from a thread like with the blocking client
+    report::start(transport.clone(), backoff);
+
+    struct State {
+        transport: Transport,
+        delays: Delays,
+        connection: Option<AsyncConnection>,
+    }
+
+    let state = State {
+        transport,
+        delays: backoff.delays(),
+        connection: None,
+    };
+
+    stream::unfold(state, |mut state| async move {
+        loop {
+            let mut connection = match state.connection.take() {
+                Some(connection) => connection,
+                None => match AsyncConnection::open(&state.transport).await {
+                    Ok(connection) => connection,
+                    Err(Session::Incompatible) => return None,
+                    Err(_) => {
+                        tokio::time::sleep(state.delays.next()?).await;
+                        continue;
+                    }
+                },
+            };
+
+            match connection.recv().await {
+                Ok(Some(frame)) => {
+                    let msg = connection.encoding.decode(&frame);
+                    state.connection = Some(connection);
+                    match msg.map(patch::resolve) {
+                        // A patch that couldn't be applied
+                        Ok(None) => {}
+                        Ok(Some(msg)) => {
+                            if let HotReloadMsg::Status(latest) = &msg {
+                                status::set_client_status(Some(latest.clone()));
+                            }
+                            return Some((msg, state));
+                        }
+                        Err(_) => eprintln!(
+                            "Could not parse hot reloading message - make sure your client is up to date"
+                        ),
+                    }
+                }
+                Ok(None) | Err(_) => {
+                    status::set_client_status(None);
+                    tokio::time::sleep(state.delays.after(Session::Closed)?).await;
+                }
+            }
+        }
+    })
+    .fuse()
+}
@@ -0,0 +1,125 @@
+//! The apps connected to the hot reloading server
+//!
+//! Several apps can be connected at once, like multiple windows or a web and a desktop build of the same project.
+//! Every message is broadcast to all of them, and apps that stopped listening are pruned on the next broadcast. Apps
+//! report back on connections of their own, which are read on a thread each.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use crate::{
+    status::update_server_status, transport::Connection, ClientInfo, ClientMsg, HotReloadMsg,
+};
+
+/// An app connected to the server
+struct Client {
+    connection: Connection,
+    info: Option<ClientInfo>,
+}
+
+impl Client {
+    fn describe(&self) -> String {
+        describe(&self.info)
+    }
+}
+
+fn describe(info: &Option<ClientInfo>) -> String {
+    match info {
+        Some(info) => info.to_string(),
+        None => "unknown app".to_string(),
+    }
+}
+
+/// The apps connected to the server, shared between the threads that accept apps and the one that watches files
+#[derive(Clone)]
+pub(crate) struct Clients {
+    clients: Arc<Mutex<Vec<Client>>>,
+    /// When the server started reading the files of the last reload
+    reloaded: Arc<Mutex<Option<Instant>>>,
+    log: bool,
+}
+
+impl Clients {
+    pub(crate) fn new(log: bool) -> Self {
+        Self {
+            clients: Default::default(),
+            reloaded: Default::default(),
+            log,
+        }
+    }
+
+    /// Start sending messages to an app that finished its handshake
+    pub(crate) fn add(&self, connection: Connection, info: Option<ClientInfo>) {
+        let client = Client { connection, info };
+        let status = {
+            let mut clients = self.clients.lock().unwrap();
+            if self.log {
+                println!(
+                    "Connected to hot reloading 🚀 ({}, {} connected)",
+                    client.describe(),
+                    clients.len() + 1
+                );
+            }
+            clients.push(client);
+            update_server_status(|status| status.clients = clients.len())
+        };
+
+        self.broadcast(&HotReloadMsg::Status(status));
+    }
+
+    /// Send the message to every app, and prune the apps that disconnected
+    pub(crate) fn broadcast(&self, msg: &impl serde::Serialize) {
+        let mut clients = self.clients.lock().unwrap();
+        clients.retain_mut(|client| {
+            let connected = client.connection.send(msg);
+            if !connected && self.log {
+                println!("Disconnected from hot reloading ({})", client.describe());
+            }
+            connected
+        });
+        update_server_status(|status| status.clients = clients.len());
+    }
+
+    /// Remember when the reload that was just broadcast started, to measure when the apps applied it
+    pub(crate) fn reloaded(&self, started: Instant) {
+        *self.reloaded.lock().unwrap() = Some(started);
+    }
+
+    /// Read the reports of an app on a separate thread until it disconnects
+    pub(crate) fn listen(&self, mut connection: Connection, info: Option<ClientInfo>) {
+        let reloaded = self.reloaded.clone();
+        let log = self.log;
+
+        std::thread::spawn(move || {
+            let app = describe(&info);
+            // Every reload is measured once per app, and only if it happened after the app connected
+            let mut measured = *reloaded.lock().unwrap();
+
+            while let Ok(Some(frame)) = connection.recv() {
+                let Ok(msg) = connection.encoding().decode_owned(&frame) else {
+                    continue;
+                };
+
+                match msg {
+                    ClientMsg::Ready => {
+                        let started = *reloaded.lock().unwrap();
+                        let Some(started) = started.filter(|_| started != measured) else {
+                            continue;
+                        };
+                        measured = Some(started);
+                        let applied = started.elapsed();
+                        update_server_status(|status| status.last_applied = Some(applied));
+                        if log {
+                            println!("Hot reloaded in {}ms ({app})", applied.as_millis());
+                        }
+                    }
+                    // The app asked for these to be shown, so they are shown even without logging
+                    ClientMsg::Log(line) => println!("[{app}] {line}"),
+                    ClientMsg::Panic(panic) => eprintln!("[{app}] {panic}"),
+                }
+            }
+        });
+    }
+}
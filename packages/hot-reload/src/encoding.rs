@@ -0,0 +1,50 @@
+//! How messages are encoded on the wire, negotiated in the [handshake](crate::Handshake)
+
+#[cfg(feature = "custom_file_watcher")]
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::HotReloadMsg;
+
+/// An encoding of the messages between the server and the app
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    /// JSON, one message per line or text frame. The handshake is always JSON.
+    Json,
+
+    /// MessagePack, one length-prefixed message or one message per binary frame
+    ///
+    /// Templates are a fraction of their size in JSON, and decode faster.
+    MessagePack,
+}
+
+impl Encoding {
+    /// Every encoding, the ones apps prefer first
+    pub const ALL: &'static [Encoding] = &[Encoding::MessagePack, Encoding::Json];
+
+    /// Encode the message, if it can be encoded
+    pub(crate) fn encode(self, msg: &impl Serialize) -> Option<Vec<u8>> {
+        match self {
+            Self::Json => serde_json::to_vec(msg).ok(),
+            Self::MessagePack => rmp_serde::to_vec_named(msg).ok(),
+        }
+    }
+
+    /// Decode a message the server sent
+    pub(crate) fn decode(self, frame: &[u8]) -> Result<HotReloadMsg, String> {
+        match self {
+            Self::Json => serde_json::from_slice(frame).map_err(|err| err.to_string()),
+            Self::MessagePack => rmp_serde::from_slice(frame).map_err(|err| err.to_string()),
+        }
+    }
+
+    /// Decode a message an app sent, which doesn't borrow from the frame
+    #[cfg(feature = "custom_file_watcher")]
+    pub(crate) fn decode_owned<T: DeserializeOwned>(self, frame: &[u8]) -> Result<T, String> {
+        match self {
+            Self::Json => serde_json::from_slice(frame).map_err(|err| err.to_string()),
+            Self::MessagePack => rmp_serde::from_slice(frame).map_err(|err| err.to_string()),
+        }
+    }
+}
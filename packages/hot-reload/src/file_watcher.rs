@@ -1,27 +1,52 @@
 use std::{
-    io::Write,
+    net::SocketAddr,
     path::PathBuf,
     str::FromStr,
     sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 
-use crate::HotReloadMsg;
+use crate::{
+    bind_local_socket,
+    clients::Clients,
+    filter::PathFilter,
+    rebuild::{RebuildReason, RebuildRequest},
+    root::{self, root_of, Root, WatchRoot},
+    server::{accept_connections, listen_tcp},
+    status::update_server_status,
+    strategy::{ReloadStrategy, Strategies},
+    transport::{set_in_process_socket, Connection},
+    AssetUpdate, HotReloadMsg, ShutdownReason, TemplatePatch, SOCKET_ENV,
+};
+use dioxus_core::{LiteralUpdate, Template};
 use dioxus_rsx::{
     hot_reload::{FileMap, FileMapBuildResult, UpdateResult},
     HotReloadingContext,
 };
-use interprocess::local_socket::LocalSocketListener;
-use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use notify::{PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
 
 #[cfg(feature = "file_watcher")]
 use dioxus_html::HtmlCtx;
 
 pub struct Config<Ctx: HotReloadingContext> {
     root_path: &'static str,
+    package: &'static str,
+    socket_path: Option<PathBuf>,
     listening_paths: &'static [&'static str],
     excluded_paths: &'static [&'static str],
+    include_globs: &'static [&'static str],
+    exclude_globs: &'static [&'static str],
+    gitignore: bool,
+    roots: Vec<WatchRoot>,
+    workspace_members: bool,
+    debounce: Duration,
+    poll_interval: Option<Duration>,
     log: bool,
-    rebuild_with: Option<Box<dyn FnMut() -> bool + Send + 'static>>,
+    rebuild_with: Option<Box<dyn FnMut(&RebuildRequest) -> bool + Send + 'static>>,
+    websocket: Option<SocketAddr>,
+    tcp: Option<SocketAddr>,
+    token: Option<&'static str>,
+    strategies: Vec<(&'static str, ReloadStrategy)>,
     phantom: std::marker::PhantomData<Ctx>,
 }
 
@@ -29,10 +54,23 @@ impl<Ctx: HotReloadingContext> Default for Config<Ctx> {
     fn default() -> Self {
         Self {
             root_path: "",
+            package: "",
+            socket_path: None,
             listening_paths: &[""],
             excluded_paths: &["./target"],
+            include_globs: &[],
+            exclude_globs: &[],
+            gitignore: true,
+            roots: Vec::new(),
+            workspace_members: false,
+            debounce: Duration::from_millis(100),
+            poll_interval: None,
             log: true,
             rebuild_with: None,
+            websocket: None,
+            tcp: None,
+            token: None,
+            strategies: Vec::new(),
             phantom: std::marker::PhantomData,
         }
     }
@@ -43,10 +81,23 @@ impl Config<HtmlCtx> {
     pub const fn new() -> Self {
         Self {
             root_path: "",
+            package: "",
+            socket_path: None,
             listening_paths: &[""],
             excluded_paths: &["./target"],
+            include_globs: &[],
+            exclude_globs: &[],
+            gitignore: true,
+            roots: Vec::new(),
+            workspace_members: false,
+            debounce: Duration::from_millis(100),
+            poll_interval: None,
             log: true,
             rebuild_with: None,
+            websocket: None,
+            tcp: None,
+            token: None,
+            strategies: Vec::new(),
             phantom: std::marker::PhantomData,
         }
     }
@@ -61,6 +112,25 @@ impl<Ctx: HotReloadingContext> Config<Ctx> {
         }
     }
 
+    /// Set the name of the package, which names the local socket. This is automatically set by the [`hot_reload_init`] macro.
+    pub fn package(self, name: &'static str) -> Self {
+        Self {
+            package: name,
+            ..self
+        }
+    }
+
+    /// Set the path of the local socket apps connect to
+    ///
+    /// By default the socket is at the path in [`SOCKET_ENV`] if it is set, or `target/dioxusin-<package>` in the
+    /// root of the project. Apps in this process connect to the socket wherever it is.
+    pub fn with_socket_path(self, path: impl Into<PathBuf>) -> Self {
+        Self {
+            socket_path: Some(path.into()),
+            ..self
+        }
+    }
+
     /// Set whether to enable logs
     pub fn with_logging(self, log: bool) -> Self {
         Self { log, ..self }
@@ -83,10 +153,22 @@ impl<Ctx: HotReloadingContext> Config<Ctx> {
     /// For example a CLI application could rebuild the application when a change is made
     pub fn with_rebuild_callback(
         self,
-        rebuild_callback: impl FnMut() -> bool + Send + 'static,
+        mut rebuild_callback: impl FnMut() -> bool + Send + 'static,
+    ) -> Self {
+        self.with_rebuild_handler(move |_| rebuild_callback())
+    }
+
+    /// Set a handler to run when a change can't be hot reloaded, with the file that changed and why. Returns if the
+    /// server should shut down.
+    ///
+    /// Use this to start your own build system instead of a shell command, like bazel or a custom build script. The
+    /// apps are told to exit once the handler returns, with [`ShutdownReason::Restart`](crate::ShutdownReason::Restart).
+    pub fn with_rebuild_handler(
+        self,
+        rebuild_handler: impl FnMut(&RebuildRequest) -> bool + Send + 'static,
     ) -> Self {
         Self {
-            rebuild_with: Some(Box::new(rebuild_callback)),
+            rebuild_with: Some(Box::new(rebuild_handler)),
             ..self
         }
     }
@@ -99,6 +181,39 @@ impl<Ctx: HotReloadingContext> Config<Ctx> {
         }
     }
 
+    /// Also accept apps over a WebSocket at the address, for apps on other machines or physical mobile devices
+    ///
+    /// The apps connect if [`WEBSOCKET_ENV`](crate::WEBSOCKET_ENV) is set to the URL of the socket, like
+    /// `ws://192.168.1.20:8080`.
+    pub fn with_websocket(self, addr: SocketAddr) -> Self {
+        Self {
+            websocket: Some(addr),
+            ..self
+        }
+    }
+
+    /// Also accept apps over plain TCP at the address, for apps in containers or WSL that can't share the local socket
+    /// inside `target/`
+    ///
+    /// The apps connect if [`TCP_ENV`](crate::TCP_ENV) is set to the address, like `127.0.0.1:8081`.
+    pub fn with_tcp(self, addr: SocketAddr) -> Self {
+        Self {
+            tcp: Some(addr),
+            ..self
+        }
+    }
+
+    /// Require apps that connect over the WebSocket or TCP to present this token instead of a random one, which the
+    /// server prints when it starts
+    ///
+    /// The apps present the token set in [`TOKEN_ENV`](crate::TOKEN_ENV) or with [`set_token`](crate::set_token).
+    pub fn with_token(self, token: &'static str) -> Self {
+        Self {
+            token: Some(token),
+            ..self
+        }
+    }
+
     /// Sets paths to ignore changes on. This will override any paths set in the [`Config::with_paths`] method in the case of conflicts.
     pub fn excluded_paths(self, paths: &'static [&'static str]) -> Self {
         Self {
@@ -106,6 +221,78 @@ impl<Ctx: HotReloadingContext> Config<Ctx> {
             ..self
         }
     }
+
+    /// Only react to changes in files matching the globs, like `src/**/*.rs`. The globs use the `.gitignore` syntax and
+    /// are relative to the root path.
+    ///
+    /// By default hot reloading reacts to changes in `.rs`, `.toml`, `.css`, `.html` and `.js` files.
+    pub fn with_include_globs(self, globs: &'static [&'static str]) -> Self {
+        Self {
+            include_globs: globs,
+            ..self
+        }
+    }
+
+    /// Ignore changes in files matching the globs, like `src/generated/` or `*.gen.rs`. The globs use the `.gitignore`
+    /// syntax and are relative to the root path.
+    pub fn with_exclude_globs(self, globs: &'static [&'static str]) -> Self {
+        Self {
+            exclude_globs: globs,
+            ..self
+        }
+    }
+
+    /// Set whether to ignore changes in files ignored by the `.gitignore` of the project or of any of its parent
+    /// directories up to the root of the repository. Enabled by default.
+    pub fn with_gitignore(self, gitignore: bool) -> Self {
+        Self { gitignore, ..self }
+    }
+
+    /// Also watch another crate, like a shared component library outside of the root path
+    ///
+    /// The excluded paths and the `.gitignore` setting apply to every crate, the other settings can be set per crate.
+    pub fn with_root(mut self, root: WatchRoot) -> Self {
+        self.roots.push(root);
+        self
+    }
+
+    /// Set how long to wait for more changes before sending the updated templates to the apps. Defaults to 100ms.
+    ///
+    /// Editors may save a file several times in a row, like when formatting on save. Every change within the window
+    /// after the last one is sent as one batch, with every template once, so the apps don't re-render for every save.
+    pub fn with_debounce(self, debounce: Duration) -> Self {
+        Self { debounce, ..self }
+    }
+
+    /// Check the watched files for changes every `interval` instead of relying on the file system events of the OS
+    ///
+    /// Events like inotify or FSEvents aren't sent on network file systems or for volumes mounted into a docker
+    /// container. Polling works everywhere, but it is slower to notice changes and uses more CPU on large projects.
+    pub fn with_polling(self, interval: Duration) -> Self {
+        Self {
+            poll_interval: Some(interval),
+            ..self
+        }
+    }
+
+    /// Reload files matching the glob with the strategy, like `*.css` with [`ReloadStrategy::InjectAsset`]. The glob
+    /// uses the `.gitignore` syntax and is relative to the root of every watched crate.
+    ///
+    /// Later patterns win over earlier ones. Files that don't match any pattern are hot reloaded if they are `.rs`
+    /// files, and rebuild the app otherwise. Files matching a pattern are watched even if they don't match the include
+    /// globs, but not if they are excluded.
+    pub fn with_strategy(mut self, glob: &'static str, strategy: ReloadStrategy) -> Self {
+        self.strategies.push((glob, strategy));
+        self
+    }
+
+    /// Also watch every other member of the workspace the root crate is in
+    pub fn with_workspace_members(self) -> Self {
+        Self {
+            workspace_members: true,
+            ..self
+        }
+    }
 }
 
 /// Initialize the hot reloading listener
@@ -118,9 +305,22 @@ pub fn init<Ctx: HotReloadingContext + Send + 'static>(cfg: Config<Ctx>) {
     let Config {
         mut rebuild_with,
         root_path,
+        package,
+        socket_path,
         listening_paths,
         log,
         excluded_paths,
+        include_globs,
+        exclude_globs,
+        gitignore,
+        roots,
+        workspace_members,
+        debounce,
+        poll_interval,
+        websocket,
+        tcp,
+        token,
+        strategies,
         ..
     } = cfg;
 
@@ -128,29 +328,73 @@ pub fn init<Ctx: HotReloadingContext + Send + 'static>(cfg: Config<Ctx>) {
         return;
     };
 
-    // try to find the gitignore file
-    let gitignore_file_path = crate_dir.join(".gitignore");
-    let (gitignore, _) = ignore::gitignore::Gitignore::new(gitignore_file_path);
-
-    // convert the excluded paths to absolute paths
-    let excluded_paths = excluded_paths
-        .iter()
-        .map(|path| crate_dir.join(PathBuf::from(path)))
-        .collect::<Vec<_>>();
-
-    let channels = Arc::new(Mutex::new(Vec::new()));
-    let FileMapBuildResult {
-        map: file_map,
-        errors,
-    } = FileMap::<Ctx>::create_with_filter(crate_dir.clone(), |path| {
-        // skip excluded paths
-        excluded_paths.iter().any(|p| path.starts_with(p)) ||
-            // respect .gitignore
-            gitignore
-                .matched_path_or_any_parents(path, path.is_dir())
-                .is_ignore()
-    })
-    .unwrap();
+    let main_root = Root::new(
+        crate_dir.clone(),
+        listening_paths,
+        PathFilter::new(
+            &crate_dir,
+            excluded_paths,
+            include_globs,
+            exclude_globs,
+            gitignore,
+            log,
+        ),
+        Strategies::new(&crate_dir, &strategies, log),
+    );
+    let mut roots: Vec<_> = std::iter::once(main_root)
+        .chain(roots.into_iter().map(|root| {
+            Root::resolve(
+                root,
+                &crate_dir,
+                excluded_paths,
+                gitignore,
+                &strategies,
+                log,
+            )
+        }))
+        .collect();
+
+    if workspace_members {
+        match root::workspace_members(&crate_dir) {
+            Ok(members) => roots.extend(members.into_iter().map(|member| {
+                Root::resolve(
+                    WatchRoot::new(member),
+                    &crate_dir,
+                    excluded_paths,
+                    gitignore,
+                    &strategies,
+                    log,
+                )
+            })),
+            Err(err) => {
+                if log {
+                    println!("hot reloading failed to find the workspace members:\n{err}");
+                }
+            }
+        }
+    }
+
+    let clients = Clients::new(log);
+    let mut errors = Vec::new();
+    let mut file_maps = roots.iter().map(|root| {
+        let FileMapBuildResult {
+            map,
+            errors: root_errors,
+        } = FileMap::<Ctx>::create_with_filter(root.crate_dir.clone(), |path| {
+            // Leave nested roots to themselves
+            !root_of(&roots, path).is_some_and(|other| std::ptr::eq(other, root))
+                || root.filter.is_ignored(path, path.is_dir())
+        })
+        .unwrap();
+        errors.extend(root_errors);
+        map
+    });
+    let mut file_map = file_maps
+        .next()
+        .expect("the root of the config is always watched");
+    for other in file_maps {
+        file_map.map.extend(other.map);
+    }
 
     for err in errors {
         if log {
@@ -161,22 +405,28 @@ pub fn init<Ctx: HotReloadingContext + Send + 'static>(cfg: Config<Ctx>) {
     let file_map = Arc::new(Mutex::new(file_map));
 
     let target_dir = crate_dir.join("target");
-    let hot_reload_socket_path = target_dir.join("dioxusin");
-
-    #[cfg(unix)]
-    {
-        // On unix, if you force quit the application, it can leave the file socket open
-        // This will cause the local socket listener to fail to open
-        // We check if the file socket is already open from an old session and then delete it
-        if hot_reload_socket_path.exists() {
-            let _ = std::fs::remove_file(hot_reload_socket_path.clone());
+    let hot_reload_socket_path = socket_path
+        .or_else(|| std::env::var_os(SOCKET_ENV).map(PathBuf::from))
+        .unwrap_or_else(|| match package {
+            "" => target_dir.join("dioxusin"),
+            package => crate::socket_path(&target_dir, package),
+        });
+
+    let local_socket_stream = match bind_local_socket(&hot_reload_socket_path) {
+        Ok(local_socket_stream) => {
+            set_in_process_socket(hot_reload_socket_path);
+            local_socket_stream
+        }
+        // Another instance of the app is already serving hot reloading, so connect to that one instead
+        Err(err) if err.kind() == std::io::ErrorKind::AddrInUse => {
+            if log {
+                println!("{err}");
+            }
+            set_in_process_socket(hot_reload_socket_path);
+            return;
         }
-    }
-
-    let local_socket_stream = match LocalSocketListener::bind(hot_reload_socket_path) {
-        Ok(local_socket_stream) => local_socket_stream,
         Err(err) => {
-            println!("failed to connect to hot reloading\n{err}");
+            println!("hot reloading failed to start: {err}");
             return;
         }
     };
@@ -184,41 +434,44 @@ pub fn init<Ctx: HotReloadingContext + Send + 'static>(cfg: Config<Ctx>) {
     let aborted = Arc::new(Mutex::new(false));
 
     // listen for connections
-    std::thread::spawn({
-        let file_map = file_map.clone();
-        let channels = channels.clone();
-        let aborted = aborted.clone();
-        let _ = local_socket_stream.set_nonblocking(true);
-        move || {
-            loop {
-                if let Ok(mut connection) = local_socket_stream.accept() {
-                    // send any templates than have changed before the socket connected
-                    let templates: Vec<_> = {
-                        file_map
-                            .lock()
-                            .unwrap()
-                            .map
-                            .values()
-                            .flat_map(|v| v.templates.values().copied())
-                            .collect()
-                    };
-
-                    for template in templates {
-                        if !send_msg(HotReloadMsg::UpdateTemplate(template), &mut connection) {
-                            continue;
-                        }
-                    }
-                    channels.lock().unwrap().push(connection);
-                    if log {
-                        println!("Connected to hot reloading 🚀");
-                    }
-                }
-                if *aborted.lock().unwrap() {
-                    break;
-                }
-            }
-        }
-    });
+    let _ = local_socket_stream.set_nonblocking(true);
+    accept_connections(
+        move || local_socket_stream.accept(),
+        Connection::accept_local,
+        file_map.clone(),
+        clients.clone(),
+        aborted.clone(),
+        None,
+    );
+
+    // Both network listeners share one token
+    let token = token.map_or_else(crate::token::generate, str::to_string);
+
+    if let Some(addr) = websocket {
+        listen_tcp(
+            addr,
+            "WebSocket",
+            Connection::accept_websocket,
+            file_map.clone(),
+            clients.clone(),
+            aborted.clone(),
+            token.clone(),
+            log,
+        );
+    }
+
+    if let Some(addr) = tcp {
+        listen_tcp(
+            addr,
+            "TCP",
+            Connection::accept_tcp,
+            file_map.clone(),
+            clients.clone(),
+            aborted.clone(),
+            token.clone(),
+            log,
+        );
+    }
 
     // watch for changes
     std::thread::spawn(move || {
@@ -226,141 +479,185 @@ pub fn init<Ctx: HotReloadingContext + Send + 'static>(cfg: Config<Ctx>) {
 
         let (tx, rx) = std::sync::mpsc::channel();
 
-        let mut watcher = RecommendedWatcher::new(tx, notify::Config::default()).unwrap();
-
-        let mut listening_pathbufs = vec![];
-
-        // We're attempting to watch the root path... which contains a target directory...
-        // And on some platforms the target directory is really really large and can cause the watcher to crash
-        // since it runs out of file handles
-        // So we're going to iterate through its children and watch them instead of the root path, skipping the target
-        // directory.
-        //
-        // In reality, this whole approach of doing embedded file watching is kinda hairy since you want full knowledge
-        // of where rust code is. We could just use the filemap we generated above as an indication of where the rust
-        // code is in this project and deduce the subfolders under the root path from that.
-        //
-        // FIXME: use a more robust system here for embedded discovery
-        //
-        // https://github.com/DioxusLabs/dioxus/issues/1914
-        if listening_paths == [""] {
-            for entry in std::fs::read_dir(&crate_dir)
-                .expect("failed to read rust crate directory. Are you running with cargo?")
-            {
-                let entry = entry.expect("failed to read directory entry");
-                let path = entry.path();
-                if path.is_dir() {
-                    if path == target_dir {
-                        continue;
-                    }
-                    listening_pathbufs.push(path);
+        let watcher: notify::Result<Box<dyn Watcher>> = match poll_interval {
+            Some(interval) => {
+                PollWatcher::new(tx, notify::Config::default().with_poll_interval(interval))
+                    .map(|watcher| Box::new(watcher) as _)
+            }
+            None => RecommendedWatcher::new(tx, notify::Config::default())
+                .map(|watcher| Box::new(watcher) as _),
+        };
+        let mut watcher = match watcher {
+            Ok(watcher) => watcher,
+            Err(err) => {
+                if log {
+                    println!("hot reloading failed to start the file watcher:\n{err:?}");
                 }
+                clients.broadcast(&HotReloadMsg::Shutdown(ShutdownReason::FatalError(
+                    format!("hot reloading failed to start the file watcher: {err}"),
+                )));
+                return;
             }
-        } else {
-            for path in listening_paths {
-                let full_path = crate_dir.join(path);
-                listening_pathbufs.push(full_path);
+        };
+
+        let watched_dirs: Vec<_> = roots.iter().flat_map(Root::watched_dirs).collect();
+
+        for full_path in &watched_dirs {
+            // Directories of nested roots are already watched with the root they are in
+            if watched_dirs
+                .iter()
+                .any(|dir| dir != full_path && full_path.starts_with(dir))
+            {
+                continue;
             }
-        }
 
-        for full_path in listening_pathbufs {
-            if let Err(err) = watcher.watch(&full_path, RecursiveMode::Recursive) {
+            if let Err(err) = watcher.watch(full_path, RecursiveMode::Recursive) {
                 if log {
                     println!("hot reloading failed to start watching {full_path:?}:\n{err:?}",);
                 }
             }
         }
 
+        update_server_status(|status| status.watching = true);
+
         let mut rebuild = {
             let aborted = aborted.clone();
-            let channels = channels.clone();
-            move || {
-                if let Some(rebuild_callback) = &mut rebuild_with {
+            let clients = clients.clone();
+            move |request: RebuildRequest| {
+                if let Some(rebuild_handler) = &mut rebuild_with {
                     if log {
                         println!("Rebuilding the application...");
                     }
-                    let shutdown = rebuild_callback();
+                    let shutdown = rebuild_handler(&request);
 
                     if shutdown {
                         *aborted.lock().unwrap() = true;
+                        update_server_status(|status| status.watching = false);
                     }
 
-                    for channel in &mut *channels.lock().unwrap() {
-                        send_msg(HotReloadMsg::Shutdown, channel);
-                    }
+                    clients.broadcast(&HotReloadMsg::Shutdown(ShutdownReason::Restart));
 
                     return shutdown;
                 } else if log {
                     println!("Rebuild needed... shutting down hot reloading.\nManually rebuild the application to view further changes.");
                 }
+                update_server_status(|status| status.watching = false);
+                clients.broadcast(&HotReloadMsg::Shutdown(ShutdownReason::WatcherExiting));
                 true
             }
         };
 
-        for evt in rx {
+        // Wait for the first change of a batch
+        while let Ok(first) = rx.recv() {
+            // Collect the changes that follow in quick succession, like the several saves of a format-on-save
+            let mut events = vec![first];
+            while let Ok(evt) = rx.recv_timeout(debounce) {
+                events.push(evt);
+            }
+
             if chrono::Local::now().timestamp_millis() < last_update_time {
                 continue;
             }
 
-            let Ok(evt) = evt else {
-                continue;
-            };
+            // Every changed file once, in the order they first changed
+            let mut real_paths = Vec::new();
+            for path in events.iter().flatten().flat_map(|evt| &evt.paths) {
+                if real_paths.iter().any(|(changed, _)| *changed == path) {
+                    continue;
+                }
+                if let Some(root) = root_of(&roots, path) {
+                    if !root.is_ignored(path) {
+                        real_paths.push((path, root));
+                    }
+                }
+            }
 
-            let real_paths = evt
-                .paths
-                .iter()
-                .filter(|path| {
-                    // skip non rust files
-                    matches!(
-                        path.extension().and_then(|p| p.to_str()),
-                        Some("rs" | "toml" | "css" | "html" | "js")
-                    ) &&
-                    // skip excluded paths
-                    !excluded_paths.iter().any(|p| path.starts_with(p)) &&
-                    // respect .gitignore
-                    !gitignore
-                        .matched_path_or_any_parents(path, false)
-                        .is_ignore()
-                })
-                .collect::<Vec<_>>();
+            let started = Instant::now();
 
             // Give time for the change to take effect before reading the file
             if !real_paths.is_empty() {
                 std::thread::sleep(std::time::Duration::from_millis(10));
             }
 
-            let mut channels = channels.lock().unwrap();
-            for path in real_paths {
-                // if this file type cannot be hot reloaded, rebuild the application
-                if path.extension().and_then(|p| p.to_str()) != Some("rs") && rebuild() {
-                    return;
+            // The templates and literals of the batch, once each in their latest version
+            let mut templates: Vec<Template> = Vec::new();
+            let mut literals: Vec<LiteralUpdate> = Vec::new();
+            // The versions of the templates the apps have, from before the batch
+            let mut previous: Vec<Template> = Vec::new();
+            // The assets to reload in place, and whether the apps should start over
+            let mut assets: Vec<AssetUpdate> = Vec::new();
+            let mut full_reload = false;
+
+            // Apps that connect during the batch wait for it, so they don't miss it or get patches they can't apply
+            let mut file_map = file_map.lock().unwrap();
+
+            for (path, root) in real_paths {
+                match root.strategy_of(path) {
+                    ReloadStrategy::PatchTemplate => {}
+                    ReloadStrategy::InjectAsset => {
+                        assets.push(AssetUpdate::new(path.to_path_buf()));
+                        continue;
+                    }
+                    ReloadStrategy::FullRefresh => {
+                        full_reload = true;
+                        continue;
+                    }
+                    ReloadStrategy::Rebuild => {
+                        let reason = match root.strategies.matched(path) {
+                            Some(_) => RebuildReason::Configured,
+                            None => RebuildReason::NotRust,
+                        };
+                        // The rebuild handler may take a while or use the file map itself
+                        drop(file_map);
+                        if rebuild(RebuildRequest::new(path, reason)) {
+                            return;
+                        }
+                        // The apps were told to shut down, so the rest of the batch is stale
+                        templates.clear();
+                        literals.clear();
+                        assets.clear();
+                        full_reload = false;
+                        break;
+                    }
+                }
+
+                if let Some(cached) = file_map.map.get(path) {
+                    for template in cached.templates.values() {
+                        if !previous.iter().any(|old| old.name == template.name) {
+                            previous.push(*template);
+                        }
+                    }
                 }
+
                 // find changes to the rsx in the file
-                let changes = file_map
-                    .lock()
-                    .unwrap()
-                    .update_rsx(path, crate_dir.as_path());
+                let changes = file_map.update_rsx_and_literals(path, root.crate_dir.as_path());
 
                 match changes {
-                    Ok(UpdateResult::UpdatedRsx(msgs)) => {
-                        for msg in msgs {
-                            let mut i = 0;
-                            while i < channels.len() {
-                                let channel = &mut channels[i];
-                                if send_msg(HotReloadMsg::UpdateTemplate(msg), channel) {
-                                    i += 1;
-                                } else {
-                                    channels.remove(i);
-                                }
+                    Ok((UpdateResult::UpdatedRsx(new_templates), new_literals)) => {
+                        for template in new_templates {
+                            match templates.iter_mut().find(|t| t.name == template.name) {
+                                Some(old) => *old = template,
+                                None => templates.push(template),
+                            }
+                        }
+                        for update in new_literals {
+                            match literals.iter_mut().find(|u| u.template == update.template) {
+                                Some(old) => *old = update,
+                                None => literals.push(update),
                             }
                         }
                     }
 
-                    Ok(UpdateResult::NeedsRebuild) => {
-                        drop(channels);
-                        if rebuild() {
+                    Ok((UpdateResult::NeedsRebuild, _)) => {
+                        drop(file_map);
+                        if rebuild(RebuildRequest::new(path, RebuildReason::CodeChanged)) {
                             return;
                         }
+                        // The apps were told to shut down, so the templates are stale
+                        templates.clear();
+                        literals.clear();
+                        assets.clear();
+                        full_reload = false;
                         break;
                     }
                     Err(err) => {
@@ -371,21 +668,44 @@ pub fn init<Ctx: HotReloadingContext + Send + 'static>(cfg: Config<Ctx>) {
                 }
             }
 
+            if !templates.is_empty() || !literals.is_empty() || !assets.is_empty() || full_reload {
+                for msg in reload_messages(&previous, templates, literals) {
+                    clients.broadcast(&msg);
+                }
+                for asset in assets {
+                    clients.broadcast(&HotReloadMsg::UpdateAsset(asset));
+                }
+                // The apps start over with the templates sent before
+                if full_reload {
+                    clients.broadcast(&HotReloadMsg::FullReload);
+                }
+
+                let status = update_server_status(|status| {
+                    status.reloads += 1;
+                    status.last_reload = Some(started.elapsed());
+                });
+                clients.broadcast(&HotReloadMsg::Status(status));
+                clients.reloaded(started);
+            }
+
             last_update_time = chrono::Local::now().timestamp_millis();
         }
     });
 }
 
-fn send_msg(msg: HotReloadMsg, channel: &mut impl Write) -> bool {
-    if let Ok(msg) = serde_json::to_string(&msg) {
-        if channel.write_all(msg.as_bytes()).is_err() {
-            return false;
-        }
-        if channel.write_all(&[b'\n']).is_err() {
-            return false;
+/// The messages that bring the apps from the previous versions of the templates to the changed templates and literals
+pub(crate) fn reload_messages(
+    previous: &[Template],
+    templates: Vec<Template>,
+    literals: Vec<LiteralUpdate>,
+) -> Vec<HotReloadMsg> {
+    let templates = templates.into_iter().map(|template| {
+        // The apps have the version of the template the server sent last, so only the changes are sent
+        match previous.iter().find(|old| old.name == template.name) {
+            Some(old) => HotReloadMsg::PatchTemplate(TemplatePatch::diff(old, &template)),
+            None => HotReloadMsg::UpdateTemplate(template),
         }
-        true
-    } else {
-        false
-    }
+    });
+    let literals = literals.into_iter().map(HotReloadMsg::UpdateLiterals);
+    templates.chain(literals).collect()
 }
@@ -0,0 +1,238 @@
+//! Which changed files hot reloading reacts to
+//!
+//! Saves in `target/`, generated files or vendored directories shouldn't trigger a reload or a rebuild. Files are
+//! skipped if they are in an excluded path, match an exclude glob, or are ignored by a `.gitignore` of the project or
+//! of one of its parent directories up to the root of the repository.
+
+use std::path::{Path, PathBuf};
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+/// The files hot reloading reacts to, in the file types it knows about
+const DEFAULT_EXTENSIONS: &[&str] = &["rs", "toml", "css", "html", "js"];
+
+pub(crate) struct PathFilter {
+    excluded_paths: Vec<PathBuf>,
+    include: Option<Gitignore>,
+    exclude: Option<Gitignore>,
+    gitignores: Vec<Gitignore>,
+}
+
+impl PathFilter {
+    pub(crate) fn new(
+        crate_dir: &Path,
+        excluded_paths: &[&str],
+        include: &[&str],
+        exclude: &[&str],
+        gitignore: bool,
+        log: bool,
+    ) -> Self {
+        let globs = |globs: &[&str]| {
+            if globs.is_empty() {
+                return None;
+            }
+
+            let mut builder = GitignoreBuilder::new(crate_dir);
+            for glob in globs {
+                if let Err(err) = builder.add_line(None, glob) {
+                    if log {
+                        println!("hot reloading ignores the invalid glob {glob:?}: {err}");
+                    }
+                }
+            }
+            builder.build().ok()
+        };
+
+        let gitignores = if gitignore {
+            find_gitignores(crate_dir)
+        } else {
+            Vec::new()
+        };
+
+        Self {
+            excluded_paths: excluded_paths
+                .iter()
+                .map(|path| crate_dir.join(path))
+                .collect(),
+            include: globs(include),
+            exclude: globs(exclude),
+            gitignores,
+        }
+    }
+
+    /// Whether changes to the file or directory should be ignored
+    pub(crate) fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if self.is_excluded(path, is_dir) {
+            return true;
+        }
+
+        // Directories may contain included files even if they don't match an include glob themselves
+        if is_dir {
+            return false;
+        }
+        match &self.include {
+            Some(include) => !matches(include, path, is_dir),
+            None => !matches!(
+                path.extension().and_then(|p| p.to_str()),
+                Some(extension) if DEFAULT_EXTENSIONS.contains(&extension)
+            ),
+        }
+    }
+
+    /// Whether the file or directory is excluded, no matter if it is included. Files mapped to a
+    /// [`ReloadStrategy`](crate::ReloadStrategy) are only skipped if they are excluded.
+    pub(crate) fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        if self.excluded_paths.iter().any(|p| path.starts_with(p)) {
+            return true;
+        }
+
+        let excludes = |globs: &Gitignore| matches(globs, path, is_dir);
+        self.exclude.as_ref().is_some_and(excludes) || self.gitignores.iter().any(excludes)
+    }
+}
+
+fn matches(globs: &Gitignore, path: &Path, is_dir: bool) -> bool {
+    path.starts_with(globs.path()) && globs.matched_path_or_any_parents(path, is_dir).is_ignore()
+}
+
+/// The `.gitignore`s of the directory and its parents, up to the root of the repository
+fn find_gitignores(dir: &Path) -> Vec<Gitignore> {
+    let mut gitignores = Vec::new();
+
+    for dir in dir.ancestors() {
+        let (gitignore, err) = Gitignore::new(dir.join(".gitignore"));
+        if err.is_none() && !gitignore.is_empty() {
+            gitignores.push(gitignore);
+        }
+
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+
+    gitignores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_ignored(filter: &PathFilter, cases: &[(&str, bool, bool)]) {
+        for &(path, is_dir, ignored) in cases {
+            assert_eq!(
+                filter.is_ignored(Path::new(path), is_dir),
+                ignored,
+                "{path}"
+            );
+        }
+    }
+
+    #[test]
+    fn default_extensions_and_excludes() {
+        let filter = PathFilter::new(
+            Path::new("/project"),
+            &["target"],
+            &[],
+            &["*.generated.rs", "vendor/"],
+            false,
+            false,
+        );
+
+        assert_ignored(
+            &filter,
+            &[
+                ("/project/src/main.rs", false, false),
+                ("/project/Dioxus.toml", false, false),
+                ("/project/assets/style.css", false, false),
+                ("/project/README.md", false, true),
+                ("/project/Makefile", false, true),
+                ("/project/src", true, false),
+                ("/project/target", true, true),
+                ("/project/target/debug/build/out.rs", false, true),
+                ("/project/src/api.generated.rs", false, true),
+                ("/project/vendor", true, true),
+                ("/project/vendor/lib.rs", false, true),
+            ],
+        );
+    }
+
+    #[test]
+    fn include_globs_replace_default_extensions() {
+        let filter = PathFilter::new(
+            Path::new("/project"),
+            &["target"],
+            &["*.rs", "assets/**"],
+            &["assets/*.tmp"],
+            false,
+            false,
+        );
+
+        assert_ignored(
+            &filter,
+            &[
+                ("/project/src/main.rs", false, false),
+                ("/project/Dioxus.toml", false, true),
+                ("/project/style.css", false, true),
+                ("/project/assets/logo.svg", false, false),
+                ("/project/assets/logo.tmp", false, true),
+                ("/project/docs", true, false),
+                ("/project/target/debug/build/out.rs", false, true),
+            ],
+        );
+    }
+
+    #[test]
+    fn excluded_files_are_excluded_even_if_included() {
+        let filter = PathFilter::new(
+            Path::new("/project"),
+            &["target"],
+            &[],
+            &["*.generated.rs"],
+            false,
+            false,
+        );
+
+        let cases = [
+            ("/project/target/app.css", true),
+            ("/project/src/api.generated.rs", true),
+            ("/project/src/main.rs", false),
+            ("/project/README.md", false),
+        ];
+        for (path, excluded) in cases {
+            assert_eq!(
+                filter.is_excluded(Path::new(path), false),
+                excluded,
+                "{path}"
+            );
+        }
+    }
+
+    #[test]
+    fn gitignores_up_to_the_repository() {
+        let repo =
+            std::env::temp_dir().join(format!("dioxus-hot-reload-filter-{}", std::process::id()));
+        let app = repo.join("app");
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+        std::fs::create_dir_all(&app).unwrap();
+        std::fs::write(repo.join(".gitignore"), "generated/\n").unwrap();
+        std::fs::write(app.join(".gitignore"), "*.css\n").unwrap();
+
+        let cases = [
+            ("generated/out.rs", true),
+            ("style.css", true),
+            ("src/main.rs", false),
+        ];
+        for gitignore in [true, false] {
+            let filter = PathFilter::new(&app, &[], &[], &[], gitignore, false);
+            for (path, ignored) in cases {
+                assert_eq!(
+                    filter.is_ignored(&app.join(path), false),
+                    gitignore && ignored,
+                    "{path}, gitignore: {gitignore}"
+                );
+            }
+        }
+
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+}
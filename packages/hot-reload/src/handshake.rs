@@ -0,0 +1,275 @@
+//! The greeting the server sends every app before any message, so apps and servers from different versions of dioxus
+//! fail with a clear error instead of misreading each other
+
+use serde::{Deserialize, Serialize};
+
+use crate::{reconnect::Session, transport::Connection, Encoding};
+
+/// The version of the protocol between the server and the app. Bumped for every change old apps or servers can't
+/// handle.
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// The kinds of [`HotReloadMsg`](crate::HotReloadMsg)s this version of the protocol can carry
+const CAPABILITIES: &[&str] = &[
+    "update_template",
+    "patch_template",
+    "update_literals",
+    "update_asset",
+    "update_resolved_asset",
+    "full_reload",
+    "build_status",
+    "status",
+    "shutdown",
+];
+
+/// The first message the server sends to every app that connects
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Handshake {
+    /// The version of the protocol the server speaks
+    pub version: u32,
+
+    /// The kinds of messages the server may send
+    pub capabilities: Vec<String>,
+
+    /// The encodings the server can send messages in. The app picks one in its [`HandshakeReply`].
+    #[serde(default)]
+    pub encodings: Vec<Encoding>,
+
+    /// Whether the app has to present the token of the server in its [`HandshakeReply`], see
+    /// [`TOKEN_ENV`](crate::TOKEN_ENV)
+    #[serde(default)]
+    pub token_required: bool,
+}
+
+impl Handshake {
+    /// The handshake of this version of the protocol
+    pub fn new() -> Self {
+        Self {
+            version: PROTOCOL_VERSION,
+            capabilities: CAPABILITIES.iter().map(|c| c.to_string()).collect(),
+            encodings: Encoding::ALL.to_vec(),
+            token_required: false,
+        }
+    }
+
+    /// Only offer the encodings
+    pub fn with_encodings(self, encodings: &[Encoding]) -> Self {
+        Self {
+            encodings: encodings.to_vec(),
+            ..self
+        }
+    }
+}
+
+impl Default for Handshake {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The app's answer to the [`Handshake`]. Every message after it is in the encoding the app picked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HandshakeReply {
+    /// The encoding the server sends messages in from now on
+    pub encoding: Encoding,
+
+    /// Which app connected, for the logs of the server
+    #[serde(default)]
+    pub client: Option<ClientInfo>,
+
+    /// Whether the app opened the connection to send [`ClientMsg`](crate::ClientMsg)s instead of receiving messages
+    #[serde(default)]
+    pub reports: bool,
+
+    /// The token of the server, if the [`Handshake`] required one
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+/// What the server knows about a connected app
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientInfo {
+    /// The name of the executable
+    pub name: String,
+
+    /// The operating system the app runs on
+    pub platform: String,
+
+    /// The id of the process of the app
+    pub pid: u32,
+}
+
+impl ClientInfo {
+    /// The info of the current process
+    pub fn current() -> Self {
+        let name = std::env::current_exe()
+            .ok()
+            .and_then(|exe| Some(exe.file_stem()?.to_string_lossy().into_owned()))
+            .unwrap_or_default();
+
+        Self {
+            name,
+            platform: std::env::consts::OS.to_string(),
+            pid: std::process::id(),
+        }
+    }
+}
+
+impl std::fmt::Display for ClientInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} on {}, pid {}", self.name, self.platform, self.pid)
+    }
+}
+
+/// Greet the app, and switch to the encoding it picked. Apps that don't present the token, if there is one, are turned
+/// away.
+///
+/// Apps that connected over the network have to answer within a timeout, so they can't hold a thread of the server
+/// forever.
+#[cfg(feature = "custom_file_watcher")]
+pub(crate) fn offer(connection: &mut Connection, token: Option<&str>) -> Option<HandshakeReply> {
+    let handshake = Handshake {
+        token_required: token.is_some(),
+        ..Handshake::new()
+    };
+    if !connection.send(&handshake) {
+        return None;
+    }
+
+    let reply = connection.recv().ok()??;
+    let reply = serde_json::from_slice::<HandshakeReply>(&reply).ok()?;
+
+    if let Some(token) = token {
+        if !crate::token::matches(reply.token.as_deref(), token) {
+            let client = reply
+                .client
+                .map(|client| format!(" ({client})"))
+                .unwrap_or_default();
+            eprintln!("Turned away a hot reloading app{client} that didn't present the token of the server");
+            return None;
+        }
+    }
+
+    connection.set_encoding(reply.encoding);
+    connection.finish_handshake();
+    Some(reply)
+}
+
+/// Wait for the handshake of the server, check that the app can understand it, and pick the encoding. Reporting
+/// connections only send [`ClientMsg`](crate::ClientMsg)s to the server.
+pub(crate) fn receive(connection: &mut Connection, reports: bool) -> Result<Handshake, Session> {
+    let Ok(Some(first)) = connection.recv() else {
+        return Err(Session::Unreachable);
+    };
+    let (handshake, mut reply) = check(&first).map_err(|err| {
+        // The connection the app receives messages on already complained
+        if !reports {
+            eprintln!("{err}");
+        }
+        Session::Incompatible
+    })?;
+    reply.reports = reports;
+    if !connection.send(&reply) {
+        return Err(Session::Unreachable);
+    }
+    connection.set_encoding(reply.encoding);
+
+    Ok(handshake)
+}
+
+/// Check the handshake the server sent first, and pick the encoding for the rest of the connection
+pub(crate) fn check(first: &[u8]) -> Result<(Handshake, HandshakeReply), String> {
+    let update =
+        "Make sure the dioxus-cli you run and the dioxus version your app depends on match";
+
+    let Ok(handshake) = serde_json::from_slice::<Handshake>(first) else {
+        return Err(format!(
+            "The hot reloading server didn't send a handshake, it is likely older than this app. {update}."
+        ));
+    };
+
+    if handshake.version != PROTOCOL_VERSION {
+        return Err(format!(
+            "The hot reloading server speaks version {} of the protocol, but this app speaks version {PROTOCOL_VERSION}. {update}.",
+            handshake.version
+        ));
+    }
+
+    let unknown: Vec<_> = handshake
+        .capabilities
+        .iter()
+        .filter(|capability| !CAPABILITIES.contains(&capability.as_str()))
+        .collect();
+    if !unknown.is_empty() {
+        eprintln!(
+            "The hot reloading server may send messages this app doesn't understand ({unknown:?}). {update}."
+        );
+    }
+
+    let encoding = Encoding::ALL
+        .iter()
+        .copied()
+        .find(|encoding| handshake.encodings.contains(encoding))
+        .unwrap_or(Encoding::Json);
+
+    // Only servers that require the token get it, so it doesn't leak to other servers the app is pointed at
+    let token = if handshake.token_required {
+        let Some(token) = crate::token::token() else {
+            return Err(format!(
+                "The hot reloading server requires a token. Set {} to the token the server printed when it started.",
+                crate::TOKEN_ENV
+            ));
+        };
+        Some(token)
+    } else {
+        None
+    };
+
+    let reply = HandshakeReply {
+        encoding,
+        client: Some(ClientInfo::current()),
+        reports: false,
+        token,
+    };
+    Ok((handshake, reply))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn picked(handshake: Handshake) -> Result<Encoding, String> {
+        let (_, reply) = check(&serde_json::to_vec(&handshake).unwrap())?;
+        Ok(reply.encoding)
+    }
+
+    #[test]
+    fn picks_encoding() {
+        let cases: &[(&[Encoding], Encoding)] = &[
+            (Encoding::ALL, Encoding::MessagePack),
+            (
+                &[Encoding::Json, Encoding::MessagePack],
+                Encoding::MessagePack,
+            ),
+            (&[Encoding::MessagePack], Encoding::MessagePack),
+            (&[Encoding::Json], Encoding::Json),
+            // Servers from before encodings were negotiated only speak JSON
+            (&[], Encoding::Json),
+        ];
+
+        for (offered, expected) in cases {
+            let handshake = Handshake::new().with_encodings(offered);
+            assert_eq!(picked(handshake), Ok(*expected), "{offered:?}");
+        }
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let handshake = Handshake {
+            version: PROTOCOL_VERSION + 1,
+            ..Handshake::new()
+        };
+        assert!(picked(handshake).is_err());
+        assert!(check(b"{\"UpdateTemplate\":{}}").is_err());
+    }
+}
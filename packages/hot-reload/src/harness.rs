@@ -0,0 +1,160 @@
+//! Hot reloading from inside tests
+//!
+//! Regressions in how the watcher diffs rsx! or in what the server sends are hard to catch by hand. The harness runs
+//! a server and an app connected to it in the test process, over a real local socket, so a test can change the source
+//! of a crate and check what the app receives.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use dioxus_core::Template;
+use dioxus_rsx::{hot_reload::FileMap, hot_reload::UpdateResult, HotReloadingContext};
+
+use crate::{
+    file_watcher::reload_messages, handshake, patch, transport::Connection, HotReloadMsg,
+    HotReloadServer, Transport,
+};
+
+/// A hot reloading server, and an app that was compiled from a source file and is connected to it
+///
+/// ```rust, ignore
+/// let mut harness = HotReloadHarness::<HtmlCtx>::new(r#"fn app() -> Element { rsx! { div { "hello" } } }"#);
+/// let msgs = harness.edit(r#"fn app() -> Element { rsx! { div { class: "big", "hello" } } }"#);
+/// assert!(matches!(
+///     msgs.as_deref(),
+///     Some([HotReloadMsg::UpdateTemplate(_), HotReloadMsg::Status(_)])
+/// ));
+/// ```
+///
+/// The source lives in `src/main.rs` of a crate in a temporary directory, which is removed once the harness is
+/// dropped.
+///
+/// # Panics
+///
+/// The harness panics if the crate can't be written or the app can't connect, since the test can't go on either way.
+pub struct HotReloadHarness<Ctx: HotReloadingContext> {
+    dir: PathBuf,
+    file: PathBuf,
+    file_map: FileMap<Ctx>,
+    server: HotReloadServer,
+    app: Connection,
+    templates: HashMap<String, Template>,
+}
+
+impl<Ctx: HotReloadingContext + Send + 'static> HotReloadHarness<Ctx> {
+    /// Start a server, and connect an app compiled from the source
+    pub fn new(source: &str) -> Self {
+        static CRATES: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "dioxus-hot-reload-{}-{}",
+            std::process::id(),
+            CRATES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = dir.join("src").join("main.rs");
+
+        std::fs::create_dir_all(dir.join("src")).expect("failed to create the crate of the test");
+        // Its own workspace, so template names are relative to the crate like in a real app
+        std::fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"hot-reload-harness\"\nversion = \"0.0.0\"\nedition = \"2021\"\n\n[workspace]\n",
+        )
+        .expect("failed to write the manifest of the test crate");
+        std::fs::write(&file, source).expect("failed to write the source of the test crate");
+
+        let file_map = FileMap::<Ctx>::create(dir.clone())
+            .expect("failed to read the test crate")
+            .map;
+
+        let socket = dir.join("dioxusin");
+        let server = HotReloadServer::bind(&socket).expect("failed to start the server");
+        let mut app = Transport::LocalSocket(socket)
+            .connect()
+            .expect("failed to connect the app");
+        if handshake::receive(&mut app, false).is_err() {
+            panic!("the app and the server of the harness didn't agree on a handshake");
+        }
+
+        let mut harness = Self {
+            dir,
+            file,
+            file_map,
+            server,
+            app,
+            templates: HashMap::new(),
+        };
+        // The server greets every app it added with its status, so nothing the test sends can miss the app
+        harness.receive();
+        harness
+    }
+
+    /// The file the source is in. Template names start with its path relative to the crate, `src/main.rs`.
+    pub fn path(&self) -> &Path {
+        &self.file
+    }
+
+    /// Change the source like the watcher would see it saved, and return every message the app receives for the
+    /// change until the [`HotReloadMsg::Status`] that ends it. Patches are resolved to whole templates like the
+    /// clients do.
+    ///
+    /// Returns `None` if the change can't be hot reloaded, and the app would be rebuilt.
+    pub fn edit(&mut self, source: &str) -> Option<Vec<HotReloadMsg>> {
+        std::fs::write(&self.file, source).expect("failed to write the source of the test crate");
+
+        let previous: Vec<Template> = self
+            .file_map
+            .map
+            .get(&self.file)
+            .map(|cached| cached.templates.values().copied().collect())
+            .unwrap_or_default();
+
+        let (templates, literals) =
+            match self.file_map.update_rsx_and_literals(&self.file, &self.dir) {
+                Ok((UpdateResult::UpdatedRsx(templates), literals)) => (templates, literals),
+                Ok((UpdateResult::NeedsRebuild, _)) | Err(_) => return None,
+            };
+
+        for msg in reload_messages(&previous, templates, literals) {
+            self.server.send(msg);
+        }
+        self.server.finish_reload();
+
+        Some(self.receive())
+    }
+
+    /// The messages of the reload, until its status
+    fn receive(&mut self) -> Vec<HotReloadMsg> {
+        let mut msgs = Vec::new();
+
+        loop {
+            let frame = self
+                .app
+                .recv()
+                .ok()
+                .flatten()
+                .expect("the server of the harness closed the connection");
+            let msg = self
+                .app
+                .encoding()
+                .decode(&frame)
+                .expect("the app couldn't decode a message of the server");
+
+            let Some(msg) = patch::resolve_in(&mut self.templates, msg) else {
+                continue;
+            };
+            let done = matches!(msg, HotReloadMsg::Status(_));
+            msgs.push(msg);
+            if done {
+                return msgs;
+            }
+        }
+    }
+}
+
+impl<Ctx: HotReloadingContext> Drop for HotReloadHarness<Ctx> {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
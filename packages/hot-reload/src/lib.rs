@@ -1,64 +1,214 @@
-use std::{
-    io::{BufRead, BufReader},
-    path::PathBuf,
-};
-
-use dioxus_core::Template;
+use dioxus_core::{LiteralUpdate, Template};
 #[cfg(feature = "file_watcher")]
 pub use dioxus_html::HtmlCtx;
-use interprocess::local_socket::LocalSocketStream;
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "custom_file_watcher")]
+mod clients;
 #[cfg(feature = "custom_file_watcher")]
 mod file_watcher;
 #[cfg(feature = "custom_file_watcher")]
+mod filter;
+#[cfg(feature = "custom_file_watcher")]
+mod harness;
+#[cfg(feature = "custom_file_watcher")]
+mod rebuild;
+#[cfg(feature = "custom_file_watcher")]
+mod root;
+#[cfg(feature = "custom_file_watcher")]
+mod server;
+#[cfg(feature = "custom_file_watcher")]
+mod strategy;
+#[cfg(feature = "custom_file_watcher")]
 pub use file_watcher::*;
+#[cfg(feature = "custom_file_watcher")]
+pub use harness::HotReloadHarness;
+#[cfg(feature = "custom_file_watcher")]
+pub use rebuild::{RebuildReason, RebuildRequest};
+#[cfg(feature = "custom_file_watcher")]
+pub use root::WatchRoot;
+#[cfg(feature = "custom_file_watcher")]
+pub use server::HotReloadServer;
+#[cfg(feature = "custom_file_watcher")]
+pub use strategy::ReloadStrategy;
+
+mod asset;
+pub use asset::{AssetUpdate, ResolvedAsset};
+
+#[cfg(feature = "tokio")]
+mod async_client;
+#[cfg(feature = "tokio")]
+pub use async_client::{connect_async, connect_async_with, connect_async_with_backoff};
+
+mod encoding;
+pub use encoding::Encoding;
+
+mod handshake;
+pub use handshake::{ClientInfo, Handshake, HandshakeReply, PROTOCOL_VERSION};
+
+mod patch;
+pub use patch::{NodePatch, TemplatePatch};
+
+mod report;
+pub use report::{forward_panics, send_to_server, ClientMsg};
+
+mod reconnect;
+pub use reconnect::Backoff;
+use reconnect::Session;
+
+mod status;
+pub use status::{server_status, status, HotReloadStatus};
+
+mod token;
+pub use token::{set_token, TOKEN_ENV};
+
+mod transport;
+pub use transport::{
+    bind_local_socket, local_socket_name, socket_path, Transport, SOCKET_ENV, TCP_ENV,
+    WEBSOCKET_ENV,
+};
 
 /// A message the hot reloading server sends to the client
 #[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(bound(deserialize = "'de: 'static"))]
 pub enum HotReloadMsg {
     /// A template has been updated
     UpdateTemplate(Template),
 
+    /// A template the app received before has been updated, and only the changes are sent. Clients turn these into
+    /// [`HotReloadMsg::UpdateTemplate`] before they are passed on, so apps never see them.
+    PatchTemplate(TemplatePatch),
+
+    /// Literals in a template have been updated, like the text of a formatted string or a number passed to a
+    /// component. The template itself is unchanged.
+    UpdateLiterals(LiteralUpdate),
+
     /// An asset discovered by rsx! has been updated
-    UpdateAsset(PathBuf),
+    UpdateAsset(AssetUpdate),
+
+    /// An asset collected by the `asset!` macro has been updated and processed again. It is still served from the url
+    /// the app was compiled with.
+    UpdateResolvedAsset {
+        /// The asset that changed
+        asset: AssetUpdate,
 
-    /// The program needs to be recompiled, and the client should shut down
-    Shutdown,
+        /// Where the app loads the asset from, and the hash of its new contents
+        resolved: ResolvedAsset,
+    },
+
+    /// Something changed that templates can't express, but that doesn't need a recompile. Web and liveview clients
+    /// reload the page, desktop clients start the app over with a new VirtualDom.
+    FullReload,
+
+    /// A build finished. The client shows the errors over the app until a build succeeds.
+    BuildStatus {
+        /// The rendered compiler errors, empty if the build succeeded
+        errors: Vec<String>,
+
+        /// The rendered compiler warnings
+        warnings: Vec<String>,
+    },
+
+    /// The server reloaded templates or an app connected. [`status()`] returns the latest one.
+    Status(HotReloadStatus),
+
+    /// Hot reloading ended, for the reason. Apps only exit if they are replaced by a new instance, see
+    /// [`ShutdownReason`].
+    Shutdown(ShutdownReason),
+}
+
+/// Why the hot reloading server sent [`HotReloadMsg::Shutdown`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum ShutdownReason {
+    /// A change can't be hot reloaded and the server is rebuilding the app. The server replaces the app once the
+    /// build succeeded, so apps keep running and show that they are rebuilding, in case the build fails.
+    RebuildRequired,
+
+    /// A new instance of the app was started, like by the command set with
+    /// [`Config::with_rebuild_command`](crate::Config::with_rebuild_command). Apps exit to make room for it.
+    Restart,
+
+    /// The server stopped watching for changes. Apps keep running without hot reloading.
+    WatcherExiting,
+
+    /// The server failed with the error and can't go on. Apps keep running without hot reloading.
+    FatalError(String),
 }
 
 /// Connect to the hot reloading listener. The callback provided will be called every time a template change is detected
-pub fn connect(mut callback: impl FnMut(HotReloadMsg) + Send + 'static) {
-    std::thread::spawn(move || {
-        let path = PathBuf::from("./").join("target").join("dioxusin");
+///
+/// The transport is picked from the environment, see [`Transport::from_env`]. If the server isn't up yet or restarts,
+/// the app keeps reconnecting with the default [`Backoff`].
+pub fn connect(callback: impl FnMut(HotReloadMsg) + Send + 'static) {
+    connect_with(Transport::from_env(), callback)
+}
 
-        // There might be a socket since the we're not running under the hot reloading server
-        let Ok(socket) = LocalSocketStream::connect(path) else {
-            return;
-        };
+/// Connect to the hot reloading listener over the transport. The callback provided will be called every time a template change is detected
+pub fn connect_with(transport: Transport, callback: impl FnMut(HotReloadMsg) + Send + 'static) {
+    connect_with_backoff(transport, Backoff::default(), callback)
+}
 
-        let mut buf_reader = BufReader::new(socket);
+/// Connect to the hot reloading listener over the transport, and reconnect with the backoff whenever the connection
+/// fails or closes
+///
+/// The app also opens a second connection to report back to the server, see [`send_to_server`].
+pub fn connect_with_backoff(
+    transport: Transport,
+    backoff: Backoff,
+    mut callback: impl FnMut(HotReloadMsg) + Send + 'static,
+) {
+    report::start(transport.clone(), backoff);
+
+    std::thread::spawn(move || {
+        let mut delays = backoff.delays();
 
         loop {
-            let mut buf = String::new();
+            let session = receive(&transport, &mut callback);
+            let Some(delay) = delays.after(session) else {
+                return;
+            };
+            std::thread::sleep(delay);
+        }
+    });
+}
+
+/// Pass the messages of one connection to the callback until it closes
+fn receive(transport: &Transport, callback: &mut impl FnMut(HotReloadMsg)) -> Session {
+    // There might be a socket since the we're not running under the hot reloading server
+    let Ok(mut connection) = transport.connect() else {
+        return Session::Unreachable;
+    };
+
+    if let Err(session) = handshake::receive(&mut connection, false) {
+        return session;
+    }
+    status::set_client_status(Some(HotReloadStatus::default()));
 
-            if let Err(err) = buf_reader.read_line(&mut buf) {
-                if err.kind() != std::io::ErrorKind::WouldBlock {
-                    break;
-                }
+    loop {
+        let frame = match connection.recv() {
+            Ok(Some(frame)) => frame,
+            Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => continue,
+            Ok(None) | Err(_) => {
+                status::set_client_status(None);
+                return Session::Closed;
             }
+        };
 
-            let Ok(template) = serde_json::from_str(Box::leak(buf.into_boxed_str())) else {
-                eprintln!(
-                    "Could not parse hot reloading message - make sure your client is up to date"
-                );
-                continue;
-            };
+        let Ok(msg) = connection.encoding().decode(&frame) else {
+            eprintln!(
+                "Could not parse hot reloading message - make sure your client is up to date"
+            );
+            continue;
+        };
 
-            callback(template);
+        let Some(msg) = patch::resolve(msg) else {
+            continue;
+        };
+        if let HotReloadMsg::Status(latest) = &msg {
+            status::set_client_status(Some(latest.clone()));
         }
-    });
+        callback(msg);
+    }
 }
 
 /// Start the hot reloading server with the current directory as the root
@@ -66,11 +216,18 @@ pub fn connect(mut callback: impl FnMut(HotReloadMsg) + Send + 'static) {
 macro_rules! hot_reload_init {
     () => {
         #[cfg(debug_assertions)]
-        dioxus_hot_reload::init(dioxus_hot_reload::Config::new().root(env!("CARGO_MANIFEST_DIR")));
+        dioxus_hot_reload::init(
+            dioxus_hot_reload::Config::new()
+                .root(env!("CARGO_MANIFEST_DIR"))
+                .package(env!("CARGO_PKG_NAME")),
+        );
     };
 
     ($cfg: expr) => {
         #[cfg(debug_assertions)]
-        dioxus_hot_reload::init($cfg.root(env!("CARGO_MANIFEST_DIR")));
+        dioxus_hot_reload::init(
+            $cfg.root(env!("CARGO_MANIFEST_DIR"))
+                .package(env!("CARGO_PKG_NAME")),
+        );
     };
 }
@@ -0,0 +1,323 @@
+//! The changes between two versions of a template
+//!
+//! Large rsx! calls make large templates, while most edits only touch a few nodes of them. Once the server sent a
+//! template, it only sends the nodes and attributes that changed since. The app patches the last version it received,
+//! and hands the VirtualDom the whole new template.
+
+use std::{collections::HashMap, sync::Mutex};
+
+use dioxus_core::{Template, TemplateAttribute, TemplateNode};
+use serde::{Deserialize, Serialize};
+
+use crate::HotReloadMsg;
+
+/// The changes that turn the last version of a template the app received into the new one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplatePatch {
+    /// The name of the template, which is the same for every version
+    pub name: String,
+
+    /// The [`fingerprint`] of the version the patch applies to, so a patch never lands on another version that happens
+    /// to have the same shape
+    pub base: u64,
+
+    /// The roots of the new version
+    pub roots: Vec<NodePatch>,
+
+    /// The paths of the dynamic nodes, if they changed
+    pub node_paths: Option<Vec<Vec<u8>>>,
+
+    /// The paths of the dynamic attributes, if they changed
+    pub attr_paths: Option<Vec<Vec<u8>>>,
+}
+
+/// A node of the new version of a template
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum NodePatch {
+    /// The node at the same position of the last version, unchanged
+    Keep,
+
+    /// A node that is new, or changed beyond its attributes and children
+    Replace(TemplateNode),
+
+    /// The element at the same position of the last version, with its attributes if they changed and its patched
+    /// children
+    Element {
+        /// The attributes of the element, if they changed
+        attrs: Option<Vec<TemplateAttribute>>,
+
+        /// The children of the new version of the element
+        children: Vec<NodePatch>,
+    },
+}
+
+impl TemplatePatch {
+    /// The changes from the old to the new version of a template
+    pub fn diff(old: &Template, new: &Template) -> Self {
+        let paths = |old: &[&[u8]], new: &[&[u8]]| {
+            (old != new).then(|| new.iter().map(|path| path.to_vec()).collect())
+        };
+
+        Self {
+            name: new.name.to_string(),
+            base: fingerprint(old),
+            roots: diff_nodes(old.roots, new.roots),
+            node_paths: paths(old.node_paths, new.node_paths),
+            attr_paths: paths(old.attr_paths, new.attr_paths),
+        }
+    }
+
+    /// The new version of the template, or `None` if the patch doesn't fit the old version
+    pub fn apply(&self, old: &Template) -> Option<Template> {
+        if fingerprint(old) != self.base {
+            return None;
+        }
+
+        // Templates are compile time values, so the new version is leaked like the templates the app receives whole
+        let paths = |paths: &Option<Vec<Vec<u8>>>, old: &'static [&'static [u8]]| match paths {
+            Some(paths) => &*Box::leak(
+                paths
+                    .iter()
+                    .map(|path| &*Box::leak(path.clone().into_boxed_slice()))
+                    .collect::<Box<[_]>>(),
+            ),
+            None => old,
+        };
+
+        Some(Template {
+            name: old.name,
+            roots: apply_nodes(old.roots, &self.roots)?,
+            node_paths: paths(&self.node_paths, old.node_paths),
+            attr_paths: paths(&self.attr_paths, old.attr_paths),
+        })
+    }
+}
+
+/// A hash of the template that is the same in the server and in apps built for any platform, unlike [`std::hash::Hash`]
+/// which depends on the size of `usize`
+fn fingerprint(template: &Template) -> u64 {
+    // FNV-1a over the serialized template
+    serde_json::to_vec(template)
+        .unwrap_or_default()
+        .iter()
+        .fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+        })
+}
+
+fn diff_nodes(old: &[TemplateNode], new: &[TemplateNode]) -> Vec<NodePatch> {
+    new.iter()
+        .enumerate()
+        .map(|(index, new)| match (old.get(index), new) {
+            (Some(old), new) if old == new => NodePatch::Keep,
+            (
+                Some(TemplateNode::Element {
+                    tag,
+                    namespace,
+                    attrs,
+                    children,
+                }),
+                TemplateNode::Element {
+                    tag: new_tag,
+                    namespace: new_namespace,
+                    attrs: new_attrs,
+                    children: new_children,
+                },
+            ) if tag == new_tag && namespace == new_namespace => NodePatch::Element {
+                attrs: (attrs != new_attrs).then(|| new_attrs.to_vec()),
+                children: diff_nodes(children, new_children),
+            },
+            _ => NodePatch::Replace(*new),
+        })
+        .collect()
+}
+
+fn apply_nodes(
+    old: &'static [TemplateNode],
+    patches: &[NodePatch],
+) -> Option<&'static [TemplateNode]> {
+    // Unchanged nodes keep sharing the old version
+    if patches.len() == old.len() && patches.iter().all(|patch| matches!(patch, NodePatch::Keep)) {
+        return Some(old);
+    }
+
+    let nodes = patches
+        .iter()
+        .enumerate()
+        .map(|(index, patch)| match patch {
+            NodePatch::Keep => old.get(index).copied(),
+            NodePatch::Replace(node) => Some(*node),
+            NodePatch::Element {
+                attrs: new_attrs,
+                children: new_children,
+            } => match *old.get(index)? {
+                TemplateNode::Element {
+                    tag,
+                    namespace,
+                    attrs,
+                    children,
+                } => Some(TemplateNode::Element {
+                    tag,
+                    namespace,
+                    attrs: match new_attrs {
+                        Some(new_attrs) => &*Box::leak(new_attrs.clone().into_boxed_slice()),
+                        None => attrs,
+                    },
+                    children: apply_nodes(children, new_children)?,
+                }),
+                _ => None,
+            },
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(Box::leak(nodes.into_boxed_slice()))
+}
+
+/// The last version of every template the app received, by name
+static TEMPLATES: Mutex<Option<HashMap<String, Template>>> = Mutex::new(None);
+
+/// Remember the templates the app receives, and turn patches into the whole template they patch to
+pub(crate) fn resolve(msg: HotReloadMsg) -> Option<HotReloadMsg> {
+    let mut templates = TEMPLATES.lock().unwrap();
+    resolve_in(templates.get_or_insert_with(HashMap::new), msg)
+}
+
+/// Like [`resolve`], with the templates of one app instead of the ones of this process
+pub(crate) fn resolve_in(
+    templates: &mut HashMap<String, Template>,
+    msg: HotReloadMsg,
+) -> Option<HotReloadMsg> {
+    match msg {
+        HotReloadMsg::UpdateTemplate(template) => {
+            templates.insert(template.name.to_string(), template);
+            Some(msg)
+        }
+        HotReloadMsg::PatchTemplate(patch) => {
+            let Some(template) = templates.get(&patch.name).and_then(|old| patch.apply(old)) else {
+                eprintln!(
+                    "Could not apply the hot reloading patch of {} - the app doesn't have the version of the template it patches",
+                    patch.name
+                );
+                return None;
+            };
+            templates.insert(patch.name, template);
+            Some(HotReloadMsg::UpdateTemplate(template))
+        }
+        msg => Some(msg),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HELLO: TemplateNode = TemplateNode::Text { text: "hello" };
+    const WORLD: TemplateNode = TemplateNode::Text { text: "world" };
+    const BIG: TemplateAttribute = TemplateAttribute::Static {
+        name: "class",
+        value: "big",
+        namespace: None,
+    };
+    const SMALL: TemplateAttribute = TemplateAttribute::Static {
+        name: "class",
+        value: "small",
+        namespace: None,
+    };
+
+    fn template(roots: &'static [TemplateNode]) -> Template {
+        Template {
+            name: "src/main.rs:4:5:0",
+            roots,
+            node_paths: &[],
+            attr_paths: &[],
+        }
+    }
+
+    // A macro instead of a function, so the nodes are promoted to the `'static` slices templates need
+    macro_rules! div {
+        ([$($attr:expr),*], [$($child:expr),*]) => {
+            TemplateNode::Element {
+                tag: "div",
+                namespace: None,
+                attrs: &[$($attr),*],
+                children: &[$($child),*],
+            }
+        };
+    }
+
+    #[test]
+    fn apply_reverses_diff() {
+        let base = template(&[div!([BIG], [HELLO])]);
+        let cases = [
+            ("unchanged", base),
+            ("changed text", template(&[div!([BIG], [WORLD])])),
+            ("added child", template(&[div!([BIG], [HELLO, WORLD])])),
+            ("removed child", template(&[div!([BIG], [])])),
+            ("changed attribute", template(&[div!([SMALL], [HELLO])])),
+            ("added attribute", template(&[div!([BIG, SMALL], [HELLO])])),
+            ("removed attribute", template(&[div!([], [HELLO])])),
+            ("added root", template(&[div!([BIG], [HELLO]), WORLD])),
+            ("removed root", template(&[])),
+            ("replaced root", template(&[WORLD])),
+            (
+                "changed paths",
+                Template {
+                    node_paths: &[&[0, 1]],
+                    ..template(&[div!([BIG], [HELLO, TemplateNode::Dynamic { id: 0 }])])
+                },
+            ),
+        ];
+
+        for (case, new) in cases {
+            let patch = TemplatePatch::diff(&base, &new);
+            assert_eq!(patch.apply(&base), Some(new), "{case}");
+        }
+    }
+
+    #[test]
+    fn unchanged_nodes_are_kept() {
+        let base = template(&[div!([BIG], [HELLO]), HELLO]);
+        let new = template(&[div!([BIG], [HELLO]), WORLD]);
+
+        let patch = TemplatePatch::diff(&base, &new);
+        assert_eq!(patch.roots[0], NodePatch::Keep);
+        assert_eq!(patch.roots[1], NodePatch::Replace(WORLD));
+        assert_eq!(patch.node_paths, None);
+        assert_eq!(patch.attr_paths, None);
+    }
+
+    #[test]
+    fn stale_base_is_rejected() {
+        let base = template(&[div!([BIG], [HELLO])]);
+        let new = template(&[div!([SMALL], [HELLO])]);
+        let patch = TemplatePatch::diff(&base, &new);
+
+        // The same shape as the base, but another version of it
+        let stale = template(&[div!([BIG], [WORLD])]);
+        assert_eq!(patch.apply(&stale), None);
+
+        // The patch was applied already
+        assert_eq!(patch.apply(&new), None);
+    }
+
+    #[test]
+    fn resolves_patches_against_the_received_template() {
+        let base = template(&[div!([BIG], [HELLO])]);
+        let new = template(&[div!([SMALL], [HELLO])]);
+        let patch = HotReloadMsg::PatchTemplate(TemplatePatch::diff(&base, &new));
+
+        let mut templates = HashMap::new();
+        assert!(resolve_in(&mut templates, patch.clone()).is_none());
+
+        resolve_in(&mut templates, HotReloadMsg::UpdateTemplate(base));
+        let Some(HotReloadMsg::UpdateTemplate(resolved)) =
+            resolve_in(&mut templates, patch.clone())
+        else {
+            panic!("the patch didn't resolve to a template");
+        };
+        assert_eq!(resolved, new);
+
+        // The app has the new version now, so the same patch doesn't fit anymore
+        assert!(resolve_in(&mut templates, patch).is_none());
+    }
+}
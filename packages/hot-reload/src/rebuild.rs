@@ -0,0 +1,43 @@
+//! Why hot reloading gave up on a change and the app needs to be rebuilt
+
+use std::path::{Path, PathBuf};
+
+/// A change hot reloading can't apply, passed to the handler set with
+/// [`Config::with_rebuild_handler`](crate::Config::with_rebuild_handler)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RebuildRequest {
+    path: PathBuf,
+    reason: RebuildReason,
+}
+
+impl RebuildRequest {
+    pub(crate) fn new(path: &Path, reason: RebuildReason) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            reason,
+        }
+    }
+
+    /// The file that changed
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Why the change can't be hot reloaded
+    pub fn reason(&self) -> RebuildReason {
+        self.reason
+    }
+}
+
+/// Why a change can't be hot reloaded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RebuildReason {
+    /// A file other than Rust code changed, like `Cargo.toml`
+    NotRust,
+
+    /// Rust code outside of rsx! changed, or rsx! changed in a way templates can't express, or a new file was added
+    CodeChanged,
+
+    /// The file matches a pattern mapped to [`ReloadStrategy::Rebuild`](crate::ReloadStrategy::Rebuild)
+    Configured,
+}
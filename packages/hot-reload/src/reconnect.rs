@@ -0,0 +1,179 @@
+//! Retrying the connection to the server, which may not be up yet when the app starts or may restart while it runs
+
+use std::time::Duration;
+
+/// How long the app waits between attempts to connect to the server
+///
+/// The delay starts at `initial` and doubles after every failed attempt, up to `max`. It starts over once the app
+/// connected.
+///
+/// By default the app retries for about five minutes after the server went away, so apps that outlive their server
+/// don't keep trying forever.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    /// The delay before the first retry
+    pub initial: Duration,
+
+    /// The longest delay between two attempts
+    pub max: Duration,
+
+    /// How many times to retry before giving up, or `None` to retry as long as the app runs
+    pub retries: Option<u32>,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_millis(100),
+            max: Duration::from_secs(5),
+            retries: Some(64),
+        }
+    }
+}
+
+impl Backoff {
+    /// Never retry, and stop receiving messages after the first connection failed or closed
+    pub fn never() -> Self {
+        Self {
+            retries: Some(0),
+            ..Self::default()
+        }
+    }
+
+    /// The delays before every retry
+    pub(crate) fn delays(self) -> Delays {
+        Delays {
+            backoff: self,
+            next: self.initial,
+            retried: 0,
+        }
+    }
+}
+
+/// The delays of a [`Backoff`], until it gives up
+pub(crate) struct Delays {
+    backoff: Backoff,
+    next: Duration,
+    retried: u32,
+}
+
+impl Iterator for Delays {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        if matches!(self.backoff.retries, Some(retries) if self.retried >= retries) {
+            return None;
+        }
+        self.retried = self.retried.saturating_add(1);
+
+        let delay = self.next;
+        self.next = (delay * 2).min(self.backoff.max);
+        Some(delay)
+    }
+}
+
+impl Delays {
+    /// The delay before the attempt that follows the session, or `None` once the app should give up
+    pub(crate) fn after(&mut self, session: Session) -> Option<Duration> {
+        match session {
+            Session::Unreachable => {}
+            // Start over with short delays, the server is likely restarting
+            Session::Closed => *self = self.backoff.delays(),
+            Session::Incompatible => return None,
+        }
+        self.next()
+    }
+}
+
+/// How a connection to the server ended
+pub(crate) enum Session {
+    /// The app couldn't reach the server
+    Unreachable,
+
+    /// The app was connected, until the server went away
+    Closed,
+
+    /// The server speaks a protocol the app doesn't understand. Retrying won't help.
+    Incompatible,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(delays: impl Iterator<Item = Duration>) -> Vec<u128> {
+        delays.map(|delay| delay.as_millis()).collect()
+    }
+
+    #[test]
+    fn delays_double_up_to_the_cap() {
+        let backoff = Backoff {
+            initial: Duration::from_millis(100),
+            max: Duration::from_millis(1000),
+            retries: None,
+        };
+
+        assert_eq!(
+            millis(backoff.delays().take(7)),
+            [100, 200, 400, 800, 1000, 1000, 1000]
+        );
+    }
+
+    #[test]
+    fn gives_up_after_the_retries() {
+        let cases = [
+            (Backoff::default(), 64),
+            (Backoff::never(), 0),
+            (
+                Backoff {
+                    retries: Some(3),
+                    ..Backoff::default()
+                },
+                3,
+            ),
+        ];
+
+        for (backoff, retries) in cases {
+            assert_eq!(backoff.delays().count(), retries, "{backoff:?}");
+        }
+    }
+
+    #[test]
+    fn connected_session_starts_over() {
+        let backoff = Backoff {
+            initial: Duration::from_millis(100),
+            max: Duration::from_secs(5),
+            retries: Some(4),
+        };
+        let mut delays = backoff.delays();
+
+        assert_eq!(
+            delays.after(Session::Unreachable),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(
+            delays.after(Session::Unreachable),
+            Some(Duration::from_millis(200))
+        );
+        assert_eq!(
+            delays.after(Session::Unreachable),
+            Some(Duration::from_millis(400))
+        );
+
+        // The retries start over too, so the app doesn't give up on a server that restarted
+        assert_eq!(
+            delays.after(Session::Closed),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(
+            millis(std::iter::from_fn(|| delays.after(Session::Unreachable))),
+            [200, 400, 800]
+        );
+    }
+
+    #[test]
+    fn incompatible_server_is_not_retried() {
+        let mut delays = Backoff::default().delays();
+        assert_eq!(delays.after(Session::Incompatible), None);
+    }
+}
@@ -0,0 +1,103 @@
+//! Messages the app sends back to the hot reloading server
+//!
+//! The server only writes to the connection an app receives messages on, so the app reports on a second connection.
+//! Apps tell the server once they applied a reload, so it can measure how long reloads take end to end, and forward
+//! their panics and log lines to the terminal the server runs in.
+
+use std::sync::{
+    mpsc::{self, Receiver, Sender},
+    Mutex,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{handshake, reconnect::Session, Backoff, Transport};
+
+/// A message the app sends to the hot reloading server
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClientMsg {
+    /// The app applied and rendered the messages of a reload. Every reload ends with a
+    /// [`HotReloadMsg::Status`](crate::HotReloadMsg::Status), so apps send this once they handled it.
+    Ready,
+
+    /// A line the app logged
+    Log(String),
+
+    /// The app panicked, with the message and location of the panic
+    Panic(String),
+}
+
+/// The messages waiting to be sent by the thread that reports to the server, if it was started
+static OUTBOX: Mutex<Option<Sender<ClientMsg>>> = Mutex::new(None);
+
+/// Send the message to the hot reloading server the app connected to with [`connect`](crate::connect)
+///
+/// Messages are queued while the app is reconnecting, and dropped if the app never connected.
+pub fn send_to_server(msg: ClientMsg) {
+    if let Some(outbox) = &*OUTBOX.lock().unwrap() {
+        let _ = outbox.send(msg);
+    }
+}
+
+/// Forward the panics of the app to the hot reloading server, after the panic hook that was set before
+pub fn forward_panics() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        send_to_server(ClientMsg::Panic(info.to_string()));
+        previous(info);
+    }));
+}
+
+/// Start reporting to the server on a separate thread, unless the app already does
+pub(crate) fn start(transport: Transport, backoff: Backoff) {
+    let mut outbox = OUTBOX.lock().unwrap();
+    if outbox.is_some() {
+        return;
+    }
+    let (tx, rx) = mpsc::channel();
+    *outbox = Some(tx);
+
+    std::thread::spawn(move || {
+        let mut delays = backoff.delays();
+        let mut unsent = None;
+
+        loop {
+            let session = report(&transport, &rx, &mut unsent);
+            let Some(delay) = delays.after(session) else {
+                return;
+            };
+            std::thread::sleep(delay);
+        }
+    });
+}
+
+/// Send the queued messages over one connection until it closes. A message that couldn't be sent is kept in `unsent`
+/// and sent first once the app reconnected.
+fn report(
+    transport: &Transport,
+    rx: &Receiver<ClientMsg>,
+    unsent: &mut Option<ClientMsg>,
+) -> Session {
+    let Ok(mut connection) = transport.connect() else {
+        return Session::Unreachable;
+    };
+
+    if let Err(session) = handshake::receive(&mut connection, true) {
+        return session;
+    }
+
+    loop {
+        let msg = match unsent.take() {
+            Some(msg) => msg,
+            // The outbox is never dropped, but if it was there would be nothing left to send
+            None => match rx.recv() {
+                Ok(msg) => msg,
+                Err(_) => return Session::Incompatible,
+            },
+        };
+        if !connection.send(&msg) {
+            *unsent = Some(msg);
+            return Session::Closed;
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! The directories the file watcher watches for changes
+//!
+//! The watcher roots at the crate [`hot_reload_init`](crate::hot_reload_init) is called in, but components often live
+//! in other crates of the workspace or in shared libraries outside of it. Every root is a crate with its own settings,
+//! and changes in it are hot reloaded relative to that crate.
+
+use std::path::{Path, PathBuf};
+
+use krates::cm::MetadataCommand;
+
+use crate::{
+    filter::PathFilter,
+    strategy::{ReloadStrategy, Strategies},
+};
+
+/// Another crate to watch for changes, next to the root of the [`Config`](crate::Config)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchRoot {
+    path: PathBuf,
+    paths: &'static [&'static str],
+    include_globs: &'static [&'static str],
+    exclude_globs: &'static [&'static str],
+}
+
+impl WatchRoot {
+    /// Watch the crate at the path (where its Cargo.toml file is). Relative paths are relative to the root of the
+    /// [`Config`](crate::Config).
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            paths: &[""],
+            include_globs: &[],
+            exclude_globs: &[],
+        }
+    }
+
+    /// Only watch these paths of the crate, see [`Config::with_paths`](crate::Config::with_paths)
+    pub fn with_paths(self, paths: &'static [&'static str]) -> Self {
+        Self { paths, ..self }
+    }
+
+    /// Only react to changes in files of the crate matching the globs, see
+    /// [`Config::with_include_globs`](crate::Config::with_include_globs)
+    pub fn with_include_globs(self, globs: &'static [&'static str]) -> Self {
+        Self {
+            include_globs: globs,
+            ..self
+        }
+    }
+
+    /// Ignore changes in files of the crate matching the globs, see
+    /// [`Config::with_exclude_globs`](crate::Config::with_exclude_globs)
+    pub fn with_exclude_globs(self, globs: &'static [&'static str]) -> Self {
+        Self {
+            exclude_globs: globs,
+            ..self
+        }
+    }
+}
+
+/// A crate the watcher watches, with its settings resolved
+pub(crate) struct Root {
+    pub(crate) crate_dir: PathBuf,
+    pub(crate) filter: PathFilter,
+    pub(crate) strategies: Strategies,
+    paths: &'static [&'static str],
+}
+
+impl Root {
+    pub(crate) fn new(
+        crate_dir: PathBuf,
+        paths: &'static [&'static str],
+        filter: PathFilter,
+        strategies: Strategies,
+    ) -> Self {
+        Self {
+            crate_dir,
+            filter,
+            strategies,
+            paths,
+        }
+    }
+
+    /// Resolve the settings of another crate to watch. The excluded paths, `.gitignore` setting and reload strategies
+    /// are shared by all roots.
+    pub(crate) fn resolve(
+        root: WatchRoot,
+        main_dir: &Path,
+        excluded_paths: &[&str],
+        gitignore: bool,
+        strategies: &[(&'static str, ReloadStrategy)],
+        log: bool,
+    ) -> Self {
+        let crate_dir = main_dir.join(root.path);
+        let filter = PathFilter::new(
+            &crate_dir,
+            excluded_paths,
+            root.include_globs,
+            root.exclude_globs,
+            gitignore,
+            log,
+        );
+        let strategies = Strategies::new(&crate_dir, strategies, log);
+        Self::new(crate_dir, root.paths, filter, strategies)
+    }
+
+    /// How to reload the changed file
+    pub(crate) fn strategy_of(&self, path: &Path) -> ReloadStrategy {
+        self.strategies
+            .matched(path)
+            .unwrap_or_else(|| ReloadStrategy::default_for(path))
+    }
+
+    /// Whether changes to the file should be ignored. Files mapped to a strategy are watched even if they aren't
+    /// included.
+    pub(crate) fn is_ignored(&self, path: &Path) -> bool {
+        match self.strategies.matched(path) {
+            Some(_) => self.filter.is_excluded(path, false),
+            None => self.filter.is_ignored(path, false),
+        }
+    }
+
+    /// The directories to watch recursively
+    pub(crate) fn watched_dirs(&self) -> Vec<PathBuf> {
+        if self.paths != [""] {
+            return self
+                .paths
+                .iter()
+                .map(|path| self.crate_dir.join(path))
+                .collect();
+        }
+
+        // We're attempting to watch the root path... which contains a target directory...
+        // And on some platforms the target directory is really really large and can cause the watcher to crash
+        // since it runs out of file handles
+        // So we're going to iterate through its children and watch them instead of the root path, skipping the target
+        // directory.
+        //
+        // In reality, this whole approach of doing embedded file watching is kinda hairy since you want full knowledge
+        // of where rust code is. We could just use the filemap we generated above as an indication of where the rust
+        // code is in this project and deduce the subfolders under the root path from that.
+        //
+        // FIXME: use a more robust system here for embedded discovery
+        //
+        // https://github.com/DioxusLabs/dioxus/issues/1914
+        let target_dir = self.crate_dir.join("target");
+        std::fs::read_dir(&self.crate_dir)
+            .expect("failed to read rust crate directory. Are you running with cargo?")
+            .map(|entry| entry.expect("failed to read directory entry").path())
+            .filter(|path| path.is_dir() && *path != target_dir)
+            .collect()
+    }
+}
+
+/// The crate the path is in, if any root contains it. Nested roots win over the roots they are in.
+pub(crate) fn root_of<'a>(roots: &'a [Root], path: &Path) -> Option<&'a Root> {
+    roots
+        .iter()
+        .filter(|root| path.starts_with(&root.crate_dir))
+        .max_by_key(|root| root.crate_dir.components().count())
+}
+
+/// The directories of the other members of the workspace the crate is in
+pub(crate) fn workspace_members(crate_dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let metadata = MetadataCommand::new()
+        .manifest_path(crate_dir.join("Cargo.toml"))
+        .no_deps()
+        .exec()
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+
+    Ok(metadata
+        .workspace_packages()
+        .into_iter()
+        .filter_map(|package| Some(package.manifest_path.parent()?.as_std_path().to_path_buf()))
+        .filter(|dir| dir != crate_dir)
+        .collect())
+}
@@ -0,0 +1,299 @@
+//! Accepting apps, and a server external tools push messages through
+//!
+//! The file watcher of [`init`](crate::init) is one source of messages. Editor plugins, other CLIs or test harnesses
+//! have changes of their own to push, and use a [`HotReloadServer`] instead of speaking the protocol themselves.
+
+use std::{
+    io,
+    net::{SocketAddr, TcpListener, TcpStream},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use dioxus_core::{LiteralUpdate, Template};
+use dioxus_rsx::{hot_reload::FileMap, HotReloadingContext};
+
+use crate::{
+    bind_local_socket, clients::Clients, handshake, status::update_server_status, token,
+    transport::Connection, HotReloadMsg, HotReloadStatus, ShutdownReason, TOKEN_ENV,
+};
+
+/// The messages an app that just connected needs to catch up with the apps that were connected before
+pub(crate) trait Backlog: Send + 'static {
+    fn backlog(&self) -> Vec<HotReloadMsg>;
+}
+
+impl<Ctx: HotReloadingContext + Send + 'static> Backlog for FileMap<Ctx> {
+    fn backlog(&self) -> Vec<HotReloadMsg> {
+        let templates = self
+            .map
+            .values()
+            .flat_map(|v| v.templates.values().copied())
+            .map(HotReloadMsg::UpdateTemplate);
+        let literals = self
+            .map
+            .values()
+            .flat_map(|v| v.literals.iter())
+            .map(|(name, literals)| {
+                HotReloadMsg::UpdateLiterals(LiteralUpdate {
+                    template: name.rsplit_once(':').unwrap().0.to_string(),
+                    literals: literals.clone(),
+                })
+            });
+        templates.chain(literals).collect()
+    }
+}
+
+/// A hot reloading server that sends the messages it is given to every connected app
+///
+/// Apps connect to it like to the server [`init`](crate::init) starts, and apps that connect later get the latest
+/// version of every template and literal that was sent before. The server stops accepting apps once it is dropped.
+///
+/// ```rust, ignore
+/// let server = HotReloadServer::bind("target/dioxusin-my-app")?;
+/// server.send(HotReloadMsg::UpdateTemplate(template));
+/// ```
+///
+/// Apps that connect over the network have to present the [`token`](HotReloadServer::token) of the server.
+pub struct HotReloadServer {
+    clients: Clients,
+    sent: Arc<Mutex<Sent>>,
+    aborted: Arc<Mutex<bool>>,
+    token: String,
+}
+
+/// The templates and literals sent so far, in their latest version
+#[derive(Default)]
+struct Sent {
+    templates: Vec<Template>,
+    literals: Vec<LiteralUpdate>,
+}
+
+impl Backlog for Sent {
+    fn backlog(&self) -> Vec<HotReloadMsg> {
+        let templates = self
+            .templates
+            .iter()
+            .copied()
+            .map(HotReloadMsg::UpdateTemplate);
+        let literals = self
+            .literals
+            .iter()
+            .cloned()
+            .map(HotReloadMsg::UpdateLiterals);
+        templates.chain(literals).collect()
+    }
+}
+
+impl HotReloadServer {
+    /// Listen for apps on the local socket at the path, see [`socket_path`](crate::socket_path)
+    pub fn bind(path: impl AsRef<Path>) -> io::Result<Self> {
+        let listener = bind_local_socket(path.as_ref())?;
+        listener.set_nonblocking(true)?;
+
+        let server = Self {
+            clients: Clients::new(false),
+            sent: Default::default(),
+            aborted: Default::default(),
+            token: token::generate(),
+        };
+        accept_connections(
+            move || listener.accept(),
+            Connection::accept_local,
+            server.sent.clone(),
+            server.clients.clone(),
+            server.aborted.clone(),
+            None,
+        );
+        Ok(server)
+    }
+
+    /// Require apps that connect over the network to present this token instead of a random one. Call this before
+    /// [`with_websocket`](Self::with_websocket) and [`with_tcp`](Self::with_tcp).
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        // The server can't be taken apart with `..self`, it stops accepting apps when dropped
+        self.token = token.into();
+        self
+    }
+
+    /// The token apps that connect over the network have to present, see [`TOKEN_ENV`](crate::TOKEN_ENV)
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Also accept apps over a WebSocket at the address, see [`WEBSOCKET_ENV`](crate::WEBSOCKET_ENV)
+    pub fn with_websocket(self, addr: SocketAddr) -> io::Result<Self> {
+        self.listen(addr, Connection::accept_websocket)
+    }
+
+    /// Also accept apps over plain TCP at the address, see [`TCP_ENV`](crate::TCP_ENV)
+    pub fn with_tcp(self, addr: SocketAddr) -> io::Result<Self> {
+        self.listen(addr, Connection::accept_tcp)
+    }
+
+    fn listen(
+        self,
+        addr: SocketAddr,
+        accept: fn(TcpStream) -> io::Result<Connection>,
+    ) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        accept_tcp(
+            listener,
+            accept,
+            self.sent.clone(),
+            self.clients.clone(),
+            self.aborted.clone(),
+            self.token.clone(),
+        );
+        Ok(self)
+    }
+
+    /// Send the message to every connected app
+    pub fn send(&self, msg: HotReloadMsg) {
+        // Apps that connect while the message is sent wait for it, so they get it either way
+        let mut sent = self.sent.lock().unwrap();
+        match &msg {
+            HotReloadMsg::UpdateTemplate(template) => {
+                match sent.templates.iter_mut().find(|t| t.name == template.name) {
+                    Some(old) => *old = *template,
+                    None => sent.templates.push(*template),
+                }
+            }
+            HotReloadMsg::PatchTemplate(patch) => {
+                let patched = sent
+                    .templates
+                    .iter_mut()
+                    .find(|t| t.name == patch.name)
+                    .and_then(|old| Some((patch.apply(old)?, old)));
+                if let Some((template, old)) = patched {
+                    *old = template;
+                }
+            }
+            HotReloadMsg::UpdateLiterals(update) => {
+                match sent
+                    .literals
+                    .iter_mut()
+                    .find(|u| u.template == update.template)
+                {
+                    Some(old) => *old = update.clone(),
+                    None => sent.literals.push(update.clone()),
+                }
+            }
+            _ => {}
+        }
+        self.clients.broadcast(&msg);
+    }
+
+    /// Tell the apps that a reload finished, and return the new status. Apps that report back measure the reload
+    /// from now.
+    pub fn finish_reload(&self) -> HotReloadStatus {
+        let status = update_server_status(|status| status.reloads += 1);
+        self.clients
+            .broadcast(&HotReloadMsg::Status(status.clone()));
+        self.clients.reloaded(std::time::Instant::now());
+        status
+    }
+}
+
+impl Drop for HotReloadServer {
+    fn drop(&mut self) {
+        *self.aborted.lock().unwrap() = true;
+        self.clients
+            .broadcast(&HotReloadMsg::Shutdown(ShutdownReason::WatcherExiting));
+    }
+}
+
+/// Listen for apps over TCP at the address, with `accept` turning their streams into connections. Apps have to
+/// present the token, which is printed even if `log` is off since apps can't connect without it.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn listen_tcp<B: Backlog>(
+    addr: SocketAddr,
+    kind: &str,
+    accept: fn(TcpStream) -> io::Result<Connection>,
+    backlog: Arc<Mutex<B>>,
+    clients: Clients,
+    aborted: Arc<Mutex<bool>>,
+    token: String,
+    log: bool,
+) {
+    let listener = match TcpListener::bind(addr) {
+        Ok(listener) => listener,
+        Err(err) => {
+            if log {
+                println!("failed to listen for hot reloading {kind} connections\n{err}");
+            }
+            return;
+        }
+    };
+
+    println!(
+        "Hot reloading is listening for {kind} connections on {addr}. Apps connect with {TOKEN_ENV}={token}"
+    );
+    let _ = listener.set_nonblocking(true);
+    accept_tcp(listener, accept, backlog, clients, aborted, token);
+}
+
+fn accept_tcp<B: Backlog>(
+    listener: TcpListener,
+    accept: fn(TcpStream) -> io::Result<Connection>,
+    backlog: Arc<Mutex<B>>,
+    clients: Clients,
+    aborted: Arc<Mutex<bool>>,
+    token: String,
+) {
+    accept_connections(
+        move || listener.accept().map(|(stream, _)| stream),
+        accept,
+        backlog,
+        clients,
+        aborted,
+        Some(token),
+    );
+}
+
+/// Accept connections on a separate thread until hot reloading is aborted. Apps have to present the token, if there is
+/// one.
+///
+/// Every app is set up on a thread of its own with `connect`, so an app that is slow to finish the WebSocket or
+/// hot reloading handshake doesn't hold up the others.
+pub(crate) fn accept_connections<B: Backlog, S: Send + 'static>(
+    mut accept: impl FnMut() -> io::Result<S> + Send + 'static,
+    connect: fn(S) -> io::Result<Connection>,
+    backlog: Arc<Mutex<B>>,
+    clients: Clients,
+    aborted: Arc<Mutex<bool>>,
+    token: Option<String>,
+) {
+    std::thread::spawn(move || loop {
+        if *aborted.lock().unwrap() {
+            break;
+        }
+
+        if let Ok(stream) = accept() {
+            let backlog = backlog.clone();
+            let clients = clients.clone();
+            let token = token.clone();
+            std::thread::spawn(move || {
+                let Ok(mut connection) = connect(stream) else {
+                    return;
+                };
+                let Some(reply) = handshake::offer(&mut connection, token.as_deref()) else {
+                    return;
+                };
+                if reply.reports {
+                    clients.listen(connection, reply.client);
+                    return;
+                }
+
+                // send any templates and literals than have changed before the socket connected. The backlog stays
+                // locked until the app was added, so it gets every message sent after these.
+                let backlog = backlog.lock().unwrap();
+                if !backlog.backlog().iter().all(|msg| connection.send(msg)) {
+                    return;
+                }
+                clients.add(connection, reply.client);
+            });
+        }
+    });
+}
@@ -0,0 +1,59 @@
+//! How hot reloading is doing, for devtools or a status bar in the app
+//!
+//! The server keeps count of its reloads and sends them to the apps after every reload. Apps read the last status they
+//! received with [`status`], and a server running in this process is read with [`server_status`].
+
+use std::{sync::Mutex, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+/// What the hot reloading server has done so far
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HotReloadStatus {
+    /// How many batches of changed templates were sent to the apps
+    pub reloads: u64,
+
+    /// How long the last reload took, from reading the changed files to sending the templates
+    pub last_reload: Option<Duration>,
+
+    /// How long the last reload took until an app applied it, from reading the changed files to the app rendering
+    /// the change. Only apps that send [`ClientMsg::Ready`](crate::ClientMsg::Ready) are measured.
+    #[serde(default)]
+    pub last_applied: Option<Duration>,
+
+    /// How many apps are connected
+    pub clients: usize,
+
+    /// Whether the server still watches for changes. It stops once the app needs to be rebuilt, or if the watcher
+    /// failed to start.
+    pub watching: bool,
+}
+
+/// The last status the app received, if it is connected
+static CLIENT_STATUS: Mutex<Option<HotReloadStatus>> = Mutex::new(None);
+
+/// The status of the server running in this process, if any
+static SERVER_STATUS: Mutex<Option<HotReloadStatus>> = Mutex::new(None);
+
+/// The last status the app received from the hot reloading server, or `None` if the app isn't connected to one
+pub fn status() -> Option<HotReloadStatus> {
+    CLIENT_STATUS.lock().unwrap().clone()
+}
+
+/// The status of the hot reloading server [`hot_reload_init`](crate::hot_reload_init) started in this process, if any
+pub fn server_status() -> Option<HotReloadStatus> {
+    SERVER_STATUS.lock().unwrap().clone()
+}
+
+pub(crate) fn set_client_status(status: Option<HotReloadStatus>) {
+    *CLIENT_STATUS.lock().unwrap() = status;
+}
+
+/// Update the status of the server in this process, and return the new status
+#[cfg(feature = "custom_file_watcher")]
+pub(crate) fn update_server_status(update: impl FnOnce(&mut HotReloadStatus)) -> HotReloadStatus {
+    let mut status = SERVER_STATUS.lock().unwrap();
+    let status = status.get_or_insert_with(HotReloadStatus::default);
+    update(status);
+    status.clone()
+}
@@ -0,0 +1,136 @@
+//! How the watcher reloads a changed file
+//!
+//! By default the rsx! in Rust files is hot reloaded and every other file rebuilds the app. That guesses wrong for
+//! stylesheets the apps could reload in place, or for Rust files without any rsx! in them, so projects can map file
+//! patterns to the strategy that fits them with [`Config::with_strategy`](crate::Config::with_strategy).
+
+use std::path::Path;
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+/// How a changed file is reloaded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReloadStrategy {
+    /// Hot reload the rsx! in the file, and rebuild if anything else in it changed. The default for `.rs` files.
+    PatchTemplate,
+
+    /// Send the file to the apps as a changed asset, which they reload in place, like stylesheets or images
+    InjectAsset,
+
+    /// Reload the page, or start desktop apps over with a new VirtualDom
+    FullRefresh,
+
+    /// Rebuild the app, see [`Config::with_rebuild_handler`](crate::Config::with_rebuild_handler). The default for
+    /// every file other than `.rs` files.
+    Rebuild,
+}
+
+impl ReloadStrategy {
+    /// The strategy for files that don't match any pattern
+    pub(crate) fn default_for(path: &Path) -> Self {
+        match path.extension().and_then(|p| p.to_str()) {
+            Some("rs") => Self::PatchTemplate,
+            _ => Self::Rebuild,
+        }
+    }
+}
+
+/// The patterns of a crate that were mapped to strategies
+pub(crate) struct Strategies {
+    globs: Vec<(Gitignore, ReloadStrategy)>,
+}
+
+impl Strategies {
+    pub(crate) fn new(
+        crate_dir: &Path,
+        strategies: &[(&'static str, ReloadStrategy)],
+        log: bool,
+    ) -> Self {
+        let globs = strategies
+            .iter()
+            .filter_map(|&(glob, strategy)| {
+                let mut builder = GitignoreBuilder::new(crate_dir);
+                if let Err(err) = builder.add_line(None, glob) {
+                    if log {
+                        println!("hot reloading ignores the invalid glob {glob:?}: {err}");
+                    }
+                    return None;
+                }
+                Some((builder.build().ok()?, strategy))
+            })
+            .collect();
+
+        Self { globs }
+    }
+
+    /// The strategy of the last pattern the file matches, like the last matching line of a `.gitignore` wins
+    pub(crate) fn matched(&self, path: &Path) -> Option<ReloadStrategy> {
+        self.globs
+            .iter()
+            .rev()
+            .find(|(glob, _)| {
+                path.starts_with(glob.path())
+                    && glob.matched_path_or_any_parents(path, false).is_ignore()
+            })
+            .map(|(_, strategy)| *strategy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_strategies() {
+        let cases = [
+            ("src/main.rs", ReloadStrategy::PatchTemplate),
+            ("build.rs", ReloadStrategy::PatchTemplate),
+            ("assets/style.css", ReloadStrategy::Rebuild),
+            ("Cargo.toml", ReloadStrategy::Rebuild),
+            ("Makefile", ReloadStrategy::Rebuild),
+        ];
+
+        for (path, strategy) in cases {
+            assert_eq!(
+                ReloadStrategy::default_for(Path::new(path)),
+                strategy,
+                "{path}"
+            );
+        }
+    }
+
+    #[test]
+    fn last_matching_pattern_wins() {
+        let strategies = Strategies::new(
+            Path::new("/project"),
+            &[
+                ("*.css", ReloadStrategy::InjectAsset),
+                ("src/generated/", ReloadStrategy::Rebuild),
+                ("assets/print.css", ReloadStrategy::FullRefresh),
+                ("[invalid", ReloadStrategy::FullRefresh),
+            ],
+            false,
+        );
+
+        let cases = [
+            (
+                "/project/assets/style.css",
+                Some(ReloadStrategy::InjectAsset),
+            ),
+            (
+                "/project/assets/print.css",
+                Some(ReloadStrategy::FullRefresh),
+            ),
+            (
+                "/project/src/generated/api.rs",
+                Some(ReloadStrategy::Rebuild),
+            ),
+            ("/project/src/main.rs", None),
+            ("/elsewhere/style.css", None),
+        ];
+
+        for (path, strategy) in cases {
+            assert_eq!(strategies.matched(Path::new(path)), strategy, "{path}");
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! The shared secret apps present to servers that listen on the network
+//!
+//! Anyone who can reach a WebSocket or TCP listener could otherwise push templates or a `Shutdown` into running apps.
+//! Servers that listen on the network print a token, and only accept apps that present it in their
+//! [`HandshakeReply`](crate::HandshakeReply). The local socket is guarded by the file system, so it needs no token.
+
+use std::sync::Mutex;
+
+/// The environment variable that holds the token the app presents to servers that listen on the network
+pub const TOKEN_ENV: &str = "DIOXUS_HOT_RELOAD_TOKEN";
+
+/// The token set with [`set_token`], which wins over [`TOKEN_ENV`]
+static TOKEN: Mutex<Option<String>> = Mutex::new(None);
+
+/// Present the token to hot reloading servers that listen on the network, instead of the one in [`TOKEN_ENV`]
+pub fn set_token(token: impl Into<String>) {
+    *TOKEN.lock().unwrap() = Some(token.into());
+}
+
+/// The token the app presents, if it has one
+pub(crate) fn token() -> Option<String> {
+    TOKEN
+        .lock()
+        .unwrap()
+        .clone()
+        .or_else(|| std::env::var(TOKEN_ENV).ok())
+        .filter(|token| !token.is_empty())
+}
+
+/// A new random token
+#[cfg(feature = "custom_file_watcher")]
+pub(crate) fn generate() -> String {
+    use std::{
+        collections::hash_map::RandomState,
+        hash::{BuildHasher, Hasher},
+    };
+
+    // The keys of RandomState are seeded from the random number generator of the operating system
+    let random = || RandomState::new().build_hasher().finish();
+    format!("{:016x}{:016x}", random(), random())
+}
+
+/// Whether the app presented the token, compared in constant time so the token can't be guessed byte by byte
+#[cfg(feature = "custom_file_watcher")]
+pub(crate) fn matches(presented: Option<&str>, token: &str) -> bool {
+    let Some(presented) = presented else {
+        return false;
+    };
+
+    presented.len() == token.len()
+        && presented
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
@@ -0,0 +1,458 @@
+//! The connections hot reloading messages travel over between the server and the running app
+//!
+//! The server always listens on a local socket. Apps on other machines or physical mobile devices can't reach it, so
+//! the server can also accept WebSocket clients. Containers and WSL often can't share the socket file inside `target/`
+//! either, so the server can also accept plain TCP clients.
+
+use std::{
+    ffi::OsString,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::TcpStream,
+    path::{Path, PathBuf},
+    sync::{Mutex, Once},
+};
+
+use crate::Encoding;
+use interprocess::local_socket::{LocalSocketListener, LocalSocketStream};
+use serde::Serialize;
+use tungstenite::{stream::MaybeTlsStream, Message, WebSocket};
+
+/// The environment variable that points the app at a WebSocket hot reloading server, like `ws://192.168.1.20:8080`
+pub const WEBSOCKET_ENV: &str = "DIOXUS_HOT_RELOAD_WS";
+
+/// The environment variable that points the app at a TCP hot reloading server, like `127.0.0.1:8081`
+pub const TCP_ENV: &str = "DIOXUS_HOT_RELOAD_TCP";
+
+/// The environment variable that sets the path of the local socket, for both the server and the app
+pub const SOCKET_ENV: &str = "DIOXUS_HOT_RELOAD_SOCKET";
+
+/// The largest message a connection accepts. Templates and literal updates are far smaller, so anything bigger is a
+/// broken or hostile peer and is refused before it is buffered.
+const MAX_FRAME: usize = 16 * 1024 * 1024;
+
+/// The path of the local socket of the app `name` in the target directory
+///
+/// The apps of a workspace share its target directory, so every app gets its own socket.
+pub fn socket_path(target_dir: &Path, name: &str) -> PathBuf {
+    target_dir.join(format!("dioxusin-{name}"))
+}
+
+/// The name the local socket at the path is bound to
+///
+/// On unix the socket is a file at the path. Named pipes on windows live in a namespace of their own, and their names
+/// can't contain backslashes, so the pipe is named after the file name and a hash of the whole path. Projects with the
+/// same package name still get a pipe of their own.
+pub fn local_socket_name(path: &Path) -> OsString {
+    if cfg!(windows) {
+        // FNV-1a, since the server and the app may be compiled by different versions of rust
+        let hash = path
+            .to_string_lossy()
+            .bytes()
+            .fold(0xcbf29ce484222325_u64, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+            });
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        format!("@{name}-{hash:016x}").into()
+    } else {
+        path.as_os_str().to_owned()
+    }
+}
+
+/// Listen on the local socket at the path
+///
+/// A server that was force quit leaves its socket file behind on unix, which is removed first. If another server is
+/// still listening on the socket, this fails instead of taking the socket from it.
+pub fn bind_local_socket(path: &Path) -> io::Result<LocalSocketListener> {
+    let in_use = || {
+        io::Error::new(
+            io::ErrorKind::AddrInUse,
+            format!(
+                "another hot reloading server is already listening at {}. Stop it, or move this one with the {SOCKET_ENV} environment variable.",
+                path.display()
+            ),
+        )
+    };
+
+    if cfg!(unix) && path.exists() {
+        if LocalSocketStream::connect(path).is_ok() {
+            return Err(in_use());
+        }
+        std::fs::remove_file(path).map_err(|err| {
+            io::Error::new(
+                err.kind(),
+                format!(
+                    "failed to remove the stale hot reloading socket at {}: {err}",
+                    path.display()
+                ),
+            )
+        })?;
+    }
+
+    LocalSocketListener::bind(local_socket_name(path)).map_err(|err| match err.kind() {
+        // The first instance of a named pipe belongs to another server
+        io::ErrorKind::AddrInUse | io::ErrorKind::PermissionDenied if cfg!(windows) => in_use(),
+        _ => io::Error::new(
+            err.kind(),
+            format!(
+                "failed to listen for hot reloading at {}: {err}",
+                path.display()
+            ),
+        ),
+    })
+}
+
+/// Explain why the app can't connect to a socket file that exists, once per app
+pub(crate) fn explain_connect_error(path: &Path, err: io::Error) -> io::Error {
+    static EXPLAINED: Once = Once::new();
+
+    if err.kind() == io::ErrorKind::ConnectionRefused && path.exists() {
+        EXPLAINED.call_once(|| {
+            eprintln!(
+                "The hot reloading socket at {} exists, but no server is listening on it. It was likely left behind by a server that was force quit, and is replaced once a server starts.",
+                path.display()
+            )
+        });
+    }
+    err
+}
+
+/// The local socket of the server [`hot_reload_init`](crate::hot_reload_init) started in this process, if any
+static IN_PROCESS_SOCKET: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Point apps in this process at the local socket of a server running in this process
+#[cfg(feature = "custom_file_watcher")]
+pub(crate) fn set_in_process_socket(path: PathBuf) {
+    *IN_PROCESS_SOCKET.lock().unwrap() = Some(path);
+}
+
+/// How the app reaches the hot reloading server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transport {
+    /// A local socket at the path. This is a unix domain socket, or a named pipe on windows, see
+    /// [`local_socket_name`].
+    LocalSocket(PathBuf),
+
+    /// A WebSocket at the URL
+    WebSocket(String),
+
+    /// A TCP socket at the `host:port` address
+    Tcp(String),
+}
+
+impl Default for Transport {
+    fn default() -> Self {
+        Self::LocalSocket(PathBuf::from("./").join("target").join("dioxusin"))
+    }
+}
+
+impl Transport {
+    /// The transport set in the environment, or a local socket
+    ///
+    /// The WebSocket transport is used if [`WEBSOCKET_ENV`] is set, otherwise the TCP transport is used if [`TCP_ENV`]
+    /// is set. Otherwise the local socket is at [`SOCKET_ENV`] if it is set, or the socket of the server running in
+    /// this process, or the default `./target/dioxusin`.
+    pub fn from_env() -> Self {
+        let var = |name| std::env::var(name).ok().filter(|value| !value.is_empty());
+
+        if let Some(url) = var(WEBSOCKET_ENV) {
+            Self::WebSocket(url)
+        } else if let Some(addr) = var(TCP_ENV) {
+            Self::Tcp(addr)
+        } else if let Some(path) = var(SOCKET_ENV) {
+            Self::LocalSocket(PathBuf::from(path))
+        } else if let Some(path) = IN_PROCESS_SOCKET.lock().unwrap().clone() {
+            Self::LocalSocket(path)
+        } else {
+            Self::default()
+        }
+    }
+
+    pub(crate) fn connect(&self) -> io::Result<Connection> {
+        match self {
+            Self::LocalSocket(path) => {
+                let socket = LocalSocketStream::connect(local_socket_name(path))
+                    .map_err(|err| explain_connect_error(path, err))?;
+                Ok(Connection::stream(socket))
+            }
+            Self::WebSocket(url) => {
+                let (socket, _) = tungstenite::connect(url.as_str()).map_err(websocket_error)?;
+                Ok(Connection::new(Frames::WebSocket(socket)))
+            }
+            Self::Tcp(addr) => {
+                let socket = TcpStream::connect(addr.as_str())?;
+                Ok(Connection::stream(socket))
+            }
+        }
+    }
+}
+
+/// How long an app that connected over the network has to finish the WebSocket and hot reloading handshakes
+#[cfg(feature = "custom_file_watcher")]
+const HANDSHAKE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// A byte stream both ends can read from and write to
+pub(crate) trait Stream: Read + Write + Send {}
+
+impl<T: Read + Write + Send> Stream for T {}
+
+/// One end of a connection between the server and an app
+pub(crate) struct Connection {
+    frames: Frames,
+    encoding: Encoding,
+
+    /// The TCP stream under the connection while reads time out, until the handshake is done
+    #[cfg(feature = "custom_file_watcher")]
+    handshake_timeout: Option<TcpStream>,
+}
+
+/// How the messages of a connection are delimited
+enum Frames {
+    /// Lines of JSON, or length-prefixed binary messages
+    Stream(BufReader<Box<dyn Stream>>),
+
+    /// One message per text or binary frame
+    WebSocket(WebSocket<MaybeTlsStream<TcpStream>>),
+}
+
+impl Connection {
+    fn new(frames: Frames) -> Self {
+        Self {
+            frames,
+            // Until the handshake picked another encoding
+            encoding: Encoding::Json,
+            #[cfg(feature = "custom_file_watcher")]
+            handshake_timeout: None,
+        }
+    }
+
+    fn stream(stream: impl Stream + 'static) -> Self {
+        Self::new(Frames::Stream(BufReader::new(Box::new(stream))))
+    }
+
+    /// Accept a local socket client
+    #[cfg(feature = "custom_file_watcher")]
+    pub(crate) fn accept_local(stream: LocalSocketStream) -> io::Result<Self> {
+        // The listener may be non-blocking, but the handshake and the writes afterwards should block
+        stream.set_nonblocking(false)?;
+        Ok(Self::stream(stream))
+    }
+
+    /// Accept a TCP client on a freshly accepted TCP stream
+    #[cfg(feature = "custom_file_watcher")]
+    pub(crate) fn accept_tcp(stream: TcpStream) -> io::Result<Self> {
+        stream.set_nonblocking(false)?;
+        // Templates are small and should arrive as soon as they are written
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+        let handshake_timeout = Some(stream.try_clone()?);
+        Ok(Self {
+            handshake_timeout,
+            ..Self::stream(stream)
+        })
+    }
+
+    /// Accept a WebSocket client on a freshly accepted TCP stream
+    #[cfg(feature = "custom_file_watcher")]
+    pub(crate) fn accept_websocket(stream: TcpStream) -> io::Result<Self> {
+        // The listener may be non-blocking, but the handshake and the writes afterwards should block
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+        let handshake_timeout = Some(stream.try_clone()?);
+        let socket = tungstenite::accept(MaybeTlsStream::Plain(stream))
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()))?;
+        Ok(Self {
+            handshake_timeout,
+            ..Self::new(Frames::WebSocket(socket))
+        })
+    }
+
+    /// Let reads wait for as long as they need, once the app finished the handshake
+    #[cfg(feature = "custom_file_watcher")]
+    pub(crate) fn finish_handshake(&mut self) {
+        if let Some(stream) = self.handshake_timeout.take() {
+            _ = stream.set_read_timeout(None);
+        }
+    }
+
+    /// The encoding of the messages after the handshake
+    pub(crate) fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    pub(crate) fn set_encoding(&mut self, encoding: Encoding) {
+        self.encoding = encoding;
+    }
+
+    /// Send the message, and return whether the other end is still connected
+    pub(crate) fn send(&mut self, msg: &impl Serialize) -> bool {
+        let Some(frame) = self.encoding.encode(msg) else {
+            return false;
+        };
+
+        match &mut self.frames {
+            Frames::Stream(reader) => reader
+                .get_mut()
+                .write_all(&stream_frame(self.encoding, frame))
+                .is_ok(),
+            Frames::WebSocket(socket) => {
+                socket.send(websocket_message(self.encoding, frame)).is_ok()
+            }
+        }
+    }
+
+    /// Wait for the next message. Returns `None` once the other end closed the connection.
+    pub(crate) fn recv(&mut self) -> io::Result<Option<Vec<u8>>> {
+        match (&mut self.frames, self.encoding) {
+            (Frames::Stream(reader), Encoding::Json) => {
+                let mut frame = Vec::new();
+                match reader
+                    .by_ref()
+                    .take(MAX_FRAME as u64 + 1)
+                    .read_until(b'\n', &mut frame)?
+                {
+                    0 => Ok(None),
+                    _ if frame.len() > MAX_FRAME => Err(frame_too_large(frame.len())),
+                    _ => Ok(Some(frame)),
+                }
+            }
+            (Frames::Stream(reader), Encoding::MessagePack) => {
+                let mut length = [0; 4];
+                match reader.read_exact(&mut length) {
+                    Ok(()) => {}
+                    Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+                    Err(err) => return Err(err),
+                }
+                let length = u32::from_le_bytes(length) as usize;
+                if length > MAX_FRAME {
+                    return Err(frame_too_large(length));
+                }
+                let mut frame = vec![0; length];
+                reader.read_exact(&mut frame)?;
+                Ok(Some(frame))
+            }
+            (Frames::WebSocket(socket), _) => loop {
+                match socket.read() {
+                    Ok(message) => match websocket_frame(message) {
+                        Some(frame) => return Ok(frame),
+                        None => continue,
+                    },
+                    Err(tungstenite::Error::ConnectionClosed) => return Ok(None),
+                    Err(err) => return Err(websocket_error(err)),
+                }
+            },
+        }
+    }
+}
+
+fn frame_too_large(length: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("The hot reloading message of {length} bytes is larger than the limit of {MAX_FRAME} bytes"),
+    )
+}
+
+/// Delimit an encoded message on a byte stream
+pub(crate) fn stream_frame(encoding: Encoding, mut frame: Vec<u8>) -> Vec<u8> {
+    match encoding {
+        Encoding::Json => {
+            frame.push(b'\n');
+            frame
+        }
+        Encoding::MessagePack => {
+            let mut delimited = (frame.len() as u32).to_le_bytes().to_vec();
+            delimited.append(&mut frame);
+            delimited
+        }
+    }
+}
+
+/// The WebSocket message carrying an encoded message
+pub(crate) fn websocket_message(encoding: Encoding, frame: Vec<u8>) -> Message {
+    match encoding {
+        Encoding::Json => Message::Text(String::from_utf8(frame).unwrap_or_default()),
+        Encoding::MessagePack => Message::Binary(frame),
+    }
+}
+
+/// The encoded message a WebSocket message carries, `Some(None)` if the socket was closed, or `None` for control
+/// messages
+pub(crate) fn websocket_frame(message: Message) -> Option<Option<Vec<u8>>> {
+    match message {
+        Message::Text(text) => Some(Some(text.into_bytes())),
+        Message::Binary(frame) => Some(Some(frame)),
+        Message::Close(_) => Some(None),
+        // Pings are answered by tungstenite
+        _ => None,
+    }
+}
+
+pub(crate) fn websocket_error(err: tungstenite::Error) -> io::Error {
+    match err {
+        tungstenite::Error::Io(err) => err,
+        err => io::Error::new(io::ErrorKind::Other, err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HotReloadMsg;
+    use std::io::Cursor;
+
+    fn connection(encoding: Encoding, bytes: Vec<u8>) -> Connection {
+        let mut connection = Connection::stream(Cursor::new(bytes));
+        connection.set_encoding(encoding);
+        connection
+    }
+
+    #[test]
+    fn frames_round_trip() {
+        for &encoding in Encoding::ALL {
+            let messages = [
+                HotReloadMsg::FullReload,
+                HotReloadMsg::BuildStatus {
+                    errors: vec!["expected `}`\nfound end of file".to_string()],
+                    warnings: Vec::new(),
+                },
+            ];
+
+            let mut bytes = Vec::new();
+            for message in &messages {
+                bytes.extend(stream_frame(encoding, encoding.encode(message).unwrap()));
+            }
+
+            let mut connection = connection(encoding, bytes);
+            for message in &messages {
+                let frame = connection.recv().unwrap().unwrap();
+                let received = encoding.decode(&frame).unwrap();
+                assert_eq!(
+                    format!("{received:?}"),
+                    format!("{message:?}"),
+                    "{encoding:?}"
+                );
+            }
+            assert!(connection.recv().unwrap().is_none(), "{encoding:?}");
+        }
+    }
+
+    #[test]
+    fn oversized_frames_are_refused() {
+        let length = (MAX_FRAME as u32 + 1).to_le_bytes().to_vec();
+        let err = connection(Encoding::MessagePack, length)
+            .recv()
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let line = vec![b'a'; MAX_FRAME + 1];
+        let err = connection(Encoding::Json, line).recv().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn truncated_frames_fail() {
+        let mut frame = stream_frame(Encoding::MessagePack, vec![1, 2, 3, 4]);
+        frame.truncate(6);
+        let err = connection(Encoding::MessagePack, frame).recv().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
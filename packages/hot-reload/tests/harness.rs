@@ -0,0 +1,62 @@
+#![cfg(feature = "file_watcher")]
+
+use dioxus_hot_reload::{HotReloadHarness, HotReloadMsg, HtmlCtx};
+
+const APP: &str = r#"
+use dioxus::prelude::*;
+
+fn app() -> Element {
+    rsx! {
+        div { "hello" }
+    }
+}
+"#;
+
+fn templates(msgs: &[HotReloadMsg]) -> Vec<dioxus_core::Template> {
+    msgs.iter()
+        .filter_map(|msg| match msg {
+            HotReloadMsg::UpdateTemplate(template) => Some(*template),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn changed_rsx_sends_template() {
+    let mut harness = HotReloadHarness::<HtmlCtx>::new(APP);
+
+    let msgs = harness
+        .edit(&APP.replace(r#"div { "hello" }"#, r#"div { class: "big", "hello" }"#))
+        .unwrap();
+
+    let templates = templates(&msgs);
+    assert_eq!(templates.len(), 1);
+    assert!(templates[0].name.starts_with("src/main.rs:"));
+    assert!(matches!(msgs.last(), Some(HotReloadMsg::Status(_))));
+}
+
+#[test]
+fn patched_template_resolves_to_whole_template() {
+    let mut harness = HotReloadHarness::<HtmlCtx>::new(APP);
+
+    let first = harness
+        .edit(&APP.replace(r#"div { "hello" }"#, r#"div { class: "big", "hello" }"#))
+        .unwrap();
+    let second = harness
+        .edit(&APP.replace(r#"div { "hello" }"#, r#"div { class: "small", "hello" }"#))
+        .unwrap();
+
+    let (first, second) = (templates(&first), templates(&second));
+    assert_eq!(second.len(), 1);
+    assert_eq!(first[0].name, second[0].name);
+    assert_ne!(first[0], second[0]);
+}
+
+#[test]
+fn changed_code_needs_rebuild() {
+    let mut harness = HotReloadHarness::<HtmlCtx>::new(APP);
+
+    let msgs = harness.edit(&APP.replace("fn app()", "fn renamed_app()"));
+
+    assert!(msgs.is_none());
+}
@@ -2,6 +2,7 @@
 
 use dioxus_core::prelude::*;
 use generational_box::{AnyStorage, GenerationalBox, UnsyncStorage};
+use serde::{de::DeserializeOwned, Serialize};
 use std::future::{poll_fn, Future, IntoFuture};
 use std::pin::Pin;
 use std::rc::Rc;
@@ -84,6 +85,118 @@ pub fn eval(script: &str) -> UseEval {
     UseEval::new(eval_provider.new_evaluator(script.to_string()))
 }
 
+/// Evaluate JavaScript with arguments that are serialized from Rust.
+///
+/// The arguments are available to the script as the `args` constant:
+///
+/// ```rust, ignore
+/// #[derive(Serialize)]
+/// struct Highlight { selector: String, color: String }
+///
+/// let count: usize = eval_with_args(
+///     r#"
+///     const elements = document.querySelectorAll(args.selector);
+///     elements.forEach((element) => element.style.background = args.color);
+///     return elements.length;
+///     "#,
+///     &Highlight { selector: ".todo".into(), color: "yellow".into() },
+/// )
+/// .join_as()
+/// .await?;
+/// ```
+pub fn eval_with_args<A: Serialize + ?Sized>(script: &str, args: &A) -> Result<UseEval, EvalError> {
+    let args =
+        serde_json::to_string(args).map_err(|err| EvalError::Serialization(err.to_string()))?;
+
+    Ok(eval(&format!("const args = {args};\n{script}")))
+}
+
+/// The script that sets up the registry of objects kept alive for [`JsHandle`]s
+const JS_HANDLES: &str = r#"
+    window.__dioxus_handles = window.__dioxus_handles || {
+        next: 0,
+        objects: new Map(),
+        insert(object) {
+            const id = this.next++;
+            this.objects.set(id, object);
+            return id;
+        },
+    };
+"#;
+
+/// Evaluate JavaScript that creates an object, and keep the object alive in the page behind a [`JsHandle`].
+///
+/// The script is the body of an async function that returns the object. This allows stateful interop with JS
+/// libraries like maps or editors, whose objects can't be serialized:
+///
+/// ```rust, ignore
+/// let map = eval_handle(r#"return L.map("map").setView([51.5, -0.09], 13);"#).await?;
+///
+/// // Later, in an event handler
+/// eval(&format!("{map}.setZoom(15);"));
+/// ```
+///
+/// > Note: Handles only stay valid until the page is reloaded.
+pub async fn eval_handle(script: &str) -> Result<JsHandle, EvalError> {
+    let provider = try_consume_context::<Rc<dyn EvalProvider>>().ok_or(EvalError::Unsupported)?;
+
+    // The id is sent instead of returned, since not every platform supports return values
+    let mut eval = UseEval::new(provider.new_evaluator(format!(
+        r#"{JS_HANDLES}
+        const object = await (async () => {{ {script} }})();
+        dioxus.send(window.__dioxus_handles.insert(object));"#
+    )));
+    let id = eval.recv_as::<u64>().await?;
+
+    Ok(JsHandle {
+        inner: Rc::new(JsHandleInner { id, provider }),
+    })
+}
+
+/// An opaque handle to a JavaScript object created with [`eval_handle`].
+///
+/// The handle displays as a JavaScript expression that evaluates to the object, so it can be passed into later
+/// scripts. The object is released in the page once the last clone of the handle is dropped.
+#[derive(Clone)]
+pub struct JsHandle {
+    inner: Rc<JsHandleInner>,
+}
+
+struct JsHandleInner {
+    id: u64,
+    provider: Rc<dyn EvalProvider>,
+}
+
+impl JsHandle {
+    /// The id of the object in the page
+    pub fn id(&self) -> u64 {
+        self.inner.id
+    }
+}
+
+impl std::fmt::Display for JsHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "window.__dioxus_handles.objects.get({})", self.inner.id)
+    }
+}
+
+impl std::fmt::Debug for JsHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JsHandle")
+            .field("id", &self.inner.id)
+            .finish()
+    }
+}
+
+impl Drop for JsHandleInner {
+    fn drop(&mut self) {
+        self.provider.new_evaluator(format!(
+            "window.__dioxus_handles && window.__dioxus_handles.objects.delete({});",
+            self.id
+        ));
+    }
+}
+
 /// A wrapper around the target platform's evaluator.
 #[derive(Clone, Copy)]
 pub struct UseEval {
@@ -102,6 +215,9 @@ impl UseEval {
     }
 
     /// Gets an UnboundedReceiver to receive messages from the evaluated JavaScript.
+    ///
+    /// Once the script completed and every value it sent with `dioxus.send` was received, this returns
+    /// [`EvalError::Finished`].
     pub async fn recv(&mut self) -> Result<serde_json::Value, EvalError> {
         poll_fn(|cx| match self.evaluator.try_write() {
             Ok(mut evaluator) => evaluator.poll_recv(cx),
@@ -110,6 +226,33 @@ impl UseEval {
         .await
     }
 
+    /// Receives the next value the evaluated JavaScript sent, or `None` once the script completed and every value
+    /// was received.
+    ///
+    /// This makes it easy to follow the progress of long-running JavaScript. If the script throws, the error is
+    /// returned as the last item.
+    ///
+    /// ```rust, ignore
+    /// let mut eval = eval(
+    ///     r#"
+    ///     for (let i = 1; i <= 10; i++) {
+    ///         await new Promise((resolve) => setTimeout(resolve, 100));
+    ///         dioxus.send(i * 10);
+    ///     }
+    ///     "#,
+    /// );
+    ///
+    /// while let Some(progress) = eval.next().await {
+    ///     println!("{}% done", progress?);
+    /// }
+    /// ```
+    pub async fn next(&mut self) -> Option<Result<serde_json::Value, EvalError>> {
+        match self.recv().await {
+            Err(EvalError::Finished) => None,
+            result => Some(result),
+        }
+    }
+
     /// Gets the return value of the evaluated JavaScript.
     pub async fn join(self) -> Result<serde_json::Value, EvalError> {
         poll_fn(|cx| match self.evaluator.try_write() {
@@ -118,6 +261,25 @@ impl UseEval {
         })
         .await
     }
+
+    /// Serializes a value and sends it to the evaluated JavaScript.
+    pub fn send_serialized<T: Serialize + ?Sized>(&self, data: &T) -> Result<(), EvalError> {
+        let data =
+            serde_json::to_value(data).map_err(|err| EvalError::Serialization(err.to_string()))?;
+        self.send(data)
+    }
+
+    /// Receives the next message from the evaluated JavaScript and deserializes it into `T`.
+    pub async fn recv_as<T: DeserializeOwned>(&mut self) -> Result<T, EvalError> {
+        let value = self.recv().await?;
+        serde_json::from_value(value).map_err(|err| EvalError::Serialization(err.to_string()))
+    }
+
+    /// Gets the return value of the evaluated JavaScript and deserializes it into `T`.
+    pub async fn join_as<T: DeserializeOwned>(self) -> Result<T, EvalError> {
+        let value = self.join().await?;
+        serde_json::from_value(value).map_err(|err| EvalError::Serialization(err.to_string()))
+    }
 }
 
 impl IntoFuture for UseEval {
@@ -131,6 +293,7 @@ impl IntoFuture for UseEval {
 
 /// Represents an error when evaluating JavaScript
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum EvalError {
     /// The platform does not support evaluating JavaScript.
     Unsupported,
@@ -143,4 +306,7 @@ pub enum EvalError {
 
     /// Represents an error communicating between JavaScript and Rust.
     Communication(String),
+
+    /// A value couldn't be serialized to or deserialized from the JavaScript side.
+    Serialization(String),
 }
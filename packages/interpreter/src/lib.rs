@@ -8,6 +8,14 @@ pub static INTERPRETER_JS: &str = include_str!("./js/core.js");
 /// The code explicitly for desktop/liveview that bridges the eval gap between the two
 pub static NATIVE_JS: &str = include_str!("./js/native.js");
 
+/// A function of the rendered errors and warnings of a build that shows them over the app, or removes the overlay
+/// once a build succeeded. Desktop and web call it the same way, so the overlay looks the same everywhere:
+///
+/// ```rust, ignore
+/// let script = format!("({BUILD_STATUS_JS})({errors}, {warnings})");
+/// ```
+pub static BUILD_STATUS_JS: &str = include_str!("./overlay/build_status.js");
+
 #[cfg(all(feature = "binary-protocol", feature = "sledgehammer"))]
 mod write_native_mutations;
 
@@ -0,0 +1,36 @@
+// Show the errors of a failed build over the app, or remove the overlay once a build succeeded.
+// Called as a function of the rendered errors and warnings by desktop and web alike.
+(errors, warnings) => {
+  document.getElementById("dioxus-build-status")?.remove();
+  if (errors.length === 0) return;
+
+  const overlay = document.createElement("div");
+  overlay.id = "dioxus-build-status";
+  overlay.style.cssText =
+    "position: fixed; inset: 0; z-index: 2147483647; overflow: auto; padding: 24px; background: rgba(24, 24, 27, 0.95); color: #f4f4f5; font-family: monospace;";
+
+  const dismiss = document.createElement("button");
+  dismiss.textContent = "Dismiss";
+  dismiss.style.cssText = "float: right;";
+  dismiss.onclick = () => overlay.remove();
+  overlay.appendChild(dismiss);
+
+  const title = document.createElement("h2");
+  title.textContent = "Build failed";
+  title.style.cssText = "color: #f87171;";
+  overlay.appendChild(title);
+
+  for (const [messages, color] of [
+    [errors, "#fca5a5"],
+    [warnings, "#fde68a"],
+  ]) {
+    for (const message of messages) {
+      const pre = document.createElement("pre");
+      pre.textContent = message;
+      pre.style.cssText = "white-space: pre-wrap; color: " + color + ";";
+      overlay.appendChild(pre);
+    }
+  }
+
+  document.body.appendChild(overlay);
+}
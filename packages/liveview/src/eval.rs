@@ -3,7 +3,7 @@ use dioxus_html::prelude::{EvalError, EvalProvider, Evaluator};
 use generational_box::{AnyStorage, GenerationalBox, UnsyncStorage};
 use std::rc::Rc;
 
-use crate::query::{Query, QueryEngine};
+use crate::query::{Query, QueryEngine, QueryError};
 
 /// Provides the DesktopEvalProvider through [`cx.provide_context`].
 pub fn init_eval() {
@@ -49,9 +49,7 @@ impl Evaluator for DesktopEvaluator {
         &mut self,
         context: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Result<serde_json::Value, EvalError>> {
-        self.query
-            .poll_result(context)
-            .map_err(|e| EvalError::Communication(e.to_string()))
+        self.query.poll_result(context).map_err(eval_error)
     }
 
     /// Sends a message to the evaluated JavaScript.
@@ -70,8 +68,15 @@ impl Evaluator for DesktopEvaluator {
         &mut self,
         context: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Result<serde_json::Value, EvalError>> {
-        self.query
-            .poll_recv(context)
-            .map_err(|e| EvalError::Communication(e.to_string()))
+        self.query.poll_recv(context).map_err(eval_error)
+    }
+}
+
+/// Surface errors thrown by the script and the end of its messages as such, instead of as communication errors
+fn eval_error(err: QueryError) -> EvalError {
+    match err {
+        QueryError::Js(err) => EvalError::InvalidJs(err),
+        QueryError::Finished => EvalError::Finished,
+        err => EvalError::Communication(err.to_string()),
     }
 }
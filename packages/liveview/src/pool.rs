@@ -117,13 +117,9 @@ impl<S> LiveViewSocket for S where
 /// You might need to transform the error types of the web backend into the LiveView error type.
 pub async fn run(mut vdom: VirtualDom, ws: impl LiveViewSocket) -> Result<(), LiveViewError> {
     #[cfg(all(feature = "hot-reload", debug_assertions))]
-    let mut hot_reload_rx = {
-        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
-        dioxus_hot_reload::connect(move |template| {
-            let _ = tx.send(template);
-        });
-        rx
-    };
+    let hot_reload_rx = dioxus_hot_reload::connect_async();
+    #[cfg(all(feature = "hot-reload", debug_assertions))]
+    pin_mut!(hot_reload_rx);
 
     let mut mutations = MutationState::default();
 
@@ -159,7 +155,7 @@ pub async fn run(mut vdom: VirtualDom, ws: impl LiveViewSocket) -> Result<(), Li
 
     loop {
         #[cfg(all(feature = "hot-reload", debug_assertions))]
-        let hot_reload_wait = hot_reload_rx.recv();
+        let hot_reload_wait = hot_reload_rx.next();
         #[cfg(not(all(feature = "hot-reload", debug_assertions)))]
         let hot_reload_wait: std::future::Pending<Option<()>> = std::future::pending();
 
@@ -218,10 +214,29 @@ pub async fn run(mut vdom: VirtualDom, ws: impl LiveViewSocket) -> Result<(), Li
                     dioxus_hot_reload::HotReloadMsg::UpdateTemplate(new_template) => {
                         vdom.replace_template(new_template);
                     }
+                    dioxus_hot_reload::HotReloadMsg::UpdateLiterals(literals) => {
+                        vdom.replace_literals(literals);
+                    }
                     // todo: enable hotreloading in liveview
-                    dioxus_hot_reload::HotReloadMsg::UpdateAsset(_) => {}
-                    dioxus_hot_reload::HotReloadMsg::Shutdown => {
-                        std::process::exit(0);
+                    dioxus_hot_reload::HotReloadMsg::UpdateAsset(_)
+                    | dioxus_hot_reload::HotReloadMsg::UpdateResolvedAsset { .. } => {}
+                    // Applied by the client, which passes on the patched template instead
+                    dioxus_hot_reload::HotReloadMsg::PatchTemplate(_) => {}
+                    dioxus_hot_reload::HotReloadMsg::BuildStatus { .. } => {}
+                    dioxus_hot_reload::HotReloadMsg::FullReload => {
+                        // The page connects again with a new VirtualDom once it reloaded
+                        let reload = ClientUpdate::Query("window.location.reload()".to_string());
+                        ws.send(text_frame(&serde_json::to_string(&reload).unwrap())).await?;
+                    }
+                    // Every reload ends with a status, and the edits of the reload are sent below
+                    dioxus_hot_reload::HotReloadMsg::Status(_) => {
+                        dioxus_hot_reload::send_to_server(dioxus_hot_reload::ClientMsg::Ready);
+                    }
+                    dioxus_hot_reload::HotReloadMsg::Shutdown(reason) => {
+                        // The server replaces a liveview app that needs a rebuild once the build succeeded
+                        if reason == dioxus_hot_reload::ShutdownReason::Restart {
+                            std::process::exit(0);
+                        }
                     },
                 }
                 #[cfg(not(all(feature = "hot-reload", debug_assertions)))]
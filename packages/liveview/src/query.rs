@@ -65,8 +65,8 @@ impl<T> Default for SharedSlab<T> {
 }
 
 pub(crate) struct QueryEntry {
-    channel_sender: tokio::sync::mpsc::UnboundedSender<Value>,
-    return_sender: Option<tokio::sync::oneshot::Sender<Value>>,
+    channel_sender: Option<tokio::sync::mpsc::UnboundedSender<Result<Value, String>>>,
+    return_sender: Option<tokio::sync::oneshot::Sender<Result<Value, String>>>,
     pub(crate) owner: Option<Owner<UnsyncStorage>>,
 }
 
@@ -92,7 +92,7 @@ impl QueryEngine {
         let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
         let (return_tx, return_rx) = tokio::sync::oneshot::channel();
         let request_id = self.active_requests.slab.borrow_mut().insert(QueryEntry {
-            channel_sender: tx,
+            channel_sender: Some(tx),
             return_sender: Some(return_tx),
             owner: None,
         });
@@ -127,6 +127,19 @@ impl QueryEngine {
                     window.ipc.postMessage(
                         JSON.stringify(returned_value)
                     );
+                }}, (error)=>{{
+                    let returned_error = {{
+                        "method":"query",
+                        "params": {{
+                            "id": {request_id},
+                            "data": null,
+                            "returned_value": true,
+                            "error": String(error)
+                        }}
+                    }};
+                    window.ipc.postMessage(
+                        JSON.stringify(returned_error)
+                    );
                 }})
             }})();"#
         )) {
@@ -148,15 +161,26 @@ impl QueryEngine {
             id,
             data,
             returned_value,
+            error,
         } = data;
         let mut slab = self.active_requests.slab.borrow_mut();
         if let Some(entry) = slab.get_mut(id) {
             if returned_value {
+                let result = match error {
+                    Some(error) => Err(error),
+                    None => Ok(data),
+                };
+                // The script is done, so the stream of messages ends here. An error is delivered to both sides.
+                if let Some(channel_sender) = entry.channel_sender.take() {
+                    if let Err(error) = &result {
+                        let _ = channel_sender.send(Err(error.clone()));
+                    }
+                }
                 if let Some(sender) = entry.return_sender.take() {
-                    let _ = sender.send(data);
+                    let _ = sender.send(result);
                 }
-            } else {
-                let _ = entry.channel_sender.send(data);
+            } else if let Some(channel_sender) = &entry.channel_sender {
+                let _ = channel_sender.send(Ok(data));
             }
         }
     }
@@ -164,8 +188,8 @@ impl QueryEngine {
 
 pub(crate) struct Query<V: DeserializeOwned> {
     query_engine: QueryEngine,
-    pub receiver: tokio::sync::mpsc::UnboundedReceiver<Value>,
-    pub return_receiver: Option<tokio::sync::oneshot::Receiver<Value>>,
+    pub receiver: tokio::sync::mpsc::UnboundedReceiver<Result<Value, String>>,
+    pub return_receiver: Option<tokio::sync::oneshot::Receiver<Result<Value, String>>>,
     pub id: usize,
     phantom: std::marker::PhantomData<V>,
 }
@@ -203,13 +227,17 @@ impl<V: DeserializeOwned> Query<V> {
     }
 
     /// Poll the query for a message
+    ///
+    /// Once the script completed and every message it sent was received, this returns [`QueryError::Finished`]. If
+    /// the script threw, the error is returned before that.
     pub fn poll_recv(
         &mut self,
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Result<Value, QueryError>> {
-        self.receiver
-            .poll_recv(cx)
-            .map(|result| result.ok_or(QueryError::Recv(RecvError::Closed)))
+        self.receiver.poll_recv(cx).map(|message| match message {
+            Some(message) => message.map_err(QueryError::Js),
+            None => Err(QueryError::Finished),
+        })
     }
 
     /// Receive the result of the query
@@ -217,7 +245,8 @@ impl<V: DeserializeOwned> Query<V> {
         match self.return_receiver.take() {
             Some(receiver) => receiver
                 .await
-                .map_err(|_| QueryError::Recv(RecvError::Closed)),
+                .map_err(|_| QueryError::Recv(RecvError::Closed))?
+                .map_err(QueryError::Js),
             None => Err(QueryError::Finished),
         }
     }
@@ -228,9 +257,11 @@ impl<V: DeserializeOwned> Query<V> {
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Result<Value, QueryError>> {
         match self.return_receiver.as_mut() {
-            Some(receiver) => receiver
-                .poll_unpin(cx)
-                .map_err(|_| QueryError::Recv(RecvError::Closed)),
+            Some(receiver) => receiver.poll_unpin(cx).map(|result| {
+                result
+                    .map_err(|_| QueryError::Recv(RecvError::Closed))?
+                    .map_err(QueryError::Js)
+            }),
             None => std::task::Poll::Ready(Err(QueryError::Finished)),
         }
     }
@@ -269,12 +300,18 @@ pub enum QueryError {
     Deserialize(serde_json::Error),
     #[error("Query has already been resolved")]
     Finished,
+    #[error("Error thrown by the query script: {0}")]
+    Js(String),
 }
 
 #[derive(Clone, Debug, Deserialize)]
 pub(crate) struct QueryResult {
     id: usize,
+    #[serde(default)]
     data: Value,
     #[serde(default)]
     returned_value: bool,
+    /// The error the script threw, if it didn't complete
+    #[serde(default)]
+    error: Option<String>,
 }
@@ -0,0 +1,250 @@
+//! Literals hot reloading can change without rebuilding the app
+//!
+//! The template of an rsx! call reads its formatted strings, and the number, string and boolean literals it passes to
+//! components, through `dioxus_core::internal` in debug builds. Every literal gets an index, counting through the
+//! dynamic nodes and then the dynamic attributes of the template, and hot reloading counts them the same way.
+
+use super::*;
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens};
+use syn::{parse_quote, Expr, ExprLit, Lit};
+
+/// A literal in rsx! that hot reloading can change
+pub(crate) enum LiteralRef<'a> {
+    Fmt(&'a IfmtInput),
+    Lit(&'a Lit),
+}
+
+/// The literals of a dynamic node, in the order they are indexed
+pub(crate) fn node_literals(node: &BodyNode) -> Vec<LiteralRef<'_>> {
+    match node {
+        BodyNode::Text(text) if !text.is_static() => vec![LiteralRef::Fmt(text)],
+        BodyNode::Component(component) => component
+            .fields
+            .iter()
+            .filter_map(|field| field_literal(&field.content))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// The literals of a dynamic attribute, in the order they are indexed
+pub(crate) fn attribute_literals(attr: &AttributeType) -> Vec<LiteralRef<'_>> {
+    match attr {
+        AttributeType::Named(ElementAttrNamed {
+            attr:
+                ElementAttr {
+                    value: ElementAttrValue::AttrLiteral(value),
+                    ..
+                },
+            ..
+        }) if !value.is_static() => vec![LiteralRef::Fmt(value)],
+        _ => Vec::new(),
+    }
+}
+
+fn field_literal(content: &ContentField) -> Option<LiteralRef<'_>> {
+    match content {
+        ContentField::Formatted(value) => Some(LiteralRef::Fmt(value)),
+        ContentField::ManExpr(Expr::Lit(ExprLit { lit, .. })) if is_hot(lit) => {
+            Some(LiteralRef::Lit(lit))
+        }
+        _ => None,
+    }
+}
+
+/// Whether hot reloading can change the literal. Integers must fit into the `i64` they are sent as.
+fn is_hot(lit: &Lit) -> bool {
+    match lit {
+        Lit::Int(int) => int.base10_parse::<i64>().is_ok(),
+        Lit::Float(_) | Lit::Bool(_) | Lit::Str(_) => true,
+        _ => false,
+    }
+}
+
+/// Render a dynamic node, reading its literals at the next indexes in debug builds
+pub(crate) fn render_node(node: &BodyNode, index: &mut usize) -> TokenStream2 {
+    match node {
+        BodyNode::Text(text) if !text.is_static() => {
+            let text = hot_fmt(text, next(index));
+            quote! {
+                dioxus_core::DynamicNode::Text(dioxus_core::VText::new(#text))
+            }
+        }
+        BodyNode::Component(component) => {
+            let mut component = component.clone();
+            for field in &mut component.fields {
+                let value = match field_literal(&field.content) {
+                    Some(LiteralRef::Fmt(value)) => hot_fmt(value, next(index)),
+                    Some(LiteralRef::Lit(value)) => hot_lit(value, next(index)),
+                    None => continue,
+                };
+                field.content = ContentField::ManExpr(parse_quote!(#value));
+            }
+            component.to_token_stream()
+        }
+        _ => node.to_token_stream(),
+    }
+}
+
+/// Render a dynamic attribute, reading its literals at the next indexes in debug builds
+pub(crate) fn render_attribute(attr: &AttributeType, index: &mut usize) -> AttributeType {
+    let mut attr = attr.clone();
+    if let AttributeType::Named(ElementAttrNamed {
+        attr: ElementAttr { value, .. },
+        ..
+    }) = &mut attr
+    {
+        if let ElementAttrValue::AttrLiteral(literal) = value {
+            if !literal.is_static() {
+                let hot = hot_fmt(literal, next(index));
+                *value = ElementAttrValue::AttrExpr(parse_quote!(#hot));
+            }
+        }
+    }
+    attr
+}
+
+fn next(index: &mut usize) -> usize {
+    *index += 1;
+    *index - 1
+}
+
+fn hot_fmt(value: &IfmtInput, index: usize) -> TokenStream2 {
+    let formatted = value.segments.iter().filter_map(|segment| match segment {
+        Segment::Formatted(segment) => Some(segment),
+        Segment::Literal(_) => None,
+    });
+
+    quote! {{
+        #[cfg(debug_assertions)]
+        let __value = dioxus_core::internal::hot_fmt(
+            TEMPLATE.name,
+            #index,
+            || vec![#(#formatted.to_string()),*],
+        )
+        .unwrap_or_else(|| #value.to_string());
+        #[cfg(not(debug_assertions))]
+        let __value = #value.to_string();
+        __value
+    }}
+}
+
+fn hot_lit(value: &Lit, index: usize) -> TokenStream2 {
+    quote! {{
+        #[cfg(debug_assertions)]
+        let __value = dioxus_core::internal::hot_literal(TEMPLATE.name, #index, #value);
+        #[cfg(not(debug_assertions))]
+        let __value = #value;
+        __value
+    }}
+}
+
+#[cfg(feature = "hot_reload")]
+impl LiteralRef<'_> {
+    /// The value hot reloading sends to the app
+    pub(crate) fn value(&self) -> dioxus_core::HotLiteral {
+        use dioxus_core::{FmtSegment, HotLiteral};
+
+        match self {
+            LiteralRef::Fmt(value) => {
+                let mut formatted = 0;
+                HotLiteral::Fmt(
+                    value
+                        .segments
+                        .iter()
+                        .map(|segment| match segment {
+                            Segment::Literal(literal) => FmtSegment::Literal(literal.clone()),
+                            Segment::Formatted(_) => FmtSegment::Formatted(next(&mut formatted)),
+                        })
+                        .collect(),
+                )
+            }
+            LiteralRef::Lit(Lit::Int(int)) => HotLiteral::Int(int.base10_parse().unwrap()),
+            LiteralRef::Lit(Lit::Float(float)) => {
+                HotLiteral::Float(float.base10_parse().unwrap_or_default())
+            }
+            LiteralRef::Lit(Lit::Bool(bool)) => HotLiteral::Bool(bool.value),
+            LiteralRef::Lit(Lit::Str(str)) => HotLiteral::Str(str.value()),
+            LiteralRef::Lit(_) => unreachable!("only hot literals are collected"),
+        }
+    }
+}
+
+/// The node with every literal replaced by a placeholder, so nodes that only differ in their literals compare equal
+#[cfg(feature = "hot_reload")]
+pub(crate) fn erase_node(node: &BodyNode) -> BodyNode {
+    match node {
+        BodyNode::Text(text) if !text.is_static() => BodyNode::Text(erase_fmt(text)),
+        BodyNode::Component(component) => {
+            let mut component = component.clone();
+            for field in &mut component.fields {
+                match &mut field.content {
+                    ContentField::Formatted(value) => *value = erase_fmt(value),
+                    ContentField::ManExpr(Expr::Lit(ExprLit { lit, .. })) if is_hot(lit) => {
+                        *lit = erase_lit(lit)
+                    }
+                    _ => {}
+                }
+            }
+            BodyNode::Component(component)
+        }
+        _ => node.clone(),
+    }
+}
+
+/// The attribute with every literal replaced by a placeholder, see [`erase_node`]
+#[cfg(feature = "hot_reload")]
+pub(crate) fn erase_attribute(attr: &AttributeType) -> AttributeType {
+    let mut attr = attr.clone();
+    if let AttributeType::Named(ElementAttrNamed {
+        attr:
+            ElementAttr {
+                value: ElementAttrValue::AttrLiteral(value),
+                ..
+            },
+        ..
+    }) = &mut attr
+    {
+        if !value.is_static() {
+            *value = erase_fmt(value);
+        }
+    }
+    attr
+}
+
+/// Only the formatted values of a formatted string. Those can't change without a rebuild.
+#[cfg(feature = "hot_reload")]
+fn erase_fmt(value: &IfmtInput) -> IfmtInput {
+    IfmtInput {
+        source: None,
+        segments: value
+            .segments
+            .iter()
+            .filter(|segment| matches!(segment, Segment::Formatted(_)))
+            .cloned()
+            .collect(),
+    }
+}
+
+/// A literal of the same type. The suffix of a number is kept since it decides the type.
+#[cfg(feature = "hot_reload")]
+fn erase_lit(lit: &Lit) -> Lit {
+    use proc_macro2::Span;
+    use syn::{LitBool, LitFloat, LitInt, LitStr};
+
+    match lit {
+        Lit::Int(int) => Lit::Int(LitInt::new(
+            &format!("0{}", int.suffix()),
+            Span::call_site(),
+        )),
+        Lit::Float(float) => Lit::Float(LitFloat::new(
+            &format!("0.0{}", float.suffix()),
+            Span::call_site(),
+        )),
+        Lit::Bool(_) => Lit::Bool(LitBool::new(false, Span::call_site())),
+        Lit::Str(_) => Lit::Str(LitStr::new("", Span::call_site())),
+        _ => lit.clone(),
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{CallBody, HotReloadingContext};
 use dioxus_core::{
     prelude::{TemplateAttribute, TemplateNode},
-    Template,
+    HotLiteral, LiteralUpdate, Template,
 };
 use krates::cm::MetadataCommand;
 use krates::Cmd;
@@ -50,6 +50,8 @@ pub struct CachedSynFile {
     pub raw: String,
     pub path: PathBuf,
     pub templates: HashMap<&'static str, Template>,
+    /// The literals last sent for each template, if any changed since the file was compiled
+    pub literals: HashMap<&'static str, Vec<HotLiteral>>,
     pub tracked_assets: HashSet<PathBuf>,
 }
 
@@ -93,6 +95,17 @@ impl<Ctx: HotReloadingContext> FileMap<Ctx> {
         file_path: &Path,
         crate_dir: &Path,
     ) -> Result<UpdateResult, HotreloadError> {
+        self.update_rsx_and_literals(file_path, crate_dir)
+            .map(|(result, _)| result)
+    }
+
+    /// Like [`FileMap::update_rsx`], but also returns the literals that changed in templates that are otherwise the
+    /// same. They are only returned once, later updates compare against them.
+    pub fn update_rsx_and_literals(
+        &mut self,
+        file_path: &Path,
+        crate_dir: &Path,
+    ) -> Result<(UpdateResult, Vec<LiteralUpdate>), HotreloadError> {
         let mut file = File::open(file_path)?;
         let mut src = String::new();
         file.read_to_string(&mut src)?;
@@ -115,7 +128,7 @@ impl<Ctx: HotReloadingContext> FileMap<Ctx> {
             // merge the new map into the old map
             self.map.extend(map.map);
 
-            return Ok(UpdateResult::NeedsRebuild);
+            return Ok((UpdateResult::NeedsRebuild, Vec::new()));
         };
 
         // If the cached file is not a valid rsx file, rebuild the project, forcing errors
@@ -138,24 +151,29 @@ impl<Ctx: HotReloadingContext> FileMap<Ctx> {
                     raw: src.clone(),
                     path: file_path.to_path_buf(),
                     templates: HashMap::new(),
+                    literals: HashMap::new(),
                     tracked_assets: HashSet::new(),
                 };
 
                 self.map.insert(file_path.to_path_buf(), cached_file);
-                return Ok(UpdateResult::NeedsRebuild);
+                return Ok((UpdateResult::NeedsRebuild, Vec::new()));
             }
         };
 
         let mut messages: Vec<Template> = Vec::new();
+        let mut literals = Vec::new();
 
         for calls in instances.into_iter() {
             let ChangedRsx { old, new } = calls;
 
             let old_start = old.span().start();
 
-            let old_parsed = syn::parse2::<CallBody>(old.tokens);
+            let old_parsed = syn::parse2::<CallBody>(old.tokens.clone());
+            let compiled_parsed = syn::parse2::<CallBody>(old.tokens);
             let new_parsed = syn::parse2::<CallBody>(new);
-            let (Ok(old_call_body), Ok(new_call_body)) = (old_parsed, new_parsed) else {
+            let (Ok(old_call_body), Ok(compiled_call_body), Ok(new_call_body)) =
+                (old_parsed, compiled_parsed, new_parsed)
+            else {
                 continue;
             };
 
@@ -177,13 +195,30 @@ impl<Ctx: HotReloadingContext> FileMap<Ctx> {
 
             // Retuns Some(template) if the template is hotreloadable
             // dynamic changes are not hot reloadable and force a rebuild
-            let hotreloadable_template =
-                new_call_body.update_template::<Ctx>(Some(old_call_body), leaked_location);
+            let hotreloadable_template = new_call_body
+                .update_template_and_literals::<Ctx>(Some(old_call_body), leaked_location);
 
             // if the template is not hotreloadable, we need to do a full rebuild
-            let Some(template) = hotreloadable_template else {
-                return Ok(UpdateResult::NeedsRebuild);
+            let Some((template, new_literals)) = hotreloadable_template else {
+                return Ok((UpdateResult::NeedsRebuild, Vec::new()));
+            };
+
+            // Literals changed since the last update, or since the app was compiled if they never changed before
+            let last_literals = match old_cached.literals.get(template.name) {
+                Some(last_literals) => Some(last_literals.clone()),
+                None => compiled_call_body
+                    .update_template_and_literals::<Ctx>(None, leaked_location)
+                    .map(|(_, literals)| literals),
             };
+            if last_literals.as_ref() != Some(&new_literals) {
+                old_cached
+                    .literals
+                    .insert(template.name, new_literals.clone());
+                literals.push(LiteralUpdate {
+                    template: template.name.rsplit_once(':').unwrap().0.to_string(),
+                    literals: new_literals,
+                });
+            }
 
             // dioxus cannot handle empty templates...
             // todo: I think it can? or we just skip them nowa
@@ -209,7 +244,7 @@ impl<Ctx: HotReloadingContext> FileMap<Ctx> {
             messages.push(template);
         }
 
-        Ok(UpdateResult::UpdatedRsx(messages))
+        Ok((UpdateResult::UpdatedRsx(messages), literals))
     }
 
     fn populate_assets(template: Template) -> HashSet<PathBuf> {
@@ -258,6 +293,7 @@ impl<Ctx: HotReloadingContext> FileMap<Ctx> {
                     path,
                     tracked_assets,
                     templates: HashMap::from([(template.name, template)]),
+                    literals: HashMap::new(),
                 },
             );
         }
@@ -348,6 +384,7 @@ fn find_rs_files(root: PathBuf, filter: &mut impl FnMut(&Path) -> bool) -> FileM
                         raw: src.clone(),
                         path: root.clone(),
                         templates: HashMap::new(),
+                        literals: HashMap::new(),
                         tracked_assets: HashSet::new(),
                     };
 
@@ -19,6 +19,7 @@ mod errors;
 mod attribute;
 mod component;
 mod element;
+mod hot_literals;
 #[cfg(feature = "hot_reload")]
 pub mod hot_reload;
 mod ifmt;
@@ -30,7 +31,7 @@ use std::{fmt::Debug, hash::Hash};
 pub use attribute::*;
 pub use component::*;
 #[cfg(feature = "hot_reload")]
-use dioxus_core::{Template, TemplateAttribute, TemplateNode};
+use dioxus_core::{HotLiteral, Template, TemplateAttribute, TemplateNode};
 pub use element::*;
 #[cfg(feature = "hot_reload")]
 pub use hot_reload::HotReloadingContext;
@@ -70,6 +71,19 @@ impl CallBody {
         template: Option<CallBody>,
         location: &'static str,
     ) -> Option<Template> {
+        self.update_template_and_literals::<Ctx>(template, location)
+            .map(|(template, _)| template)
+    }
+
+    #[cfg(feature = "hot_reload")]
+    /// Like [`CallBody::update_template`], but also returns the new values of the literals of the template. Literals
+    /// may change without a rebuild, so the dynamic parts only need to match the previous body once their literals are
+    /// ignored.
+    pub fn update_template_and_literals<Ctx: HotReloadingContext>(
+        &self,
+        template: Option<CallBody>,
+        location: &'static str,
+    ) -> Option<(Template, Vec<HotLiteral>)> {
         let mut renderer: TemplateRenderer = TemplateRenderer {
             roots: &self.roots,
             location: None,
@@ -80,10 +94,10 @@ impl CallBody {
 
     /// Render the template with a manually set file location. This should be used when multiple rsx! calls are used in the same macro
     pub fn render_with_location(&self, location: String) -> TokenStream2 {
-        let body = TemplateRenderer {
+        let body = HotLiteralRenderer(TemplateRenderer {
             roots: &self.roots,
             location: Some(location),
-        };
+        });
 
         // Empty templates just are placeholders for "none"
         if self.roots.is_empty() {
@@ -116,10 +130,10 @@ impl Parse for CallBody {
 
 impl ToTokens for CallBody {
     fn to_tokens(&self, out_tokens: &mut TokenStream2) {
-        let body: TemplateRenderer = TemplateRenderer {
+        let body = HotLiteralRenderer(TemplateRenderer {
             roots: &self.roots,
             location: None,
-        };
+        });
 
         // Empty templates just are placeholders for "none"
         if self.roots.is_empty() {
@@ -137,13 +151,23 @@ pub struct TemplateRenderer<'a> {
     pub location: Option<String>,
 }
 
+/// Renders the template of an rsx! call itself, which reads its literals through hot reloading in debug builds. The
+/// templates nested in it are rendered by a plain [`TemplateRenderer`] and are not hot reloaded.
+struct HotLiteralRenderer<'a>(TemplateRenderer<'a>);
+
+impl<'a> ToTokens for HotLiteralRenderer<'a> {
+    fn to_tokens(&self, out_tokens: &mut TokenStream2) {
+        self.0.render(true, out_tokens)
+    }
+}
+
 impl<'a> TemplateRenderer<'a> {
     #[cfg(feature = "hot_reload")]
     fn update_template<Ctx: HotReloadingContext>(
         &mut self,
         previous_call: Option<CallBody>,
         location: &'static str,
-    ) -> Option<Template> {
+    ) -> Option<(Template, Vec<HotLiteral>)> {
         let mut mapping = previous_call.map(|call| DynamicMapping::from(call.roots));
 
         let mut context = DynamicContext::default();
@@ -156,7 +180,21 @@ impl<'a> TemplateRenderer<'a> {
             context.current_path.pop();
         }
 
-        Some(Template {
+        // Dynamic parts of the previous call that are gone keep their literals, so the indexes of the others don't change
+        let (node_literals, attribute_literals) = match &mapping {
+            Some(mapping) => (
+                merge_literals(&mapping.node_literals, context.node_literals),
+                merge_literals(&mapping.attribute_literals, context.attribute_literals),
+            ),
+            None => (context.node_literals, context.attribute_literals),
+        };
+        let literals = node_literals
+            .into_iter()
+            .chain(attribute_literals)
+            .flatten()
+            .collect();
+
+        let template = Template {
             name: location,
             roots: intern(roots.as_slice()),
             node_paths: intern(
@@ -175,12 +213,44 @@ impl<'a> TemplateRenderer<'a> {
                     .collect::<Vec<_>>()
                     .as_slice(),
             ),
-        })
+        };
+
+        Some((template, literals))
+    }
+}
+
+/// Store the values of the literals of the dynamic part at the index
+#[cfg(feature = "hot_reload")]
+fn set_literals(
+    literals: &mut Vec<Vec<HotLiteral>>,
+    idx: usize,
+    new: Vec<hot_literals::LiteralRef>,
+) {
+    if literals.len() <= idx {
+        literals.resize_with(idx + 1, Vec::new);
     }
+    literals[idx] = new.iter().map(|literal| literal.value()).collect();
+}
+
+/// The literals of the dynamic parts of the previous call, replaced by the new ones of the parts that are still there
+#[cfg(feature = "hot_reload")]
+fn merge_literals(previous: &[Vec<HotLiteral>], new: Vec<Vec<HotLiteral>>) -> Vec<Vec<HotLiteral>> {
+    (0..previous.len().max(new.len()))
+        .map(|idx| match new.get(idx) {
+            Some(new) if !new.is_empty() => new.clone(),
+            _ => previous.get(idx).cloned().unwrap_or_default(),
+        })
+        .collect()
 }
 
 impl<'a> ToTokens for TemplateRenderer<'a> {
     fn to_tokens(&self, out_tokens: &mut TokenStream2) {
+        self.render(false, out_tokens)
+    }
+}
+
+impl<'a> TemplateRenderer<'a> {
+    fn render(&self, hot_literals: bool, out_tokens: &mut TokenStream2) {
         let mut context = DynamicContext::default();
 
         let key = match self.roots.first() {
@@ -229,11 +299,29 @@ impl<'a> ToTokens for TemplateRenderer<'a> {
 
         // Render and release the mutable borrow on context
         let roots = quote! { #( #root_printer ),* };
-        let node_printer = &context.dynamic_nodes;
-        let dyn_attr_printer = context
+        let mut literal_index = 0;
+        let node_printer: Vec<_> = context
+            .dynamic_nodes
+            .iter()
+            .map(|node| match hot_literals {
+                true => hot_literals::render_node(node, &mut literal_index),
+                false => node.to_token_stream(),
+            })
+            .collect();
+        let dyn_attr_printer: Vec<_> = context
             .dynamic_attributes
             .iter()
-            .map(|attrs| AttributeType::merge_quote(attrs));
+            .map(|attrs| match hot_literals {
+                true => {
+                    let attrs: Vec<_> = attrs
+                        .iter()
+                        .map(|attr| hot_literals::render_attribute(attr, &mut literal_index))
+                        .collect();
+                    AttributeType::merge_quote(&attrs.iter().collect::<Vec<_>>())
+                }
+                false => AttributeType::merge_quote(attrs),
+            })
+            .collect();
         let node_paths = context.node_paths.iter().map(|it| quote!(&[#(#it),*]));
         let attr_paths = context.attr_paths.iter().map(|it| quote!(&[#(#it),*]));
 
@@ -266,6 +354,10 @@ struct DynamicMapping {
     last_attribute_idx: usize,
     node_to_idx: std::collections::HashMap<BodyNode, Vec<usize>>,
     last_element_idx: usize,
+    /// The literals of every dynamic attribute and node, by index. The parts are mapped with their literals erased,
+    /// since literals may change without a rebuild.
+    attribute_literals: Vec<Vec<HotLiteral>>,
+    node_literals: Vec<Vec<HotLiteral>>,
 }
 
 #[cfg(feature = "hot_reload")]
@@ -280,19 +372,30 @@ impl DynamicMapping {
 
     fn get_attribute_idx(&mut self, attr: &AttributeType) -> Option<usize> {
         self.attribute_to_idx
-            .get_mut(attr)
+            .get_mut(&hot_literals::erase_attribute(attr))
             .and_then(|idxs| idxs.pop())
     }
 
     fn get_node_idx(&mut self, node: &BodyNode) -> Option<usize> {
-        self.node_to_idx.get_mut(node).and_then(|idxs| idxs.pop())
+        self.node_to_idx
+            .get_mut(&hot_literals::erase_node(node))
+            .and_then(|idxs| idxs.pop())
     }
 
     fn insert_attribute(&mut self, attr: AttributeType) -> usize {
         let idx = self.last_attribute_idx;
         self.last_attribute_idx += 1;
 
-        self.attribute_to_idx.entry(attr).or_default().push(idx);
+        self.attribute_literals.push(
+            hot_literals::attribute_literals(&attr)
+                .iter()
+                .map(|literal| literal.value())
+                .collect(),
+        );
+        self.attribute_to_idx
+            .entry(hot_literals::erase_attribute(&attr))
+            .or_default()
+            .push(idx);
 
         idx
     }
@@ -301,7 +404,16 @@ impl DynamicMapping {
         let idx = self.last_element_idx;
         self.last_element_idx += 1;
 
-        self.node_to_idx.entry(node).or_default().push(idx);
+        self.node_literals.push(
+            hot_literals::node_literals(&node)
+                .iter()
+                .map(|literal| literal.value())
+                .collect(),
+        );
+        self.node_to_idx
+            .entry(hot_literals::erase_node(&node))
+            .or_default()
+            .push(idx);
 
         idx
     }
@@ -353,6 +465,11 @@ pub struct DynamicContext<'a> {
 
     node_paths: Vec<Vec<u8>>,
     attr_paths: Vec<Vec<u8>>,
+
+    #[cfg(feature = "hot_reload")]
+    node_literals: Vec<Vec<HotLiteral>>,
+    #[cfg(feature = "hot_reload")]
+    attribute_literals: Vec<Vec<HotLiteral>>,
 }
 
 impl<'a> DynamicContext<'a> {
@@ -400,6 +517,11 @@ impl<'a> DynamicContext<'a> {
                                 self.attr_paths.resize_with(idx + 1, Vec::new);
                             }
                             self.attr_paths[idx] = self.current_path.clone();
+                            set_literals(
+                                &mut self.attribute_literals,
+                                idx,
+                                hot_literals::attribute_literals(attr),
+                            );
                             static_attrs.push(TemplateAttribute::Dynamic { id: idx })
                         }
                     }
@@ -444,6 +566,11 @@ impl<'a> DynamicContext<'a> {
                     self.node_paths.resize_with(idx + 1, Vec::new);
                 }
                 self.node_paths[idx] = self.current_path.clone();
+                set_literals(
+                    &mut self.node_literals,
+                    idx,
+                    hot_literals::node_literals(root),
+                );
 
                 Some(match root {
                     BodyNode::Text(_) => TemplateNode::DynamicText { id: idx },
@@ -772,3 +899,71 @@ fn diff_template() {
         },
     )
 }
+
+#[cfg(feature = "hot_reload")]
+#[test]
+fn diff_literals() {
+    use dioxus_core::{FmtSegment, HotLiteral};
+
+    struct Mock;
+
+    impl HotReloadingContext for Mock {
+        fn map_attribute(_: &str, _: &str) -> Option<(&'static str, Option<&'static str>)> {
+            None
+        }
+
+        fn map_element(_: &str) -> Option<(&'static str, Option<&'static str>)> {
+            None
+        }
+    }
+
+    let call_body1: CallBody = syn::parse2(quote! {
+        div {
+            class: "small {x}",
+            "count {x}",
+            Comp { size: 1, label: "one", enabled: true }
+        }
+    })
+    .unwrap();
+    let (template1, _) = call_body1
+        .update_template_and_literals::<Mock>(None, "testing")
+        .unwrap();
+
+    // changing only literals keeps the template, and sends the new literals instead
+    let call_body2: CallBody = syn::parse2(quote! {
+        div {
+            class: "large {x}",
+            "total: {x}",
+            Comp { size: 2, label: "two", enabled: false }
+        }
+    })
+    .unwrap();
+    let (template2, literals) = call_body2
+        .update_template_and_literals::<Mock>(Some(call_body1), "testing")
+        .unwrap();
+
+    assert_eq!(template1, template2);
+    assert_eq!(
+        literals,
+        [
+            HotLiteral::Fmt(vec![
+                FmtSegment::Literal("total: ".to_string()),
+                FmtSegment::Formatted(0)
+            ]),
+            HotLiteral::Int(2),
+            HotLiteral::Str("two".to_string()),
+            HotLiteral::Bool(false),
+            HotLiteral::Fmt(vec![
+                FmtSegment::Literal("large ".to_string()),
+                FmtSegment::Formatted(0)
+            ]),
+        ]
+    );
+
+    // changing a formatted value can't be hot reloaded
+    let call_body1: CallBody = syn::parse2(quote! { "count {x}" }).unwrap();
+    let call_body2: CallBody = syn::parse2(quote! { "count {y}" }).unwrap();
+    assert!(call_body2
+        .update_template_and_literals::<Mock>(Some(call_body1), "testing")
+        .is_none());
+}
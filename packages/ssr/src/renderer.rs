@@ -55,9 +55,10 @@ impl Renderer {
         dom: &VirtualDom,
         scope: ScopeId,
     ) -> std::fmt::Result {
-        // We should never ever run into async or errored nodes in SSR
-        // Error boundaries and suspense boundaries will convert these to sync
-        if let RenderReturn::Ready(node) = dom.get_scope(scope).unwrap().root_node() {
+        // Suspended components render their placeholder, and the fallback of their suspense boundary shows
+        if let RenderReturn::Ready(node) | RenderReturn::Aborted(node) =
+            dom.get_scope(scope).unwrap().root_node()
+        {
             self.dynamic_node_id = 0;
             self.render_template(buf, dom, node)?
         };
@@ -122,14 +123,9 @@ impl Renderer {
                         } else {
                             let scope = node.mounted_scope(*idx, template, dom).unwrap();
                             let node = scope.root_node();
-                            match node {
-                                RenderReturn::Ready(node) => {
-                                    self.render_template(buf, dom, node)?
-                                }
-                                _ => todo!(
-                                    "generally, scopes should be sync, only if being traversed"
-                                ),
-                            }
+                            // Suspended components render their placeholder
+                            let (RenderReturn::Ready(node) | RenderReturn::Aborted(node)) = node;
+                            self.render_template(buf, dom, node)?
                         }
                     }
                     DynamicNode::Text(text) => {
@@ -4,7 +4,7 @@ use futures_util::StreamExt;
 use generational_box::{AnyStorage, GenerationalBox, UnsyncStorage};
 use js_sys::Function;
 use serde_json::Value;
-use std::{rc::Rc, str::FromStr};
+use std::{cell::RefCell, rc::Rc, str::FromStr};
 use wasm_bindgen::prelude::*;
 
 /// Provides the WebEvalProvider through [`cx.provide_context`].
@@ -39,7 +39,10 @@ struct WebEvaluator {
 impl WebEvaluator {
     /// Creates a new evaluator for web-based targets.
     fn create(js: String) -> GenerationalBox<Box<dyn Evaluator>> {
-        let (mut channel_sender, channel_receiver) = futures_channel::mpsc::unbounded();
+        let (channel_sender, channel_receiver) = futures_channel::mpsc::unbounded();
+        // The sender is dropped once the script completed, which ends the stream of messages
+        let channel_sender = Rc::new(RefCell::new(Some(channel_sender)));
+        let sender = channel_sender.clone();
         let owner = UnsyncStorage::owner();
         let invalid = owner.invalid();
 
@@ -48,7 +51,11 @@ impl WebEvaluator {
             // Drop the owner when the sender is dropped.
             let _ = &owner;
             match serde_wasm_bindgen::from_value::<serde_json::Value>(data) {
-                Ok(data) => _ = channel_sender.start_send(data),
+                Ok(data) => {
+                    if let Some(sender) = sender.borrow().as_ref() {
+                        _ = sender.unbounded_send(data);
+                    }
+                }
                 Err(e) => {
                     // Can't really do much here.
                     tracing::error!("failed to serialize JsValue to serde_json::Value (eval communication) - {}", e);
@@ -64,6 +71,14 @@ impl WebEvaluator {
 
         let result = match Function::new_with_args("dioxus", &code).call1(&JsValue::NULL, &dioxus) {
             Ok(result) => {
+                if let Some(promise) = result.dyn_ref::<js_sys::Promise>() {
+                    let finish = Closure::<dyn FnMut(JsValue)>::new(move |_| {
+                        channel_sender.borrow_mut().take();
+                    });
+                    _ = promise.then2(&finish, &finish);
+                    finish.forget();
+                }
+
                 if let Ok(stringified) = js_sys::JSON::stringify(&result) {
                     if !stringified.is_undefined() && stringified.is_valid_utf16() {
                         let string: String = stringified.into();
@@ -125,11 +140,10 @@ impl Evaluator for WebEvaluator {
         &mut self,
         context: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Result<serde_json::Value, EvalError>> {
-        self.channel_receiver.poll_next_unpin(context).map(|poll| {
-            poll.ok_or_else(|| {
-                EvalError::Communication("failed to receive data from js".to_string())
-            })
-        })
+        // Once the script completed and every message it sent was received, there is nothing left to receive
+        self.channel_receiver
+            .poll_next_unpin(context)
+            .map(|message| message.ok_or(EvalError::Finished))
     }
 }
 
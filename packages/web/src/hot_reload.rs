@@ -2,10 +2,16 @@
 
 use futures_channel::mpsc::UnboundedReceiver;
 
-use dioxus_core::Template;
+use dioxus_core::{LiteralUpdate, Template};
 use web_sys::{console, Element};
 
-pub(crate) fn init() -> UnboundedReceiver<Template> {
+/// A change the `VirtualDom` applies
+pub(crate) enum HotReloadUpdate {
+    Template(Template),
+    Literals(LiteralUpdate),
+}
+
+pub(crate) fn init() -> UnboundedReceiver<HotReloadUpdate> {
     use wasm_bindgen::closure::Closure;
     use wasm_bindgen::JsCast;
     use web_sys::{MessageEvent, WebSocket};
@@ -39,26 +45,19 @@ pub(crate) fn init() -> UnboundedReceiver<Template> {
                 // leak the value
                 let val: &'static serde_json::Value = Box::leak(Box::new(val));
                 let template: Template = Template::deserialize(val).unwrap();
-                tx.unbounded_send(template).unwrap();
-            } else {
-                // it might be triggering a reload of assets
-                // invalidate all the stylesheets on the page
-                let links = web_sys::window()
-                    .unwrap()
-                    .document()
-                    .unwrap()
-                    .query_selector_all("link[rel=stylesheet]")
+                tx.unbounded_send(HotReloadUpdate::Template(template))
                     .unwrap();
-
-                console::log_1(&links.clone().into());
-
-                for x in 0..links.length() {
-                    console::log_1(&x.into());
-
-                    let link: Element = links.get(x).unwrap().unchecked_into();
-                    let href = link.get_attribute("href").unwrap();
-                    _ = link.set_attribute("href", &format!("{}?{}", href, js_sys::Math::random()));
+            } else if let Some(literals) = string.strip_prefix("update-literals: ") {
+                if let Ok(literals) = serde_json::from_str(literals) {
+                    tx.unbounded_send(HotReloadUpdate::Literals(literals))
+                        .unwrap();
                 }
+            } else if let Some(asset) = string.strip_prefix("reload-asset: ") {
+                reload_asset(asset);
+            } else if let Some(status) = string.strip_prefix("build-status: ") {
+                show_build_status(status);
+            } else if string == "full-reload" {
+                _ = web_sys::window().unwrap().location().reload();
             }
         }
     }) as Box<dyn FnMut(MessageEvent)>);
@@ -68,3 +67,91 @@ pub(crate) fn init() -> UnboundedReceiver<Template> {
 
     rx
 }
+
+/// Reload the elements that load the asset of a `reload-asset: {json}` message
+fn reload_asset(asset: &str) {
+    let Ok(asset) = serde_json::from_str::<serde_json::Value>(asset) else {
+        return;
+    };
+    let kind = asset["kind"].as_str().unwrap_or("other");
+    let path = asset["path"].as_str().unwrap_or_default();
+
+    // Assets resolved by the asset! macro are served from a hashed url, and busted with the hash of their new contents
+    let resolved = &asset["resolved"];
+    let served = match resolved["url"].as_str() {
+        Some(url) => url.split(['?', '#']).next().unwrap_or(url),
+        None => path,
+    };
+    let name = served.rsplit(['/', '\\']).next().unwrap_or(served);
+    let version = match resolved["hash"].as_str() {
+        Some(hash) => hash.to_string(),
+        None => js_sys::Math::random().to_string(),
+    };
+
+    let reloaded = match kind {
+        "stylesheet" => reload_matching("link[rel=stylesheet]", "href", name, &version),
+        "image" => reload_matching("img", "src", name, &version),
+        "script" => {
+            console::log_1(&format!("{path} changed, scripts can't be hot reloaded").into());
+            return;
+        }
+        // fonts and anything else may be loaded from any stylesheet
+        _ => false,
+    };
+
+    // stylesheets may be @imported by another one, so if nothing loads the asset directly, kick all of them
+    if !reloaded {
+        let version = js_sys::Math::random().to_string();
+        reload_matching("link[rel=stylesheet]", "href", "", &version);
+    }
+}
+
+/// Bust the cache of every element whose url ends with the name, and return whether there were any
+fn reload_matching(selector: &str, attribute: &str, name: &str, version: &str) -> bool {
+    use wasm_bindgen::JsCast;
+
+    let elements = web_sys::window()
+        .unwrap()
+        .document()
+        .unwrap()
+        .query_selector_all(selector)
+        .unwrap();
+
+    let mut found = false;
+    for x in 0..elements.length() {
+        let element: Element = elements.get(x).unwrap().unchecked_into();
+        let Some(url) = element.get_attribute(attribute) else {
+            continue;
+        };
+
+        let base = url.split('?').next().unwrap_or(&url);
+        if base.ends_with(name) {
+            _ = element.set_attribute(attribute, &format!("{}?{}", base, version));
+            found = true;
+        }
+    }
+
+    found
+}
+
+/// Show the errors of a `build-status: {json}` message over the app, or remove the overlay once a build succeeded
+fn show_build_status(status: &str) {
+    let Ok(status) = serde_json::from_str::<serde_json::Value>(status) else {
+        return;
+    };
+    let messages = |key| {
+        let messages: Vec<&str> = status[key]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|message| message.as_str())
+            .collect();
+        serde_json::to_string(&messages).unwrap_or_default()
+    };
+    let (errors, warnings) = (messages("errors"), messages("warnings"));
+
+    _ = js_sys::eval(&format!(
+        "({})({errors}, {warnings});",
+        dioxus_interpreter_js::BUILD_STATUS_JS
+    ));
+}
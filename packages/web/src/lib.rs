@@ -132,9 +132,14 @@ pub async fn run(virtual_dom: VirtualDom, web_config: Config) {
             }
         };
 
-        if let Some(template) = template {
-            dom.replace_template(template);
+        #[cfg(all(feature = "hot_reload", debug_assertions))]
+        match template {
+            Some(hot_reload::HotReloadUpdate::Template(template)) => dom.replace_template(template),
+            Some(hot_reload::HotReloadUpdate::Literals(literals)) => dom.replace_literals(literals),
+            None => {}
         }
+        #[cfg(not(all(feature = "hot_reload", debug_assertions)))]
+        let _: Option<()> = template;
 
         // Dequeue all of the events from the channel in send order
         // todo: we should re-order these if possible